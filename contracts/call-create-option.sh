@@ -4,10 +4,10 @@
 # ============================================================================
 #
 # Usage:
-#   ./call-create-option.sh <secret_key> <contract_hash> <option_id> <strike_price> <expiry>
+#   ./call-create-option.sh <secret_key> <contract_hash> <option_id> <strike_price> <expiry> <option_type> <nonce>
 #
 # Example:
-#   ./call-create-option.sh ./keys/secret_key.pem hash-abc123... 1 1000000 1735689600
+#   ./call-create-option.sh ./keys/secret_key.pem hash-abc123... 1 1000000 1735689600 call 1
 #
 # ============================================================================
 
@@ -17,8 +17,8 @@ NODE_ADDRESS="http://65.21.235.219:7777"
 CHAIN_NAME="casper-test"
 PAYMENT_AMOUNT="5000000000"  # 5 CSPR for entry point call
 
-if [ "$#" -ne 5 ]; then
-    echo "Usage: ./call-create-option.sh <secret_key> <contract_hash> <option_id> <strike_price> <expiry>"
+if [ "$#" -ne 7 ]; then
+    echo "Usage: ./call-create-option.sh <secret_key> <contract_hash> <option_id> <strike_price> <expiry> <option_type> <nonce>"
     echo ""
     echo "Arguments:"
     echo "  secret_key    - Path to your secret key PEM file"
@@ -26,6 +26,8 @@ if [ "$#" -ne 5 ]; then
     echo "  option_id     - Unique option ID (u64)"
     echo "  strike_price  - Strike price in smallest unit (U256)"
     echo "  expiry        - Expiry timestamp in unix seconds (u64)"
+    echo "  option_type   - Option type: call or put"
+    echo "  nonce         - Greater than the last nonce used by this key (u64)"
     exit 1
 fi
 
@@ -34,12 +36,24 @@ CONTRACT_HASH="$2"
 OPTION_ID="$3"
 STRIKE_PRICE="$4"
 EXPIRY="$5"
+NONCE="$7"
+
+case "$6" in
+    call) OPTION_TYPE="true" ;;
+    put)  OPTION_TYPE="false" ;;
+    *)
+        echo "Error: option_type must be 'call' or 'put'"
+        exit 1
+        ;;
+esac
 
 echo "Creating option..."
 echo "  Contract: $CONTRACT_HASH"
 echo "  Option ID: $OPTION_ID"
 echo "  Strike Price: $STRIKE_PRICE"
 echo "  Expiry: $EXPIRY"
+echo "  Type: $6"
+echo "  Nonce: $NONCE"
 echo ""
 
 casper-client put-deploy \
@@ -51,7 +65,9 @@ casper-client put-deploy \
     --session-entry-point "create_option" \
     --session-arg "id:u64='$OPTION_ID'" \
     --session-arg "strike_price:u256='$STRIKE_PRICE'" \
-    --session-arg "expiry:u64='$EXPIRY'"
+    --session-arg "expiry:u64='$EXPIRY'" \
+    --session-arg "option_type:bool='$OPTION_TYPE'" \
+    --session-arg "nonce:u64='$NONCE'"
 
 echo ""
 echo "Option creation submitted! Check deploy status on testnet explorer."
@@ -12,7 +12,12 @@
 #   4. Contract built: make build
 #
 # Usage:
-#   ./deploy.sh /path/to/secret_key.pem
+#   ./deploy.sh /path/to/secret_key.pem <ORACLE_CONTRACT_HASH> [FEE_BPS] [KEEPER_REWARD]
+#
+# The oracle contract must expose a `get_price` entry point returning U256.
+# FEE_BPS is the settlement fee in basis points (0-10000, default 0).
+# KEEPER_REWARD is paid in motes, out of collected fees, for each option a
+# keeper expires (default 0).
 #
 # Get testnet CSPR from faucet:
 #   https://testnet.cspr.live/tools/faucet
@@ -40,10 +45,10 @@ echo "=============================================="
 echo ""
 
 # Check for secret key argument
-if [ -z "$1" ]; then
-    echo -e "${RED}Error: Secret key path required${NC}"
+if [ -z "$1" ] || [ -z "$2" ]; then
+    echo -e "${RED}Error: Secret key path and oracle contract hash required${NC}"
     echo ""
-    echo "Usage: ./deploy.sh /path/to/secret_key.pem"
+    echo "Usage: ./deploy.sh /path/to/secret_key.pem <ORACLE_CONTRACT_HASH> [FEE_BPS] [KEEPER_REWARD]"
     echo ""
     echo "To generate a key pair:"
     echo "  casper-client keygen ./keys"
@@ -54,6 +59,9 @@ if [ -z "$1" ]; then
 fi
 
 SECRET_KEY="$1"
+ORACLE_HASH="${2#hash-}"
+FEE_BPS="${3:-0}"
+KEEPER_REWARD="${4:-0}"
 
 # Verify secret key exists
 if [ ! -f "$SECRET_KEY" ]; then
@@ -82,6 +90,9 @@ echo "  Chain:    $CHAIN_NAME"
 echo "  Payment:  $PAYMENT_AMOUNT motes (30 CSPR)"
 echo "  WASM:     $WASM_PATH"
 echo "  Key:      $SECRET_KEY"
+echo "  Oracle:   $ORACLE_HASH"
+echo "  Fee:      $FEE_BPS bps"
+echo "  Keeper:   $KEEPER_REWARD motes per expired option"
 echo ""
 
 # Get WASM file size
@@ -107,7 +118,10 @@ DEPLOY_OUTPUT=$(casper-client put-deploy \
     --chain-name "$CHAIN_NAME" \
     --secret-key "$SECRET_KEY" \
     --payment-amount "$PAYMENT_AMOUNT" \
-    --session-path "$WASM_PATH" 2>&1)
+    --session-path "$WASM_PATH" \
+    --session-arg "oracle_contract_hash:byte_array_32='$ORACLE_HASH'" \
+    --session-arg "fee_bps:u32='$FEE_BPS'" \
+    --session-arg "keeper_reward_per_option:u512='$KEEPER_REWARD'" 2>&1)
 
 # Check if deployment was successful
 if [ $? -eq 0 ]; then
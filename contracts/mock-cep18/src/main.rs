@@ -0,0 +1,190 @@
+//! Test-only CEP-18 token. Implements just enough of the standard for
+//! option-registry delivery and collateral tests: `mint`, `balance_of`,
+//! `transfer` and `transfer_from`. Balances live under `balance_{key}` named
+//! keys so tests can read them directly, and allowances are not tracked — any
+//! caller may move any balance with `transfer_from`. Contracts calling
+//! `transfer` spend the balance of `Key::Hash` of their contract hash.
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+
+use casper_types::{
+    ApiError, CLType, CLValue, EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter,
+    URef, U256,
+    account::AccountHash,
+    contracts::{ContractHash, EntryPoint, NamedKeys},
+};
+
+const CONTRACT_KEY: &str = "mock_cep18";
+const CONTRACT_PACKAGE_KEY: &str = "mock_cep18_package";
+
+const ENTRY_POINT_MINT: &str = "mint";
+const ENTRY_POINT_BALANCE_OF: &str = "balance_of";
+const ENTRY_POINT_TRANSFER: &str = "transfer";
+const ENTRY_POINT_TRANSFER_FROM: &str = "transfer_from";
+
+const ARG_OWNER: &str = "owner";
+const ARG_RECIPIENT: &str = "recipient";
+const ARG_ADDRESS: &str = "address";
+const ARG_AMOUNT: &str = "amount";
+
+/// Mirrors the CEP-18 `InsufficientBalance` error code.
+const ERROR_INSUFFICIENT_BALANCE: u16 = 60001;
+
+/// `CallerInfo` field indices of the calling account and contract.
+const CALLER_FIELD_ACCOUNT: u8 = 0;
+const CALLER_FIELD_CONTRACT: u8 = 4;
+
+fn balance_key(address: &Key) -> String {
+    let mut key = String::from("balance_");
+    key.push_str(&address.to_formatted_string());
+    key
+}
+
+fn balance_uref(address: &Key) -> Option<URef> {
+    runtime::get_key(&balance_key(address))
+        .map(|key| key.into_uref().unwrap_or_revert_with(ApiError::UnexpectedKeyVariant))
+}
+
+fn read_balance(address: &Key) -> U256 {
+    match balance_uref(address) {
+        Some(uref) => storage::read(uref)
+            .unwrap_or_revert_with(ApiError::Read)
+            .unwrap_or_revert_with(ApiError::ValueNotFound),
+        None => U256::zero(),
+    }
+}
+
+fn write_balance(address: &Key, balance: U256) {
+    match balance_uref(address) {
+        Some(uref) => storage::write(uref, balance),
+        None => runtime::put_key(&balance_key(address), storage::new_uref(balance).into()),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn mint() {
+    let owner: Key = runtime::get_named_arg(ARG_OWNER);
+    let amount: U256 = runtime::get_named_arg(ARG_AMOUNT);
+    write_balance(&owner, read_balance(&owner) + amount);
+}
+
+#[no_mangle]
+pub extern "C" fn balance_of() {
+    let address: Key = runtime::get_named_arg(ARG_ADDRESS);
+    runtime::ret(CLValue::from_t(read_balance(&address)).unwrap_or_revert());
+}
+
+fn move_balance(owner: &Key, recipient: &Key, amount: U256) {
+    let owner_balance = read_balance(owner);
+    if owner_balance < amount {
+        runtime::revert(ApiError::User(ERROR_INSUFFICIENT_BALANCE));
+    }
+    write_balance(owner, owner_balance - amount);
+    write_balance(recipient, read_balance(recipient) + amount);
+}
+
+/// The immediate caller: `Key::Hash` of its contract hash for a contract,
+/// `Key::Account` for an account.
+fn caller_key() -> Key {
+    let caller = runtime::get_immediate_caller().unwrap_or_revert();
+    let field = |index| {
+        caller
+            .get_field_by_index(index)
+            .cloned()
+            .unwrap_or_revert_with(ApiError::MissingArgument)
+    };
+    let contract: Option<ContractHash> = field(CALLER_FIELD_CONTRACT).into_t().unwrap_or_revert();
+    match contract {
+        Some(contract_hash) => Key::Hash(contract_hash.value()),
+        None => {
+            let account: Option<AccountHash> =
+                field(CALLER_FIELD_ACCOUNT).into_t().unwrap_or_revert();
+            Key::Account(account.unwrap_or_revert_with(ApiError::InvalidCallerInfoRequest))
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn transfer() {
+    let recipient: Key = runtime::get_named_arg(ARG_RECIPIENT);
+    let amount: U256 = runtime::get_named_arg(ARG_AMOUNT);
+    move_balance(&caller_key(), &recipient, amount);
+}
+
+#[no_mangle]
+pub extern "C" fn transfer_from() {
+    let owner: Key = runtime::get_named_arg(ARG_OWNER);
+    let recipient: Key = runtime::get_named_arg(ARG_RECIPIENT);
+    let amount: U256 = runtime::get_named_arg(ARG_AMOUNT);
+    move_balance(&owner, &recipient, amount);
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mut entry_points = EntryPoints::new();
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_MINT,
+        vec![
+            Parameter::new(ARG_OWNER, CLType::Key),
+            Parameter::new(ARG_AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_BALANCE_OF,
+        vec![
+            Parameter::new(ARG_ADDRESS, CLType::Key),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_TRANSFER,
+        vec![
+            Parameter::new(ARG_RECIPIENT, CLType::Key),
+            Parameter::new(ARG_AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_TRANSFER_FROM,
+        vec![
+            Parameter::new(ARG_OWNER, CLType::Key),
+            Parameter::new(ARG_RECIPIENT, CLType::Key),
+            Parameter::new(ARG_AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    let (contract_hash, _) = storage::new_contract(
+        entry_points,
+        Some(NamedKeys::new()),
+        Some(CONTRACT_PACKAGE_KEY.to_string()),
+        None,
+        None,
+    );
+    
+    runtime::put_key(CONTRACT_KEY, contract_hash.into());
+}
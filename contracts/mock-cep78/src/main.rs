@@ -0,0 +1,171 @@
+//! Test-only CEP-78 collection. Implements just enough of the standard for
+//! option-registry NFT tests: `mint`, `transfer` and `burn` with ordinal
+//! token ids. Each token's owner lives under `token_owner_{id}`, its metadata
+//! under `token_meta_data_{id}` and its burnt flag under `burnt_{id}` so tests
+//! can read them directly. Minting and transfers are not access controlled.
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+
+use casper_types::{
+    ApiError, CLType, CLTyped, CLValue, EntryPointAccess, EntryPointType, EntryPoints, Key,
+    Parameter, URef,
+    bytesrepr::FromBytes,
+    contracts::{EntryPoint, NamedKeys},
+};
+
+const CONTRACT_KEY: &str = "mock_cep78";
+const CONTRACT_PACKAGE_KEY: &str = "mock_cep78_package";
+const TOKEN_COUNT_KEY: &str = "token_count";
+const RECEIPT_NAME: &str = "mock_cep78_receipt";
+
+const ENTRY_POINT_MINT: &str = "mint";
+const ENTRY_POINT_TRANSFER: &str = "transfer";
+const ENTRY_POINT_BURN: &str = "burn";
+
+const ARG_TOKEN_OWNER: &str = "token_owner";
+const ARG_TOKEN_META_DATA: &str = "token_meta_data";
+const ARG_TOKEN_ID: &str = "token_id";
+const ARG_SOURCE_KEY: &str = "source_key";
+const ARG_TARGET_KEY: &str = "target_key";
+
+/// `source_key` does not own the token being transferred.
+const ERROR_INVALID_TOKEN_OWNER: u16 = 1;
+/// The token was already burnt.
+const ERROR_PREVIOUSLY_BURNT_TOKEN: u16 = 2;
+
+fn token_key(prefix: &str, token_id: u64) -> String {
+    let mut key = String::from(prefix);
+    key.push_str(&token_id.to_string());
+    key
+}
+
+fn named_uref(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert_with(ApiError::MissingKey)
+        .into_uref()
+        .unwrap_or_revert_with(ApiError::UnexpectedKeyVariant)
+}
+
+fn read<T: CLTyped + FromBytes>(name: &str) -> T {
+    storage::read(named_uref(name))
+        .unwrap_or_revert_with(ApiError::Read)
+        .unwrap_or_revert_with(ApiError::ValueNotFound)
+}
+
+fn require_unburnt(token_id: u64) {
+    if read::<bool>(&token_key("burnt_", token_id)) {
+        runtime::revert(ApiError::User(ERROR_PREVIOUSLY_BURNT_TOKEN));
+    }
+}
+
+/// Mints the next ordinal token to `token_owner` and returns
+/// `(receipt_name, owner_key, token_id)` like CEP-78's `mint`.
+#[no_mangle]
+pub extern "C" fn mint() {
+    let owner: Key = runtime::get_named_arg(ARG_TOKEN_OWNER);
+    let metadata: String = runtime::get_named_arg(ARG_TOKEN_META_DATA);
+    
+    let count_uref = named_uref(TOKEN_COUNT_KEY);
+    let token_id: u64 = read(TOKEN_COUNT_KEY);
+    storage::write(count_uref, token_id + 1);
+    runtime::put_key(&token_key("token_owner_", token_id), storage::new_uref(owner).into());
+    runtime::put_key(&token_key("token_meta_data_", token_id), storage::new_uref(metadata).into());
+    runtime::put_key(&token_key("burnt_", token_id), storage::new_uref(false).into());
+    
+    let receipt = (RECEIPT_NAME.to_string(), owner, token_id.to_string());
+    runtime::ret(CLValue::from_t(receipt).unwrap_or_revert());
+}
+
+/// Moves token `token_id` from `source_key` to `target_key` and returns
+/// `(receipt_name, target_key)`.
+#[no_mangle]
+pub extern "C" fn transfer() {
+    let token_id: u64 = runtime::get_named_arg(ARG_TOKEN_ID);
+    let source: Key = runtime::get_named_arg(ARG_SOURCE_KEY);
+    let target: Key = runtime::get_named_arg(ARG_TARGET_KEY);
+    require_unburnt(token_id);
+    
+    let owner_key = token_key("token_owner_", token_id);
+    if read::<Key>(&owner_key) != source {
+        runtime::revert(ApiError::User(ERROR_INVALID_TOKEN_OWNER));
+    }
+    storage::write(named_uref(&owner_key), target);
+    runtime::ret(CLValue::from_t((RECEIPT_NAME.to_string(), target)).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn burn() {
+    let token_id: u64 = runtime::get_named_arg(ARG_TOKEN_ID);
+    require_unburnt(token_id);
+    storage::write(named_uref(&token_key("burnt_", token_id)), true);
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mut entry_points = EntryPoints::new();
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_MINT,
+        vec![
+            Parameter::new(ARG_TOKEN_OWNER, CLType::Key),
+            Parameter::new(ARG_TOKEN_META_DATA, CLType::String),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::String),
+            Box::new(CLType::Key),
+            Box::new(CLType::String),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_TRANSFER,
+        vec![
+            Parameter::new(ARG_TOKEN_ID, CLType::U64),
+            Parameter::new(ARG_SOURCE_KEY, CLType::Key),
+            Parameter::new(ARG_TARGET_KEY, CLType::Key),
+        ],
+        CLType::Tuple2([
+            Box::new(CLType::String),
+            Box::new(CLType::Key),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_BURN,
+        vec![
+            Parameter::new(ARG_TOKEN_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(TOKEN_COUNT_KEY.to_string(), storage::new_uref(0u64).into());
+    
+    let (contract_hash, _) = storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(CONTRACT_PACKAGE_KEY.to_string()),
+        None,
+        None,
+    );
+    
+    runtime::put_key(CONTRACT_KEY, contract_hash.into());
+}
@@ -0,0 +1,150 @@
+//! Test-only DEX quoting CEP-18 tokens in motes at fixed prices. `set_price`
+//! stores how many motes one token unit is worth under `price_{key}`, and
+//! `get_amount_out` and `get_amount_in` quote sales at that price, so
+//! option-registry tests can value and liquidate token collateral. Prices are
+//! not access controlled, and a token with no price is worth nothing.
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+
+use casper_types::{
+    ApiError, CLType, CLValue, EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter,
+    U256, U512,
+    contracts::{EntryPoint, NamedKeys},
+};
+
+const CONTRACT_KEY: &str = "mock_dex";
+const CONTRACT_PACKAGE_KEY: &str = "mock_dex_package";
+
+const ENTRY_POINT_SET_PRICE: &str = "set_price";
+const ENTRY_POINT_GET_AMOUNT_OUT: &str = "get_amount_out";
+const ENTRY_POINT_GET_AMOUNT_IN: &str = "get_amount_in";
+
+const ARG_TOKEN: &str = "token";
+const ARG_PRICE: &str = "price";
+const ARG_AMOUNT_IN: &str = "amount_in";
+const ARG_AMOUNT_OUT: &str = "amount_out";
+
+fn price_key(token: &Key) -> String {
+    let mut key = String::from("price_");
+    key.push_str(&token.to_formatted_string());
+    key
+}
+
+fn read_price(token: &Key) -> U512 {
+    match runtime::get_key(&price_key(token)) {
+        Some(key) => {
+            let uref = key.into_uref().unwrap_or_revert_with(ApiError::UnexpectedKeyVariant);
+            storage::read(uref)
+                .unwrap_or_revert_with(ApiError::Read)
+                .unwrap_or_revert_with(ApiError::ValueNotFound)
+        }
+        None => U512::zero(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn set_price() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let price: U512 = runtime::get_named_arg(ARG_PRICE);
+    match runtime::get_key(&price_key(&token)) {
+        Some(key) => {
+            let uref = key.into_uref().unwrap_or_revert_with(ApiError::UnexpectedKeyVariant);
+            storage::write(uref, price);
+        }
+        None => runtime::put_key(&price_key(&token), storage::new_uref(price).into()),
+    }
+}
+
+/// Returns the motes selling `amount_in` of `token` would raise.
+#[no_mangle]
+pub extern "C" fn get_amount_out() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let amount_in: U256 = runtime::get_named_arg(ARG_AMOUNT_IN);
+    
+    let mut amount_in_bytes = [0u8; 32];
+    amount_in.to_little_endian(&mut amount_in_bytes);
+    let amount_out = U512::from_little_endian(&amount_in_bytes) * read_price(&token);
+    runtime::ret(CLValue::from_t(amount_out).unwrap_or_revert());
+}
+
+/// Returns the tokens that must be sold to raise `amount_out` motes, rounded
+/// up, or `U256::MAX` if `token` has no price.
+#[no_mangle]
+pub extern "C" fn get_amount_in() {
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let amount_out: U512 = runtime::get_named_arg(ARG_AMOUNT_OUT);
+    
+    let price = read_price(&token);
+    let amount_in = if price.is_zero() {
+        U256::MAX
+    } else {
+        let units = (amount_out + price - 1) / price;
+        if units.bits() > 256 {
+            U256::MAX
+        } else {
+            let mut units_bytes = [0u8; 64];
+            units.to_little_endian(&mut units_bytes);
+            U256::from_little_endian(&units_bytes[..32])
+        }
+    };
+    runtime::ret(CLValue::from_t(amount_in).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mut entry_points = EntryPoints::new();
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_PRICE,
+        vec![
+            Parameter::new(ARG_TOKEN, CLType::Key),
+            Parameter::new(ARG_PRICE, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_AMOUNT_OUT,
+        vec![
+            Parameter::new(ARG_TOKEN, CLType::Key),
+            Parameter::new(ARG_AMOUNT_IN, CLType::U256),
+        ],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_AMOUNT_IN,
+        vec![
+            Parameter::new(ARG_TOKEN, CLType::Key),
+            Parameter::new(ARG_AMOUNT_OUT, CLType::U512),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    let (contract_hash, _) = storage::new_contract(
+        entry_points,
+        Some(NamedKeys::new()),
+        Some(CONTRACT_PACKAGE_KEY.to_string()),
+        None,
+        None,
+    );
+    
+    runtime::put_key(CONTRACT_KEY, contract_hash.into());
+}
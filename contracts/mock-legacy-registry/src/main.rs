@@ -0,0 +1,75 @@
+//! Test-only stand-in for an option registry installed before strike, expiry,
+//! exercised and owner moved into dictionaries. It installs under the
+//! registry's package and access key names with those fields in flat
+//! `option_{id}_*` named keys, so option-registry tests can upgrade it and
+//! exercise `migrate_options`. It has no entry points of its own.
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use casper_contract::contract_api::{runtime, storage};
+
+use casper_types::{
+    EntryPoints, U256,
+    account::AccountHash,
+    contracts::NamedKeys,
+};
+
+const CONTRACT_KEY: &str = "option_registry";
+const CONTRACT_PACKAGE_KEY: &str = "option_registry_package";
+const CONTRACT_ACCESS_KEY: &str = "option_registry_access";
+const OPTION_COUNT_KEY: &str = "option_count";
+const ADMIN_KEY: &str = "option_registry_admin";
+
+const ARG_IDS: &str = "ids";
+
+/// Expiry of option 0; option `id` expires `id` seconds later.
+const BASE_EXPIRY: u64 = 1_735_689_600;
+
+fn option_key(id: u64) -> String {
+    let mut key = String::from("option_");
+    key.push_str(&id.to_string());
+    key
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    // Option `id` gets strike `id * 1000`, and even ids are exercised
+    let ids: Vec<u64> = runtime::get_named_arg(ARG_IDS);
+    let owner: AccountHash = runtime::get_caller();
+    
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(ADMIN_KEY.to_string(), storage::new_uref(owner).into());
+    named_keys.insert(OPTION_COUNT_KEY.to_string(), storage::new_uref(ids.len() as u64).into());
+    
+    for (index, &id) in ids.iter().enumerate() {
+        let key_name = option_key(id);
+        named_keys.insert(key_name.clone(), storage::new_uref(id).into());
+        named_keys.insert(
+            key_name.clone() + "_strike",
+            storage::new_uref(U256::from(id) * 1000).into(),
+        );
+        named_keys.insert(key_name.clone() + "_expiry", storage::new_uref(BASE_EXPIRY + id).into());
+        named_keys.insert(key_name.clone() + "_exercised", storage::new_uref(id % 2 == 0).into());
+        named_keys.insert(key_name + "_owner", storage::new_uref(owner).into());
+        
+        let mut index_key = String::from("all_options_");
+        index_key.push_str(&index.to_string());
+        named_keys.insert(index_key, storage::new_uref(id).into());
+    }
+    
+    let (contract_hash, _) = storage::new_contract(
+        EntryPoints::new(),
+        Some(named_keys),
+        Some(CONTRACT_PACKAGE_KEY.to_string()),
+        Some(CONTRACT_ACCESS_KEY.to_string()),
+        None,
+    );
+    
+    runtime::put_key(CONTRACT_KEY, contract_hash.into());
+}
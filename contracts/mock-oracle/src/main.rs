@@ -0,0 +1,137 @@
+//! Test-only price oracle. `get_price` returns whatever was last stored by
+//! `set_price` (or the `price` install argument), so option-registry tests can
+//! drive settlement with a known price. After `set_reentry_target`, every
+//! `get_price` first calls back into `exercise_option` on the target, so tests
+//! can check the registry's reentrancy guard.
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::ToString;
+use alloc::vec;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+
+use casper_types::{
+    ApiError, CLType, CLValue, EntryPointAccess, EntryPointType, EntryPoints, Parameter, URef,
+    U256,
+    contracts::{ContractHash, EntryPoint, NamedKeys},
+    runtime_args,
+};
+
+const CONTRACT_KEY: &str = "mock_oracle";
+const CONTRACT_PACKAGE_KEY: &str = "mock_oracle_package";
+const PRICE_KEY: &str = "price";
+const REENTRY_TARGET_KEY: &str = "reentry_target";
+
+const ENTRY_POINT_GET_PRICE: &str = "get_price";
+const ENTRY_POINT_SET_PRICE: &str = "set_price";
+const ENTRY_POINT_SET_REENTRY_TARGET: &str = "set_reentry_target";
+const REGISTRY_ENTRY_POINT_EXERCISE_OPTION: &str = "exercise_option";
+
+const ARG_PRICE: &str = "price";
+const ARG_CONTRACT_HASH: &str = "contract_hash";
+const ARG_ID: &str = "id";
+
+fn named_uref(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert_with(ApiError::MissingKey)
+        .into_uref()
+        .unwrap_or_revert_with(ApiError::UnexpectedKeyVariant)
+}
+
+fn price_uref() -> URef {
+    named_uref(PRICE_KEY)
+}
+
+#[no_mangle]
+pub extern "C" fn get_price() {
+    let target: Option<(ContractHash, u64)> = storage::read(named_uref(REENTRY_TARGET_KEY))
+        .unwrap_or_revert_with(ApiError::Read)
+        .unwrap_or_revert_with(ApiError::ValueNotFound);
+    if let Some((registry_hash, id)) = target {
+        runtime::call_contract::<()>(
+            registry_hash,
+            REGISTRY_ENTRY_POINT_EXERCISE_OPTION,
+            runtime_args! {
+                ARG_ID => id,
+            },
+        );
+    }
+    
+    let price: U256 = storage::read(price_uref())
+        .unwrap_or_revert_with(ApiError::Read)
+        .unwrap_or_revert_with(ApiError::ValueNotFound);
+    runtime::ret(CLValue::from_t(price).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn set_price() {
+    let price: U256 = runtime::get_named_arg(ARG_PRICE);
+    storage::write(price_uref(), price);
+}
+
+#[no_mangle]
+pub extern "C" fn set_reentry_target() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    storage::write(named_uref(REENTRY_TARGET_KEY), Some((registry_hash, id)));
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let price: U256 = runtime::try_get_named_arg(ARG_PRICE).unwrap_or_default();
+    
+    let mut entry_points = EntryPoints::new();
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_PRICE,
+        vec![],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_PRICE,
+        vec![
+            Parameter::new(ARG_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_REENTRY_TARGET,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(PRICE_KEY.to_string(), storage::new_uref(price).into());
+    named_keys.insert(
+        REENTRY_TARGET_KEY.to_string(),
+        storage::new_uref(Option::<(ContractHash, u64)>::None).into(),
+    );
+    
+    let (contract_hash, _) = storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(CONTRACT_PACKAGE_KEY.to_string()),
+        None,
+        None,
+    );
+    
+    runtime::put_key(CONTRACT_KEY, contract_hash.into());
+}
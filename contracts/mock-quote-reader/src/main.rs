@@ -0,0 +1,719 @@
+//! Test-only reader for the registry's `estimate_call_price`,
+//! `get_amm_option_price`, put-call ratio, expiry status, moneyness,
+//! intrinsic and time value, unrealized PnL, spread bound, user portfolio,
+//! liquidation, order book, fee, TWAP, max pain, portfolio greeks, accrued
+//! funding and full option queries. Return values of contract calls are not
+//! visible to engine tests, so each `read_*` entry point calls the registry
+//! and stores the results in named keys (`call_price`, `amm_option_price`,
+//! `put_call_ratio`, `time_to_expiry` and `is_expired`, `moneyness`,
+//! `option_value`, `unrealized_pnl`, `spread_bounds`, `user_portfolio`,
+//! `is_liquidatable`, `best_bid_ask`, `fees`, `twap`, `max_pain`,
+//! `portfolio_greeks`, `accrued_funding`, `option_full`), where
+//! option-registry tests can read them.
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+
+use casper_types::{
+    ApiError, CLType, EntryPointAccess, EntryPointType, EntryPoints, Parameter, URef, U256, U512,
+    account::AccountHash,
+    bytesrepr::Bytes,
+    contracts::{ContractHash, EntryPoint, NamedKeys},
+    runtime_args,
+};
+
+const CONTRACT_KEY: &str = "mock_quote_reader";
+const CONTRACT_PACKAGE_KEY: &str = "mock_quote_reader_package";
+const CALL_PRICE_KEY: &str = "call_price";
+const AMM_OPTION_PRICE_KEY: &str = "amm_option_price";
+const PUT_CALL_RATIO_KEY: &str = "put_call_ratio";
+const TIME_TO_EXPIRY_KEY: &str = "time_to_expiry";
+const IS_EXPIRED_KEY: &str = "is_expired";
+const MONEYNESS_KEY: &str = "moneyness";
+const OPTION_VALUE_KEY: &str = "option_value";
+const UNREALIZED_PNL_KEY: &str = "unrealized_pnl";
+const SPREAD_BOUNDS_KEY: &str = "spread_bounds";
+const USER_PORTFOLIO_KEY: &str = "user_portfolio";
+const IS_LIQUIDATABLE_KEY: &str = "is_liquidatable";
+const BEST_BID_ASK_KEY: &str = "best_bid_ask";
+const FEES_KEY: &str = "fees";
+const TWAP_KEY: &str = "twap";
+const MAX_PAIN_KEY: &str = "max_pain";
+const PORTFOLIO_GREEKS_KEY: &str = "portfolio_greeks";
+const ACCRUED_FUNDING_KEY: &str = "accrued_funding";
+const OPTION_FULL_KEY: &str = "option_full";
+
+const ENTRY_POINT_READ_CALL_PRICE: &str = "read_call_price";
+const ENTRY_POINT_READ_AMM_OPTION_PRICE: &str = "read_amm_option_price";
+const ENTRY_POINT_READ_PUT_CALL_RATIO: &str = "read_put_call_ratio";
+const ENTRY_POINT_READ_EXPIRY_STATUS: &str = "read_expiry_status";
+const ENTRY_POINT_READ_MONEYNESS: &str = "read_moneyness";
+const ENTRY_POINT_READ_OPTION_VALUE: &str = "read_option_value";
+const ENTRY_POINT_READ_UNREALIZED_PNL: &str = "read_unrealized_pnl";
+const ENTRY_POINT_READ_SPREAD_BOUNDS: &str = "read_spread_bounds";
+const ENTRY_POINT_READ_USER_PORTFOLIO: &str = "read_user_portfolio";
+const ENTRY_POINT_READ_IS_LIQUIDATABLE: &str = "read_is_liquidatable";
+const ENTRY_POINT_READ_BEST_BID_ASK: &str = "read_best_bid_ask";
+const ENTRY_POINT_READ_FEES: &str = "read_fees";
+const ENTRY_POINT_READ_TWAP: &str = "read_twap";
+const ENTRY_POINT_READ_MAX_PAIN: &str = "read_max_pain";
+const ENTRY_POINT_READ_PORTFOLIO_GREEKS: &str = "read_portfolio_greeks";
+const ENTRY_POINT_READ_ACCRUED_FUNDING: &str = "read_accrued_funding";
+const ENTRY_POINT_READ_OPTION_FULL: &str = "read_option_full";
+const REGISTRY_ENTRY_POINT_ESTIMATE_CALL_PRICE: &str = "estimate_call_price";
+const REGISTRY_ENTRY_POINT_GET_AMM_OPTION_PRICE: &str = "get_amm_option_price";
+const REGISTRY_ENTRY_POINT_GET_PUT_CALL_RATIO: &str = "get_put_call_ratio";
+const REGISTRY_ENTRY_POINT_GET_PUT_CALL_RATIO_FOR_EXPIRY: &str = "get_put_call_ratio_for_expiry";
+const REGISTRY_ENTRY_POINT_GET_TIME_TO_EXPIRY: &str = "get_time_to_expiry";
+const REGISTRY_ENTRY_POINT_IS_EXPIRED: &str = "is_expired";
+const REGISTRY_ENTRY_POINT_IS_IN_THE_MONEY: &str = "is_in_the_money";
+const REGISTRY_ENTRY_POINT_IS_OUT_OF_THE_MONEY: &str = "is_out_of_the_money";
+const REGISTRY_ENTRY_POINT_IS_AT_THE_MONEY: &str = "is_at_the_money";
+const REGISTRY_ENTRY_POINT_GET_INTRINSIC_VALUE: &str = "get_intrinsic_value";
+const REGISTRY_ENTRY_POINT_GET_TIME_VALUE: &str = "get_time_value";
+const REGISTRY_ENTRY_POINT_GET_UNREALIZED_PNL: &str = "get_unrealized_pnl";
+const REGISTRY_ENTRY_POINT_GET_SPREAD_MAX_PROFIT: &str = "get_spread_max_profit";
+const REGISTRY_ENTRY_POINT_GET_SPREAD_MAX_LOSS: &str = "get_spread_max_loss";
+const REGISTRY_ENTRY_POINT_GET_USER_PORTFOLIO: &str = "get_user_portfolio";
+const REGISTRY_ENTRY_POINT_GET_USER_PORTFOLIO_DELTA: &str = "get_user_portfolio_delta";
+const REGISTRY_ENTRY_POINT_IS_LIQUIDATABLE: &str = "is_liquidatable";
+const REGISTRY_ENTRY_POINT_GET_BEST_BID: &str = "get_best_bid";
+const REGISTRY_ENTRY_POINT_GET_BEST_ASK: &str = "get_best_ask";
+const REGISTRY_ENTRY_POINT_GET_ACCUMULATED_FEES: &str = "get_accumulated_fees";
+const REGISTRY_ENTRY_POINT_GET_TOTAL_FEES_COLLECTED: &str = "get_total_fees_collected";
+const REGISTRY_ENTRY_POINT_COMPUTE_TWAP: &str = "compute_twap";
+const REGISTRY_ENTRY_POINT_CALCULATE_MAX_PAIN: &str = "calculate_max_pain";
+const REGISTRY_ENTRY_POINT_GET_PORTFOLIO_GREEKS: &str = "get_portfolio_greeks";
+const REGISTRY_ENTRY_POINT_GET_ACCRUED_FUNDING: &str = "get_accrued_funding";
+const REGISTRY_ENTRY_POINT_GET_OPTION_FULL: &str = "get_option_full";
+
+const ARG_CONTRACT_HASH: &str = "contract_hash";
+const ARG_ID: &str = "id";
+const ARG_UNDERLYING_PRICE: &str = "underlying_price";
+const ARG_STRIKE_PRICE: &str = "strike_price";
+const ARG_EXPIRY: &str = "expiry";
+const ARG_OPTION_AMOUNT: &str = "option_amount";
+const ARG_CURRENT_PRICE: &str = "current_price";
+const ARG_OPTION_PRICE: &str = "option_price";
+const ARG_SPREAD_ID: &str = "spread_id";
+const ARG_OWNER: &str = "owner";
+const ARG_STRIKE: &str = "strike";
+const ARG_OPTION_TYPE: &str = "option_type";
+const ARG_WINDOW_SECONDS: &str = "window_seconds";
+const ARG_PRICE_RANGE_LOW: &str = "price_range_low";
+const ARG_PRICE_RANGE_HIGH: &str = "price_range_high";
+
+fn named_uref(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert_with(ApiError::MissingKey)
+        .into_uref()
+        .unwrap_or_revert_with(ApiError::UnexpectedKeyVariant)
+}
+
+#[no_mangle]
+pub extern "C" fn read_call_price() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let underlying_price: U256 = runtime::get_named_arg(ARG_UNDERLYING_PRICE);
+    
+    let call_price: U256 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_ESTIMATE_CALL_PRICE,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_UNDERLYING_PRICE => underlying_price,
+        },
+    );
+    storage::write(named_uref(CALL_PRICE_KEY), call_price);
+}
+
+#[no_mangle]
+pub extern "C" fn read_amm_option_price() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let option_amount: U256 = runtime::get_named_arg(ARG_OPTION_AMOUNT);
+    
+    let price: U512 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_AMM_OPTION_PRICE,
+        runtime_args! {
+            ARG_STRIKE_PRICE => strike_price,
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_AMOUNT => option_amount,
+        },
+    );
+    storage::write(named_uref(AMM_OPTION_PRICE_KEY), price);
+}
+
+/// Reads `get_put_call_ratio_for_expiry` when an `expiry` is given, and
+/// `get_put_call_ratio` otherwise.
+#[no_mangle]
+pub extern "C" fn read_put_call_ratio() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let expiry: Option<u64> = runtime::try_get_named_arg(ARG_EXPIRY);
+    
+    let ratio: (u64, u64) = match expiry {
+        Some(expiry) => runtime::call_contract(
+            registry_hash,
+            REGISTRY_ENTRY_POINT_GET_PUT_CALL_RATIO_FOR_EXPIRY,
+            runtime_args! { ARG_EXPIRY => expiry },
+        ),
+        None => runtime::call_contract(
+            registry_hash,
+            REGISTRY_ENTRY_POINT_GET_PUT_CALL_RATIO,
+            runtime_args! {},
+        ),
+    };
+    storage::write(named_uref(PUT_CALL_RATIO_KEY), ratio);
+}
+
+/// Reads both `get_time_to_expiry` and `is_expired` for option `id`.
+#[no_mangle]
+pub extern "C" fn read_expiry_status() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    
+    let time_to_expiry: u64 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_TIME_TO_EXPIRY,
+        runtime_args! { ARG_ID => id },
+    );
+    let is_expired: bool = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_IS_EXPIRED,
+        runtime_args! { ARG_ID => id },
+    );
+    storage::write(named_uref(TIME_TO_EXPIRY_KEY), time_to_expiry);
+    storage::write(named_uref(IS_EXPIRED_KEY), is_expired);
+}
+
+/// Reads `is_in_the_money`, `is_out_of_the_money` and `is_at_the_money` for
+/// option `id` at `current_price`, stored in that order.
+#[no_mangle]
+pub extern "C" fn read_moneyness() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let current_price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    
+    let query = |entry_point: &str| -> bool {
+        runtime::call_contract(
+            registry_hash,
+            entry_point,
+            runtime_args! {
+                ARG_ID => id,
+                ARG_CURRENT_PRICE => current_price,
+            },
+        )
+    };
+    let moneyness = (
+        query(REGISTRY_ENTRY_POINT_IS_IN_THE_MONEY),
+        query(REGISTRY_ENTRY_POINT_IS_OUT_OF_THE_MONEY),
+        query(REGISTRY_ENTRY_POINT_IS_AT_THE_MONEY),
+    );
+    storage::write(named_uref(MONEYNESS_KEY), moneyness);
+}
+
+/// Reads `get_intrinsic_value` and `get_time_value` for option `id` at
+/// `current_price` and `option_price`, stored as `(intrinsic, time)`.
+#[no_mangle]
+pub extern "C" fn read_option_value() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let current_price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    let option_price: U256 = runtime::get_named_arg(ARG_OPTION_PRICE);
+    
+    let intrinsic_value: U256 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_INTRINSIC_VALUE,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_CURRENT_PRICE => current_price,
+        },
+    );
+    let time_value: U256 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_TIME_VALUE,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_CURRENT_PRICE => current_price,
+            ARG_OPTION_PRICE => option_price,
+        },
+    );
+    storage::write(named_uref(OPTION_VALUE_KEY), (intrinsic_value, time_value));
+}
+
+/// Reads `get_unrealized_pnl`, stored as `(is_loss, magnitude)`.
+#[no_mangle]
+pub extern "C" fn read_unrealized_pnl() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let current_price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    
+    let pnl: (bool, U512) = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_UNREALIZED_PNL,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_CURRENT_PRICE => current_price,
+        },
+    );
+    storage::write(named_uref(UNREALIZED_PNL_KEY), pnl);
+}
+
+/// Reads `get_spread_max_profit` and `get_spread_max_loss` for spread
+/// `spread_id`, stored as `(max_profit, max_loss)`.
+#[no_mangle]
+pub extern "C" fn read_spread_bounds() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let spread_id: u64 = runtime::get_named_arg(ARG_SPREAD_ID);
+    
+    let max_profit: U512 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_SPREAD_MAX_PROFIT,
+        runtime_args! { ARG_SPREAD_ID => spread_id },
+    );
+    let max_loss: U512 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_SPREAD_MAX_LOSS,
+        runtime_args! { ARG_SPREAD_ID => spread_id },
+    );
+    storage::write(named_uref(SPREAD_BOUNDS_KEY), (max_profit, max_loss));
+}
+
+/// Reads `get_user_portfolio` and `get_user_portfolio_delta` for `owner`,
+/// stored as `(ids, delta)`.
+#[no_mangle]
+pub extern "C" fn read_user_portfolio() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let owner: AccountHash = runtime::get_named_arg(ARG_OWNER);
+    let current_price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    
+    let ids: Vec<u64> = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_USER_PORTFOLIO,
+        runtime_args! { ARG_OWNER => owner },
+    );
+    let delta: i64 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_USER_PORTFOLIO_DELTA,
+        runtime_args! {
+            ARG_OWNER => owner,
+            ARG_CURRENT_PRICE => current_price,
+        },
+    );
+    storage::write(named_uref(USER_PORTFOLIO_KEY), (ids, delta));
+}
+
+/// Reads `is_liquidatable` for option `id` at `current_price`.
+#[no_mangle]
+pub extern "C" fn read_is_liquidatable() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let current_price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    
+    let liquidatable: bool = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_IS_LIQUIDATABLE,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_CURRENT_PRICE => current_price,
+        },
+    );
+    storage::write(named_uref(IS_LIQUIDATABLE_KEY), liquidatable);
+}
+
+/// Reads `get_best_bid` and `get_best_ask` for options struck at `strike`
+/// expiring at `expiry`, stored as `(best_bid, best_ask)`.
+#[no_mangle]
+pub extern "C" fn read_best_bid_ask() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let strike: U256 = runtime::get_named_arg(ARG_STRIKE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    
+    let args = runtime_args! {
+        ARG_STRIKE => strike,
+        ARG_EXPIRY => expiry,
+        ARG_OPTION_TYPE => option_type,
+    };
+    let best_bid: Option<U512> =
+        runtime::call_contract(registry_hash, REGISTRY_ENTRY_POINT_GET_BEST_BID, args.clone());
+    let best_ask: Option<U512> =
+        runtime::call_contract(registry_hash, REGISTRY_ENTRY_POINT_GET_BEST_ASK, args);
+    storage::write(named_uref(BEST_BID_ASK_KEY), (best_bid, best_ask));
+}
+
+/// Reads `get_accumulated_fees` and `get_total_fees_collected`, stored as
+/// `(accumulated, total_collected)`.
+#[no_mangle]
+pub extern "C" fn read_fees() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    
+    let accumulated: U512 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_ACCUMULATED_FEES,
+        runtime_args! {},
+    );
+    let total_collected: U512 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_TOTAL_FEES_COLLECTED,
+        runtime_args! {},
+    );
+    storage::write(named_uref(FEES_KEY), (accumulated, total_collected));
+}
+
+/// Reads `compute_twap` over `window_seconds`, stored as `twap`.
+#[no_mangle]
+pub extern "C" fn read_twap() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let window_seconds: u64 = runtime::get_named_arg(ARG_WINDOW_SECONDS);
+    
+    let twap: U256 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_COMPUTE_TWAP,
+        runtime_args! {
+            ARG_WINDOW_SECONDS => window_seconds,
+        },
+    );
+    storage::write(named_uref(TWAP_KEY), twap);
+}
+
+#[no_mangle]
+pub extern "C" fn read_max_pain() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let low: U256 = runtime::get_named_arg(ARG_PRICE_RANGE_LOW);
+    let high: U256 = runtime::get_named_arg(ARG_PRICE_RANGE_HIGH);
+    
+    let max_pain: U256 = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_CALCULATE_MAX_PAIN,
+        runtime_args! {
+            ARG_EXPIRY => expiry,
+            ARG_PRICE_RANGE_LOW => low,
+            ARG_PRICE_RANGE_HIGH => high,
+        },
+    );
+    storage::write(named_uref(MAX_PAIN_KEY), max_pain);
+}
+
+#[no_mangle]
+pub extern "C" fn read_portfolio_greeks() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let owner: AccountHash = runtime::get_named_arg(ARG_OWNER);
+    let current_price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    
+    let greeks: (i64, i64, (i64, i64)) = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_PORTFOLIO_GREEKS,
+        runtime_args! {
+            ARG_OWNER => owner,
+            ARG_CURRENT_PRICE => current_price,
+        },
+    );
+    storage::write(named_uref(PORTFOLIO_GREEKS_KEY), greeks);
+}
+
+/// Reads `get_accrued_funding`, stored as `(holder_pays, amount)`.
+#[no_mangle]
+pub extern "C" fn read_accrued_funding() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    
+    let funding: (bool, U512) = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_ACCRUED_FUNDING,
+        runtime_args! {
+            ARG_ID => id,
+        },
+    );
+    storage::write(named_uref(ACCRUED_FUNDING_KEY), funding);
+}
+
+/// Reads `get_option_full`, stored as its map of serialized field values.
+#[no_mangle]
+pub extern "C" fn read_option_full() {
+    let registry_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    
+    let fields: BTreeMap<String, Bytes> = runtime::call_contract(
+        registry_hash,
+        REGISTRY_ENTRY_POINT_GET_OPTION_FULL,
+        runtime_args! {
+            ARG_ID => id,
+        },
+    );
+    storage::write(named_uref(OPTION_FULL_KEY), fields);
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mut entry_points = EntryPoints::new();
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_CALL_PRICE,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_UNDERLYING_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_AMM_OPTION_PRICE,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_PUT_CALL_RATIO,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_EXPIRY_STATUS,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_MONEYNESS,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_OPTION_VALUE,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+            Parameter::new(ARG_OPTION_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_UNREALIZED_PNL,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_SPREAD_BOUNDS,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_SPREAD_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_USER_PORTFOLIO,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_IS_LIQUIDATABLE,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_BEST_BID_ASK,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_STRIKE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_FEES,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_TWAP,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_WINDOW_SECONDS, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_MAX_PAIN,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_PRICE_RANGE_LOW, CLType::U256),
+            Parameter::new(ARG_PRICE_RANGE_HIGH, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_PORTFOLIO_GREEKS,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_ACCRUED_FUNDING,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_READ_OPTION_FULL,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(CALL_PRICE_KEY.to_string(), storage::new_uref(U256::zero()).into());
+    named_keys.insert(AMM_OPTION_PRICE_KEY.to_string(), storage::new_uref(U512::zero()).into());
+    named_keys.insert(PUT_CALL_RATIO_KEY.to_string(), storage::new_uref((0u64, 0u64)).into());
+    named_keys.insert(TIME_TO_EXPIRY_KEY.to_string(), storage::new_uref(0u64).into());
+    named_keys.insert(IS_EXPIRED_KEY.to_string(), storage::new_uref(false).into());
+    named_keys.insert(MONEYNESS_KEY.to_string(), storage::new_uref((false, false, false)).into());
+    named_keys.insert(
+        OPTION_VALUE_KEY.to_string(),
+        storage::new_uref((U256::zero(), U256::zero())).into(),
+    );
+    named_keys.insert(
+        UNREALIZED_PNL_KEY.to_string(),
+        storage::new_uref((false, U512::zero())).into(),
+    );
+    named_keys.insert(
+        SPREAD_BOUNDS_KEY.to_string(),
+        storage::new_uref((U512::zero(), U512::zero())).into(),
+    );
+    named_keys.insert(
+        USER_PORTFOLIO_KEY.to_string(),
+        storage::new_uref((Vec::<u64>::new(), 0i64)).into(),
+    );
+    named_keys.insert(IS_LIQUIDATABLE_KEY.to_string(), storage::new_uref(false).into());
+    named_keys.insert(
+        BEST_BID_ASK_KEY.to_string(),
+        storage::new_uref((None::<U512>, None::<U512>)).into(),
+    );
+    named_keys.insert(FEES_KEY.to_string(), storage::new_uref((U512::zero(), U512::zero())).into());
+    named_keys.insert(TWAP_KEY.to_string(), storage::new_uref(U256::zero()).into());
+    named_keys.insert(MAX_PAIN_KEY.to_string(), storage::new_uref(U256::zero()).into());
+    named_keys.insert(
+        PORTFOLIO_GREEKS_KEY.to_string(),
+        storage::new_uref((0i64, 0i64, (0i64, 0i64))).into(),
+    );
+    named_keys.insert(
+        ACCRUED_FUNDING_KEY.to_string(),
+        storage::new_uref((false, U512::zero())).into(),
+    );
+    named_keys.insert(
+        OPTION_FULL_KEY.to_string(),
+        storage::new_uref(BTreeMap::<String, Bytes>::new()).into(),
+    );
+    
+    let (contract_hash, _) = storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(CONTRACT_PACKAGE_KEY.to_string()),
+        None,
+        None,
+    );
+    
+    runtime::put_key(CONTRACT_KEY, contract_hash.into());
+}
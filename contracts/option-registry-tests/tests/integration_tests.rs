@@ -5,16 +5,27 @@
 //! - Property 2: Option Count Monotonicity
 //! - Property 3: Exercise Idempotence
 //! - Property 4: Storage Key Uniqueness
+//! - Property 5: Option Type Persistence
+//! - Property 6: Exercise Follows Current Owner
+//! - Property 7: Batch Exercise Atomicity
+//! - Property 8: Greeks Round Trip
 
 use casper_engine_test_support::{
     ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
-    DEFAULT_RUN_GENESIS_REQUEST, PRODUCTION_RUN_GENESIS_REQUEST,
+    DEFAULT_RUN_GENESIS_REQUEST, MINIMUM_ACCOUNT_CREATION_BALANCE,
+    PRODUCTION_RUN_GENESIS_REQUEST,
 };
+use casper_execution_engine::core::{engine_state, execution};
 use casper_execution_engine::storage::global_state::in_memory::InMemoryGlobalState;
 use casper_types::{
-    account::AccountHash, runtime_args, ContractHash, Key, RuntimeArgs, U256, U512,
+    account::AccountHash,
+    bytesrepr::{Bytes, FromBytes, ToBytes},
+    crypto, runtime_args,
+    system::mint,
+    ApiError, CLTyped, CLValue, ContractHash, Key, PublicKey, RuntimeArgs, SecretKey, U256, U512,
 };
 use proptest::prelude::*;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 // ============================================================================
@@ -23,26 +34,436 @@ use std::path::PathBuf;
 
 const CONTRACT_WASM: &str = "option-registry.wasm";
 const CONTRACT_KEY: &str = "option_registry";
+const MOCK_ORACLE_WASM: &str = "mock-oracle.wasm";
+const MOCK_ORACLE_KEY: &str = "mock_oracle";
+const ORACLE_KEY: &str = "option_registry_oracle";
+const MOCK_CEP18_WASM: &str = "mock-cep18.wasm";
+const MOCK_CEP18_KEY: &str = "mock_cep18";
+const MOCK_CEP78_WASM: &str = "mock-cep78.wasm";
+const MOCK_CEP78_KEY: &str = "mock_cep78";
+const MOCK_LEGACY_REGISTRY_WASM: &str = "mock-legacy-registry.wasm";
+const MOCK_QUOTE_READER_WASM: &str = "mock-quote-reader.wasm";
+const MOCK_QUOTE_READER_KEY: &str = "mock_quote_reader";
+const MOCK_DEX_WASM: &str = "mock-dex.wasm";
+const MOCK_DEX_KEY: &str = "mock_dex";
 const OPTION_COUNT_KEY: &str = "option_count";
+const CONTRACT_PURSE_KEY: &str = "contract_purse";
+const EVENT_COUNTER_KEY: &str = "event_counter";
+const OPTIONS_STRIKE_DICT: &str = "options_strike";
+const OPTIONS_EXPIRY_DICT: &str = "options_expiry";
+const OPTIONS_EXERCISED_DICT: &str = "options_exercised";
+const OPTIONS_OWNER_DICT: &str = "options_owner";
+const NONCES_DICT: &str = "nonces";
+const WRITER_WHITELIST_DICT: &str = "writer_whitelist";
+const LP_BALANCES_DICT: &str = "lp_balances";
+const LP_ALLOWANCES_DICT: &str = "lp_allowances";
+const POOL_TOTAL_VALUE_KEY: &str = "pool_total_value";
+const POOL_TOTAL_SHARES_KEY: &str = "pool_total_shares";
+const POOL_UTILIZED_COLLATERAL_KEY: &str = "pool_utilized_collateral";
 
 // Entry points
 const ENTRY_POINT_CREATE_OPTION: &str = "create_option";
+const ENTRY_POINT_CREATE_OPTION_BATCH: &str = "create_option_batch";
+const ENTRY_POINT_CREATE_OPTION_WITH_PERMIT: &str = "create_option_with_permit";
+const ENTRY_POINT_DEPOSIT_PERMIT_COLLATERAL: &str = "deposit_permit_collateral";
+const ENTRY_POINT_WITHDRAW_PERMIT_COLLATERAL: &str = "withdraw_permit_collateral";
 const ENTRY_POINT_EXERCISE_OPTION: &str = "exercise_option";
+const ENTRY_POINT_EXERCISE_OPTIONS_BATCH: &str = "exercise_options_batch";
+const ENTRY_POINT_EXERCISE_WITH_SIGNATURE: &str = "exercise_with_signature";
+const ENTRY_POINT_SET_EXERCISE_APPROVAL_POLICY: &str = "set_exercise_approval_policy";
+const ENTRY_POINT_APPROVE_EXERCISE: &str = "approve_exercise";
+const ENTRY_POINT_APPROVE_EXERCISE_FOR: &str = "approve_exercise_for";
+const ENTRY_POINT_REVOKE_EXERCISE_APPROVAL: &str = "revoke_exercise_approval";
+const ENTRY_POINT_CREATE_SYNDICATED_OPTION: &str = "create_syndicated_option";
+const ENTRY_POINT_JOIN_SYNDICATE: &str = "join_syndicate";
+const ENTRY_POINT_PLACE_OPTION_ORDER: &str = "place_option_order";
+const ENTRY_POINT_CANCEL_ORDER: &str = "cancel_order";
+const ENTRY_POINT_MATCH_ORDERS: &str = "match_orders";
+const ENTRY_POINT_ACTIVATE_OPTION: &str = "activate_option";
+const ENTRY_POINT_EXPIRE_OPTION: &str = "expire_option";
+const ENTRY_POINT_CANCEL_OPTION: &str = "cancel_option";
+const ENTRY_POINT_CANCEL_SERIES: &str = "cancel_series";
+const ENTRY_POINT_ROLLOVER_OPTION: &str = "rollover_option";
+const ENTRY_POINT_APPROVE_ROLLOVER: &str = "approve_rollover";
+const ENTRY_POINT_GET_EXERCISE_HISTORY: &str = "get_exercise_history";
+const ENTRY_POINT_GET_EXERCISE_COUNT_FOR_OPTION: &str = "get_exercise_count_for_option";
+const ENTRY_POINT_APPLY_DIVIDEND_ADJUSTMENT: &str = "apply_dividend_adjustment";
+const ENTRY_POINT_CREATE_STRADDLE: &str = "create_straddle";
+const ENTRY_POINT_CLONE_OPTION: &str = "clone_option";
+const ENTRY_POINT_GET_STRADDLE: &str = "get_straddle";
+const ENTRY_POINT_CANCEL_STRADDLE: &str = "cancel_straddle";
+const ENTRY_POINT_CREATE_STRIP: &str = "create_strip";
+const ENTRY_POINT_CREATE_STRAP: &str = "create_strap";
+const ENTRY_POINT_EXERCISE_BUNDLE: &str = "exercise_bundle";
+const ENTRY_POINT_CREATE_SPREAD: &str = "create_spread";
+const ENTRY_POINT_TRANSFER_OPTION: &str = "transfer_option";
+const ENTRY_POINT_CLAIM_PREMIUM: &str = "claim_premium";
+const ENTRY_POINT_SETTLE_OPTION: &str = "settle_option";
+const ENTRY_POINT_LIQUIDATE_OPTION: &str = "liquidate_option";
+const ENTRY_POINT_ADD_COLLATERAL: &str = "add_collateral";
+const ENTRY_POINT_WITHDRAW_COLLATERAL: &str = "withdraw_collateral";
+const ENTRY_POINT_PAUSE: &str = "pause";
+const ENTRY_POINT_UNPAUSE: &str = "unpause";
+const ENTRY_POINT_TRANSFER_ADMIN: &str = "transfer_admin";
+const ENTRY_POINT_ACCEPT_ADMIN: &str = "accept_admin";
+const ENTRY_POINT_COLLECT_FEES: &str = "collect_fees";
+const ENTRY_POINT_WITHDRAW_FEES_TO_TREASURY: &str = "withdraw_fees_to_treasury";
+const ENTRY_POINT_DEPRECATE: &str = "deprecate";
+const ENTRY_POINT_EMERGENCY_CANCEL_BATCH: &str = "emergency_cancel_batch";
+const ENTRY_POINT_WITHDRAW_MY_COLLATERAL: &str = "withdraw_my_collateral";
+const ENTRY_POINT_SET_METADATA_URI: &str = "set_metadata_uri";
+const ENTRY_POINT_ADD_PRICE_PUBLISHER: &str = "add_price_publisher";
+const ENTRY_POINT_UPDATE_ORACLE_PRICE: &str = "update_oracle_price";
+const ENTRY_POINT_RECORD_PRICE_SNAPSHOT: &str = "record_price_snapshot";
+const ENTRY_POINT_REGISTER_KEEPER: &str = "register_keeper";
+const ENTRY_POINT_DEREGISTER_KEEPER: &str = "deregister_keeper";
+const ENTRY_POINT_KEEPER_EXPIRE_OPTIONS: &str = "keeper_expire_options";
+const ENTRY_POINT_SCHEDULE_EXERCISE: &str = "schedule_exercise";
+const ENTRY_POINT_CANCEL_SCHEDULED_EXERCISE: &str = "cancel_scheduled_exercise";
+const ENTRY_POINT_PROCESS_SCHEDULED_EXERCISES: &str = "process_scheduled_exercises";
+const ENTRY_POINT_SET_AUTO_EXERCISE: &str = "set_auto_exercise";
+const ENTRY_POINT_PROCESS_AUTO_EXERCISES: &str = "process_auto_exercises";
+const ENTRY_POINT_ADD_WRITER: &str = "add_writer";
+const ENTRY_POINT_REMOVE_WRITER: &str = "remove_writer";
+const ENTRY_POINT_SET_GREEKS_ORACLE: &str = "set_greeks_oracle";
+const ENTRY_POINT_UPDATE_GREEKS: &str = "update_greeks";
+const ENTRY_POINT_GET_GREEKS: &str = "get_greeks";
+const ENTRY_POINT_SET_VOL_ORACLE: &str = "set_vol_oracle";
+const ENTRY_POINT_UPDATE_IV: &str = "update_iv";
+const ENTRY_POINT_GET_IV: &str = "get_iv";
+const ENTRY_POINT_UPDATE_VOLATILITY_SURFACE_POINT: &str = "update_volatility_surface_point";
+const ENTRY_POINT_UPDATE_VOLATILITY_SURFACE_BATCH: &str = "update_volatility_surface_batch";
+const ENTRY_POINT_GET_VOLATILITY_SURFACE_POINT: &str = "get_volatility_surface_point";
+const ENTRY_POINT_DEPOSIT_TO_POOL: &str = "deposit_to_pool";
+const ENTRY_POINT_CONTRIBUTE_TO_INSURANCE_FUND: &str = "contribute_to_insurance_fund";
+const ENTRY_POINT_WITHDRAW_FROM_POOL: &str = "withdraw_from_pool";
+const ENTRY_POINT_WRITE_OPTION_FROM_POOL: &str = "write_option_from_pool";
+const ENTRY_POINT_BUY_OPTION_FROM_POOL: &str = "buy_option_from_pool";
+const ENTRY_POINT_TRANSFER_LP_SHARES: &str = "transfer_lp_shares";
+const ENTRY_POINT_APPROVE_LP_SHARES: &str = "approve_lp_shares";
+const ENTRY_POINT_TRANSFER_LP_SHARES_FROM: &str = "transfer_lp_shares_from";
+const ENTRY_POINT_READ_CALL_PRICE: &str = "read_call_price";
+const ENTRY_POINT_READ_AMM_OPTION_PRICE: &str = "read_amm_option_price";
+const ENTRY_POINT_READ_PUT_CALL_RATIO: &str = "read_put_call_ratio";
+const ENTRY_POINT_READ_EXPIRY_STATUS: &str = "read_expiry_status";
+const ENTRY_POINT_READ_MONEYNESS: &str = "read_moneyness";
+const ENTRY_POINT_READ_OPTION_VALUE: &str = "read_option_value";
+const ENTRY_POINT_READ_UNREALIZED_PNL: &str = "read_unrealized_pnl";
+const ENTRY_POINT_READ_SPREAD_BOUNDS: &str = "read_spread_bounds";
+const ENTRY_POINT_READ_USER_PORTFOLIO: &str = "read_user_portfolio";
+const ENTRY_POINT_READ_IS_LIQUIDATABLE: &str = "read_is_liquidatable";
+const ENTRY_POINT_READ_BEST_BID_ASK: &str = "read_best_bid_ask";
+const ENTRY_POINT_READ_FEES: &str = "read_fees";
+const ENTRY_POINT_SET_MAX_NOTIONAL_PER_ACCOUNT: &str = "set_max_notional_per_account";
+const ENTRY_POINT_SET_EARLY_EXERCISE_PENALTY: &str = "set_early_exercise_penalty";
+const ENTRY_POINT_SET_PRICE_VALIDATION: &str = "set_price_validation";
+const ENTRY_POINT_READ_TWAP: &str = "read_twap";
+const ENTRY_POINT_READ_MAX_PAIN: &str = "read_max_pain";
+const ENTRY_POINT_READ_PORTFOLIO_GREEKS: &str = "read_portfolio_greeks";
+const ENTRY_POINT_READ_ACCRUED_FUNDING: &str = "read_accrued_funding";
+const ENTRY_POINT_READ_OPTION_FULL: &str = "read_option_full";
+const ENTRY_POINT_COLLECT_FUNDING: &str = "collect_funding";
+const ENTRY_POINT_PROPOSE_PARAMETER_CHANGE: &str = "propose_parameter_change";
+const ENTRY_POINT_EXECUTE_PARAMETER_CHANGE: &str = "execute_parameter_change";
+const ENTRY_POINT_CANCEL_PARAMETER_CHANGE: &str = "cancel_parameter_change";
+const ENTRY_POINT_CREATE_GOVERNANCE_PROPOSAL: &str = "create_governance_proposal";
+const ENTRY_POINT_VOTE_FOR_PROPOSAL: &str = "vote_for_proposal";
+const ENTRY_POINT_EXECUTE_PROPOSAL: &str = "execute_proposal";
+const ENTRY_POINT_CANCEL_PROPOSAL: &str = "cancel_proposal";
+const ENTRY_POINT_UPGRADE: &str = "upgrade";
+const ENTRY_POINT_MIGRATE_OPTIONS: &str = "migrate_options";
+const ENTRY_POINT_GET_OPTION: &str = "get_option";
+const ENTRY_POINT_GET_CONTRACT_VERSION: &str = "get_contract_version";
+const ENTRY_POINT_IS_MIGRATION_COMPLETE: &str = "is_migration_complete";
+const ENTRY_POINT_GET_SERIES_OPTIONS: &str = "get_series_options";
+const ENTRY_POINT_GET_ALL_OPTIONS: &str = "get_all_options";
+const ENTRY_POINT_GET_OPTIONS_EXPIRING_BETWEEN: &str = "get_options_expiring_between";
+const ENTRY_POINT_GET_ALL_EXPIRIES: &str = "get_all_expiries";
+const ENTRY_POINT_GET_STRIKES_FOR_EXPIRY: &str = "get_strikes_for_expiry";
+const ENTRY_POINT_GET_OPTION_CHAIN: &str = "get_option_chain";
+const ENTRY_POINT_GET_OPEN_INTEREST_BY_STRIKE: &str = "get_open_interest_by_strike";
+const ENTRY_POINT_GET_OPEN_INTEREST_BY_EXPIRY: &str = "get_open_interest_by_expiry";
+const ENTRY_POINT_GET_TOTAL_OPEN_INTEREST: &str = "get_total_open_interest";
+const ENTRY_POINT_GET_TOTAL_VOLUME: &str = "get_total_volume";
+const ENTRY_POINT_GET_VOLUME_BY_STRIKE: &str = "get_volume_by_strike";
+const ENTRY_POINT_GET_VOLUME_BY_EXPIRY: &str = "get_volume_by_expiry";
+const ENTRY_POINT_GET_OPTIONS_BY_OWNER: &str = "get_options_by_owner";
+const ENTRY_POINT_GET_OPTION_STATE: &str = "get_option_state";
+const ENTRY_POINT_GET_CREATION_TIMESTAMP: &str = "get_creation_timestamp";
+const ENTRY_POINT_GET_EXERCISE_TIMESTAMP: &str = "get_exercise_timestamp";
+const ENTRY_POINT_FINALIZE_SETTLEMENT_PRICE: &str = "finalize_settlement_price";
+const ENTRY_POINT_DISPUTE_SETTLEMENT_PRICE: &str = "dispute_settlement_price";
+const ENTRY_POINT_RESOLVE_DISPUTE: &str = "resolve_dispute";
+const ENTRY_POINT_IS_SETTLEMENT_FINAL: &str = "is_settlement_final";
+const ENTRY_POINT_GET_OPTIONS_IN_STATE: &str = "get_options_in_state";
+const ENTRY_POINT_GET_ACTIVE_OPTION_COUNT: &str = "get_active_option_count";
+const ENTRY_POINT_GET_EXERCISED_OPTION_COUNT: &str = "get_exercised_option_count";
+const ENTRY_POINT_GET_EXPIRED_OPTION_COUNT: &str = "get_expired_option_count";
+const ENTRY_POINT_GET_CANCELLED_OPTION_COUNT: &str = "get_cancelled_option_count";
+const ENTRY_POINT_GET_SETTLED_OPTION_COUNT: &str = "get_settled_option_count";
+const ENTRY_POINT_GET_TOTAL_OPTION_COUNT: &str = "get_total_option_count";
+const ENTRY_POINT_IS_EXERCISABLE: &str = "is_exercisable";
+const ENTRY_POINT_EXERCISE_REVERT_REASON: &str = "exercise_revert_reason";
+const ENTRY_POINT_IS_WHITELISTED: &str = "is_whitelisted";
+const ENTRY_POINT_SET_PRICE: &str = "set_price";
+const ENTRY_POINT_SET_REENTRY_TARGET: &str = "set_reentry_target";
+const ENTRY_POINT_MINT: &str = "mint";
+const ENTRY_POINT_ADD_COLLATERAL_TYPE: &str = "add_collateral_type";
+const ENTRY_POINT_REMOVE_COLLATERAL_TYPE: &str = "remove_collateral_type";
+const ENTRY_POINT_SET_COLLATERAL_DEX: &str = "set_collateral_dex";
+const ENTRY_POINT_DEPLOY_OPTION_TOKEN: &str = "deploy_option_token";
+const ENTRY_POINT_TRANSFER: &str = "transfer";
 
 // Arguments
 const ARG_ID: &str = "id";
 const ARG_STRIKE_PRICE: &str = "strike_price";
 const ARG_EXPIRY: &str = "expiry";
+const ARG_OPTION_TYPE: &str = "option_type";
+const ARG_NEW_OWNER: &str = "new_owner";
+const ARG_ACTIVATION: &str = "activation";
+const ARG_IDS: &str = "ids";
+const ARG_STRIKE_PRICES: &str = "strike_prices";
+const ARG_EXPIRIES: &str = "expiries";
+const ARG_OPTION_STYLE: &str = "option_style";
+const ARG_PREMIUM: &str = "premium";
+const ARG_PURSE: &str = "purse";
+const ARG_AMOUNT: &str = "amount";
+const ARG_WRITER: &str = "writer";
+const ARG_NEW_ADMIN: &str = "new_admin";
+const ARG_ORACLE_CONTRACT_HASH: &str = "oracle_contract_hash";
+const ARG_FEE_BPS: &str = "fee_bps";
+const ARG_KEEPER_REWARD_PER_OPTION: &str = "keeper_reward_per_option";
+const ARG_KEEPER: &str = "keeper";
+const ARG_WHITELIST_ENABLED: &str = "whitelist_enabled";
+const ARG_MAX_UTILIZATION_BPS: &str = "max_utilization_bps";
+const ARG_OPTION_NFT_CONTRACT_HASH: &str = "option_nft_contract_hash";
+const ARG_MAX_NOTIONAL_PER_ACCOUNT: &str = "max_notional_per_account";
+const ARG_EARLY_EXERCISE_PENALTY_BPS: &str = "early_exercise_penalty_bps";
+const ARG_PRICE_VALIDATION_ENABLED: &str = "price_validation_enabled";
+const ARG_SHARES: &str = "shares";
+const ARG_RECIPIENT: &str = "recipient";
+const ARG_APPROVED: &str = "approved";
+const ARG_SPENDER: &str = "spender";
+const ARG_GREEKS_ORACLE: &str = "greeks_oracle";
+const ARG_DELTA: &str = "delta";
+const ARG_GAMMA: &str = "gamma";
+const ARG_THETA: &str = "theta";
+const ARG_VEGA: &str = "vega";
+const ARG_VOL_ORACLE: &str = "vol_oracle";
+const ARG_IV: &str = "iv";
+const ARG_IV_BPS: &str = "iv_bps";
+const ARG_IVS_BPS: &str = "ivs_bps";
+const ARG_UNDERLYING_PRICE: &str = "underlying_price";
+const ARG_CURRENT_PRICE: &str = "current_price";
+const ARG_OPTION_PRICE: &str = "option_price";
+const ARG_CONTRACT_HASH: &str = "contract_hash";
+const ARG_CONTRACT_VERSION: &str = "contract_version";
+const ARG_PARAM_KEY: &str = "param_key";
+const ARG_PARAM_VALUE: &str = "param_value";
+const ARG_PARAM_NAME: &str = "param_name";
+const ARG_NEW_VALUE: &str = "new_value";
+const ARG_DESCRIPTION: &str = "description";
+const ARG_PROPOSAL_ID: &str = "proposal_id";
+const ARG_PRICE: &str = "price";
+const ARG_OPTION_AMOUNT: &str = "option_amount";
+const ARG_UNDERLYING_TOKEN: &str = "underlying_token";
+const ARG_UNDERLYING_AMOUNT: &str = "underlying_amount";
+const ARG_COLLATERAL_TOKEN: &str = "collateral_token";
+const ARG_COLLATERAL_AMOUNT: &str = "collateral_amount";
+const ARG_VESTING_START: &str = "vesting_start";
+const ARG_CLIFF_SECONDS: &str = "cliff_seconds";
+const ARG_VESTING_DURATION_SECONDS: &str = "vesting_duration_seconds";
+const ARG_COLLATERAL_FACTOR_BPS: &str = "collateral_factor_bps";
+const ARG_TOKEN: &str = "token";
+const ARG_DEX: &str = "dex";
+const ARG_OWNER: &str = "owner";
+const ARG_EXERCISE_AMOUNT: &str = "exercise_amount";
+const ARG_SERIES_ID: &str = "series_id";
+const ARG_DIVIDEND_PER_SHARE: &str = "dividend_per_share";
+const ARG_OLD_ID: &str = "old_id";
+const ARG_NEW_ID: &str = "new_id";
+const ARG_NEW_EXPIRY: &str = "new_expiry";
+const ARG_CALL_ID: &str = "call_id";
+const ARG_SOURCE_ID: &str = "source_id";
+const ARG_PUT_ID: &str = "put_id";
+const ARG_STRADDLE_ID: &str = "straddle_id";
+const ARG_BASE_ID: &str = "base_id";
+const ARG_LONG_ID: &str = "long_id";
+const ARG_SHORT_ID: &str = "short_id";
+const ARG_LONG_STRIKE: &str = "long_strike";
+const ARG_SHORT_STRIKE: &str = "short_strike";
+const ARG_SPREAD_ID: &str = "spread_id";
+const ARG_OFFSET: &str = "offset";
+const ARG_LIMIT: &str = "limit";
+const ARG_FROM_EXPIRY: &str = "from_expiry";
+const ARG_TO_EXPIRY: &str = "to_expiry";
+const ARG_AUTO_EXERCISE: &str = "auto_exercise";
+const ARG_NONCE: &str = "nonce";
+const ARG_SIGNATURE: &str = "signature";
+const ARG_SIGNER: &str = "signer";
+const ARG_PERMIT_SIGNATURE: &str = "permit_signature";
+const ARG_PERMIT_NONCE: &str = "permit_nonce";
+const ARG_PERMIT_DEADLINE: &str = "permit_deadline";
+const ARG_STATE: &str = "state";
+const ARG_CALLER: &str = "caller";
+const ARG_HIGH_VALUE_THRESHOLD: &str = "high_value_threshold";
+const ARG_SIGNERS: &str = "signers";
+const ARG_EXERCISE_THRESHOLD: &str = "exercise_threshold";
+const ARG_SYNDICATORS: &str = "syndicators";
+const ARG_STRIKE: &str = "strike";
+const ARG_IS_BUY: &str = "is_buy";
+const ARG_MAX_PRICE: &str = "max_price";
+const ARG_MIN_PRICE: &str = "min_price";
+const ARG_ORDER_ID: &str = "order_id";
+const ARG_BUY_ORDER_ID: &str = "buy_order_id";
+const ARG_SELL_ORDER_ID: &str = "sell_order_id";
+const ARG_DESTINATION: &str = "destination";
+const ARG_METADATA_URI: &str = "metadata_uri";
+const ARG_PUBLISHER: &str = "publisher";
+const ARG_USE_TWAP: &str = "use_twap";
+const ARG_TWAP_WINDOW: &str = "twap_window_seconds";
+const ARG_FUNDING_RATE_PER_DAY: &str = "funding_rate_per_day";
+const ARG_WINDOW_SECONDS: &str = "window_seconds";
+const ARG_PRICE_RANGE_LOW: &str = "price_range_low";
+const ARG_PRICE_RANGE_HIGH: &str = "price_range_high";
+const ARG_UNDERLYING_PRICE_AT_CREATION: &str = "underlying_price_at_creation";
+const ARG_EXERCISE_DATES: &str = "exercise_dates";
+
+/// Price the mock oracle reports until a test changes it
+const INITIAL_ORACLE_PRICE: u64 = 1000;
+
+const DAY_IN_SECONDS: u64 = 86_400;
+const GOVERNANCE_DELAY_SECS: u64 = 48 * 3600;
+/// Fixed-point scale of stored greeks
+const GREEK_SCALE: i64 = 100_000_000;
+
+// Error codes
+const ERROR_OPTION_ALREADY_EXISTS: u16 = 1;
+const ERROR_OPTION_NOT_FOUND: u16 = 2;
+const ERROR_NOT_OPTION_OWNER: u16 = 3;
+const ERROR_OPTION_ALREADY_EXERCISED: u16 = 4;
+const ERROR_OPTION_EXPIRED: u16 = 5;
+const ERROR_OPTION_CANCELLED: u16 = 6;
+const ERROR_INVALID_PARAMETER: u16 = 7;
+const ERROR_OPTION_NOT_ACTIVE: u16 = 8;
+const ERROR_INVALID_EXERCISE_TIME: u16 = 12;
+const ERROR_INCORRECT_PREMIUM: u16 = 13;
+const ERROR_NOT_OPTION_WRITER: u16 = 15;
+const ERROR_OPTION_NOT_EXPIRED: u16 = 16;
+const ERROR_NOT_ADMIN: u16 = 18;
+const ERROR_CONTRACT_PAUSED: u16 = 19;
+const ERROR_NOT_PENDING_ADMIN: u16 = 20;
+const ERROR_OPTION_ALREADY_SETTLED: u16 = 22;
+const ERROR_INSUFFICIENT_COLLATERAL: u16 = 23;
+const ERROR_OPTION_NOT_SETTLED: u16 = 24;
+const ERROR_EXCEEDS_OPTION_AMOUNT: u16 = 25;
+const ERROR_INVALID_NONCE: u16 = 26;
+const ERROR_INVALID_SIGNATURE: u16 = 27;
+const ERROR_INVALID_STATE_TRANSITION: u16 = 28;
+const ERROR_REENTRANCY: u16 = 30;
+const ERROR_PROPOSAL_NOT_FOUND: u16 = 31;
+const ERROR_PROPOSAL_NOT_READY: u16 = 32;
+const ERROR_NOT_KEEPER: u16 = 33;
+const ERROR_NOT_WHITELISTED: u16 = 34;
+const ERROR_POOL_UTILIZATION_EXCEEDED: u16 = 35;
+const ERROR_INSUFFICIENT_LP_SHARES: u16 = 36;
+const ERROR_INSUFFICIENT_POOL_LIQUIDITY: u16 = 37;
+const ERROR_INSUFFICIENT_LP_ALLOWANCE: u16 = 38;
+const ERROR_NOT_EXERCISED: u16 = 39;
+const ERROR_SETTLEMENT_PRICE_ALREADY_SET: u16 = 40;
+const ERROR_SETTLEMENT_PRICE_NOT_FINAL: u16 = 42;
+const ERROR_DISPUTE_WINDOW_CLOSED: u16 = 43;
+const ERROR_STRADDLE_NOT_FOUND: u16 = 45;
+const ERROR_SPREAD_SHORT_LEG_OPEN: u16 = 47;
+const ERROR_NOT_LIQUIDATABLE: u16 = 48;
+const ERROR_INSUFFICIENT_APPROVALS: u16 = 49;
+const ERROR_NOT_EXERCISE_SIGNER: u16 = 50;
+const ERROR_SYNDICATE_NOT_FUNDED: u16 = 52;
+const ERROR_NOT_ORDER_OWNER: u16 = 54;
+const ERROR_ORDER_NOT_OPEN: u16 = 55;
+const ERROR_ORDERS_DO_NOT_MATCH: u16 = 56;
+const ERROR_INSUFFICIENT_ACCUMULATED_FEES: u16 = 57;
+const ERROR_METADATA_TOO_LONG: u16 = 58;
+const ERROR_ORACLE_PRICE_STALE: u16 = 59;
+const ERROR_NOT_PRICE_PUBLISHER: u16 = 60;
+const ERROR_SNAPSHOT_TOO_EARLY: u16 = 61;
+const ERROR_POSITION_LIMIT_EXCEEDED: u16 = 63;
+const ERROR_ROLLOVER_WINDOW_NOT_OPEN: u16 = 64;
+const ERROR_BUNDLE_NOT_FOUND: u16 = 65;
+const ERROR_ALREADY_VOTED: u16 = 66;
+const ERROR_COLLATERAL_NOT_ACCEPTED: u16 = 67;
+const ERROR_PERMIT_NONCE_USED: u16 = 74;
+const ERROR_EXCEEDS_VESTED_AMOUNT: u16 = 75;
+const ERROR_PREMIUM_DEVIATION_TOO_HIGH: u16 = 76;
+const ERROR_CONTRACT_DEPRECATED: u16 = 77;
+const ERROR_CONTRACT_NOT_DEPRECATED: u16 = 78;
+const ERROR_CONTRACT_NOT_PAUSED: u16 = 79;
+const ERROR_ROLLOVER_NOT_APPROVED: u16 = 80;
+
+// Option types
+const OPTION_TYPE_CALL: bool = true;
+const OPTION_TYPE_PUT: bool = false;
+
+// Option styles
+const OPTION_STYLE_EUROPEAN: u8 = 0;
+const OPTION_STYLE_AMERICAN: u8 = 1;
+const OPTION_STYLE_BERMUDAN: u8 = 2;
+
+// Option lifecycle states
+const OPTION_STATE_CREATED: u8 = 0;
+const OPTION_STATE_ACTIVE: u8 = 1;
+const OPTION_STATE_EXERCISED: u8 = 2;
+const OPTION_STATE_EXPIRED: u8 = 3;
+const OPTION_STATE_CANCELLED: u8 = 4;
+const OPTION_STATE_SETTLED: u8 = 5;
+
+// Event type codes
+const EVENT_OPTION_CREATED: u8 = 0;
+const EVENT_OPTION_EXERCISED: u8 = 1;
 
 /// Get the path to the compiled WASM file
 fn get_wasm_path() -> PathBuf {
+    get_crate_wasm_path("option-registry", CONTRACT_WASM)
+}
+
+/// Get the path to the compiled mock oracle WASM file
+fn get_mock_oracle_wasm_path() -> PathBuf {
+    get_crate_wasm_path("mock-oracle", MOCK_ORACLE_WASM)
+}
+
+/// Get the path to the compiled mock CEP-18 WASM file
+fn get_mock_cep18_wasm_path() -> PathBuf {
+    get_crate_wasm_path("mock-cep18", MOCK_CEP18_WASM)
+}
+
+/// Get the path to the compiled mock CEP-78 WASM file
+fn get_mock_cep78_wasm_path() -> PathBuf {
+    get_crate_wasm_path("mock-cep78", MOCK_CEP78_WASM)
+}
+
+/// Get the path to the compiled mock legacy registry WASM file
+fn get_mock_legacy_registry_wasm_path() -> PathBuf {
+    get_crate_wasm_path("mock-legacy-registry", MOCK_LEGACY_REGISTRY_WASM)
+}
+
+/// Get the path to the compiled mock quote reader WASM file
+fn get_mock_quote_reader_wasm_path() -> PathBuf {
+    get_crate_wasm_path("mock-quote-reader", MOCK_QUOTE_READER_WASM)
+}
+
+/// Get the path to the compiled mock DEX WASM file
+fn get_mock_dex_wasm_path() -> PathBuf {
+    get_crate_wasm_path("mock-dex", MOCK_DEX_WASM)
+}
+
+fn get_crate_wasm_path(crate_dir: &str, wasm: &str) -> PathBuf {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("..");
-    path.push("option-registry");
+    path.push(crate_dir);
     path.push("target");
     path.push("wasm32-unknown-unknown");
     path.push("release");
-    path.push(CONTRACT_WASM);
+    path.push(wasm);
     path
 }
 
@@ -50,16 +471,55 @@ fn get_wasm_path() -> PathBuf {
 // TEST HELPERS
 // ============================================================================
 
-/// Creates a new test builder with the contract installed
+/// Creates a new test builder with the mock oracle and the contract installed
 fn setup_contract() -> InMemoryWasmTestBuilder {
-    let mut builder = InMemoryWasmTestBuilder::default();
-    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST).commit();
+    setup_contract_with_fee(0)
+}
+
+/// Installs the mock oracle and the registry, taking `fee_bps` of each CSPR
+/// settlement payout
+fn setup_contract_with_fee(fee_bps: u32) -> InMemoryWasmTestBuilder {
+    setup_contract_with_keeper_reward(fee_bps, U512::zero())
+}
+
+/// Installs the mock oracle and the registry with a `fee_bps` settlement fee,
+/// paying keepers `keeper_reward` per option they expire
+fn setup_contract_with_keeper_reward(fee_bps: u32, keeper_reward: U512) -> InMemoryWasmTestBuilder {
+    install_registry(fee_bps, keeper_reward, false, 10_000)
+}
+
+/// Installs the mock oracle and the registry with the writer whitelist enabled
+fn setup_contract_with_whitelist() -> InMemoryWasmTestBuilder {
+    install_registry(0, U512::zero(), true, 10_000)
+}
+
+/// Installs the mock oracle and the registry, letting pool-written options
+/// lock at most `max_utilization_bps` of the LP pool's value
+fn setup_contract_with_max_utilization(max_utilization_bps: u32) -> InMemoryWasmTestBuilder {
+    install_registry(0, U512::zero(), false, max_utilization_bps)
+}
+
+/// Installs the mock oracle and the registry with the given install arguments
+fn install_registry(
+    fee_bps: u32,
+    keeper_reward: U512,
+    whitelist_enabled: bool,
+    max_utilization_bps: u32,
+) -> InMemoryWasmTestBuilder {
+    let mut builder = setup_mock_oracle();
+    let oracle_hash = get_mock_oracle_hash(&builder);
 
     // Install the contract
     let install_request = ExecuteRequestBuilder::standard(
         *DEFAULT_ACCOUNT_ADDR,
         &get_wasm_path().to_string_lossy(),
-        RuntimeArgs::new(),
+        runtime_args! {
+            ARG_ORACLE_CONTRACT_HASH => oracle_hash,
+            ARG_FEE_BPS => fee_bps,
+            ARG_KEEPER_REWARD_PER_OPTION => keeper_reward,
+            ARG_WHITELIST_ENABLED => whitelist_enabled,
+            ARG_MAX_UTILIZATION_BPS => max_utilization_bps,
+        },
     )
     .build();
 
@@ -67,6 +527,47 @@ fn setup_contract() -> InMemoryWasmTestBuilder {
     builder
 }
 
+/// Creates a new test builder with only the mock oracle the registry reads
+/// prices from installed
+fn setup_mock_oracle() -> InMemoryWasmTestBuilder {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST).commit();
+
+    let oracle_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_mock_oracle_wasm_path().to_string_lossy(),
+        runtime_args! {
+            ARG_PRICE => U256::from(INITIAL_ORACLE_PRICE),
+        },
+    )
+    .build();
+
+    builder.exec(oracle_request).expect_success().commit();
+    builder
+}
+
+/// Creates and funds a new account derived from `seed`
+fn create_funded_account(builder: &mut InMemoryWasmTestBuilder, seed: u8) -> AccountHash {
+    let account = AccountHash::new([seed; 32]);
+    fund_account(builder, account);
+    account
+}
+
+/// Creates `account` with the minimum account creation balance
+fn fund_account(builder: &mut InMemoryWasmTestBuilder, account: AccountHash) {
+    let transfer_request = ExecuteRequestBuilder::transfer(
+        *DEFAULT_ACCOUNT_ADDR,
+        runtime_args! {
+            mint::ARG_TARGET => account,
+            mint::ARG_AMOUNT => U512::from(MINIMUM_ACCOUNT_CREATION_BALANCE),
+            mint::ARG_ID => Option::<u64>::None,
+        },
+    )
+    .build();
+
+    builder.exec(transfer_request).expect_success().commit();
+}
+
 /// Gets the contract hash from the builder
 fn get_contract_hash(builder: &InMemoryWasmTestBuilder) -> ContractHash {
     builder
@@ -79,6 +580,34 @@ fn get_contract_hash(builder: &InMemoryWasmTestBuilder) -> ContractHash {
         .expect("Invalid contract hash")
 }
 
+/// Gets the mock oracle's contract hash from the builder
+fn get_mock_oracle_hash(builder: &InMemoryWasmTestBuilder) -> ContractHash {
+    builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(MOCK_ORACLE_KEY)
+        .expect("Mock oracle not found")
+        .into_hash()
+        .map(ContractHash::new)
+        .expect("Invalid mock oracle hash")
+}
+
+/// Sets the price the mock oracle reports
+fn set_oracle_price(builder: &mut InMemoryWasmTestBuilder, price: U256) {
+    let oracle_hash = get_mock_oracle_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        oracle_hash,
+        ENTRY_POINT_SET_PRICE,
+        runtime_args! {
+            ARG_PRICE => price,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
 /// Gets the option count from contract storage
 fn get_option_count(builder: &InMemoryWasmTestBuilder, contract_hash: ContractHash) -> u64 {
     let contract = builder
@@ -100,7 +629,53 @@ fn get_option_count(builder: &InMemoryWasmTestBuilder, contract_hash: ContractHa
         .expect("Failed to parse as u64")
 }
 
-/// Creates an option via the contract
+/// Executes a contract entry point as `sender` without asserting on the outcome
+fn call_entry_point<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    entry_point: &str,
+    args: RuntimeArgs,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point_at(builder, sender, contract_hash, entry_point, args, 0)
+}
+
+/// Executes a contract entry point as `sender` in a block at `block_time`
+/// (unix seconds) without asserting on the outcome
+fn call_entry_point_at<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    entry_point: &str,
+    args: RuntimeArgs,
+    block_time: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    let request = ExecuteRequestBuilder::contract_call_by_hash(
+        sender,
+        contract_hash,
+        entry_point,
+        args,
+    )
+    .with_block_time(block_time * 1000)
+    .build();
+
+    builder.exec(request)
+}
+
+/// Asserts that the last execution reverted with the given `ApiError::User` code
+fn assert_user_error(builder: &InMemoryWasmTestBuilder, expected_code: u16) {
+    match builder.get_error() {
+        Some(engine_state::Error::Exec(execution::Error::Revert(ApiError::User(code)))) => {
+            assert_eq!(code, expected_code, "Unexpected user error code");
+        }
+        other => panic!(
+            "Expected ApiError::User({}), got {:?}",
+            expected_code, other
+        ),
+    }
+}
+
+/// Creates and activates a call option via the contract
 fn create_option(
     builder: &mut InMemoryWasmTestBuilder,
     contract_hash: ContractHash,
@@ -108,19 +683,172 @@ fn create_option(
     strike_price: U256,
     expiry: u64,
 ) {
-    let create_request = ExecuteRequestBuilder::contract_call_by_hash(
+    create_option_with_type(
+        builder,
+        contract_hash,
+        id,
+        strike_price,
+        expiry,
+        OPTION_TYPE_CALL,
+    );
+}
+
+/// Creates and activates an option of the given type (true = Call, false = Put)
+fn create_option_with_type(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    strike_price: U256,
+    expiry: u64,
+    option_type: bool,
+) {
+    create_option_as(
+        builder,
         *DEFAULT_ACCOUNT_ADDR,
         contract_hash,
+        id,
+        strike_price,
+        expiry,
+        option_type,
+    );
+}
+
+/// Returns the last `create_option` nonce `account` used, if any
+fn get_last_nonce(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    account: AccountHash,
+) -> Option<u64> {
+    let nonces_uref = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get(NONCES_DICT)
+        .expect("Nonces dictionary not found")
+        .into_uref()
+        .expect("Nonces dictionary is not a URef");
+    let hex: String = account.value().iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    builder
+        .query_dictionary_item(None, nonces_uref, &format!("nonce_{}", hex))
+        .ok()
+        .map(|value| {
+            value
+                .as_cl_value()
+                .expect("Not a CLValue")
+                .clone()
+                .into_t::<u64>()
+                .expect("Failed to parse nonce")
+        })
+}
+
+/// Returns the next nonce `account` can pass to `create_option`
+fn next_nonce(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    account: AccountHash,
+) -> u64 {
+    get_last_nonce(builder, contract_hash, account).map_or(0, |nonce| nonce + 1)
+}
+
+/// Creates an option as `sender`, who becomes its owner, and activates it
+fn create_option_as(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    strike_price: U256,
+    expiry: u64,
+    option_type: bool,
+) {
+    let nonce = next_nonce(builder, contract_hash, sender);
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
         ENTRY_POINT_CREATE_OPTION,
         runtime_args! {
             ARG_ID => id,
             ARG_STRIKE_PRICE => strike_price,
             ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => option_type,
+            ARG_NONCE => nonce,
         },
     )
-    .build();
+    .expect_success()
+    .commit();
+    activate_option(builder, sender, contract_hash, id)
+        .expect_success()
+        .commit();
+}
+
+/// Calls `activate_option` for option `id` as `sender`
+fn activate_option(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_ACTIVATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+        },
+    )
+}
+
+/// Calls `expire_option` for option `id` as `sender` at `block_time_secs`
+fn expire_option_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_EXPIRE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+        },
+        block_time_secs,
+    )
+}
+
+/// Reads the lifecycle state stored for an option
+fn get_option_state(builder: &InMemoryWasmTestBuilder, contract_hash: ContractHash, id: u64) -> u8 {
+    get_contract_value(builder, contract_hash, &format!("option_{}_state", id))
+}
 
-    builder.exec(create_request).expect_success().commit();
+/// Attempts to create a call option, expecting the execution to fail
+fn create_option_expect_failure(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    strike_price: U256,
+    expiry: u64,
+) {
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => strike_price,
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+        },
+    )
+    .expect_failure()
+    .commit();
 }
 
 /// Exercises an option via the contract
@@ -142,6 +870,48 @@ fn exercise_option(
     builder.exec(exercise_request).expect_success().commit();
 }
 
+/// Cancels an option as `sender`
+fn cancel_option(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+) {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_CANCEL_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+/// Transfers an option from `sender` to `new_owner`
+fn transfer_option(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    new_owner: AccountHash,
+) {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_TRANSFER_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_NEW_OWNER => new_owner,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
 /// Checks if an option exists in storage
 fn option_exists(
     builder: &InMemoryWasmTestBuilder,
@@ -158,24 +928,108 @@ fn option_exists(
 /// Checks if an option is exercised
 fn is_option_exercised(
     builder: &InMemoryWasmTestBuilder,
-    account: AccountHash,
+    contract_hash: ContractHash,
     id: u64,
 ) -> bool {
-    let key_name = format!("option_{}_exercised", id);
-    if let Some(key) = builder
-        .get_expected_account(account)
+    get_option_field(builder, contract_hash, OPTIONS_EXERCISED_DICT, id)
+}
+
+/// Reads an option's entry from one of the contract's per-field dictionaries
+fn get_option_field<T: CLTyped + FromBytes>(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    dictionary: &str,
+    id: u64,
+) -> T {
+    let dictionary_uref = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
         .named_keys()
-        .get(&key_name)
-    {
-        builder
-            .query(None, *key, &[])
-            .ok()
-            .and_then(|v| v.as_cl_value().cloned())
-            .and_then(|v| v.into_t::<bool>().ok())
-            .unwrap_or(false)
-    } else {
-        false
-    }
+        .get(dictionary)
+        .unwrap_or_else(|| panic!("Dictionary {} not found", dictionary))
+        .into_uref()
+        .expect("Dictionary is not a URef");
+
+    builder
+        .query_dictionary_item(None, dictionary_uref, &id.to_string())
+        .unwrap_or_else(|_| panic!("Option {} not found in {}", id, dictionary))
+        .as_cl_value()
+        .expect("Not a CLValue")
+        .clone()
+        .into_t::<T>()
+        .expect("Failed to parse CLValue")
+}
+
+/// Reads a value stored under one of the contract's named keys
+fn get_contract_value<T: CLTyped + FromBytes>(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    key_name: &str,
+) -> T {
+    let contract = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found");
+
+    let key = contract
+        .named_keys()
+        .get(key_name)
+        .unwrap_or_else(|| panic!("Named key {} not found", key_name));
+
+    builder
+        .query(None, *key, &[])
+        .expect("Failed to query named key")
+        .as_cl_value()
+        .expect("Not a CLValue")
+        .clone()
+        .into_t::<T>()
+        .expect("Failed to parse CLValue")
+}
+
+/// Checks whether the contract has a named key
+fn contract_has_key(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    key_name: &str,
+) -> bool {
+    builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .contains_key(key_name)
+}
+
+/// Returns the balance of the contract's premium escrow purse
+fn get_contract_purse_balance(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+) -> U512 {
+    let purse = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get(CONTRACT_PURSE_KEY)
+        .expect("Contract purse key not found")
+        .into_uref()
+        .expect("Contract purse is not a URef");
+    builder.get_purse_balance(purse)
+}
+
+/// Reads the strike price stored for an option
+fn get_option_strike(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+) -> U256 {
+    get_option_field(builder, contract_hash, OPTIONS_STRIKE_DICT, id)
+}
+
+/// Reads the option type (true = Call, false = Put) stored for an option
+fn get_option_type(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+) -> bool {
+    get_contract_value(builder, contract_hash, &format!("option_{}_type", id))
 }
 
 // ============================================================================
@@ -268,7 +1122,8 @@ proptest! {
     /// **Feature: casper-options-hybrid, Property 3: Exercise Idempotence**
     /// 
     /// *For any* option ID, calling `exercise_option` multiple times SHALL result
-    /// in the same final state (option marked as exercised).
+    /// in the same final state (option marked as exercised). Repeat calls revert
+    /// with `OptionAlreadyExercised` without changing that state.
     /// 
     /// **Validates: Requirements 3.1, 3.2**
     #[test]
@@ -289,12 +1144,27 @@ proptest! {
         );
         
         // Exercise multiple times
-        for _ in 0..num_exercises {
-            exercise_option(&mut builder, contract_hash, id);
+        for i in 0..num_exercises {
+            if i == 0 {
+                exercise_option(&mut builder, contract_hash, id);
+            } else {
+                call_entry_point(
+                    &mut builder,
+                    *DEFAULT_ACCOUNT_ADDR,
+                    contract_hash,
+                    ENTRY_POINT_EXERCISE_OPTION,
+                    runtime_args! {
+                        ARG_ID => id,
+                    },
+                )
+                .expect_failure()
+                .commit();
+                assert_user_error(&builder, ERROR_OPTION_ALREADY_EXERCISED);
+            }
             
             // Verify option is exercised after each call
             prop_assert!(
-                is_option_exercised(&builder, *DEFAULT_ACCOUNT_ADDR, id),
+                is_option_exercised(&builder, contract_hash, id),
                 "Option {} should be exercised",
                 id
             );
@@ -352,19 +1222,184 @@ proptest! {
         let key2 = format!("option_{}", id2);
         prop_assert_ne!(key1, key2, "Storage keys should be unique");
     }
-}
 
-// ============================================================================
-// UNIT TESTS (Examples and Edge Cases)
-// ============================================================================
+    /// **Feature: casper-options-hybrid, Property 5: Option Type Persistence**
+    ///
+    /// *For any* option created as a Call or a Put, reading the option type back
+    /// SHALL return the same type it was created with.
+    #[test]
+    fn prop_option_type_persistence(
+        id in 0u64..1000000,
+        option_type in any::<bool>(),
+    ) {
+        let mut builder = setup_contract();
+        let contract_hash = get_contract_hash(&builder);
 
-#[test]
-fn test_contract_installation() {
-    let builder = setup_contract();
-    let contract_hash = get_contract_hash(&builder);
-    
-    // Verify contract was installed
-    assert!(
+        create_option_with_type(
+            &mut builder,
+            contract_hash,
+            id,
+            U256::from(1000u64),
+            1735689600u64,
+            option_type,
+        );
+
+        let stored_type = get_option_type(&builder, contract_hash, id);
+        if option_type == OPTION_TYPE_CALL {
+            prop_assert!(stored_type, "Call option {} should not read back as a Put", id);
+        } else {
+            prop_assert!(!stored_type, "Put option {} should not read back as a Call", id);
+        }
+    }
+
+    /// **Feature: casper-options-hybrid, Property 6: Exercise Follows Current Owner**
+    ///
+    /// *For any* sender/receiver pair, after the sender transfers an option to the
+    /// receiver, only the receiver SHALL be able to exercise it.
+    #[test]
+    fn prop_exercise_follows_current_owner(
+        sender_seed in 1u8..=255,
+        receiver_seed in 1u8..=255,
+    ) {
+        prop_assume!(sender_seed != receiver_seed);
+
+        let mut builder = setup_contract();
+        let contract_hash = get_contract_hash(&builder);
+        let sender = create_funded_account(&mut builder, sender_seed);
+        let receiver = create_funded_account(&mut builder, receiver_seed);
+
+        create_option_as(
+            &mut builder,
+            sender,
+            contract_hash,
+            1,
+            U256::from(1000u64),
+            1735689600u64,
+            OPTION_TYPE_CALL,
+        );
+        transfer_option(&mut builder, sender, contract_hash, 1, receiver);
+
+        call_entry_point(
+            &mut builder,
+            sender,
+            contract_hash,
+            ENTRY_POINT_EXERCISE_OPTION,
+            runtime_args! {
+                ARG_ID => 1u64,
+            },
+        )
+        .expect_failure()
+        .commit();
+        assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+
+        call_entry_point(
+            &mut builder,
+            receiver,
+            contract_hash,
+            ENTRY_POINT_EXERCISE_OPTION,
+            runtime_args! {
+                ARG_ID => 1u64,
+            },
+        )
+        .expect_success()
+        .commit();
+
+        let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 1);
+        prop_assert!(exercised, "Option 1 should be exercised by the new owner");
+    }
+
+    /// **Feature: casper-options-hybrid, Property 7: Batch Exercise Atomicity**
+    ///
+    /// *For any* mix of valid and invalid option IDs, `exercise_options_batch`
+    /// SHALL either exercise every option in the batch or none of them.
+    #[test]
+    fn prop_batch_exercise_atomicity(
+        valid_ids in proptest::collection::vec(0u64..10, 1..5),
+        invalid_ids in proptest::collection::vec(1000u64..2000, 0..3),
+    ) {
+        let mut builder = setup_contract();
+        let contract_hash = get_contract_hash(&builder);
+
+        for id in 0..10u64 {
+            create_option(&mut builder, contract_hash, id, U256::from(1000u64), 1735689600u64);
+        }
+
+        let mut valid_ids = valid_ids;
+        valid_ids.sort_unstable();
+        valid_ids.dedup();
+
+        let mut batch = valid_ids.clone();
+        batch.extend(invalid_ids.iter().copied());
+
+        let request = call_entry_point(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            ENTRY_POINT_EXERCISE_OPTIONS_BATCH,
+            runtime_args! {
+                ARG_IDS => batch,
+            },
+        );
+
+        if invalid_ids.is_empty() {
+            request.expect_success().commit();
+        } else {
+            request.expect_failure().commit();
+            assert_user_error(&builder, ERROR_OPTION_NOT_FOUND);
+        }
+
+        for id in valid_ids {
+            let exercised: bool =
+                get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, id);
+            prop_assert_eq!(
+                exercised,
+                invalid_ids.is_empty(),
+                "Option {} exercise state should match batch outcome",
+                id
+            );
+        }
+    }
+
+    /// **Feature: casper-options-hybrid, Property 8: Greeks Round Trip**
+    ///
+    /// *For any* delta supplied for a deep-in-the-money call, `update_greeks`
+    /// SHALL store it unchanged, so the stored delta stays near 1e8.
+    #[test]
+    fn prop_deep_in_the_money_delta_near_one(
+        delta in (GREEK_SCALE * 99 / 100)..=GREEK_SCALE,
+        gamma in 0i64..1_000_000,
+    ) {
+        let mut builder = setup_contract();
+        let contract_hash = get_contract_hash(&builder);
+
+        // Strike 1 against an oracle price of 1000
+        create_option(&mut builder, contract_hash, 1, U256::one(), 1735689600u64);
+        update_greeks(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, [delta, gamma, -5, 0])
+            .expect_success()
+            .commit();
+
+        let [stored_delta, stored_gamma, _, _] = get_greeks(&builder, contract_hash, 1);
+        prop_assert_eq!(stored_delta, delta);
+        prop_assert_eq!(stored_gamma, gamma);
+        prop_assert!(
+            (GREEK_SCALE - stored_delta) <= GREEK_SCALE / 100,
+            "Deep in-the-money delta {} should be within 1% of 1e8",
+            stored_delta
+        );
+    }
+}
+
+// ============================================================================
+// UNIT TESTS (Examples and Edge Cases)
+// ============================================================================
+
+#[test]
+fn test_contract_installation() {
+    let builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    
+    // Verify contract was installed
+    assert!(
         builder.get_contract(contract_hash).is_some(),
         "Contract should be installed"
     );
@@ -419,7 +1454,7 @@ fn test_exercise_option() {
     exercise_option(&mut builder, contract_hash, 1);
     
     assert!(
-        is_option_exercised(&builder, *DEFAULT_ACCOUNT_ADDR, 1),
+        is_option_exercised(&builder, contract_hash, 1),
         "Option 1 should be exercised"
     );
 }
@@ -488,3 +1523,9917 @@ fn test_edge_case_max_values() {
         "Option with max values should exist"
     );
 }
+
+#[test]
+fn test_strike_price_stored_as_u256() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    // A strike above u64::MAX only round-trips if the contract stores U256
+    let strike_price = U256::from(u64::MAX) * U256::from(1000u64);
+
+    create_option(
+        &mut builder,
+        contract_hash,
+        1,
+        strike_price,
+        1735689600u64,
+    );
+
+    assert_eq!(
+        get_option_strike(&builder, contract_hash, 1),
+        strike_price,
+        "Strike price should be stored as U256"
+    );
+}
+
+#[test]
+fn test_create_call_and_put_options() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option_with_type(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000000u64),
+        1735689600u64,
+        OPTION_TYPE_CALL,
+    );
+    create_option_with_type(
+        &mut builder,
+        contract_hash,
+        2,
+        U256::from(1000000u64),
+        1735689600u64,
+        OPTION_TYPE_PUT,
+    );
+
+    assert!(get_option_type(&builder, contract_hash, 1), "Option 1 should be a Call");
+    assert!(!get_option_type(&builder, contract_hash, 2), "Option 2 should be a Put");
+}
+
+#[test]
+fn test_duplicate_option_id_rejected() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000000u64),
+        1735689600u64,
+    );
+
+    // Same id with a different strike must not overwrite the original
+    create_option_expect_failure(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(2000000u64),
+        1735689600u64,
+    );
+    assert_user_error(&builder, ERROR_OPTION_ALREADY_EXISTS);
+
+    assert_eq!(
+        get_option_strike(&builder, contract_hash, 1),
+        U256::from(1000000u64),
+        "Original strike price should be preserved"
+    );
+
+    let count = get_option_count(&builder, contract_hash);
+    assert_eq!(count, 1, "Rejected duplicate should not increment option count");
+}
+
+#[test]
+fn test_owner_can_cancel_option() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000000u64),
+        1735689600u64,
+    );
+
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1);
+
+    let cancelled: bool = get_contract_value(&builder, contract_hash, "option_1_cancelled");
+    assert!(cancelled, "Option 1 should be cancelled");
+}
+
+#[test]
+fn test_non_owner_cannot_cancel_option() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let other_account = create_funded_account(&mut builder, 1);
+
+    create_option(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000000u64),
+        1735689600u64,
+    );
+
+    call_entry_point(
+        &mut builder,
+        other_account,
+        contract_hash,
+        ENTRY_POINT_CANCEL_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+
+    let cancelled: bool = get_contract_value(&builder, contract_hash, "option_1_cancelled");
+    assert!(!cancelled, "Option 1 should not be cancelled by a non-owner");
+}
+
+#[test]
+fn test_exercise_cancelled_option_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000000u64),
+        1735689600u64,
+    );
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_OPTION_CANCELLED);
+}
+
+#[test]
+fn test_only_owner_can_exercise_option() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let account_b = create_funded_account(&mut builder, 1);
+
+    // Account A (the default account) writes the option
+    create_option(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000000u64),
+        1735689600u64,
+    );
+
+    call_entry_point(
+        &mut builder,
+        account_b,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+
+    let owner: AccountHash = get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, 1);
+    assert_eq!(owner, *DEFAULT_ACCOUNT_ADDR, "Option 1 should be owned by account A");
+
+    exercise_option(&mut builder, contract_hash, 1);
+
+    let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 1);
+    assert!(exercised, "Option 1 should be exercised by its owner");
+}
+
+#[test]
+fn test_non_owner_cannot_transfer_option() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let other_account = create_funded_account(&mut builder, 1);
+
+    create_option(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000000u64),
+        1735689600u64,
+    );
+
+    call_entry_point(
+        &mut builder,
+        other_account,
+        contract_hash,
+        ENTRY_POINT_TRANSFER_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_NEW_OWNER => other_account,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+
+    let owner: AccountHash = get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, 1);
+    assert_eq!(owner, *DEFAULT_ACCOUNT_ADDR, "Owner should be unchanged");
+}
+
+#[test]
+fn test_exercise_before_expiry_succeeds() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000000u64), expiry);
+
+    call_entry_point_at(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+        expiry,
+    )
+    .expect_success()
+    .commit();
+
+    let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 1);
+    assert!(exercised, "Option 1 should be exercisable at expiry");
+}
+
+#[test]
+fn test_exercise_after_expiry_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000000u64), expiry);
+
+    call_entry_point_at(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+        expiry + 1,
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_OPTION_EXPIRED);
+}
+
+#[test]
+fn test_exercise_before_activation_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let activation = 1735600000u64;
+    let expiry = 1735689600u64;
+
+    let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_ACTIVATION => activation,
+        },
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+
+    call_entry_point_at(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+        activation - 1,
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_OPTION_NOT_ACTIVE);
+
+    call_entry_point_at(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+        activation,
+    )
+    .expect_success()
+    .commit();
+}
+
+// ============================================================================
+// ERROR CODE TESTS
+// ============================================================================
+
+/// Each contract error path must surface its exact `ApiError::User` code so
+/// downstream clients can match on specific failures.
+mod error_codes {
+    use super::*;
+
+    const EXPIRY: u64 = 1735689600;
+
+    fn setup_with_option() -> (InMemoryWasmTestBuilder, ContractHash) {
+        let mut builder = setup_contract();
+        let contract_hash = get_contract_hash(&builder);
+        create_option(&mut builder, contract_hash, 1, U256::from(1000u64), EXPIRY);
+        (builder, contract_hash)
+    }
+
+    fn expect_exercise_error(
+        builder: &mut InMemoryWasmTestBuilder,
+        sender: AccountHash,
+        contract_hash: ContractHash,
+        id: u64,
+        block_time: u64,
+        expected_code: u16,
+    ) {
+        call_entry_point_at(
+            builder,
+            sender,
+            contract_hash,
+            ENTRY_POINT_EXERCISE_OPTION,
+            runtime_args! {
+                ARG_ID => id,
+            },
+            block_time,
+        )
+        .expect_failure()
+        .commit();
+        assert_user_error(builder, expected_code);
+    }
+
+    #[test]
+    fn option_already_exists() {
+        let (mut builder, contract_hash) = setup_with_option();
+        create_option_expect_failure(&mut builder, contract_hash, 1, U256::from(1000u64), EXPIRY);
+        assert_user_error(&builder, ERROR_OPTION_ALREADY_EXISTS);
+    }
+
+    #[test]
+    fn option_not_found() {
+        let (mut builder, contract_hash) = setup_with_option();
+        expect_exercise_error(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            2,
+            0,
+            ERROR_OPTION_NOT_FOUND,
+        );
+    }
+
+    #[test]
+    fn not_option_owner() {
+        let (mut builder, contract_hash) = setup_with_option();
+        let other_account = create_funded_account(&mut builder, 1);
+        expect_exercise_error(
+            &mut builder,
+            other_account,
+            contract_hash,
+            1,
+            0,
+            ERROR_NOT_OPTION_OWNER,
+        );
+    }
+
+    #[test]
+    fn option_already_exercised() {
+        let (mut builder, contract_hash) = setup_with_option();
+        exercise_option(&mut builder, contract_hash, 1);
+        expect_exercise_error(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            1,
+            0,
+            ERROR_OPTION_ALREADY_EXERCISED,
+        );
+    }
+
+    #[test]
+    fn option_expired() {
+        let (mut builder, contract_hash) = setup_with_option();
+        expect_exercise_error(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            1,
+            EXPIRY + 1,
+            ERROR_OPTION_EXPIRED,
+        );
+    }
+
+    #[test]
+    fn option_cancelled() {
+        let (mut builder, contract_hash) = setup_with_option();
+        cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1);
+        expect_exercise_error(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            1,
+            0,
+            ERROR_OPTION_CANCELLED,
+        );
+    }
+
+    #[test]
+    fn invalid_parameter() {
+        let mut builder = setup_contract();
+        let contract_hash = get_contract_hash(&builder);
+
+        // Activation after expiry can never be exercised
+        let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+        call_entry_point(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            ENTRY_POINT_CREATE_OPTION,
+            runtime_args! {
+                ARG_ID => 1u64,
+                ARG_STRIKE_PRICE => U256::from(1000u64),
+                ARG_EXPIRY => EXPIRY,
+                ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+                ARG_NONCE => nonce,
+                ARG_ACTIVATION => EXPIRY + 1,
+            },
+        )
+        .expect_failure()
+        .commit();
+        assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+    }
+
+    #[test]
+    fn option_not_active() {
+        let mut builder = setup_contract();
+        let contract_hash = get_contract_hash(&builder);
+
+        let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+        call_entry_point(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            ENTRY_POINT_CREATE_OPTION,
+            runtime_args! {
+                ARG_ID => 1u64,
+                ARG_STRIKE_PRICE => U256::from(1000u64),
+                ARG_EXPIRY => EXPIRY,
+                ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+                ARG_NONCE => nonce,
+                ARG_ACTIVATION => EXPIRY - 100,
+            },
+        )
+        .expect_success()
+        .commit();
+        activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+            .expect_success()
+            .commit();
+
+        expect_exercise_error(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            1,
+            EXPIRY - 101,
+            ERROR_OPTION_NOT_ACTIVE,
+        );
+    }
+
+    #[test]
+    fn invalid_state_transition() {
+        let mut builder = setup_contract();
+        let contract_hash = get_contract_hash(&builder);
+
+        // Created but never activated by the buyer
+        create_option_with_nonce(&mut builder, contract_hash, 1, 0)
+            .expect_success()
+            .commit();
+
+        expect_exercise_error(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            1,
+            0,
+            ERROR_INVALID_STATE_TRANSITION,
+        );
+    }
+}
+
+#[test]
+fn test_get_option_fields_round_trip() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let strike_price = U256::from(1234567u64);
+    let expiry = 1735689600u64;
+
+    create_option(&mut builder, contract_hash, 7, strike_price, expiry);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_OPTION,
+        runtime_args! {
+            ARG_ID => 7u64,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    // get_option reads back exactly these named keys
+    let id: u64 = get_contract_value(&builder, contract_hash, "option_7");
+    let stored_expiry: u64 = get_option_field(&builder, contract_hash, OPTIONS_EXPIRY_DICT, 7);
+    assert_eq!(id, 7);
+    assert_eq!(get_option_strike(&builder, contract_hash, 7), strike_price);
+    assert_eq!(stored_expiry, expiry);
+}
+
+#[test]
+fn test_get_missing_option_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_OPTION_NOT_FOUND);
+}
+
+/// Field `name` of the `get_option_full` map, decoded from its `CLValue`
+fn option_full_field<T: CLTyped + FromBytes>(fields: &BTreeMap<String, Bytes>, name: &str) -> T {
+    let bytes = fields.get(name).unwrap_or_else(|| panic!("Field {} missing", name));
+    let (cl_value, _) = CLValue::from_bytes(bytes).expect("Field is not a CLValue");
+    cl_value.into_t().expect("Field has the wrong type")
+}
+
+#[test]
+fn test_get_option_full_returns_every_field() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let strike_price = U256::from(1234567u64);
+    let expiry = 1735689600u64;
+    let premium = U512::from(5_000u64);
+
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+    let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 7u64,
+            ARG_STRIKE_PRICE => strike_price,
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_PUT,
+            ARG_NONCE => nonce,
+            ARG_OPTION_STYLE => OPTION_STYLE_EUROPEAN,
+            ARG_PREMIUM => premium,
+            ARG_PURSE => purse,
+            // The premium plus strike * 3 units of collateral
+            ARG_AMOUNT => premium + U512::from(3 * 1234567u64),
+            ARG_OPTION_AMOUNT => U256::from(3u64),
+            ARG_IV => 4_200u64,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    let reader_hash = mock_quote_reader_hash(&mut builder);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_OPTION_FULL,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_ID => 7u64,
+        },
+    )
+    .expect_success()
+    .commit();
+    let fields: BTreeMap<String, Bytes> = get_contract_value(&builder, reader_hash, "option_full");
+
+    assert_eq!(fields.len(), 11);
+    assert_eq!(option_full_field::<u64>(&fields, "id"), 7);
+    assert_eq!(option_full_field::<U256>(&fields, "strike"), strike_price);
+    assert_eq!(option_full_field::<u64>(&fields, "expiry"), expiry);
+    assert_eq!(option_full_field::<bool>(&fields, "type"), OPTION_TYPE_PUT);
+    assert_eq!(option_full_field::<u8>(&fields, "style"), OPTION_STYLE_EUROPEAN);
+    assert_eq!(option_full_field::<u8>(&fields, "state"), OPTION_STATE_CREATED);
+    assert_eq!(option_full_field::<AccountHash>(&fields, "owner"), *DEFAULT_ACCOUNT_ADDR);
+    assert_eq!(option_full_field::<AccountHash>(&fields, "writer"), *DEFAULT_ACCOUNT_ADDR);
+    assert_eq!(option_full_field::<U512>(&fields, "premium"), premium);
+    assert_eq!(option_full_field::<U256>(&fields, "amount"), U256::from(3u64));
+    assert_eq!(option_full_field::<u64>(&fields, "iv"), 4_200);
+}
+
+#[test]
+fn test_create_option_batch() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    let ids: Vec<u64> = (0..20).collect();
+    let strike_prices: Vec<U256> = ids.iter().map(|i| U256::from(1000u64 * (i + 1))).collect();
+    let expiries: Vec<u64> = ids.iter().map(|i| 1735689600u64 + i * 86400).collect();
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION_BATCH,
+        runtime_args! {
+            ARG_IDS => ids.clone(),
+            ARG_STRIKE_PRICES => strike_prices.clone(),
+            ARG_EXPIRIES => expiries,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    for (id, strike_price) in ids.iter().zip(strike_prices) {
+        assert!(
+            contract_has_key(&builder, contract_hash, &format!("option_{}", id)),
+            "Option {} should exist",
+            id
+        );
+        assert_eq!(get_option_strike(&builder, contract_hash, *id), strike_price);
+    }
+
+    let count = get_option_count(&builder, contract_hash);
+    assert_eq!(count, 20, "Option count should be 20");
+}
+
+#[test]
+fn test_create_option_batch_length_mismatch_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION_BATCH,
+        runtime_args! {
+            ARG_IDS => vec![1u64, 2u64],
+            ARG_STRIKE_PRICES => vec![U256::from(1000u64)],
+            ARG_EXPIRIES => vec![1735689600u64, 1735689600u64],
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+
+    let count = get_option_count(&builder, contract_hash);
+    assert_eq!(count, 0, "Failed batch should not create options");
+}
+
+#[test]
+fn test_exercise_options_batch() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    for id in 0..3u64 {
+        create_option(&mut builder, contract_hash, id, U256::from(1000u64), 1735689600u64);
+    }
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTIONS_BATCH,
+        runtime_args! {
+            ARG_IDS => vec![0u64, 1u64, 2u64],
+        },
+    )
+    .expect_success()
+    .commit();
+
+    for id in 0..3u64 {
+        let exercised: bool =
+            get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, id);
+        assert!(exercised, "Option {} should be exercised", id);
+    }
+}
+
+#[test]
+fn test_exercise_options_batch_reverts_on_cancelled_option() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(&mut builder, contract_hash, 0, U256::from(1000u64), 1735689600u64);
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTIONS_BATCH,
+        runtime_args! {
+            ARG_IDS => vec![0u64, 1u64],
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_OPTION_CANCELLED);
+
+    let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 0);
+    assert!(!exercised, "Option 0 should not be exercised by a failed batch");
+}
+
+#[test]
+fn test_option_count_increments_once_per_batch_option() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    // One increment_u64 call per stored option
+    let ids: Vec<u64> = (0..1_000).collect();
+    let strike_prices: Vec<U256> = ids.iter().map(|_| U256::from(1000u64)).collect();
+    let expiries: Vec<u64> = ids.iter().map(|_| 1735689600u64).collect();
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION_BATCH,
+        runtime_args! {
+            ARG_IDS => ids,
+            ARG_STRIKE_PRICES => strike_prices,
+            ARG_EXPIRIES => expiries,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    assert_eq!(get_option_count(&builder, contract_hash), 1_000);
+}
+
+/// Creates and activates a call option with the given exercise style as the
+/// default account
+fn create_option_with_style(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    expiry: u64,
+    option_style: u8,
+) {
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_OPTION_STYLE => option_style,
+        },
+    )
+    .expect_success()
+    .commit();
+    activate_option(builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id)
+        .expect_success()
+        .commit();
+}
+
+/// Exercises option `id` as the default account at `block_time_secs`
+fn exercise_option_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_european_option_exercised_early_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option_with_style(&mut builder, contract_hash, 1, expiry, OPTION_STYLE_EUROPEAN);
+
+    exercise_option_at(&mut builder, contract_hash, 1, expiry - 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_EXERCISE_TIME);
+}
+
+#[test]
+fn test_european_option_exercised_on_expiry_succeeds() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option_with_style(&mut builder, contract_hash, 1, expiry, OPTION_STYLE_EUROPEAN);
+
+    exercise_option_at(&mut builder, contract_hash, 1, expiry)
+        .expect_success()
+        .commit();
+
+    let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 1);
+    assert!(exercised, "European option should be exercisable on expiry");
+}
+
+#[test]
+fn test_american_option_exercised_early_succeeds() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option_with_style(&mut builder, contract_hash, 1, expiry, OPTION_STYLE_AMERICAN);
+
+    exercise_option_at(&mut builder, contract_hash, 1, expiry - 86_400)
+        .expect_success()
+        .commit();
+
+    let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 1);
+    assert!(exercised, "American option should be exercisable before expiry");
+}
+
+#[test]
+fn test_american_option_exercised_on_expiry_succeeds() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option_with_style(&mut builder, contract_hash, 1, expiry, OPTION_STYLE_AMERICAN);
+
+    exercise_option_at(&mut builder, contract_hash, 1, expiry)
+        .expect_success()
+        .commit();
+
+    let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 1);
+    assert!(exercised, "American option should be exercisable on expiry");
+}
+
+/// Creates a call option with a premium, paying `amount` from the default
+/// account's main purse into escrow
+fn create_option_with_premium<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    expiry: u64,
+    premium: U512,
+    amount: U512,
+) -> &'a mut InMemoryWasmTestBuilder {
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_PREMIUM => premium,
+            ARG_PURSE => purse,
+            ARG_AMOUNT => amount,
+        },
+    )
+}
+
+#[test]
+fn test_premium_escrowed_on_create() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let premium = U512::from(5_000_000_000u64);
+
+    create_option_with_premium(&mut builder, contract_hash, 1, 1735689600u64, premium, premium)
+        .expect_success()
+        .commit();
+
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), premium);
+    let stored: U512 = get_contract_value(&builder, contract_hash, "option_1_premium");
+    assert_eq!(stored, premium);
+}
+
+#[test]
+fn test_price_validation_rejects_off_market_premiums() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_SET_PRICE_VALIDATION,
+        runtime_args! {
+            ARG_PRICE_VALIDATION_ENABLED => true,
+        },
+    )
+    .expect_success()
+    .commit();
+    // With no implied volatility on the surface the strike 1000 call is worth
+    // its intrinsic value, 600, give or take the default 10%
+    set_oracle_price(&mut builder, U256::from(1600u64));
+
+    let premium = U512::from(5_000_000_000u64);
+    create_option_with_premium(&mut builder, contract_hash, 1, expiry, premium, premium)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PREMIUM_DEVIATION_TOO_HIGH);
+
+    let premium = U512::from(620u64);
+    create_option_with_premium(&mut builder, contract_hash, 1, expiry, premium, premium)
+        .expect_success()
+        .commit();
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), premium);
+}
+
+#[test]
+fn test_premium_underpayment_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let premium = U512::from(5_000_000_000u64);
+
+    create_option_with_premium(
+        &mut builder,
+        contract_hash,
+        1,
+        1735689600u64,
+        premium,
+        premium - U512::one(),
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INCORRECT_PREMIUM);
+
+    assert!(!contract_has_key(&builder, contract_hash, "option_1"));
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::zero());
+}
+
+#[test]
+fn test_writer_claims_premium_after_expiry() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let premium = U512::from(5_000_000_000u64);
+
+    create_option_with_premium(&mut builder, contract_hash, 1, expiry, premium, premium)
+        .expect_success()
+        .commit();
+
+    call_entry_point_at(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CLAIM_PREMIUM,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+        expiry + 1,
+    )
+    .expect_success()
+    .commit();
+
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::zero());
+    let claimed: bool = get_contract_value(&builder, contract_hash, "option_1_premium_claimed");
+    assert!(claimed, "Premium should be marked as claimed");
+}
+
+/// Creates option 1 written by a second account and transferred to the
+/// default account as buyer, returning the writer
+fn setup_written_option(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    expiry: u64,
+) -> AccountHash {
+    let writer = create_funded_account(builder, 7);
+    let nonce = next_nonce(builder, contract_hash, writer);
+    call_entry_point(
+        builder,
+        writer,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+        },
+    )
+    .expect_success()
+    .commit();
+    transfer_option(builder, writer, contract_hash, 1, *DEFAULT_ACCOUNT_ADDR);
+    writer
+}
+
+#[test]
+fn test_create_option_naming_another_writer_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let victim = create_funded_account(&mut builder, 7);
+
+    let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_WRITER => victim,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_WRITER);
+    assert!(!contract_has_key(&builder, contract_hash, "option_1_writer"));
+}
+
+#[test]
+fn test_writer_and_buyer_stored_separately() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    let writer = setup_written_option(&mut builder, contract_hash, 1735689600u64);
+
+    let stored_writer: AccountHash = get_contract_value(&builder, contract_hash, "option_1_writer");
+    let stored_owner: AccountHash =
+        get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, 1);
+    assert_eq!(stored_writer, writer);
+    assert_eq!(stored_owner, *DEFAULT_ACCOUNT_ADDR);
+}
+
+#[test]
+fn test_writer_defaults_to_caller() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+
+    let stored_writer: AccountHash = get_contract_value(&builder, contract_hash, "option_1_writer");
+    assert_eq!(stored_writer, *DEFAULT_ACCOUNT_ADDR);
+}
+
+#[test]
+fn test_writer_cannot_exercise_or_transfer() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    let writer = setup_written_option(&mut builder, contract_hash, 1735689600u64);
+
+    call_entry_point(
+        &mut builder,
+        writer,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+
+    call_entry_point(
+        &mut builder,
+        writer,
+        contract_hash,
+        ENTRY_POINT_TRANSFER_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_NEW_OWNER => writer,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+}
+
+#[test]
+fn test_only_writer_can_claim_premium() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    let writer = setup_written_option(&mut builder, contract_hash, expiry);
+
+    call_entry_point_at(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CLAIM_PREMIUM,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+        expiry + 1,
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_WRITER);
+
+    call_entry_point_at(
+        &mut builder,
+        writer,
+        contract_hash,
+        ENTRY_POINT_CLAIM_PREMIUM,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+        expiry + 1,
+    )
+    .expect_success()
+    .commit();
+}
+
+#[test]
+fn test_transfer_moves_buyer_role_only() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    let writer = setup_written_option(&mut builder, contract_hash, 1735689600u64);
+    let new_owner = create_funded_account(&mut builder, 8);
+
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, new_owner);
+
+    let stored_writer: AccountHash = get_contract_value(&builder, contract_hash, "option_1_writer");
+    let stored_owner: AccountHash =
+        get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, 1);
+    assert_eq!(stored_writer, writer);
+    assert_eq!(stored_owner, new_owner);
+}
+
+#[test]
+fn test_pause_blocks_create_until_unpaused() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_PAUSE,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+    let paused: bool = get_contract_value(&builder, contract_hash, "paused");
+    assert!(paused, "Contract should be paused");
+
+    create_option_expect_failure(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000u64),
+        1735689600u64,
+    );
+    assert_user_error(&builder, ERROR_CONTRACT_PAUSED);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_UNPAUSE,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    assert_eq!(get_option_count(&builder, contract_hash), 1);
+}
+
+/// Calls `pause` as `sender` without asserting on the outcome
+fn pause_as(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(builder, sender, contract_hash, ENTRY_POINT_PAUSE, runtime_args! {})
+}
+
+#[test]
+fn test_non_admin_cannot_pause() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let other = create_funded_account(&mut builder, 2);
+
+    pause_as(&mut builder, other, contract_hash).expect_failure().commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+}
+
+#[test]
+fn test_admin_transfer_requires_acceptance() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let new_admin = create_funded_account(&mut builder, 2);
+    let stranger = create_funded_account(&mut builder, 3);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_TRANSFER_ADMIN,
+        runtime_args! {
+            ARG_NEW_ADMIN => new_admin,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    // Proposal alone does not hand over the role
+    let admin: AccountHash = get_contract_value(&builder, contract_hash, "option_registry_admin");
+    assert_eq!(admin, *DEFAULT_ACCOUNT_ADDR);
+
+    call_entry_point(
+        &mut builder,
+        stranger,
+        contract_hash,
+        ENTRY_POINT_ACCEPT_ADMIN,
+        runtime_args! {},
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_PENDING_ADMIN);
+
+    call_entry_point(
+        &mut builder,
+        new_admin,
+        contract_hash,
+        ENTRY_POINT_ACCEPT_ADMIN,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+
+    let admin: AccountHash = get_contract_value(&builder, contract_hash, "option_registry_admin");
+    assert_eq!(admin, new_admin);
+}
+
+#[test]
+fn test_old_admin_loses_privileges_after_transfer() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let new_admin = create_funded_account(&mut builder, 2);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_TRANSFER_ADMIN,
+        runtime_args! {
+            ARG_NEW_ADMIN => new_admin,
+        },
+    )
+    .expect_success()
+    .commit();
+    call_entry_point(
+        &mut builder,
+        new_admin,
+        contract_hash,
+        ENTRY_POINT_ACCEPT_ADMIN,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+
+    pause_as(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+
+    pause_as(&mut builder, new_admin, contract_hash).expect_success().commit();
+}
+
+/// Reads event `index` as `(event_type, option_id, (timestamp, actor))`
+fn get_event(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    index: u64,
+) -> (u8, u64, (u64, AccountHash)) {
+    get_contract_value(builder, contract_hash, &format!("event_{}", index))
+}
+
+#[test]
+fn test_initial_event_count_is_zero() {
+    let builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    let count: u64 = get_contract_value(&builder, contract_hash, EVENT_COUNTER_KEY);
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_create_and_exercise_emit_events() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(&mut builder, contract_hash, 7, U256::from(1000u64), 1735689600u64);
+    exercise_option(&mut builder, contract_hash, 7);
+
+    let count: u64 = get_contract_value(&builder, contract_hash, EVENT_COUNTER_KEY);
+    assert_eq!(count, 2);
+
+    let (event_type, option_id, (_, actor)) = get_event(&builder, contract_hash, 0);
+    assert_eq!(event_type, EVENT_OPTION_CREATED);
+    assert_eq!(option_id, 7);
+    assert_eq!(actor, *DEFAULT_ACCOUNT_ADDR);
+
+    let (event_type, option_id, _) = get_event(&builder, contract_hash, 1);
+    assert_eq!(event_type, EVENT_OPTION_EXERCISED);
+    assert_eq!(option_id, 7);
+}
+
+#[test]
+fn test_failed_call_emits_no_event() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    create_option_expect_failure(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000u64),
+        1735689600u64,
+    );
+
+    let count: u64 = get_contract_value(&builder, contract_hash, EVENT_COUNTER_KEY);
+    assert_eq!(count, 1);
+    assert!(!contract_has_key(&builder, contract_hash, "event_1"));
+}
+
+#[test]
+fn test_oracle_hash_stored_at_install() {
+    let builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    let oracle_hash: ContractHash = get_contract_value(&builder, contract_hash, ORACLE_KEY);
+    assert_eq!(oracle_hash, get_mock_oracle_hash(&builder));
+}
+
+#[test]
+fn test_mock_oracle_price_is_settable() {
+    let mut builder = setup_contract();
+    let oracle_hash = get_mock_oracle_hash(&builder);
+
+    let price: U256 = get_contract_value(&builder, oracle_hash, ARG_PRICE);
+    assert_eq!(price, U256::from(INITIAL_ORACLE_PRICE));
+
+    set_oracle_price(&mut builder, U256::from(2500u64));
+    let price: U256 = get_contract_value(&builder, oracle_hash, ARG_PRICE);
+    assert_eq!(price, U256::from(2500u64));
+}
+
+#[test]
+fn test_install_without_oracle_reverts() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST).commit();
+
+    let install_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_wasm_path().to_string_lossy(),
+        RuntimeArgs::new(),
+    )
+    .build();
+
+    builder.exec(install_request).expect_failure();
+}
+
+/// Calls `settle_option` for `id` as `sender` at `block_time_secs`
+fn settle_option_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_SETTLE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+        },
+        block_time_secs,
+    )
+}
+
+/// Returns the balance of `account`'s main purse
+fn get_account_balance(builder: &InMemoryWasmTestBuilder, account: AccountHash) -> U512 {
+    let purse = builder.get_expected_account(account).main_purse();
+    builder.get_purse_balance(purse)
+}
+
+#[test]
+fn test_settle_in_the_money_call_pays_owner() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 2);
+    let expiry = 1735689600u64;
+    let collateral = U512::from(5_000_000_000u64);
+
+    // One unit struck at 1000, whose collateral the payout comes from
+    create_collateralised_call(&mut builder, contract_hash, 1, expiry, U256::one(), collateral)
+        .expect_success()
+        .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    set_oracle_price(&mut builder, U256::from(1600u64));
+
+    // Expired and settled by a third party so the owner's balance only moves
+    // by the payout
+    let owner_before = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    expire_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    let payout = U512::from(600u64);
+    assert_eq!(
+        get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR),
+        owner_before + payout
+    );
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), collateral - payout);
+
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, payout);
+    let settled: bool = get_contract_value(&builder, contract_hash, "option_1_settled");
+    assert!(settled, "Option should be marked as settled");
+}
+
+#[test]
+fn test_settle_out_of_the_money_put_pays_nothing() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option_with_type(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1000u64),
+        expiry,
+        OPTION_TYPE_PUT,
+    );
+    set_oracle_price(&mut builder, U256::from(1600u64));
+
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, U512::zero());
+}
+
+#[test]
+fn test_settle_before_expiry_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), expiry);
+
+    // An American option can still be exercised on its expiry
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_OPTION_NOT_EXPIRED);
+
+    // Still Active, so there is nothing to settle yet
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_STATE_TRANSITION);
+}
+
+#[test]
+fn test_settle_twice_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    // Oracle price 1000 equals the strike, so the payout is zero
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), expiry);
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_OPTION_ALREADY_SETTLED);
+}
+
+/// Writes a call option of `option_amount` units at strike 1000 as the
+/// default account, paying `collateral` into the contract purse
+fn create_collateralised_call<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    expiry: u64,
+    option_amount: U256,
+    collateral: U512,
+) -> &'a mut InMemoryWasmTestBuilder {
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_OPTION_AMOUNT => option_amount,
+            ARG_PURSE => purse,
+            ARG_AMOUNT => collateral,
+        },
+    )
+}
+
+#[test]
+fn test_call_writer_locks_exact_collateral() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let collateral = U512::from(2000u64);
+
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        1735689600u64,
+        U256::from(2u64),
+        collateral,
+    )
+    .expect_success()
+    .commit();
+
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(locked, collateral);
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), collateral);
+    assert!(contract_has_key(&builder, contract_hash, "option_1_collateral_purse"));
+}
+
+#[test]
+fn test_call_writer_insufficient_collateral_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        1735689600u64,
+        U256::from(2u64),
+        U512::from(1999u64),
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_COLLATERAL);
+
+    assert!(!contract_has_key(&builder, contract_hash, "option_1"));
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::zero());
+}
+
+#[test]
+fn test_settlement_releases_collateral_to_owner() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let buyer = create_funded_account(&mut builder, 2);
+    let keeper = create_funded_account(&mut builder, 3);
+    let expiry = 1735689600u64;
+
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        expiry,
+        U256::from(2u64),
+        U512::from(2000u64),
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, buyer);
+    set_oracle_price(&mut builder, U256::from(1600u64));
+
+    let buyer_before = get_account_balance(&builder, buyer);
+    expire_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    // (1600 - 1000) * 2 units comes out of the writer's collateral
+    let payout = U512::from(1200u64);
+    assert_eq!(get_account_balance(&builder, buyer), buyer_before + payout);
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(locked, U512::from(800u64));
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, payout);
+}
+
+#[test]
+fn test_settlement_payout_capped_at_collateral() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        expiry,
+        U256::one(),
+        U512::from(1000u64),
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    set_oracle_price(&mut builder, U256::from(5000u64));
+
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, U512::from(1000u64));
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(locked, U512::zero());
+}
+
+/// Calls `withdraw_collateral` for `id` as the default account at `block_time_secs`
+fn withdraw_collateral_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_WITHDRAW_COLLATERAL,
+        runtime_args! {
+            ARG_ID => id,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_writer_adds_collateral() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        1735689600u64,
+        U256::one(),
+        U512::from(1000u64),
+    )
+    .expect_success()
+    .commit();
+
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_ADD_COLLATERAL,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_PURSE => purse,
+            ARG_AMOUNT => U512::from(500u64),
+        },
+    )
+    .expect_success()
+    .commit();
+
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(locked, U512::from(1500u64));
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::from(1500u64));
+}
+
+#[test]
+fn test_withdraw_collateral_before_expiry_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        expiry,
+        U256::one(),
+        U512::from(1000u64),
+    )
+    .expect_success()
+    .commit();
+
+    withdraw_collateral_at(&mut builder, contract_hash, 1, expiry - 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_OPTION_NOT_EXPIRED);
+}
+
+#[test]
+fn test_uncollateralised_option_settles_for_nothing() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let attacker = create_funded_account(&mut builder, 2);
+
+    // Another writer's collateral sits in the contract purse
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        1735689600,
+        U256::one(),
+        U512::from(1000u64),
+    )
+    .expect_success()
+    .commit();
+
+    // A self-written, unsized put struck far above the oracle's 1000 that has
+    // already expired
+    let strike_price = U256::from(1_000_000u64);
+    create_option_as(&mut builder, attacker, contract_hash, 2, strike_price, 1, OPTION_TYPE_PUT);
+    expire_option_at(&mut builder, attacker, contract_hash, 2, 2)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, attacker, contract_hash, 2, 2)
+        .expect_success()
+        .commit();
+
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_2_settlement");
+    assert_eq!(settlement, U512::zero());
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::from(1000u64));
+}
+
+#[test]
+fn test_put_writer_locks_strike_times_amount() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+
+    // Two units struck at 1000 need 2000
+    let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_PUT,
+            ARG_NONCE => nonce,
+            ARG_OPTION_AMOUNT => U256::from(2u64),
+            ARG_PURSE => purse,
+            ARG_AMOUNT => U512::from(1999u64),
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_COLLATERAL);
+
+    create_put_of_size(&mut builder, contract_hash, 1, 2).expect_success().commit();
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(locked, U512::from(2000u64));
+}
+
+#[test]
+fn test_withdraw_collateral_after_expiry_requires_settlement() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        expiry,
+        U256::one(),
+        U512::from(1000u64),
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    set_oracle_price(&mut builder, U256::from(1300u64));
+
+    withdraw_collateral_at(&mut builder, contract_hash, 1, expiry + 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_OPTION_NOT_SETTLED);
+
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    withdraw_collateral_at(&mut builder, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    // 300 went to the owner on settlement; the remaining 700 went back to the writer
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(locked, U512::zero());
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::zero());
+}
+
+/// Installs the mock CEP-18 token and mints `supply` to the default account
+fn setup_mock_cep18(builder: &mut InMemoryWasmTestBuilder, supply: U256) -> ContractHash {
+    let install_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_mock_cep18_wasm_path().to_string_lossy(),
+        RuntimeArgs::new(),
+    )
+    .build();
+    builder.exec(install_request).expect_success().commit();
+
+    let token_hash = builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(MOCK_CEP18_KEY)
+        .expect("Mock CEP-18 not found")
+        .into_hash()
+        .map(ContractHash::new)
+        .expect("Invalid mock CEP-18 hash");
+
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        token_hash,
+        ENTRY_POINT_MINT,
+        runtime_args! {
+            ARG_OWNER => Key::Account(*DEFAULT_ACCOUNT_ADDR),
+            ARG_AMOUNT => supply,
+        },
+    )
+    .expect_success()
+    .commit();
+    token_hash
+}
+
+/// Reads `account`'s balance from the mock CEP-18 token
+fn get_token_balance(
+    builder: &InMemoryWasmTestBuilder,
+    token_hash: ContractHash,
+    account: AccountHash,
+) -> U256 {
+    let key_name = format!("balance_{}", Key::Account(account).to_formatted_string());
+    if contract_has_key(builder, token_hash, &key_name) {
+        get_contract_value(builder, token_hash, &key_name)
+    } else {
+        U256::zero()
+    }
+}
+
+/// Writes and activates a call over 5 mock CEP-18 tokens as the default account
+fn create_physical_call(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    token_hash: ContractHash,
+    id: u64,
+    expiry: u64,
+) {
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_UNDERLYING_TOKEN => Key::Hash(token_hash.value()),
+            ARG_UNDERLYING_AMOUNT => U256::from(5u64),
+        },
+    )
+    .expect_success()
+    .commit();
+    activate_option(builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id)
+        .expect_success()
+        .commit();
+}
+
+#[test]
+fn test_physical_call_delivers_underlying_on_settlement() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let token_hash = setup_mock_cep18(&mut builder, U256::from(10u64));
+    let buyer = create_funded_account(&mut builder, 2);
+    let expiry = 1735689600u64;
+
+    create_physical_call(&mut builder, contract_hash, token_hash, 1, expiry);
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, buyer);
+    set_oracle_price(&mut builder, U256::from(1600u64));
+
+    expire_option_at(&mut builder, buyer, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, buyer, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    assert_eq!(get_token_balance(&builder, token_hash, buyer), U256::from(5u64));
+    assert_eq!(
+        get_token_balance(&builder, token_hash, *DEFAULT_ACCOUNT_ADDR),
+        U256::from(5u64)
+    );
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, U512::zero());
+}
+
+#[test]
+fn test_out_of_the_money_physical_call_delivers_nothing() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let token_hash = setup_mock_cep18(&mut builder, U256::from(10u64));
+    let buyer = create_funded_account(&mut builder, 2);
+    let expiry = 1735689600u64;
+
+    create_physical_call(&mut builder, contract_hash, token_hash, 1, expiry);
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, buyer);
+    set_oracle_price(&mut builder, U256::from(900u64));
+
+    expire_option_at(&mut builder, buyer, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, buyer, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    assert_eq!(get_token_balance(&builder, token_hash, buyer), U256::zero());
+    assert_eq!(
+        get_token_balance(&builder, token_hash, *DEFAULT_ACCOUNT_ADDR),
+        U256::from(10u64)
+    );
+}
+
+/// Exercises `exercise_amount` units of option `id` as the default account
+fn exercise_amount_of(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    exercise_amount: U256,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_EXERCISE_AMOUNT => exercise_amount,
+        },
+    )
+}
+
+/// Creates and activates a put over `option_amount` units at strike 1000,
+/// locking the `1000 * option_amount` collateral it needs
+fn create_sized_put(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    option_amount: U256,
+) {
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_PUT,
+            ARG_NONCE => nonce,
+            ARG_OPTION_AMOUNT => option_amount,
+            ARG_PURSE => purse,
+            ARG_AMOUNT => U512::from(1000 * option_amount.as_u64()),
+        },
+    )
+    .expect_success()
+    .commit();
+    activate_option(builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id)
+        .expect_success()
+        .commit();
+}
+
+#[test]
+fn test_partial_then_full_exercise() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_sized_put(&mut builder, contract_hash, 1, U256::from(10u64));
+
+    exercise_amount_of(&mut builder, contract_hash, 1, U256::from(4u64))
+        .expect_success()
+        .commit();
+    let exercised_amount: U256 =
+        get_contract_value(&builder, contract_hash, "option_1_exercised_amount");
+    assert_eq!(exercised_amount, U256::from(4u64));
+    let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 1);
+    assert!(!exercised, "Option should stay open after a partial exercise");
+
+    exercise_amount_of(&mut builder, contract_hash, 1, U256::from(6u64))
+        .expect_success()
+        .commit();
+    let exercised_amount: U256 =
+        get_contract_value(&builder, contract_hash, "option_1_exercised_amount");
+    assert_eq!(exercised_amount, U256::from(10u64));
+    let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 1);
+    assert!(exercised, "Option should be exercised once every unit is");
+}
+
+#[test]
+fn test_exercise_more_than_remaining_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_sized_put(&mut builder, contract_hash, 1, U256::from(10u64));
+    exercise_amount_of(&mut builder, contract_hash, 1, U256::from(7u64))
+        .expect_success()
+        .commit();
+
+    exercise_amount_of(&mut builder, contract_hash, 1, U256::from(4u64))
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_EXCEEDS_OPTION_AMOUNT);
+}
+
+#[test]
+fn test_exercise_without_amount_exercises_remainder() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_sized_put(&mut builder, contract_hash, 1, U256::from(10u64));
+    exercise_amount_of(&mut builder, contract_hash, 1, U256::from(3u64))
+        .expect_success()
+        .commit();
+
+    exercise_option(&mut builder, contract_hash, 1);
+
+    let exercised_amount: U256 =
+        get_contract_value(&builder, contract_hash, "option_1_exercised_amount");
+    assert_eq!(exercised_amount, U256::from(10u64));
+}
+
+/// Creates and activates call option `id` in `series_id` as the default account
+fn create_option_in_series(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    series_id: u64,
+) {
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_SERIES_ID => series_id,
+        },
+    )
+    .expect_success()
+    .commit();
+    activate_option(builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id)
+        .expect_success()
+        .commit();
+}
+
+/// Reads the option ids recorded for `series_id`, in creation order
+fn get_series_options(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    series_id: u64,
+) -> Vec<u64> {
+    let count_key = format!("series_{}_count", series_id);
+    if !contract_has_key(builder, contract_hash, &count_key) {
+        return Vec::new();
+    }
+    let count: u64 = get_contract_value(builder, contract_hash, &count_key);
+    (0..count)
+        .map(|index| {
+            get_contract_value(builder, contract_hash, &format!("series_{}_{}", series_id, index))
+        })
+        .collect()
+}
+
+#[test]
+fn test_series_are_retrieved_independently() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    // Interleave the two series so their indices are independent
+    for i in 0..5u64 {
+        create_option_in_series(&mut builder, contract_hash, 100 + i, 1);
+        create_option_in_series(&mut builder, contract_hash, 200 + i, 2);
+    }
+
+    assert_eq!(
+        get_series_options(&builder, contract_hash, 1),
+        vec![100, 101, 102, 103, 104]
+    );
+    assert_eq!(
+        get_series_options(&builder, contract_hash, 2),
+        vec![200, 201, 202, 203, 204]
+    );
+    assert!(get_series_options(&builder, contract_hash, 3).is_empty());
+
+    let series: Option<u64> = get_contract_value(&builder, contract_hash, "option_102_series");
+    assert_eq!(series, Some(1));
+}
+
+#[test]
+fn test_cancel_series_skips_exercised_options() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    for id in 1..=5u64 {
+        create_option_in_series(&mut builder, contract_hash, id, 7);
+    }
+    create_option_in_series(&mut builder, contract_hash, 6, 8);
+    exercise_option(&mut builder, contract_hash, 3);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CANCEL_SERIES,
+        runtime_args! {
+            ARG_SERIES_ID => 7u64,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    for id in [1u64, 2, 4, 5] {
+        let cancelled: bool =
+            get_contract_value(&builder, contract_hash, &format!("option_{}_cancelled", id));
+        assert!(cancelled, "Option {} should be cancelled", id);
+    }
+    let cancelled: bool = get_contract_value(&builder, contract_hash, "option_3_cancelled");
+    assert!(!cancelled, "Exercised option should not be cancelled");
+    let cancelled: bool = get_contract_value(&builder, contract_hash, "option_6_cancelled");
+    assert!(!cancelled, "Options in other series should not be cancelled");
+}
+
+/// Calls `get_all_options` for one page, then reads back the ids that page
+/// covers from the creation-order index, returning them with the total
+fn get_all_options_page(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    offset: u64,
+    limit: u64,
+) -> (Vec<u64>, u64) {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_ALL_OPTIONS,
+        runtime_args! {
+            ARG_OFFSET => offset,
+            ARG_LIMIT => limit,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    let total = get_option_count(builder, contract_hash);
+    let end = offset.saturating_add(limit).min(total);
+    let ids = (offset..end)
+        .map(|index| get_contract_value(builder, contract_hash, &format!("all_options_{}", index)))
+        .collect();
+    (ids, total)
+}
+
+#[test]
+fn test_get_all_options_pages_without_gaps() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    // Ids deliberately out of order to show paging follows creation order
+    let ids: Vec<u64> = (0..50).map(|i| (i * 7) % 50 + 1000).collect();
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION_BATCH,
+        runtime_args! {
+            ARG_IDS => ids.clone(),
+            ARG_STRIKE_PRICES => vec![U256::from(1000u64); 50],
+            ARG_EXPIRIES => vec![1735689600u64; 50],
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    let mut seen = Vec::new();
+    let mut offset = 0;
+    loop {
+        let (page, total) = get_all_options_page(&mut builder, contract_hash, offset, 10);
+        assert_eq!(total, 50);
+        if page.is_empty() {
+            break;
+        }
+        assert!(page.len() <= 10);
+        offset += page.len() as u64;
+        seen.extend(page);
+    }
+
+    assert_eq!(seen, ids, "Pages should cover every option once, in creation order");
+}
+
+#[test]
+fn test_get_series_options_accepts_page_past_end() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    for id in 1..=3u64 {
+        create_option_in_series(&mut builder, contract_hash, id, 9);
+    }
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_SERIES_OPTIONS,
+        runtime_args! {
+            ARG_SERIES_ID => 9u64,
+            ARG_OFFSET => 10u64,
+            ARG_LIMIT => 10u64,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+/// Reads the option ids in one `expiry_index` day bucket
+fn get_expiry_bucket(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    bucket: u64,
+) -> Vec<u64> {
+    let index_uref = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get("expiry_index")
+        .expect("Expiry index not found")
+        .into_uref()
+        .expect("Expiry index is not a URef");
+
+    match builder.query_dictionary_item(None, index_uref, &bucket.to_string()) {
+        Ok(value) => value
+            .as_cl_value()
+            .expect("Not a CLValue")
+            .clone()
+            .into_t::<Vec<u64>>()
+            .expect("Failed to parse bucket"),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Calls `get_options_expiring_between`, then resolves the same range from
+/// the expiry index and each option's stored expiry
+fn get_options_expiring_between(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    from_expiry: u64,
+    to_expiry: u64,
+) -> Vec<u64> {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_OPTIONS_EXPIRING_BETWEEN,
+        runtime_args! {
+            ARG_FROM_EXPIRY => from_expiry,
+            ARG_TO_EXPIRY => to_expiry,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    let builder: &InMemoryWasmTestBuilder = builder;
+    ((from_expiry / DAY_IN_SECONDS)..=(to_expiry / DAY_IN_SECONDS))
+        .flat_map(|bucket| get_expiry_bucket(builder, contract_hash, bucket))
+        .filter(|id| {
+            let expiry: u64 =
+                get_option_field(builder, contract_hash, OPTIONS_EXPIRY_DICT, *id);
+            (from_expiry..=to_expiry).contains(&expiry)
+        })
+        .collect()
+}
+
+#[test]
+fn test_expiry_index_buckets_by_day() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let day = 1735689600u64 / DAY_IN_SECONDS;
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), day * DAY_IN_SECONDS);
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), day * DAY_IN_SECONDS + 3600);
+    create_option(&mut builder, contract_hash, 3, U256::from(1000u64), (day + 1) * DAY_IN_SECONDS);
+
+    assert_eq!(get_expiry_bucket(&builder, contract_hash, day), vec![1, 2]);
+    assert_eq!(get_expiry_bucket(&builder, contract_hash, day + 1), vec![3]);
+}
+
+#[test]
+fn test_options_expiring_between_returns_subset() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let start = 1735689600u64;
+
+    // Expiries at day 0, day 0 + 12h, day 1, day 3 and day 10
+    let expiries = [
+        start,
+        start + DAY_IN_SECONDS / 2,
+        start + DAY_IN_SECONDS,
+        start + 3 * DAY_IN_SECONDS,
+        start + 10 * DAY_IN_SECONDS,
+    ];
+    for (id, expiry) in expiries.iter().enumerate() {
+        create_option(&mut builder, contract_hash, id as u64, U256::from(1000u64), *expiry);
+    }
+
+    // Starts mid-bucket, so the day-0 option at `start` is excluded
+    let ids = get_options_expiring_between(
+        &mut builder,
+        contract_hash,
+        start + 1,
+        start + 3 * DAY_IN_SECONDS,
+    );
+    assert_eq!(ids, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_options_expiring_between_rejects_inverted_range() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_OPTIONS_EXPIRING_BETWEEN,
+        runtime_args! {
+            ARG_FROM_EXPIRY => 2000u64,
+            ARG_TO_EXPIRY => 1000u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+}
+
+/// Reads `owner`'s live option ids from the portfolio index, skipping
+/// tombstoned slots
+fn get_portfolio(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    owner: AccountHash,
+) -> Vec<u64> {
+    let prefix: String = owner.value().iter().map(|byte| format!("{:02x}", byte)).collect();
+    let count_key = format!("owner_{}_count", prefix);
+    if !contract_has_key(builder, contract_hash, &count_key) {
+        return Vec::new();
+    }
+    let count: u64 = get_contract_value(builder, contract_hash, &count_key);
+    (0..count)
+        .filter_map(|index| {
+            get_contract_value::<Option<u64>>(
+                builder,
+                contract_hash,
+                &format!("owner_{}_{}", prefix, index),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_portfolios_follow_transfers() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let alice = *DEFAULT_ACCOUNT_ADDR;
+    let bob = create_funded_account(&mut builder, 2);
+
+    let strike_price = U256::from(1000u64);
+    let expiry = 1735689600u64;
+    for id in 0..10u64 {
+        create_option_as(
+            &mut builder, alice, contract_hash, id, strike_price, expiry, OPTION_TYPE_CALL,
+        );
+        create_option_as(
+            &mut builder, bob, contract_hash, 100 + id, strike_price, expiry, OPTION_TYPE_PUT,
+        );
+    }
+
+    transfer_option(&mut builder, alice, contract_hash, 3, bob);
+    transfer_option(&mut builder, alice, contract_hash, 7, bob);
+    transfer_option(&mut builder, bob, contract_hash, 105, alice);
+    // Round trip back to bob leaves a tombstone in alice's portfolio too
+    transfer_option(&mut builder, bob, contract_hash, 3, alice);
+    transfer_option(&mut builder, alice, contract_hash, 3, bob);
+
+    assert_eq!(
+        get_portfolio(&builder, contract_hash, alice),
+        vec![0, 1, 2, 4, 5, 6, 8, 9, 105]
+    );
+    assert_eq!(
+        get_portfolio(&builder, contract_hash, bob),
+        vec![100, 101, 102, 103, 104, 106, 107, 108, 109, 7, 3]
+    );
+
+    call_entry_point(
+        &mut builder,
+        bob,
+        contract_hash,
+        ENTRY_POINT_GET_OPTIONS_BY_OWNER,
+        runtime_args! {
+            ARG_OWNER => alice,
+            ARG_OFFSET => 0u64,
+            ARG_LIMIT => 5u64,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+/// Calls `create_option` for a call option with an explicit `nonce`
+fn create_option_with_nonce(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    nonce: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+        },
+    )
+}
+
+#[test]
+fn test_sequential_nonces_succeed() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    for (id, nonce) in [(1u64, 1u64), (2, 2), (3, 3)] {
+        create_option_with_nonce(&mut builder, contract_hash, id, nonce)
+            .expect_success()
+            .commit();
+    }
+
+    assert_eq!(get_last_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR), Some(3));
+}
+
+#[test]
+fn test_reused_nonce_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option_with_nonce(&mut builder, contract_hash, 1, 5)
+        .expect_success()
+        .commit();
+
+    // Same nonce for a fresh id: rejected as a replay, not as a duplicate id
+    create_option_with_nonce(&mut builder, contract_hash, 2, 5)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_NONCE);
+    assert!(!contract_has_key(&builder, contract_hash, "option_2"));
+}
+
+#[test]
+fn test_out_of_order_nonce_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option_with_nonce(&mut builder, contract_hash, 1, 10)
+        .expect_success()
+        .commit();
+
+    create_option_with_nonce(&mut builder, contract_hash, 2, 9)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_NONCE);
+    assert_eq!(get_last_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR), Some(10));
+}
+
+#[test]
+fn test_nonces_are_tracked_per_caller() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let other = create_funded_account(&mut builder, 2);
+
+    create_option_with_nonce(&mut builder, contract_hash, 1, 1)
+        .expect_success()
+        .commit();
+    // Another account starts its own sequence
+    create_option_as(
+        &mut builder, other, contract_hash, 2, U256::from(1000u64), 1735689600u64, OPTION_TYPE_PUT,
+    );
+
+    assert_eq!(get_last_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR), Some(1));
+    assert_eq!(get_last_nonce(&builder, contract_hash, other), Some(0));
+}
+
+/// Derives an ed25519 key pair from `seed`
+fn ed25519_key_pair(seed: u8) -> (SecretKey, PublicKey) {
+    let secret_key = SecretKey::ed25519_from_bytes([seed; 32]).expect("Invalid secret key");
+    let public_key = PublicKey::from(&secret_key);
+    (secret_key, public_key)
+}
+
+/// Returns the raw 32-byte ed25519 public key passed as `signer`
+fn signer_bytes(public_key: &PublicKey) -> [u8; 32] {
+    Vec::<u8>::from(public_key)
+        .try_into()
+        .expect("Not an ed25519 public key")
+}
+
+/// Signs `blake2b((id, nonce, contract_hash))` for the option's current
+/// signed-exercise nonce, as an owner would off-chain
+fn sign_exercise(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    secret_key: &SecretKey,
+    id: u64,
+) -> [u8; 64] {
+    let nonce_key = format!("signed_exercise_nonce_{}", id);
+    let nonce: u64 = if contract_has_key(builder, contract_hash, &nonce_key) {
+        get_contract_value(builder, contract_hash, &nonce_key)
+    } else {
+        0
+    };
+    let message = (id, nonce, contract_hash).to_bytes().expect("Failed to serialize message");
+    let public_key = PublicKey::from(secret_key);
+    let signature = crypto::sign(crypto::blake2b(message), secret_key, &public_key);
+    Vec::<u8>::from(&signature)
+        .try_into()
+        .expect("Not an ed25519 signature")
+}
+
+/// Submits a signed exercise of option `id` from the default account acting as keeper
+fn exercise_with_signature(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    signature: [u8; 64],
+    signer: [u8; 32],
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_WITH_SIGNATURE,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_SIGNATURE => signature,
+            ARG_SIGNER => signer,
+        },
+    )
+}
+
+#[test]
+fn test_exercise_with_signature_by_keeper() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let (secret_key, public_key) = ed25519_key_pair(9);
+    let owner = AccountHash::from(&public_key);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, owner);
+
+    let signature = sign_exercise(&builder, contract_hash, &secret_key, 1);
+    exercise_with_signature(&mut builder, contract_hash, 1, signature, signer_bytes(&public_key))
+        .expect_success()
+        .commit();
+
+    assert!(is_option_exercised(&builder, contract_hash, 1));
+    let nonce: u64 = get_contract_value(&builder, contract_hash, "signed_exercise_nonce_1");
+    assert_eq!(nonce, 1);
+}
+
+#[test]
+fn test_replayed_exercise_signature_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let (secret_key, public_key) = ed25519_key_pair(9);
+    let owner = AccountHash::from(&public_key);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, owner);
+
+    let signature = sign_exercise(&builder, contract_hash, &secret_key, 1);
+    exercise_with_signature(&mut builder, contract_hash, 1, signature, signer_bytes(&public_key))
+        .expect_success()
+        .commit();
+
+    // The nonce moved on, so the same signature no longer verifies
+    exercise_with_signature(&mut builder, contract_hash, 1, signature, signer_bytes(&public_key))
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_SIGNATURE);
+}
+
+#[test]
+fn test_exercise_signature_for_other_option_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let (secret_key, public_key) = ed25519_key_pair(9);
+    let owner = AccountHash::from(&public_key);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), 1735689600u64);
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, owner);
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 2, owner);
+
+    let signature = sign_exercise(&builder, contract_hash, &secret_key, 2);
+    exercise_with_signature(&mut builder, contract_hash, 1, signature, signer_bytes(&public_key))
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_SIGNATURE);
+    assert!(!is_option_exercised(&builder, contract_hash, 1));
+}
+
+#[test]
+fn test_exercise_signature_from_non_owner_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let (secret_key, public_key) = ed25519_key_pair(9);
+
+    // The default account still owns the option, so a valid signature from
+    // another key does not authorise exercising it
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+
+    let signature = sign_exercise(&builder, contract_hash, &secret_key, 1);
+    exercise_with_signature(&mut builder, contract_hash, 1, signature, signer_bytes(&public_key))
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+}
+
+#[test]
+fn test_state_created_active_exercised_settled() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option_with_nonce(&mut builder, contract_hash, 1, 0)
+        .expect_success()
+        .commit();
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_CREATED);
+
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_ACTIVE);
+
+    exercise_option(&mut builder, contract_hash, 1);
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_EXERCISED);
+
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_SETTLED);
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, U512::zero());
+}
+
+#[test]
+fn test_state_active_expired_settled() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 2);
+    let expiry = 1735689600u64;
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), expiry);
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_ACTIVE);
+
+    expire_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_EXPIRED);
+
+    settle_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_SETTLED);
+}
+
+#[test]
+fn test_state_created_and_active_can_be_cancelled() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option_with_nonce(&mut builder, contract_hash, 1, 0)
+        .expect_success()
+        .commit();
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), 1735689600u64);
+
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1);
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 2);
+
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_CANCELLED);
+    assert_eq!(get_option_state(&builder, contract_hash, 2), OPTION_STATE_CANCELLED);
+}
+
+#[test]
+fn test_invalid_state_transitions_revert() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option_with_nonce(&mut builder, contract_hash, 1, 0)
+        .expect_success()
+        .commit();
+
+    // Only Active options expire
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_STATE_TRANSITION);
+
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_STATE_TRANSITION);
+
+    // An Expired option can no longer be exercised
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INVALID_STATE_TRANSITION);
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_EXPIRED);
+}
+
+#[test]
+fn test_only_buyer_can_activate() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let other = create_funded_account(&mut builder, 2);
+
+    create_option_with_nonce(&mut builder, contract_hash, 1, 0)
+        .expect_success()
+        .commit();
+
+    activate_option(&mut builder, other, contract_hash, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_CREATED);
+}
+
+/// Reads the option ids in one `state_index` bucket, slot by slot up to the
+/// state's count
+fn get_state_bucket(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    state: u8,
+) -> Vec<u64> {
+    let count_keys = [
+        "created_option_count",
+        "active_option_count",
+        "exercised_option_count",
+        "expired_option_count",
+        "cancelled_option_count",
+        "settled_option_count",
+    ];
+    let count: u64 = get_contract_value(builder, contract_hash, count_keys[usize::from(state)]);
+    let index_uref = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get("state_index")
+        .expect("State index not found")
+        .into_uref()
+        .expect("State index is not a URef");
+
+    (0..count)
+        .map(|slot| {
+            builder
+                .query_dictionary_item(None, index_uref, &format!("{}_{}", state, slot))
+                .expect("State slot not found")
+                .as_cl_value()
+                .expect("Not a CLValue")
+                .clone()
+                .into_t::<u64>()
+                .expect("Failed to parse state slot")
+        })
+        .collect()
+}
+
+/// Calls `get_options_in_state`, then resolves the same bucket from the
+/// state index
+fn get_options_in_state(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    state: u8,
+) -> Vec<u64> {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_OPTIONS_IN_STATE,
+        runtime_args! {
+            ARG_STATE => state,
+            ARG_OFFSET => 0u64,
+            ARG_LIMIT => 100u64,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    get_state_bucket(builder, contract_hash, state)
+}
+
+#[test]
+fn test_options_in_state_track_transitions() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), expiry);
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), expiry);
+    create_option(&mut builder, contract_hash, 3, U256::from(1000u64), expiry);
+    let active = get_options_in_state(&mut builder, contract_hash, OPTION_STATE_ACTIVE);
+    assert_eq!(active, vec![1, 2, 3]);
+
+    // The last id moves into the slot an option leaves
+    exercise_option(&mut builder, contract_hash, 1);
+    let active = get_options_in_state(&mut builder, contract_hash, OPTION_STATE_ACTIVE);
+    assert_eq!(active, vec![3, 2]);
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 2, expiry + 1)
+        .expect_success()
+        .commit();
+
+    let expected: [(u8, Vec<u64>); 5] = [
+        (OPTION_STATE_CREATED, vec![]),
+        (OPTION_STATE_ACTIVE, vec![3]),
+        (OPTION_STATE_EXERCISED, vec![1]),
+        (OPTION_STATE_EXPIRED, vec![2]),
+        (OPTION_STATE_SETTLED, vec![]),
+    ];
+    for (state, ids) in expected {
+        assert_eq!(get_options_in_state(&mut builder, contract_hash, state), ids);
+    }
+}
+
+#[test]
+fn test_get_option_state_entry_point() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_OPTION_STATE,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_OPTION_STATE,
+        runtime_args! {
+            ARG_ID => 99u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_OPTION_NOT_FOUND);
+}
+
+#[test]
+fn test_options_in_unknown_state_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_OPTIONS_IN_STATE,
+        runtime_args! {
+            ARG_STATE => OPTION_STATE_SETTLED + 1,
+            ARG_OFFSET => 0u64,
+            ARG_LIMIT => 100u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+}
+
+#[test]
+fn test_state_counts_track_transitions() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    for id in 1..=5 {
+        create_option(&mut builder, contract_hash, id, U256::from(1000u64), expiry);
+    }
+    // Option 6 is never activated
+    create_put_of_size(&mut builder, contract_hash, 6, 1)
+        .expect_success()
+        .commit();
+
+    exercise_option(&mut builder, contract_hash, 1);
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 2);
+    for id in [3, 4] {
+        expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id, expiry + 1)
+            .expect_success()
+            .commit();
+    }
+    // Out of the money, so settling pays nothing
+    set_oracle_price(&mut builder, U256::from(500u64));
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 4, expiry + 1)
+        .expect_success()
+        .commit();
+
+    let expected = [
+        (ENTRY_POINT_GET_ACTIVE_OPTION_COUNT, "active_option_count"),
+        (ENTRY_POINT_GET_EXERCISED_OPTION_COUNT, "exercised_option_count"),
+        (ENTRY_POINT_GET_EXPIRED_OPTION_COUNT, "expired_option_count"),
+        (ENTRY_POINT_GET_CANCELLED_OPTION_COUNT, "cancelled_option_count"),
+        (ENTRY_POINT_GET_SETTLED_OPTION_COUNT, "settled_option_count"),
+    ];
+    // The total also counts option 6, still Created
+    let mut total: u64 = get_contract_value(&builder, contract_hash, "created_option_count");
+    assert_eq!(total, 1);
+    for (entry_point, key) in expected {
+        call_entry_point(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            entry_point,
+            runtime_args! {},
+        )
+        .expect_success()
+        .commit();
+        let count: u64 = get_contract_value(&builder, contract_hash, key);
+        assert_eq!(count, 1, "{}", key);
+        total += count;
+    }
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_TOTAL_OPTION_COUNT,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(total, get_option_count(&builder, contract_hash));
+}
+
+/// Queries `is_exercisable` and `exercise_revert_reason` for `caller` at
+/// `block_time_secs`, asserting neither reverts, then attempts the exercise
+/// itself, which shares the queries' precondition checks
+fn exercise_checked<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    caller: AccountHash,
+    id: u64,
+    block_time_secs: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    for entry_point in [ENTRY_POINT_IS_EXERCISABLE, ENTRY_POINT_EXERCISE_REVERT_REASON] {
+        call_entry_point_at(
+            builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            entry_point,
+            runtime_args! {
+                ARG_ID => id,
+                ARG_CALLER => caller,
+            },
+            block_time_secs,
+        )
+        .expect_success()
+        .commit();
+    }
+
+    call_entry_point_at(
+        builder,
+        caller,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+        },
+        block_time_secs,
+    )
+}
+
+/// Asserts option `id` is not exercisable by `caller` at `block_time_secs`,
+/// for the reason `expected_code`
+fn assert_exercise_blocked(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    caller: AccountHash,
+    id: u64,
+    block_time_secs: u64,
+    expected_code: u16,
+) {
+    exercise_checked(builder, contract_hash, caller, id, block_time_secs)
+        .expect_failure()
+        .commit();
+    assert_user_error(builder, expected_code);
+}
+
+#[test]
+fn test_is_exercisable_when_all_checks_pass() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+
+    exercise_checked(&mut builder, contract_hash, *DEFAULT_ACCOUNT_ADDR, 1, 0)
+        .expect_success()
+        .commit();
+}
+
+#[test]
+fn test_is_exercisable_blocking_conditions() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let other = create_funded_account(&mut builder, 2);
+    let owner = *DEFAULT_ACCOUNT_ADDR;
+    let expiry = 1735689600u64;
+
+    assert_exercise_blocked(&mut builder, contract_hash, owner, 99, 0, ERROR_OPTION_NOT_FOUND);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), expiry);
+    assert_exercise_blocked(&mut builder, contract_hash, other, 1, 0, ERROR_NOT_OPTION_OWNER);
+
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), expiry);
+    cancel_option(&mut builder, owner, contract_hash, 2);
+    assert_exercise_blocked(&mut builder, contract_hash, owner, 2, 0, ERROR_OPTION_CANCELLED);
+
+    create_option(&mut builder, contract_hash, 3, U256::from(1000u64), expiry);
+    exercise_option(&mut builder, contract_hash, 3);
+    let code = ERROR_OPTION_ALREADY_EXERCISED;
+    assert_exercise_blocked(&mut builder, contract_hash, owner, 3, 0, code);
+
+    create_option(&mut builder, contract_hash, 4, U256::from(1000u64), expiry);
+    expire_option_at(&mut builder, owner, contract_hash, 4, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, owner, contract_hash, 4, expiry + 1)
+        .expect_success()
+        .commit();
+    let code = ERROR_OPTION_ALREADY_SETTLED;
+    assert_exercise_blocked(&mut builder, contract_hash, owner, 4, expiry + 1, code);
+
+    // Created but never activated
+    let nonce = next_nonce(&builder, contract_hash, owner);
+    create_option_with_nonce(&mut builder, contract_hash, 5, nonce)
+        .expect_success()
+        .commit();
+    let code = ERROR_INVALID_STATE_TRANSITION;
+    assert_exercise_blocked(&mut builder, contract_hash, owner, 5, 0, code);
+
+    create_option_with_style(&mut builder, contract_hash, 6, expiry, OPTION_STYLE_EUROPEAN);
+    let code = ERROR_INVALID_EXERCISE_TIME;
+    assert_exercise_blocked(&mut builder, contract_hash, owner, 6, expiry - 1, code);
+    let code = ERROR_OPTION_EXPIRED;
+    // European options can be exercised for one day from expiry
+    let window_end = expiry + DAY_IN_SECONDS;
+    assert_exercise_blocked(&mut builder, contract_hash, owner, 6, window_end, code);
+
+    create_option(&mut builder, contract_hash, 7, U256::from(1000u64), expiry);
+    assert_exercise_blocked(&mut builder, contract_hash, owner, 7, expiry + 1, code);
+}
+
+#[test]
+fn test_is_exercisable_before_activation_time() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let activation = 1735600000u64;
+
+    let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_ACTIVATION => activation,
+        },
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+
+    let owner = *DEFAULT_ACCOUNT_ADDR;
+    let code = ERROR_OPTION_NOT_ACTIVE;
+    assert_exercise_blocked(&mut builder, contract_hash, owner, 1, activation - 1, code);
+    exercise_checked(&mut builder, contract_hash, owner, 1, activation)
+        .expect_success()
+        .commit();
+}
+
+/// Settles an in-the-money call paying `payout` gross with a `fee_bps` fee,
+/// returning the builder, contract hash and what the owner received
+fn settle_with_fee(
+    fee_bps: u32,
+    payout: u64,
+) -> (InMemoryWasmTestBuilder, ContractHash, U512) {
+    let mut builder = setup_contract_with_fee(fee_bps);
+    let contract_hash = get_contract_hash(&builder);
+    let received = settle_in_the_money(&mut builder, contract_hash, payout);
+    (builder, contract_hash, received)
+}
+
+/// Creates option 1 as an in-the-money call paying `payout` gross, then
+/// expires and settles it, returning what the owner received
+fn settle_in_the_money(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    payout: u64,
+) -> U512 {
+    let keeper = create_funded_account(builder, 2);
+    let expiry = 1735689600u64;
+    let collateral = U512::from(5_000_000_000u64);
+
+    // One unit struck at 1000, whose collateral the payout comes from
+    create_collateralised_call(builder, contract_hash, 1, expiry, U256::one(), collateral)
+        .expect_success()
+        .commit();
+    activate_option(builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    set_oracle_price(builder, U256::from(1000u64 + payout));
+
+    let owner_before = get_account_balance(builder, *DEFAULT_ACCOUNT_ADDR);
+    expire_option_at(builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    let received = get_account_balance(builder, *DEFAULT_ACCOUNT_ADDR) - owner_before;
+    received
+}
+
+#[test]
+fn test_settlement_fee_deducted_at_various_rates() {
+    let payout = 1_000_000u64;
+    for (fee_bps, fee) in [(0u32, 0u64), (10, 1_000), (100, 10_000), (9_999, 999_900)] {
+        let (builder, contract_hash, received) = settle_with_fee(fee_bps, payout);
+
+        assert_eq!(received, U512::from(payout - fee), "fee_bps {}", fee_bps);
+        let settlement: U512 =
+            get_contract_value(&builder, contract_hash, "option_1_settlement");
+        assert_eq!(settlement, U512::from(payout - fee), "fee_bps {}", fee_bps);
+        let accumulated: U512 = get_contract_value(&builder, contract_hash, "accumulated_fees");
+        assert_eq!(accumulated, U512::from(fee), "fee_bps {}", fee_bps);
+    }
+}
+
+#[test]
+fn test_admin_collects_fees() {
+    let (mut builder, contract_hash, _) = settle_with_fee(100, 1_000_000);
+    let purse_before = get_contract_purse_balance(&builder, contract_hash);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_COLLECT_FEES,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+
+    assert_eq!(
+        get_contract_purse_balance(&builder, contract_hash),
+        purse_before - U512::from(10_000u64)
+    );
+    let accumulated: U512 = get_contract_value(&builder, contract_hash, "accumulated_fees");
+    assert_eq!(accumulated, U512::zero());
+}
+
+#[test]
+fn test_non_admin_cannot_collect_fees() {
+    let (mut builder, contract_hash, _) = settle_with_fee(100, 1_000_000);
+    let other = create_funded_account(&mut builder, 3);
+
+    call_entry_point(&mut builder, other, contract_hash, ENTRY_POINT_COLLECT_FEES, runtime_args! {})
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+
+    let accumulated: U512 = get_contract_value(&builder, contract_hash, "accumulated_fees");
+    assert_eq!(accumulated, U512::from(10_000u64));
+}
+
+#[test]
+fn test_install_rejects_fee_above_100_percent() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST).commit();
+
+    let install_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_wasm_path().to_string_lossy(),
+        runtime_args! {
+            ARG_ORACLE_CONTRACT_HASH => ContractHash::default(),
+            ARG_FEE_BPS => 10_001u32,
+            ARG_KEEPER_REWARD_PER_OPTION => U512::zero(),
+        },
+    )
+    .build();
+
+    builder.exec(install_request).expect_failure().commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+}
+
+/// Re-runs the registry wasm as `sender`, which adds a new contract version
+/// when `sender` holds the package from an earlier install
+fn upgrade_registry(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+) -> &mut InMemoryWasmTestBuilder {
+    let request = ExecuteRequestBuilder::standard(
+        sender,
+        &get_wasm_path().to_string_lossy(),
+        runtime_args! {},
+    )
+    .build();
+
+    builder.exec(request)
+}
+
+#[test]
+fn test_upgrade_keeps_v1_options_readable() {
+    let mut builder = setup_contract();
+    let v1_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option(&mut builder, v1_hash, 1, U256::from(1000u64), expiry);
+    create_option(&mut builder, v1_hash, 2, U256::from(2000u64), expiry);
+    exercise_option(&mut builder, v1_hash, 2);
+    let v1_version: u32 = get_contract_value(&builder, v1_hash, "option_registry_contract_version");
+    assert_eq!(v1_version, 1);
+
+    upgrade_registry(&mut builder, *DEFAULT_ACCOUNT_ADDR)
+        .expect_success()
+        .commit();
+    let v2_hash = get_contract_hash(&builder);
+    assert_ne!(v2_hash, v1_hash);
+
+    let v2_version: u32 = get_contract_value(&builder, v2_hash, "option_registry_contract_version");
+    assert_eq!(v2_version, 2);
+    let stored_hash: ContractHash =
+        get_contract_value(&builder, v2_hash, "option_registry_contract_hash");
+    assert_eq!(stored_hash, v2_hash);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        v2_hash,
+        ENTRY_POINT_GET_CONTRACT_VERSION,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+
+    // Options created under v1 are served by v2
+    assert_eq!(get_option_strike(&builder, v2_hash, 1), U256::from(1000u64));
+    assert_eq!(get_option_strike(&builder, v2_hash, 2), U256::from(2000u64));
+    assert!(!is_option_exercised(&builder, v2_hash, 1));
+    assert!(is_option_exercised(&builder, v2_hash, 2));
+    assert_eq!(get_option_state(&builder, v2_hash, 1), OPTION_STATE_ACTIVE);
+    assert_eq!(get_option_count(&builder, v2_hash), 2);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        v2_hash,
+        ENTRY_POINT_GET_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    // ...and can still be exercised there, while v1 is disabled
+    exercise_option(&mut builder, v2_hash, 1);
+    assert!(is_option_exercised(&builder, v2_hash, 1));
+    create_option_with_nonce(&mut builder, v1_hash, 3, 10)
+        .expect_failure()
+        .commit();
+}
+
+#[test]
+fn test_upgrade_requires_admin() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let new_admin = create_funded_account(&mut builder, 2);
+
+    // The installer keeps the package but hands the admin role away
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_TRANSFER_ADMIN,
+        runtime_args! {
+            ARG_NEW_ADMIN => new_admin,
+        },
+    )
+    .expect_success()
+    .commit();
+    call_entry_point(
+        &mut builder,
+        new_admin,
+        contract_hash,
+        ENTRY_POINT_ACCEPT_ADMIN,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+
+    upgrade_registry(&mut builder, *DEFAULT_ACCOUNT_ADDR)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+    assert_eq!(get_contract_hash(&builder), contract_hash);
+}
+
+#[test]
+fn test_upgrade_entry_point_is_admin_only() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let other = create_funded_account(&mut builder, 2);
+
+    call_entry_point(
+        &mut builder,
+        other,
+        contract_hash,
+        ENTRY_POINT_UPGRADE,
+        runtime_args! {
+            ARG_CONTRACT_HASH => ContractHash::default(),
+            ARG_CONTRACT_VERSION => 7u32,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+}
+
+/// Installs a registry in the flat key layout holding options `ids`, then
+/// upgrades it to the current registry
+fn setup_legacy_registry(ids: &[u64]) -> InMemoryWasmTestBuilder {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST).commit();
+
+    let legacy_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_mock_legacy_registry_wasm_path().to_string_lossy(),
+        runtime_args! {
+            ARG_IDS => ids.to_vec(),
+        },
+    )
+    .build();
+    builder.exec(legacy_request).expect_success().commit();
+
+    upgrade_registry(&mut builder, *DEFAULT_ACCOUNT_ADDR)
+        .expect_success()
+        .commit();
+    builder
+}
+
+/// Calls `migrate_options` for `ids` as the default account
+fn migrate_options<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    ids: Vec<u64>,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_MIGRATE_OPTIONS,
+        runtime_args! {
+            ARG_IDS => ids,
+        },
+    )
+}
+
+fn get_migration_cursor(builder: &InMemoryWasmTestBuilder, contract_hash: ContractHash) -> u64 {
+    get_contract_value(builder, contract_hash, "migration_cursor")
+}
+
+#[test]
+fn test_migrate_options_in_batches() {
+    let ids: Vec<u64> = (0..120).collect();
+    let mut builder = setup_legacy_registry(&ids);
+    let contract_hash = get_contract_hash(&builder);
+
+    assert_eq!(get_migration_cursor(&builder, contract_hash), 0);
+    assert!(contract_has_key(&builder, contract_hash, "option_7_strike"));
+
+    for (batch, chunk) in ids.chunks(50).enumerate() {
+        migrate_options(&mut builder, contract_hash, chunk.to_vec())
+            .expect_success()
+            .commit();
+        let migrated = ((batch as u64 + 1) * 50).min(120);
+        assert_eq!(get_migration_cursor(&builder, contract_hash), migrated);
+    }
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_IS_MIGRATION_COMPLETE,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+
+    for id in ids {
+        assert_eq!(get_option_strike(&builder, contract_hash, id), U256::from(id * 1000));
+        let expiry: u64 = get_option_field(&builder, contract_hash, OPTIONS_EXPIRY_DICT, id);
+        assert_eq!(expiry, 1735689600 + id);
+        assert_eq!(is_option_exercised(&builder, contract_hash, id), id % 2 == 0);
+        let owner: AccountHash =
+            get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, id);
+        assert_eq!(owner, *DEFAULT_ACCOUNT_ADDR);
+
+        for field in ["strike", "expiry", "exercised", "owner"] {
+            let key = format!("option_{}_{}", id, field);
+            assert!(!contract_has_key(&builder, contract_hash, &key), "{} not removed", key);
+        }
+    }
+}
+
+#[test]
+fn test_migration_cursor_waits_for_earlier_options() {
+    let ids: Vec<u64> = (0..120).collect();
+    let mut builder = setup_legacy_registry(&ids);
+    let contract_hash = get_contract_hash(&builder);
+
+    // Option 0 still has flat keys, so the cursor can't move
+    migrate_options(&mut builder, contract_hash, (50..100).collect())
+        .expect_success()
+        .commit();
+    assert_eq!(get_migration_cursor(&builder, contract_hash), 0);
+
+    // The cursor moves at most 50 options per call; an empty batch only advances it
+    migrate_options(&mut builder, contract_hash, (0..50).collect())
+        .expect_success()
+        .commit();
+    assert_eq!(get_migration_cursor(&builder, contract_hash), 50);
+    migrate_options(&mut builder, contract_hash, Vec::new())
+        .expect_success()
+        .commit();
+    assert_eq!(get_migration_cursor(&builder, contract_hash), 100);
+
+    // Migrating an option twice is a no-op
+    migrate_options(&mut builder, contract_hash, vec![0, 100])
+        .expect_success()
+        .commit();
+    assert_eq!(get_option_strike(&builder, contract_hash, 0), U256::zero());
+    assert_eq!(get_option_strike(&builder, contract_hash, 100), U256::from(100_000u64));
+}
+
+#[test]
+fn test_migrate_options_rejects_oversized_batch() {
+    let ids: Vec<u64> = (0..51).collect();
+    let mut builder = setup_legacy_registry(&ids);
+    let contract_hash = get_contract_hash(&builder);
+
+    migrate_options(&mut builder, contract_hash, ids)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+    assert!(contract_has_key(&builder, contract_hash, "option_0_strike"));
+}
+
+#[test]
+fn test_migrate_options_is_admin_only() {
+    let mut builder = setup_legacy_registry(&[1, 2]);
+    let contract_hash = get_contract_hash(&builder);
+    let other = create_funded_account(&mut builder, 2);
+
+    call_entry_point(
+        &mut builder,
+        other,
+        contract_hash,
+        ENTRY_POINT_MIGRATE_OPTIONS,
+        runtime_args! {
+            ARG_IDS => vec![1u64, 2u64],
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+}
+
+#[test]
+fn test_fresh_install_has_nothing_to_migrate() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    assert!(!contract_has_key(&builder, contract_hash, "migration_cursor"));
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_IS_MIGRATION_COMPLETE,
+        runtime_args! {},
+    )
+    .expect_success()
+    .commit();
+}
+
+/// Makes the mock oracle call `exercise_option` for `id` on the registry
+/// each time its price is read
+fn set_oracle_reentry_target(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+) {
+    let oracle_hash = get_mock_oracle_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        oracle_hash,
+        ENTRY_POINT_SET_REENTRY_TARGET,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_ID => id,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+#[test]
+fn test_oracle_reentering_exercise_during_settle_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), expiry);
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), expiry);
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    set_oracle_reentry_target(&mut builder, contract_hash, 2);
+
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_REENTRANCY);
+
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_EXPIRED);
+    assert!(!is_option_exercised(&builder, contract_hash, 2));
+    let in_progress: bool = get_contract_value(&builder, contract_hash, "in_progress");
+    assert!(!in_progress, "Guard should be released by the revert");
+}
+
+#[test]
+fn test_oracle_reentering_exercise_during_exercise_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    // Exercising a collateralised call reads the oracle to price the payout
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        expiry,
+        U256::from(2u64),
+        U512::from(2000u64),
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), expiry);
+    set_oracle_reentry_target(&mut builder, contract_hash, 2);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_REENTRANCY);
+
+    assert!(!is_option_exercised(&builder, contract_hash, 1));
+    assert!(!is_option_exercised(&builder, contract_hash, 2));
+}
+
+#[test]
+fn test_guard_released_between_calls() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), 1735689600u64);
+
+    exercise_option(&mut builder, contract_hash, 1);
+    let in_progress: bool = get_contract_value(&builder, contract_hash, "in_progress");
+    assert!(!in_progress);
+    exercise_option(&mut builder, contract_hash, 2);
+}
+
+/// Proposes changing `param_key` to `param_value` as `sender` at `block_time_secs`
+fn propose_parameter_change<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    param_key: &str,
+    param_value: u64,
+    block_time_secs: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_PROPOSE_PARAMETER_CHANGE,
+        runtime_args! {
+            ARG_PARAM_KEY => param_key.to_string(),
+            ARG_PARAM_VALUE => param_value,
+        },
+        block_time_secs,
+    )
+}
+
+/// Calls a governance entry point taking a proposal nonce as the default
+/// account at `block_time_secs`
+fn call_with_proposal_nonce<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    entry_point: &str,
+    nonce: u64,
+    block_time_secs: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        entry_point,
+        runtime_args! {
+            ARG_NONCE => nonce,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_fee_change_waits_for_timelock() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let now = 1735689600u64;
+    let execute = ENTRY_POINT_EXECUTE_PARAMETER_CHANGE;
+
+    propose_parameter_change(&mut builder, admin, contract_hash, "fee_bps", 250, now)
+        .expect_success()
+        .commit();
+
+    for block_time in [now, now + GOVERNANCE_DELAY_SECS - 1] {
+        call_with_proposal_nonce(&mut builder, contract_hash, execute, 0, block_time)
+            .expect_failure()
+            .commit();
+        assert_user_error(&builder, ERROR_PROPOSAL_NOT_READY);
+    }
+    let fee_bps: u32 = get_contract_value(&builder, contract_hash, "option_registry_fee_bps");
+    assert_eq!(fee_bps, 0);
+
+    call_with_proposal_nonce(&mut builder, contract_hash, execute, 0, now + GOVERNANCE_DELAY_SECS)
+        .expect_success()
+        .commit();
+    let fee_bps: u32 = get_contract_value(&builder, contract_hash, "option_registry_fee_bps");
+    assert_eq!(fee_bps, 250);
+
+    // Executed proposals can't be replayed
+    call_with_proposal_nonce(&mut builder, contract_hash, execute, 0, now + GOVERNANCE_DELAY_SECS)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PROPOSAL_NOT_FOUND);
+}
+
+#[test]
+fn test_cancelled_parameter_change_cannot_execute() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let now = 1735689600u64;
+
+    propose_parameter_change(&mut builder, admin, contract_hash, "fee_bps", 250, now)
+        .expect_success()
+        .commit();
+    propose_parameter_change(&mut builder, admin, contract_hash, "fee_bps", 75, now)
+        .expect_success()
+        .commit();
+    let cancel = ENTRY_POINT_CANCEL_PARAMETER_CHANGE;
+    call_with_proposal_nonce(&mut builder, contract_hash, cancel, 0, now)
+        .expect_success()
+        .commit();
+
+    let later = now + GOVERNANCE_DELAY_SECS;
+    let execute = ENTRY_POINT_EXECUTE_PARAMETER_CHANGE;
+    call_with_proposal_nonce(&mut builder, contract_hash, execute, 0, later)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PROPOSAL_NOT_FOUND);
+    call_with_proposal_nonce(&mut builder, contract_hash, execute, 1, later)
+        .expect_success()
+        .commit();
+    let fee_bps: u32 = get_contract_value(&builder, contract_hash, "option_registry_fee_bps");
+    assert_eq!(fee_bps, 75);
+}
+
+#[test]
+fn test_invalid_parameter_change_proposals_revert() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let other = create_funded_account(&mut builder, 2);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+
+    propose_parameter_change(&mut builder, other, contract_hash, "fee_bps", 250, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+
+    propose_parameter_change(&mut builder, admin, contract_hash, "fee_bps", 10_001, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+
+    propose_parameter_change(&mut builder, admin, contract_hash, "paused", 1, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+
+    let cancel = ENTRY_POINT_CANCEL_PARAMETER_CHANGE;
+    call_with_proposal_nonce(&mut builder, contract_hash, cancel, 0, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PROPOSAL_NOT_FOUND);
+}
+
+/// Calls `create_governance_proposal` as `sender` at `block_time_secs`
+fn create_governance_proposal<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    param_name: &str,
+    new_value: u64,
+    block_time_secs: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_CREATE_GOVERNANCE_PROPOSAL,
+        runtime_args! {
+            ARG_PARAM_NAME => param_name.to_string(),
+            ARG_NEW_VALUE => new_value,
+            ARG_DESCRIPTION => format!("Set {} to {}", param_name, new_value),
+        },
+        block_time_secs,
+    )
+}
+
+/// Calls a governance entry point taking a proposal id as `sender` at
+/// `block_time_secs`
+fn call_with_proposal_id<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    entry_point: &str,
+    proposal_id: u64,
+    block_time_secs: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        sender,
+        contract_hash,
+        entry_point,
+        runtime_args! {
+            ARG_PROPOSAL_ID => proposal_id,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_governance_proposal_changes_fee_after_votes_and_timelock() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let outsider = create_funded_account(&mut builder, 5);
+    let voters: Vec<AccountHash> =
+        (2..=4).map(|seed| create_funded_account(&mut builder, seed)).collect();
+    for &voter in &voters {
+        call_with_writer(&mut builder, admin, contract_hash, ENTRY_POINT_ADD_WRITER, voter)
+            .expect_success()
+            .commit();
+    }
+    let now = 1735689600u64;
+    let vote = ENTRY_POINT_VOTE_FOR_PROPOSAL;
+    let execute = ENTRY_POINT_EXECUTE_PROPOSAL;
+
+    // Only voters, standing in for token holders, can propose and vote
+    create_governance_proposal(&mut builder, outsider, contract_hash, "fee_bps", 250, now)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_WHITELISTED);
+    create_governance_proposal(&mut builder, voters[0], contract_hash, "fee_bps", 250, now)
+        .expect_success()
+        .commit();
+    call_with_proposal_id(&mut builder, outsider, contract_hash, vote, 0, now)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_WHITELISTED);
+
+    // Two of the three votes needed: no timelock has started
+    for &voter in &voters[..2] {
+        call_with_proposal_id(&mut builder, voter, contract_hash, vote, 0, now)
+            .expect_success()
+            .commit();
+    }
+    call_with_proposal_id(&mut builder, voters[0], contract_hash, vote, 0, now)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_ALREADY_VOTED);
+    let much_later = now + 10 * GOVERNANCE_DELAY_SECS;
+    call_with_proposal_id(&mut builder, outsider, contract_hash, execute, 0, much_later)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PROPOSAL_NOT_READY);
+
+    // The third vote starts the 48-hour timelock
+    let passed_at = now + 100;
+    call_with_proposal_id(&mut builder, voters[2], contract_hash, vote, 0, passed_at)
+        .expect_success()
+        .commit();
+    let unlocked_at = passed_at + GOVERNANCE_DELAY_SECS;
+    call_with_proposal_id(&mut builder, outsider, contract_hash, execute, 0, unlocked_at - 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PROPOSAL_NOT_READY);
+    call_with_proposal_id(&mut builder, outsider, contract_hash, execute, 0, unlocked_at)
+        .expect_success()
+        .commit();
+    let fee_bps: u32 = get_contract_value(&builder, contract_hash, "option_registry_fee_bps");
+    assert_eq!(fee_bps, 250);
+
+    call_with_proposal_id(&mut builder, outsider, contract_hash, execute, 0, unlocked_at)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PROPOSAL_NOT_FOUND);
+}
+
+#[test]
+fn test_cancelled_governance_proposal_takes_no_votes() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let voter = create_funded_account(&mut builder, 2);
+    call_with_writer(&mut builder, admin, contract_hash, ENTRY_POINT_ADD_WRITER, voter)
+        .expect_success()
+        .commit();
+    let cancel = ENTRY_POINT_CANCEL_PROPOSAL;
+    let vote = ENTRY_POINT_VOTE_FOR_PROPOSAL;
+
+    create_governance_proposal(&mut builder, voter, contract_hash, "fee_bps", 10_001, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+    create_governance_proposal(&mut builder, voter, contract_hash, "fee_bps", 75, 0)
+        .expect_success()
+        .commit();
+
+    call_with_proposal_id(&mut builder, voter, contract_hash, cancel, 0, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+    call_with_proposal_id(&mut builder, admin, contract_hash, cancel, 0, 0)
+        .expect_success()
+        .commit();
+    call_with_proposal_id(&mut builder, voter, contract_hash, vote, 0, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PROPOSAL_NOT_FOUND);
+
+    // Changes the admin queued directly are not open to votes
+    propose_parameter_change(&mut builder, admin, contract_hash, "fee_bps", 75, 0)
+        .expect_success()
+        .commit();
+    call_with_proposal_id(&mut builder, voter, contract_hash, vote, 1, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PROPOSAL_NOT_FOUND);
+}
+
+/// Calls `register_keeper` for `keeper` as `sender`
+fn register_keeper(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    keeper: AccountHash,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_REGISTER_KEEPER,
+        runtime_args! {
+            ARG_KEEPER => keeper,
+        },
+    )
+}
+
+/// Calls `keeper_expire_options` for `ids` as `keeper` at `block_time_secs`
+fn keeper_expire_options(
+    builder: &mut InMemoryWasmTestBuilder,
+    keeper: AccountHash,
+    contract_hash: ContractHash,
+    ids: Vec<u64>,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        keeper,
+        contract_hash,
+        ENTRY_POINT_KEEPER_EXPIRE_OPTIONS,
+        runtime_args! {
+            ARG_IDS => ids,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_keeper_expires_options_and_is_paid_from_fees() {
+    let reward = U512::from(1_000u64);
+    let mut builder = setup_contract_with_keeper_reward(100, reward);
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 4);
+    let expiry = 1735689600u64;
+
+    // Settling option 1 leaves 10_000 motes of fees to pay rewards from
+    settle_in_the_money(&mut builder, contract_hash, 1_000_000);
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), expiry);
+    create_option(&mut builder, contract_hash, 3, U256::from(1000u64), expiry);
+    let later_expiry = expiry + 30 * DAY_IN_SECONDS;
+    create_option(&mut builder, contract_hash, 4, U256::from(1000u64), later_expiry);
+    register_keeper(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, keeper)
+        .expect_success()
+        .commit();
+    let purse_before = get_contract_purse_balance(&builder, contract_hash);
+
+    // Option 1 is already settled, 4 is still live and 99 does not exist
+    keeper_expire_options(&mut builder, keeper, contract_hash, vec![1, 2, 3, 4, 99], expiry + 1)
+        .expect_success()
+        .commit();
+
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_SETTLED);
+    assert_eq!(get_option_state(&builder, contract_hash, 2), OPTION_STATE_EXPIRED);
+    assert_eq!(get_option_state(&builder, contract_hash, 3), OPTION_STATE_EXPIRED);
+    assert_eq!(get_option_state(&builder, contract_hash, 4), OPTION_STATE_ACTIVE);
+    assert_eq!(
+        get_contract_purse_balance(&builder, contract_hash),
+        purse_before - reward * 2
+    );
+    let accumulated: U512 = get_contract_value(&builder, contract_hash, "accumulated_fees");
+    assert_eq!(accumulated, U512::from(10_000u64) - reward * 2);
+}
+
+#[test]
+fn test_keeper_reward_capped_at_accumulated_fees() {
+    let mut builder = setup_contract_with_keeper_reward(0, U512::from(1_000u64));
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 4);
+    let expiry = 1735689600u64;
+    let premium = U512::from(5_000_000_000u64);
+
+    create_option_with_premium(&mut builder, contract_hash, 1, expiry, premium, premium)
+        .expect_success()
+        .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    register_keeper(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, keeper)
+        .expect_success()
+        .commit();
+
+    keeper_expire_options(&mut builder, keeper, contract_hash, vec![1], expiry + 1)
+        .expect_success()
+        .commit();
+
+    // No fees have been taken, so the escrowed premium is left untouched
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_EXPIRED);
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), premium);
+    let accumulated: U512 = get_contract_value(&builder, contract_hash, "accumulated_fees");
+    assert_eq!(accumulated, U512::zero());
+}
+
+#[test]
+fn test_only_registered_keepers_expire_options() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 4);
+    let expiry = 1735689600u64;
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), expiry);
+    keeper_expire_options(&mut builder, keeper, contract_hash, vec![1], expiry + 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_KEEPER);
+
+    register_keeper(&mut builder, keeper, contract_hash, keeper)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+
+    register_keeper(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, keeper)
+        .expect_success()
+        .commit();
+    call_entry_point(
+        &mut builder,
+        keeper,
+        contract_hash,
+        ENTRY_POINT_DEREGISTER_KEEPER,
+        runtime_args! {
+            ARG_KEEPER => keeper,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    keeper_expire_options(&mut builder, keeper, contract_hash, vec![1], expiry + 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_KEEPER);
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_ACTIVE);
+}
+
+/// Calls `entry_point` (`add_writer` or `remove_writer`) for `writer` as `sender`
+fn call_with_writer<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    entry_point: &str,
+    writer: AccountHash,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        entry_point,
+        runtime_args! {
+            ARG_WRITER => writer,
+        },
+    )
+}
+
+/// Returns whether `account` is marked in the registry's writer whitelist
+fn is_whitelisted(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    account: AccountHash,
+) -> bool {
+    let whitelist_uref = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get(WRITER_WHITELIST_DICT)
+        .expect("Writer whitelist not found")
+        .into_uref()
+        .expect("Writer whitelist is not a URef");
+    let hex: String = account.value().iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    builder
+        .query_dictionary_item(None, whitelist_uref, &hex)
+        .ok()
+        .map_or(false, |value| {
+            value
+                .as_cl_value()
+                .expect("Not a CLValue")
+                .clone()
+                .into_t::<bool>()
+                .expect("Failed to parse whitelist entry")
+        })
+}
+
+/// Calls `create_option` for option `id` as `sender`
+fn create_option_from<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    let nonce = next_nonce(builder, contract_hash, sender);
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+        },
+    )
+}
+
+#[test]
+fn test_whitelist_restricts_option_writers() {
+    let mut builder = setup_contract_with_whitelist();
+    let contract_hash = get_contract_hash(&builder);
+    let writer = create_funded_account(&mut builder, 2);
+
+    create_option_from(&mut builder, writer, contract_hash, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_WHITELISTED);
+
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    call_with_writer(&mut builder, admin, contract_hash, ENTRY_POINT_ADD_WRITER, writer)
+        .expect_success()
+        .commit();
+    assert!(is_whitelisted(&builder, contract_hash, writer));
+    create_option_from(&mut builder, writer, contract_hash, 1)
+        .expect_success()
+        .commit();
+
+    call_with_writer(&mut builder, admin, contract_hash, ENTRY_POINT_REMOVE_WRITER, writer)
+        .expect_success()
+        .commit();
+    assert!(!is_whitelisted(&builder, contract_hash, writer));
+    create_option_from(&mut builder, writer, contract_hash, 2)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_WHITELISTED);
+
+    let count: u64 = get_contract_value(&builder, contract_hash, OPTION_COUNT_KEY);
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_whitelist_disabled_by_default() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let writer = create_funded_account(&mut builder, 2);
+
+    assert!(!is_whitelisted(&builder, contract_hash, writer));
+    create_option_from(&mut builder, writer, contract_hash, 1)
+        .expect_success()
+        .commit();
+}
+
+#[test]
+fn test_whitelist_managed_by_admin_only() {
+    let mut builder = setup_contract_with_whitelist();
+    let contract_hash = get_contract_hash(&builder);
+    let writer = create_funded_account(&mut builder, 2);
+
+    call_with_writer(&mut builder, writer, contract_hash, ENTRY_POINT_ADD_WRITER, writer)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+    assert!(!is_whitelisted(&builder, contract_hash, writer));
+
+    call_entry_point(
+        &mut builder,
+        writer,
+        contract_hash,
+        ENTRY_POINT_IS_WHITELISTED,
+        runtime_args! {
+            ARG_WRITER => writer,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+/// Calls `update_greeks` for option `id` as `sender` with
+/// `[delta, gamma, theta, vega]`
+fn update_greeks(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    greeks: [i64; 4],
+) -> &mut InMemoryWasmTestBuilder {
+    let [delta, gamma, theta, vega] = greeks;
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_UPDATE_GREEKS,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_DELTA => delta,
+            ARG_GAMMA => gamma,
+            ARG_THETA => theta,
+            ARG_VEGA => vega,
+        },
+    )
+}
+
+/// Reads option `id`'s stored `[delta, gamma, theta, vega]`
+fn get_greeks(builder: &InMemoryWasmTestBuilder, contract_hash: ContractHash, id: u64) -> [i64; 4] {
+    ["delta", "gamma", "theta", "vega"].map(|greek| {
+        get_contract_value(builder, contract_hash, &format!("option_{}_{}", id, greek))
+    })
+}
+
+/// Calls `set_greeks_oracle` with `greeks_oracle` as the admin
+fn set_greeks_oracle(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    greeks_oracle: Option<AccountHash>,
+) {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_SET_GREEKS_ORACLE,
+        runtime_args! {
+            ARG_GREEKS_ORACLE => greeks_oracle,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+#[test]
+fn test_update_greeks_round_trip() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let greeks = [55_000_000, 1_200_000, -3_400_000, 12_500_000];
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    update_greeks(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, greeks)
+        .expect_success()
+        .commit();
+    assert_eq!(get_greeks(&builder, contract_hash, 1), greeks);
+
+    let updated = [60_000_000, 1_100_000, -3_600_000, 12_000_000];
+    update_greeks(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, updated)
+        .expect_success()
+        .commit();
+    assert_eq!(get_greeks(&builder, contract_hash, 1), updated);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_GREEKS,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+#[test]
+fn test_greeks_oracle_can_update_greeks() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let greeks_oracle = create_funded_account(&mut builder, 2);
+    let greeks = [50_000_000, 0, 0, 0];
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    update_greeks(&mut builder, greeks_oracle, contract_hash, 1, greeks)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+
+    set_greeks_oracle(&mut builder, contract_hash, Some(greeks_oracle));
+    update_greeks(&mut builder, greeks_oracle, contract_hash, 1, greeks)
+        .expect_success()
+        .commit();
+    assert_eq!(get_greeks(&builder, contract_hash, 1), greeks);
+
+    set_greeks_oracle(&mut builder, contract_hash, None);
+    update_greeks(&mut builder, greeks_oracle, contract_hash, 1, greeks)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+}
+
+#[test]
+fn test_update_greeks_for_missing_option_reverts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    update_greeks(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 99, [0; 4])
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_OPTION_NOT_FOUND);
+}
+
+/// Calls `update_iv` for option `id` as `sender`
+fn update_iv(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    iv: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_UPDATE_IV,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_IV => iv,
+        },
+    )
+}
+
+/// Reads option `id`'s implied volatility and greeks-stale flag
+fn get_iv_and_staleness(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+) -> (u64, bool) {
+    let iv = get_contract_value(builder, contract_hash, &format!("option_{}_iv", id));
+    let stale = get_contract_value(builder, contract_hash, &format!("option_{}_greeks_stale", id));
+    (iv, stale)
+}
+
+#[test]
+fn test_iv_update_marks_greeks_stale() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+
+    call_entry_point(
+        &mut builder,
+        admin,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => 0u64,
+            ARG_IV => 5_000u64,
+        },
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(get_iv_and_staleness(&builder, contract_hash, 1), (5_000, false));
+
+    update_iv(&mut builder, admin, contract_hash, 1, 6_500)
+        .expect_success()
+        .commit();
+    assert_eq!(get_iv_and_staleness(&builder, contract_hash, 1), (6_500, true));
+
+    update_greeks(&mut builder, admin, contract_hash, 1, [50_000_000, 0, 0, 0])
+        .expect_success()
+        .commit();
+    assert_eq!(get_iv_and_staleness(&builder, contract_hash, 1), (6_500, false));
+
+    call_entry_point(
+        &mut builder,
+        admin,
+        contract_hash,
+        ENTRY_POINT_GET_IV,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+#[test]
+fn test_iv_defaults_to_zero() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    assert_eq!(get_iv_and_staleness(&builder, contract_hash, 1), (0, false));
+}
+
+#[test]
+fn test_vol_oracle_can_update_iv() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let vol_oracle = create_funded_account(&mut builder, 2);
+
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    update_iv(&mut builder, vol_oracle, contract_hash, 1, 4_000)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_SET_VOL_ORACLE,
+        runtime_args! {
+            ARG_VOL_ORACLE => Some(vol_oracle),
+        },
+    )
+    .expect_success()
+    .commit();
+    update_iv(&mut builder, vol_oracle, contract_hash, 1, 4_000)
+        .expect_success()
+        .commit();
+    assert_eq!(get_iv_and_staleness(&builder, contract_hash, 1), (4_000, true));
+
+    update_iv(&mut builder, vol_oracle, contract_hash, 99, 4_000)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_OPTION_NOT_FOUND);
+}
+
+/// Creates a call option as the default account with implied volatility `iv`
+fn create_option_with_iv(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    strike_price: U256,
+    expiry: u64,
+    iv: u64,
+) {
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => strike_price,
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_IV => iv,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+/// Returns the mock quote reader's hash, installing it on first use
+fn mock_quote_reader_hash(builder: &mut InMemoryWasmTestBuilder) -> ContractHash {
+    match builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(MOCK_QUOTE_READER_KEY)
+    {
+        Some(key) => ContractHash::new(key.into_hash().expect("Invalid mock quote reader hash")),
+        None => {
+            let install_request = ExecuteRequestBuilder::standard(
+                *DEFAULT_ACCOUNT_ADDR,
+                &get_mock_quote_reader_wasm_path().to_string_lossy(),
+                RuntimeArgs::new(),
+            )
+            .build();
+            builder.exec(install_request).expect_success().commit();
+            builder
+                .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+                .named_keys()
+                .get(MOCK_QUOTE_READER_KEY)
+                .expect("Mock quote reader not found")
+                .into_hash()
+                .map(ContractHash::new)
+                .expect("Invalid mock quote reader hash")
+        }
+    }
+}
+
+/// Returns `estimate_call_price` for option `id` at `underlying_price` and
+/// `block_time_secs`, read back through the mock quote reader
+fn estimate_call_price(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    underlying_price: U256,
+    block_time_secs: u64,
+) -> U256 {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_CALL_PRICE,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_ID => id,
+            ARG_UNDERLYING_PRICE => underlying_price,
+        },
+        block_time_secs,
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "call_price")
+}
+
+/// Asserts `actual` is within 1% of `expected`
+fn assert_within_one_percent(actual: U256, expected: u64) {
+    let expected = U256::from(expected);
+    let difference = if actual > expected { actual - expected } else { expected - actual };
+    assert!(
+        difference * 100 <= expected,
+        "{} is not within 1% of {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_estimate_call_price_matches_black_scholes_at_the_money() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let now = 1735689600u64;
+    let strike = U256::from(1_000_000_000u64);
+    let half_year = 182 * DAY_IN_SECONDS + DAY_IN_SECONDS / 2;
+    let year = 365 * DAY_IN_SECONDS;
+
+    // Reference prices computed in Python as S * N(d1) - K * N(d2), with
+    // N(x) = (1 + erf(x / sqrt(2))) / 2, a zero rate and a 365-day year
+    create_option_with_iv(&mut builder, contract_hash, 1, strike, now + half_year, 5_000);
+    create_option_with_iv(&mut builder, contract_hash, 2, strike, now + year, 2_000);
+
+    let half_year_50_vol = estimate_call_price(&mut builder, contract_hash, 1, strike, now);
+    assert_within_one_percent(half_year_50_vol, 140_316_205);
+    let one_year_20_vol = estimate_call_price(&mut builder, contract_hash, 2, strike, now);
+    assert_within_one_percent(one_year_20_vol, 79_655_675);
+}
+
+#[test]
+fn test_estimate_call_price_is_intrinsic_without_time_or_volatility() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let strike = U256::from(1_000_000_000u64);
+    let spot = U256::from(1_200_000_000u64);
+
+    create_option_with_iv(&mut builder, contract_hash, 1, strike, expiry, 5_000);
+    create_option(&mut builder, contract_hash, 2, strike, expiry + 365 * DAY_IN_SECONDS);
+
+    assert_eq!(
+        estimate_call_price(&mut builder, contract_hash, 1, spot, expiry + 1),
+        U256::from(200_000_000u64)
+    );
+    assert_eq!(
+        estimate_call_price(&mut builder, contract_hash, 2, spot, expiry),
+        U256::from(200_000_000u64)
+    );
+    assert_eq!(estimate_call_price(&mut builder, contract_hash, 2, strike, expiry), U256::zero());
+}
+
+/// Calls `deposit_to_pool` as `sender`, paying `amount` from its main purse
+fn deposit_to_pool(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    amount: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let purse = builder.get_expected_account(sender).main_purse();
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_DEPOSIT_TO_POOL,
+        runtime_args! {
+            ARG_PURSE => purse,
+            ARG_AMOUNT => U512::from(amount),
+        },
+    )
+}
+
+/// Calls `withdraw_from_pool` for `shares` as `sender`
+fn withdraw_from_pool(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    shares: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_WITHDRAW_FROM_POOL,
+        runtime_args! {
+            ARG_SHARES => U512::from(shares),
+        },
+    )
+}
+
+/// Calls `write_option_from_pool` as `sender` for a call on `option_amount`
+/// units struck at 1000, owned by `sender`
+fn write_option_from_pool(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    option_amount: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_WRITE_OPTION_FROM_POOL,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_OPTION_AMOUNT => U256::from(option_amount),
+        },
+    )
+}
+
+/// Reads `account`'s LP shares from the `lp_balances` dictionary
+fn lp_balance(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    account: AccountHash,
+) -> U512 {
+    lp_dictionary_value(builder, contract_hash, LP_BALANCES_DICT, &account_hex(account))
+}
+
+/// Reads how many of `owner`'s LP shares `spender` may move from the
+/// `lp_allowances` dictionary
+fn lp_allowance(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    owner: AccountHash,
+    spender: AccountHash,
+) -> U512 {
+    let key = format!("{}_{}", account_hex(owner), account_hex(spender));
+    lp_dictionary_value(builder, contract_hash, LP_ALLOWANCES_DICT, &key)
+}
+
+/// Lowercase hex of `account`, as used in the registry's account-keyed dictionaries
+fn account_hex(account: AccountHash) -> String {
+    account.value().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Calls `update_volatility_surface_batch` as `sender` with one
+/// `(strike, expiry, iv_bps)` per point
+fn update_volatility_surface_batch<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    points: &[(u64, u64, u64)],
+) -> &'a mut InMemoryWasmTestBuilder {
+    let strike_prices: Vec<U256> =
+        points.iter().map(|&(strike, _, _)| U256::from(strike)).collect();
+    let expiries: Vec<u64> = points.iter().map(|&(_, expiry, _)| expiry).collect();
+    let ivs_bps: Vec<u64> = points.iter().map(|&(_, _, iv_bps)| iv_bps).collect();
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_UPDATE_VOLATILITY_SURFACE_BATCH,
+        runtime_args! {
+            ARG_STRIKE_PRICES => strike_prices,
+            ARG_EXPIRIES => expiries,
+            ARG_IVS_BPS => ivs_bps,
+        },
+    )
+}
+
+/// Reads the `vol_surface` point at `strike` and `expiry`, if set
+fn get_vol_surface_point(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    strike: u64,
+    expiry: u64,
+) -> Option<u64> {
+    let surface_uref = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get("vol_surface")
+        .expect("vol_surface not found")
+        .into_uref()
+        .expect("vol_surface is not a URef");
+
+    builder
+        .query_dictionary_item(None, surface_uref, &format!("{}_{}", strike, expiry))
+        .ok()
+        .map(|value| {
+            value
+                .as_cl_value()
+                .expect("Not a CLValue")
+                .clone()
+                .into_t::<u64>()
+                .expect("Failed to parse volatility surface point")
+        })
+}
+
+#[test]
+fn test_volatility_surface_grid_round_trips_and_fills_iv() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let vol_oracle = create_funded_account(&mut builder, 2);
+    let expiries = [1735689600u64, 1738368000, 1740787200, 1743465600];
+    let strikes = [900u64, 1000, 1100, 1200];
+
+    // Smile across strikes, rising with term
+    let mut grid = Vec::new();
+    for (row, &expiry) in expiries.iter().enumerate() {
+        for (column, &strike) in strikes.iter().enumerate() {
+            let smile = [600u64, 0, 200, 800][column];
+            grid.push((strike, expiry, 4_000 + smile + 250 * row as u64));
+        }
+    }
+
+    update_volatility_surface_batch(&mut builder, vol_oracle, contract_hash, &grid)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_SET_VOL_ORACLE,
+        runtime_args! {
+            ARG_VOL_ORACLE => Some(vol_oracle),
+        },
+    )
+    .expect_success()
+    .commit();
+    update_volatility_surface_batch(&mut builder, vol_oracle, contract_hash, &grid)
+        .expect_success()
+        .commit();
+    for &(strike, expiry, iv_bps) in &grid {
+        assert_eq!(get_vol_surface_point(&builder, contract_hash, strike, expiry), Some(iv_bps));
+    }
+    assert_eq!(get_vol_surface_point(&builder, contract_hash, 1300, expiries[0]), None);
+
+    call_entry_point(
+        &mut builder,
+        vol_oracle,
+        contract_hash,
+        ENTRY_POINT_UPDATE_VOLATILITY_SURFACE_POINT,
+        runtime_args! {
+            ARG_STRIKE => U256::from(1000u64),
+            ARG_EXPIRY => expiries[0],
+            ARG_IV_BPS => 3_900u64,
+        },
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(get_vol_surface_point(&builder, contract_hash, 1000, expiries[0]), Some(3_900));
+
+    call_entry_point(
+        &mut builder,
+        vol_oracle,
+        contract_hash,
+        ENTRY_POINT_GET_VOLATILITY_SURFACE_POINT,
+        runtime_args! {
+            ARG_STRIKE => U256::from(1000u64),
+            ARG_EXPIRY => expiries[0],
+        },
+    )
+    .expect_success()
+    .commit();
+
+    // Options without an iv take the surface's, where it has a point
+    create_option(&mut builder, contract_hash, 1, U256::from(1100u64), expiries[2]);
+    assert_eq!(get_iv_and_staleness(&builder, contract_hash, 1), (4_700, false));
+    create_option(&mut builder, contract_hash, 2, U256::from(1300u64), expiries[2]);
+    assert_eq!(get_iv_and_staleness(&builder, contract_hash, 2), (0, false));
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 3u64,
+            ARG_STRIKE_PRICE => U256::from(1100u64),
+            ARG_EXPIRY => expiries[2],
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => 3u64,
+            ARG_IV => 5_000u64,
+        },
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(get_iv_and_staleness(&builder, contract_hash, 3), (5_000, false));
+
+    call_entry_point(
+        &mut builder,
+        vol_oracle,
+        contract_hash,
+        ENTRY_POINT_UPDATE_VOLATILITY_SURFACE_BATCH,
+        runtime_args! {
+            ARG_STRIKE_PRICES => vec![U256::from(1000u64)],
+            ARG_EXPIRIES => vec![expiries[0], expiries[1]],
+            ARG_IVS_BPS => vec![4_000u64],
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+}
+
+/// Reads `key` from the `U512`-valued LP dictionary `dictionary`; absent
+/// items read as zero
+fn lp_dictionary_value(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    dictionary: &str,
+    key: &str,
+) -> U512 {
+    let dictionary_uref = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get(dictionary)
+        .unwrap_or_else(|| panic!("Dictionary {} not found", dictionary))
+        .into_uref()
+        .expect("Dictionary is not a URef");
+
+    builder
+        .query_dictionary_item(None, dictionary_uref, key)
+        .ok()
+        .map_or(U512::zero(), |value| {
+            value
+                .as_cl_value()
+                .expect("Not a CLValue")
+                .clone()
+                .into_t::<U512>()
+                .expect("Failed to parse LP dictionary value")
+        })
+}
+
+#[test]
+fn test_pool_deposits_mint_shares_at_current_value() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let provider = create_funded_account(&mut builder, 2);
+
+    deposit_to_pool(&mut builder, admin, contract_hash, 10_000)
+        .expect_success()
+        .commit();
+    assert_eq!(lp_balance(&builder, contract_hash, admin), U512::from(10_000u64));
+
+    // Paying out 2_000 on an exercise leaves 8_000 of value behind 10_000 shares
+    write_option_from_pool(&mut builder, admin, contract_hash, 1, 5)
+        .expect_success()
+        .commit();
+    activate_option(&mut builder, admin, contract_hash, 1)
+        .expect_success()
+        .commit();
+    set_oracle_price(&mut builder, U256::from(1400u64));
+    exercise_option(&mut builder, contract_hash, 1);
+
+    deposit_to_pool(&mut builder, provider, contract_hash, 4_000)
+        .expect_success()
+        .commit();
+    assert_eq!(lp_balance(&builder, contract_hash, provider), U512::from(5_000u64));
+    let total_shares: U512 = get_contract_value(&builder, contract_hash, POOL_TOTAL_SHARES_KEY);
+    assert_eq!(total_shares, U512::from(15_000u64));
+    let total_value: U512 = get_contract_value(&builder, contract_hash, POOL_TOTAL_VALUE_KEY);
+    assert_eq!(total_value, U512::from(12_000u64));
+}
+
+#[test]
+fn test_pool_exercise_lowers_value_by_payout() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+
+    deposit_to_pool(&mut builder, admin, contract_hash, 10_000)
+        .expect_success()
+        .commit();
+    write_option_from_pool(&mut builder, admin, contract_hash, 1, 5)
+        .expect_success()
+        .commit();
+    let utilized: U512 = get_contract_value(&builder, contract_hash, POOL_UTILIZED_COLLATERAL_KEY);
+    assert_eq!(utilized, U512::from(5_000u64));
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_CREATED);
+
+    activate_option(&mut builder, admin, contract_hash, 1)
+        .expect_success()
+        .commit();
+    set_oracle_price(&mut builder, U256::from(1100u64));
+    let purse_before = get_contract_purse_balance(&builder, contract_hash);
+    exercise_option(&mut builder, contract_hash, 1);
+
+    // 5 units, 100 in the money each, less the default 0.5% early exercise penalty
+    let payout = U512::from(500u64);
+    let paid_out = payout - U512::from(2u64);
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), purse_before - paid_out);
+    let total_value: U512 = get_contract_value(&builder, contract_hash, POOL_TOTAL_VALUE_KEY);
+    assert_eq!(total_value, U512::from(10_000u64) - payout);
+    let utilized: U512 = get_contract_value(&builder, contract_hash, POOL_UTILIZED_COLLATERAL_KEY);
+    assert_eq!(utilized, U512::from(5_000u64) - payout);
+
+    // Releasing what is left of the collateral frees it without moving CSPR
+    call_entry_point(
+        &mut builder,
+        admin,
+        contract_hash,
+        ENTRY_POINT_WITHDRAW_COLLATERAL,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_success()
+    .commit();
+    let utilized: U512 = get_contract_value(&builder, contract_hash, POOL_UTILIZED_COLLATERAL_KEY);
+    assert_eq!(utilized, U512::zero());
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), purse_before - paid_out);
+}
+
+#[test]
+fn test_pool_write_respects_max_utilization() {
+    let mut builder = setup_contract_with_max_utilization(5_000);
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+
+    deposit_to_pool(&mut builder, admin, contract_hash, 10_000)
+        .expect_success()
+        .commit();
+    write_option_from_pool(&mut builder, admin, contract_hash, 1, 6)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_POOL_UTILIZATION_EXCEEDED);
+
+    write_option_from_pool(&mut builder, admin, contract_hash, 1, 5)
+        .expect_success()
+        .commit();
+    write_option_from_pool(&mut builder, admin, contract_hash, 2, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_POOL_UTILIZATION_EXCEEDED);
+}
+
+#[test]
+fn test_pool_withdrawal_limited_to_unlocked_value() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+
+    deposit_to_pool(&mut builder, admin, contract_hash, 10_000)
+        .expect_success()
+        .commit();
+    write_option_from_pool(&mut builder, admin, contract_hash, 1, 6)
+        .expect_success()
+        .commit();
+
+    withdraw_from_pool(&mut builder, admin, contract_hash, 4_001)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_POOL_LIQUIDITY);
+
+    let purse_before = get_contract_purse_balance(&builder, contract_hash);
+    withdraw_from_pool(&mut builder, admin, contract_hash, 4_000)
+        .expect_success()
+        .commit();
+    let purse_after = get_contract_purse_balance(&builder, contract_hash);
+    assert_eq!(purse_before - purse_after, U512::from(4_000u64));
+    assert_eq!(lp_balance(&builder, contract_hash, admin), U512::from(6_000u64));
+    let total_value: U512 = get_contract_value(&builder, contract_hash, POOL_TOTAL_VALUE_KEY);
+    assert_eq!(total_value, U512::from(6_000u64));
+}
+
+#[test]
+fn test_write_option_from_pool_requires_admin_or_keeper() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let keeper = create_funded_account(&mut builder, 2);
+
+    deposit_to_pool(&mut builder, admin, contract_hash, 10_000)
+        .expect_success()
+        .commit();
+    write_option_from_pool(&mut builder, keeper, contract_hash, 1, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+
+    register_keeper(&mut builder, admin, contract_hash, keeper)
+        .expect_success()
+        .commit();
+    write_option_from_pool(&mut builder, keeper, contract_hash, 1, 1)
+        .expect_success()
+        .commit();
+    let owner: AccountHash = get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, 1);
+    assert_eq!(owner, keeper);
+}
+
+/// Calls `transfer_lp_shares` as `sender`, sending `amount` shares to `recipient`
+fn transfer_lp_shares(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    recipient: AccountHash,
+    amount: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_TRANSFER_LP_SHARES,
+        runtime_args! {
+            ARG_RECIPIENT => recipient,
+            ARG_AMOUNT => U512::from(amount),
+        },
+    )
+}
+
+/// Calls `transfer_lp_shares_from` as `spender`, moving `amount` of `owner`'s
+/// shares to `recipient`
+fn transfer_lp_shares_from(
+    builder: &mut InMemoryWasmTestBuilder,
+    spender: AccountHash,
+    contract_hash: ContractHash,
+    owner: AccountHash,
+    recipient: AccountHash,
+    amount: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        spender,
+        contract_hash,
+        ENTRY_POINT_TRANSFER_LP_SHARES_FROM,
+        runtime_args! {
+            ARG_OWNER => owner,
+            ARG_RECIPIENT => recipient,
+            ARG_AMOUNT => U512::from(amount),
+        },
+    )
+}
+
+#[test]
+fn test_transferred_lp_shares_can_be_withdrawn_by_recipient() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let provider = *DEFAULT_ACCOUNT_ADDR;
+    let recipient = create_funded_account(&mut builder, 2);
+
+    deposit_to_pool(&mut builder, provider, contract_hash, 10_000)
+        .expect_success()
+        .commit();
+    transfer_lp_shares(&mut builder, provider, contract_hash, recipient, 4_000)
+        .expect_success()
+        .commit();
+    assert_eq!(lp_balance(&builder, contract_hash, provider), U512::from(6_000u64));
+    assert_eq!(lp_balance(&builder, contract_hash, recipient), U512::from(4_000u64));
+
+    transfer_lp_shares(&mut builder, recipient, contract_hash, provider, 4_001)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_LP_SHARES);
+
+    // The recipient's shares redeem for their part of the pool, not the sender's
+    let purse_before = get_contract_purse_balance(&builder, contract_hash);
+    withdraw_from_pool(&mut builder, recipient, contract_hash, 4_000)
+        .expect_success()
+        .commit();
+    let purse_after = get_contract_purse_balance(&builder, contract_hash);
+    assert_eq!(purse_before - purse_after, U512::from(4_000u64));
+    assert_eq!(lp_balance(&builder, contract_hash, recipient), U512::zero());
+    assert_eq!(lp_balance(&builder, contract_hash, provider), U512::from(6_000u64));
+    let total_value: U512 = get_contract_value(&builder, contract_hash, POOL_TOTAL_VALUE_KEY);
+    assert_eq!(total_value, U512::from(6_000u64));
+
+    withdraw_from_pool(&mut builder, recipient, contract_hash, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_LP_SHARES);
+}
+
+#[test]
+fn test_transfer_lp_shares_from_spends_allowance() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let owner = *DEFAULT_ACCOUNT_ADDR;
+    let spender = create_funded_account(&mut builder, 2);
+    let recipient = create_funded_account(&mut builder, 3);
+
+    deposit_to_pool(&mut builder, owner, contract_hash, 10_000)
+        .expect_success()
+        .commit();
+    transfer_lp_shares_from(&mut builder, spender, contract_hash, owner, recipient, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_LP_ALLOWANCE);
+
+    call_entry_point(
+        &mut builder,
+        owner,
+        contract_hash,
+        ENTRY_POINT_APPROVE_LP_SHARES,
+        runtime_args! {
+            ARG_SPENDER => spender,
+            ARG_AMOUNT => U512::from(3_000u64),
+        },
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(lp_allowance(&builder, contract_hash, owner, spender), U512::from(3_000u64));
+
+    transfer_lp_shares_from(&mut builder, spender, contract_hash, owner, recipient, 2_000)
+        .expect_success()
+        .commit();
+    assert_eq!(lp_allowance(&builder, contract_hash, owner, spender), U512::from(1_000u64));
+    assert_eq!(lp_balance(&builder, contract_hash, owner), U512::from(8_000u64));
+    assert_eq!(lp_balance(&builder, contract_hash, recipient), U512::from(2_000u64));
+
+    transfer_lp_shares_from(&mut builder, spender, contract_hash, owner, recipient, 1_001)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_LP_ALLOWANCE);
+}
+
+/// Returns `get_amm_option_price` for `option_amount` units struck at 1000
+/// expiring at `expiry`, as of `block_time_secs`, read back through the mock
+/// quote reader
+fn get_amm_option_price(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    expiry: u64,
+    option_amount: u64,
+    block_time_secs: u64,
+) -> U512 {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_AMM_OPTION_PRICE,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_AMOUNT => U256::from(option_amount),
+        },
+        block_time_secs,
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "amm_option_price")
+}
+
+/// Calls `buy_option_from_pool` as `buyer` for option `id`, a call on 1000
+/// units struck at 1000 expiring at 1735689600, paying `paid` from its main
+/// purse at `block_time_secs`
+fn buy_option_from_pool(
+    builder: &mut InMemoryWasmTestBuilder,
+    buyer: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    paid: U512,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let purse = builder.get_expected_account(buyer).main_purse();
+    call_entry_point_at(
+        builder,
+        buyer,
+        contract_hash,
+        ENTRY_POINT_BUY_OPTION_FROM_POOL,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_OPTION_AMOUNT => U256::from(1_000u64),
+            ARG_PURSE => purse,
+            ARG_AMOUNT => paid,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_amm_price_rises_with_pool_utilization() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let expiry = 1735689600u64;
+    let now = expiry - 365 * DAY_IN_SECONDS;
+
+    deposit_to_pool(&mut builder, admin, contract_hash, 10_000_000)
+        .expect_success()
+        .commit();
+    let idle_price = get_amm_option_price(&mut builder, contract_hash, expiry, 1_000, now);
+    assert!(!idle_price.is_zero());
+
+    // Locks half the pool behind option 1
+    write_option_from_pool(&mut builder, admin, contract_hash, 1, 5_000)
+        .expect_success()
+        .commit();
+    let half_used_price = get_amm_option_price(&mut builder, contract_hash, expiry, 1_000, now);
+    assert!(half_used_price > idle_price, "{} <= {}", half_used_price, idle_price);
+
+    write_option_from_pool(&mut builder, admin, contract_hash, 2, 3_000)
+        .expect_success()
+        .commit();
+    let mostly_used_price = get_amm_option_price(&mut builder, contract_hash, expiry, 1_000, now);
+    assert!(mostly_used_price > half_used_price);
+
+    // Less time to expiry costs less
+    let later = get_amm_option_price(&mut builder, contract_hash, expiry, 1_000, expiry - 1);
+    assert!(later < mostly_used_price);
+}
+
+#[test]
+fn test_buy_option_from_pool_requires_exact_price() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let buyer = create_funded_account(&mut builder, 2);
+    let expiry = 1735689600u64;
+    let now = expiry - 365 * DAY_IN_SECONDS;
+
+    deposit_to_pool(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 10_000_000)
+        .expect_success()
+        .commit();
+    let price = get_amm_option_price(&mut builder, contract_hash, expiry, 1_000, now);
+    assert!(!price.is_zero());
+
+    for paid in [price - 1, price + 1] {
+        buy_option_from_pool(&mut builder, buyer, contract_hash, 1, paid, now)
+            .expect_failure()
+            .commit();
+        assert_user_error(&builder, ERROR_INCORRECT_PREMIUM);
+    }
+
+    buy_option_from_pool(&mut builder, buyer, contract_hash, 1, price, now)
+        .expect_success()
+        .commit();
+    let owner: AccountHash = get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, 1);
+    assert_eq!(owner, buyer);
+    let writer: AccountHash = get_contract_value(&builder, contract_hash, "option_1_writer");
+    assert_eq!(writer, AccountHash::new([0; 32]));
+    let total_value: U512 = get_contract_value(&builder, contract_hash, POOL_TOTAL_VALUE_KEY);
+    assert_eq!(total_value, U512::from(10_000_000u64) + price);
+    let utilized: U512 = get_contract_value(&builder, contract_hash, POOL_UTILIZED_COLLATERAL_KEY);
+    assert_eq!(utilized, U512::from(1_000_000u64));
+}
+
+#[test]
+fn test_amm_price_includes_intrinsic_value() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let now = expiry - 365 * DAY_IN_SECONDS;
+
+    deposit_to_pool(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 10_000_000)
+        .expect_success()
+        .commit();
+    let at_the_money = get_amm_option_price(&mut builder, contract_hash, expiry, 1_000, now);
+
+    // 500 in the money on each of the 1000 units
+    set_oracle_price(&mut builder, U256::from(1500u64));
+    let in_the_money = get_amm_option_price(&mut builder, contract_hash, expiry, 1_000, now);
+    assert_eq!(in_the_money, at_the_money + U512::from(500_000u64));
+}
+
+#[test]
+fn test_buy_option_from_pool_requires_minimum_term() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let buyer = create_funded_account(&mut builder, 2);
+    let expiry = 1735689600u64;
+
+    deposit_to_pool(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 10_000_000)
+        .expect_success()
+        .commit();
+    let now = expiry - DAY_IN_SECONDS + 1;
+    let price = get_amm_option_price(&mut builder, contract_hash, expiry, 1_000, now);
+    buy_option_from_pool(&mut builder, buyer, contract_hash, 1, price, now)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+
+    let now = expiry - DAY_IN_SECONDS;
+    let price = get_amm_option_price(&mut builder, contract_hash, expiry, 1_000, now);
+    buy_option_from_pool(&mut builder, buyer, contract_hash, 1, price, now)
+        .expect_success()
+        .commit();
+}
+
+/// Calls `get_all_expiries` for one page, then rebuilds that page from the
+/// `expiry_set_{index}` keys the entry point sorts
+fn get_all_expiries_page(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    offset: u64,
+    limit: u64,
+) -> Vec<u64> {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_ALL_EXPIRIES,
+        runtime_args! {
+            ARG_OFFSET => offset,
+            ARG_LIMIT => limit,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    let count: u64 = get_contract_value(builder, contract_hash, "expiry_set_count");
+    let mut expiries: Vec<u64> = (0..count)
+        .map(|index| get_contract_value(builder, contract_hash, &format!("expiry_set_{}", index)))
+        .collect();
+    expiries.sort_unstable();
+    expiries.into_iter().skip(offset as usize).take(limit as usize).collect()
+}
+
+#[test]
+fn test_get_all_expiries_lists_distinct_expiries_in_order() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let base = 1735689600u64;
+
+    // Five distinct expiries, out of order and with repeats
+    let expiries: Vec<u64> = [3, 1, 4, 1, 5, 2, 5, 3]
+        .iter()
+        .map(|&day| base + day * DAY_IN_SECONDS)
+        .collect();
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION_BATCH,
+        runtime_args! {
+            ARG_IDS => (1..=8u64).collect::<Vec<u64>>(),
+            ARG_STRIKE_PRICES => vec![U256::from(1000u64); 8],
+            ARG_EXPIRIES => expiries,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+        },
+    )
+    .expect_success()
+    .commit();
+    create_option(&mut builder, contract_hash, 9, U256::from(1000u64), base + 4 * DAY_IN_SECONDS);
+
+    let count: u64 = get_contract_value(&builder, contract_hash, "expiry_set_count");
+    assert_eq!(count, 5);
+
+    let mut seen = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = get_all_expiries_page(&mut builder, contract_hash, offset, 2);
+        if page.is_empty() {
+            break;
+        }
+        offset += page.len() as u64;
+        seen.extend(page);
+    }
+    let expected: Vec<u64> = (1..=5).map(|day| base + day * DAY_IN_SECONDS).collect();
+    assert_eq!(seen, expected);
+}
+
+/// Calls `get_option_chain` for `expiry`, then rebuilds the chain from the
+/// `expiry_{expiry}_strikes_{index}` keys and `option_chain` dictionary it reads
+fn get_option_chain(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    expiry: u64,
+) -> Vec<(U256, Vec<u64>, Vec<u64>)> {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_OPTION_CHAIN,
+        runtime_args! { ARG_EXPIRY => expiry },
+    )
+    .expect_success()
+    .commit();
+
+    let count: u64 =
+        get_contract_value(builder, contract_hash, &format!("expiry_{}_strikes_count", expiry));
+    let mut strikes: Vec<U256> = (0..count)
+        .map(|index| {
+            let key = format!("expiry_{}_strikes_{}", expiry, index);
+            get_contract_value(builder, contract_hash, &key)
+        })
+        .collect();
+    strikes.sort_unstable();
+
+    let chain_uref = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get("option_chain")
+        .expect("Dictionary option_chain not found")
+        .into_uref()
+        .expect("Dictionary is not a URef");
+    strikes
+        .into_iter()
+        .map(|strike| {
+            let (calls, puts): (Vec<u64>, Vec<u64>) = builder
+                .query_dictionary_item(None, chain_uref, &format!("{}_{}", expiry, strike))
+                .expect("Chain entry not found")
+                .as_cl_value()
+                .expect("Not a CLValue")
+                .clone()
+                .into_t()
+                .expect("Failed to parse chain entry");
+            (strike, calls, puts)
+        })
+        .collect()
+}
+
+#[test]
+fn test_option_chain_groups_calls_and_puts_by_strike() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let strikes: Vec<U256> = [1300u64, 900, 1100, 1000, 1200]
+        .iter()
+        .map(|&strike| U256::from(strike))
+        .collect();
+
+    // Calls 1..=5 and puts 6..=10 over five strikes, plus a second call at 1000
+    // and a call at another expiry that must stay out of the chain
+    for (first_id, option_type) in [(1u64, OPTION_TYPE_CALL), (6, OPTION_TYPE_PUT)] {
+        call_entry_point(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            ENTRY_POINT_CREATE_OPTION_BATCH,
+            runtime_args! {
+                ARG_IDS => (first_id..first_id + 5).collect::<Vec<u64>>(),
+                ARG_STRIKE_PRICES => strikes.clone(),
+                ARG_EXPIRIES => vec![expiry; 5],
+                ARG_OPTION_TYPE => option_type,
+            },
+        )
+        .expect_success()
+        .commit();
+    }
+    create_option(&mut builder, contract_hash, 11, U256::from(1000u64), expiry);
+    create_option(&mut builder, contract_hash, 12, U256::from(1000u64), expiry + DAY_IN_SECONDS);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_STRIKES_FOR_EXPIRY,
+        runtime_args! { ARG_EXPIRY => expiry },
+    )
+    .expect_success()
+    .commit();
+
+    let chain = get_option_chain(&mut builder, contract_hash, expiry);
+    let expected: Vec<(U256, Vec<u64>, Vec<u64>)> = vec![
+        (U256::from(900u64), vec![2], vec![7]),
+        (U256::from(1000u64), vec![4, 11], vec![9]),
+        (U256::from(1100u64), vec![3], vec![8]),
+        (U256::from(1200u64), vec![5], vec![10]),
+        (U256::from(1300u64), vec![1], vec![6]),
+    ];
+    assert_eq!(chain, expected);
+
+    let other = get_option_chain(&mut builder, contract_hash, expiry + DAY_IN_SECONDS);
+    assert_eq!(other, vec![(U256::from(1000u64), vec![12], vec![])]);
+}
+
+#[test]
+fn test_open_interest_drops_as_options_close() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let low_strike = U256::from(1000u64);
+    let high_strike = U256::from(2000u64);
+
+    // Options 1..=5 at the low strike and 6..=10 at the high strike
+    for id in 1..=10u64 {
+        let strike = if id <= 5 { low_strike } else { high_strike };
+        create_option(&mut builder, contract_hash, id, strike, expiry);
+    }
+    let total: u64 = get_contract_value(&builder, contract_hash, "total_open_interest");
+    assert_eq!(total, 10);
+
+    for id in 1..=3 {
+        exercise_option(&mut builder, contract_hash, id);
+    }
+    for id in 6..=7 {
+        expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id, expiry + 1)
+            .expect_success()
+            .commit();
+    }
+
+    for (entry_point, args) in [
+        (
+            ENTRY_POINT_GET_OPEN_INTEREST_BY_STRIKE,
+            runtime_args! { ARG_STRIKE_PRICE => low_strike, ARG_EXPIRY => expiry },
+        ),
+        (ENTRY_POINT_GET_OPEN_INTEREST_BY_EXPIRY, runtime_args! { ARG_EXPIRY => expiry }),
+        (ENTRY_POINT_GET_TOTAL_OPEN_INTEREST, runtime_args! {}),
+    ] {
+        call_entry_point(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, entry_point, args)
+            .expect_success()
+            .commit();
+    }
+
+    let low: u64 =
+        get_contract_value(&builder, contract_hash, &format!("oi_strike_{}_1000", expiry));
+    let high: u64 =
+        get_contract_value(&builder, contract_hash, &format!("oi_strike_{}_2000", expiry));
+    let by_expiry: u64 =
+        get_contract_value(&builder, contract_hash, &format!("oi_expiry_{}", expiry));
+    let total: u64 = get_contract_value(&builder, contract_hash, "total_open_interest");
+    assert_eq!((low, high), (2, 3));
+    assert_eq!(by_expiry, 5);
+    assert_eq!(total, 5);
+}
+
+#[test]
+fn test_volume_sums_exercised_amount_times_strike() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+
+    // (id, type, strike, size, units exercised); each locks strike * size
+    let options = [
+        (1u64, OPTION_TYPE_CALL, 1000u64, 2u64, 2u64),
+        (2, OPTION_TYPE_CALL, 1000, 3, 1),
+        (3, OPTION_TYPE_CALL, 2000, 1, 1),
+        (4, OPTION_TYPE_PUT, 1000, 4, 4),
+        (5, OPTION_TYPE_PUT, 500, 5, 3),
+    ];
+    for (id, option_type, strike, size, exercised) in options {
+        let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+        let collateral = strike * size;
+        call_entry_point(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            ENTRY_POINT_CREATE_OPTION,
+            runtime_args! {
+                ARG_ID => id,
+                ARG_STRIKE_PRICE => U256::from(strike),
+                ARG_EXPIRY => expiry,
+                ARG_OPTION_TYPE => option_type,
+                ARG_NONCE => nonce,
+                ARG_OPTION_AMOUNT => U256::from(size),
+                ARG_PURSE => purse,
+                ARG_AMOUNT => U512::from(collateral),
+            },
+        )
+        .expect_success()
+        .commit();
+        activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id)
+            .expect_success()
+            .commit();
+        exercise_amount_of(&mut builder, contract_hash, id, U256::from(exercised))
+            .expect_success()
+            .commit();
+    }
+
+    for (entry_point, args) in [
+        (ENTRY_POINT_GET_TOTAL_VOLUME, runtime_args! {}),
+        (
+            ENTRY_POINT_GET_VOLUME_BY_STRIKE,
+            runtime_args! {
+                ARG_STRIKE_PRICE => U256::from(1000u64),
+                ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            },
+        ),
+        (ENTRY_POINT_GET_VOLUME_BY_EXPIRY, runtime_args! { ARG_EXPIRY => expiry }),
+    ] {
+        call_entry_point(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, entry_point, args)
+            .expect_success()
+            .commit();
+    }
+
+    // Calls: 2 * 1000 + 1 * 1000 + 1 * 2000; puts: 4 * 1000 + 3 * 500
+    for (key, volume) in [
+        ("volume_call_exercised".to_string(), 5000u64),
+        ("volume_put_exercised".to_string(), 5500),
+        ("volume_call_1000".to_string(), 3000),
+        ("volume_call_2000".to_string(), 2000),
+        ("volume_put_1000".to_string(), 4000),
+        ("volume_put_500".to_string(), 1500),
+        (format!("volume_expiry_{}", expiry), 10500),
+    ] {
+        let stored: U256 = get_contract_value(&builder, contract_hash, &key);
+        assert_eq!(stored, U256::from(volume), "{}", key);
+    }
+}
+
+/// Reads `(puts, calls)` through the mock quote reader: for `expiry` if
+/// given, otherwise across the registry
+fn get_put_call_ratio(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    expiry: Option<u64>,
+) -> (u64, u64) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    let mut args = runtime_args! { ARG_CONTRACT_HASH => contract_hash };
+    if let Some(expiry) = expiry {
+        args.insert(ARG_EXPIRY, expiry).expect("Failed to add expiry");
+    }
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_PUT_CALL_RATIO,
+        args,
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "put_call_ratio")
+}
+
+#[test]
+fn test_put_call_ratio_counts_created_puts_and_calls() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let later_expiry = expiry + DAY_IN_SECONDS;
+
+    assert_eq!(get_put_call_ratio(&mut builder, contract_hash, None), (0, 0));
+
+    // 3 calls and 7 puts; two of the puts expire a day later
+    for id in 1..=10u64 {
+        let option_type = if id <= 3 { OPTION_TYPE_CALL } else { OPTION_TYPE_PUT };
+        let option_expiry = if id <= 8 { expiry } else { later_expiry };
+        create_option_with_type(
+            &mut builder,
+            contract_hash,
+            id,
+            U256::from(1000u64),
+            option_expiry,
+            option_type,
+        );
+    }
+
+    assert_eq!(get_put_call_ratio(&mut builder, contract_hash, None), (7, 3));
+    assert_eq!(get_put_call_ratio(&mut builder, contract_hash, Some(expiry)), (5, 3));
+    assert_eq!(get_put_call_ratio(&mut builder, contract_hash, Some(later_expiry)), (2, 0));
+    assert_eq!(
+        get_put_call_ratio(&mut builder, contract_hash, Some(later_expiry + DAY_IN_SECONDS)),
+        (0, 0)
+    );
+}
+
+#[test]
+fn test_creation_and_exercise_timestamps_recorded() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let created_time = 1_700_000_000u64;
+    let exercised_time = created_time + DAY_IN_SECONDS;
+
+    let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point_at(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+        },
+        created_time,
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+
+    let by_id = runtime_args! { ARG_ID => 1u64 };
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_CREATION_TIMESTAMP,
+        by_id.clone(),
+    )
+    .expect_success()
+    .commit();
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_EXERCISE_TIMESTAMP,
+        by_id.clone(),
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_EXERCISED);
+
+    exercise_option_at(&mut builder, contract_hash, 1, exercised_time)
+        .expect_success()
+        .commit();
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_EXERCISE_TIMESTAMP,
+        by_id,
+    )
+    .expect_success()
+    .commit();
+
+    let created_at: u64 = get_contract_value(&builder, contract_hash, "option_1_created_at");
+    let exercised_at: u64 = get_contract_value(&builder, contract_hash, "option_1_exercised_at");
+    assert_eq!(created_at, created_time);
+    assert_eq!(exercised_at, exercised_time);
+    assert!(exercised_at >= created_at);
+}
+
+/// Reads `(get_time_to_expiry, is_expired)` for option `id` at
+/// `block_time_secs` through the mock quote reader
+fn get_expiry_status(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    block_time_secs: u64,
+) -> (u64, bool) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_EXPIRY_STATUS,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_ID => id,
+        },
+        block_time_secs,
+    )
+    .expect_success()
+    .commit();
+    (
+        get_contract_value(builder, reader_hash, "time_to_expiry"),
+        get_contract_value(builder, reader_hash, "is_expired"),
+    )
+}
+
+#[test]
+fn test_time_to_expiry_counts_down_to_zero() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let created_time = expiry - 30 * DAY_IN_SECONDS;
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), expiry);
+
+    assert_eq!(
+        get_expiry_status(&mut builder, contract_hash, 1, created_time),
+        (30 * DAY_IN_SECONDS, false)
+    );
+    assert_eq!(get_expiry_status(&mut builder, contract_hash, 1, expiry), (0, false));
+    assert_eq!(get_expiry_status(&mut builder, contract_hash, 1, expiry + 1), (0, true));
+    assert_eq!(
+        get_expiry_status(&mut builder, contract_hash, 1, expiry + DAY_IN_SECONDS),
+        (0, true)
+    );
+}
+
+/// Calls an admin settlement price entry point, `finalize_settlement_price`
+/// or `resolve_dispute`, as the default account at `block_time_secs`
+fn set_settlement_price<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    entry_point: &str,
+    expiry: u64,
+    price: u64,
+    block_time_secs: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        entry_point,
+        runtime_args! {
+            ARG_EXPIRY => expiry,
+            ARG_PRICE => U256::from(price),
+        },
+        block_time_secs,
+    )
+}
+
+/// Calls `dispute_settlement_price` for option `id` as the default account
+fn dispute_settlement_price(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_DISPUTE_SETTLEMENT_PRICE,
+        runtime_args! { ARG_ID => id },
+        block_time_secs,
+    )
+}
+
+/// Creates option 1, a one-unit call struck at 1000 whose collateral funds
+/// its payout, and expires it; the oracle meanwhile reports 1600
+fn setup_expired_call(builder: &mut InMemoryWasmTestBuilder, expiry: u64) -> ContractHash {
+    let contract_hash = get_contract_hash(builder);
+    let collateral = U512::from(5_000_000_000u64);
+    create_collateralised_call(builder, contract_hash, 1, expiry, U256::one(), collateral)
+        .expect_success()
+        .commit();
+    activate_option(builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    set_oracle_price(builder, U256::from(1600u64));
+    expire_option_at(builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    contract_hash
+}
+
+#[test]
+fn test_disputed_settlement_price_waits_for_resolution() {
+    let mut builder = setup_contract();
+    let expiry = 1735689600u64;
+    let contract_hash = setup_expired_call(&mut builder, expiry);
+    let keeper = create_funded_account(&mut builder, 2);
+    let finalize_ep = ENTRY_POINT_FINALIZE_SETTLEMENT_PRICE;
+
+    set_settlement_price(&mut builder, contract_hash, finalize_ep, expiry, 1300, expiry + 1)
+        .expect_success()
+        .commit();
+    set_settlement_price(&mut builder, contract_hash, finalize_ep, expiry, 1400, expiry + 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_SETTLEMENT_PRICE_ALREADY_SET);
+
+    // Within the window: the dispute holds settlement until it is resolved
+    dispute_settlement_price(&mut builder, contract_hash, 1, expiry + 3600)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, keeper, contract_hash, 1, expiry + 2 * DAY_IN_SECONDS)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_SETTLEMENT_PRICE_NOT_FINAL);
+
+    let resolve_ep = ENTRY_POINT_RESOLVE_DISPUTE;
+    set_settlement_price(&mut builder, contract_hash, resolve_ep, expiry, 1200, expiry + 7200)
+        .expect_success()
+        .commit();
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_IS_SETTLEMENT_FINAL,
+        runtime_args! { ARG_EXPIRY => expiry },
+    )
+    .expect_success()
+    .commit();
+    dispute_settlement_price(&mut builder, contract_hash, 1, expiry + 7200)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_DISPUTE_WINDOW_CLOSED);
+
+    // Settled at the resolved price of 1200, not the oracle's 1600
+    let owner_before = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    settle_option_at(&mut builder, keeper, contract_hash, 1, expiry + 7200)
+        .expect_success()
+        .commit();
+    assert_eq!(
+        get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR),
+        owner_before + U512::from(200u64)
+    );
+    let price: U256 =
+        get_contract_value(&builder, contract_hash, &format!("settlement_price_{}", expiry));
+    assert_eq!(price, U256::from(1200u64));
+}
+
+#[test]
+fn test_settlement_price_final_after_dispute_window() {
+    let mut builder = setup_contract();
+    let expiry = 1735689600u64;
+    let contract_hash = setup_expired_call(&mut builder, expiry);
+    let keeper = create_funded_account(&mut builder, 2);
+    let finalized_at = expiry + 1;
+    let window_closed = finalized_at + DAY_IN_SECONDS + 1;
+
+    // Before expiry there is no settlement price to finalize yet
+    let finalize_ep = ENTRY_POINT_FINALIZE_SETTLEMENT_PRICE;
+    set_settlement_price(&mut builder, contract_hash, finalize_ep, expiry, 1300, expiry - 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+    set_settlement_price(&mut builder, contract_hash, finalize_ep, expiry, 1300, finalized_at)
+        .expect_success()
+        .commit();
+
+    settle_option_at(&mut builder, keeper, contract_hash, 1, finalized_at + 60)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_SETTLEMENT_PRICE_NOT_FINAL);
+    dispute_settlement_price(&mut builder, contract_hash, 1, window_closed)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_DISPUTE_WINDOW_CLOSED);
+
+    // Settled at the finalized 1300 rather than the oracle's 1600
+    let owner_before = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    settle_option_at(&mut builder, keeper, contract_hash, 1, window_closed)
+        .expect_success()
+        .commit();
+    assert_eq!(
+        get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR),
+        owner_before + U512::from(300u64)
+    );
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, U512::from(300u64));
+}
+
+/// Reads `(is_in_the_money, is_out_of_the_money, is_at_the_money)` for option
+/// `id` at `current_price` through the mock quote reader
+fn get_moneyness(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    current_price: u64,
+) -> (bool, bool, bool) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_MONEYNESS,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_ID => id,
+            ARG_CURRENT_PRICE => U256::from(current_price),
+        },
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "moneyness")
+}
+
+#[test]
+fn test_moneyness_of_calls_and_puts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let strike = U256::from(10_000u64);
+    create_option_with_type(&mut builder, contract_hash, 1, strike, expiry, OPTION_TYPE_CALL);
+    create_option_with_type(&mut builder, contract_hash, 2, strike, expiry, OPTION_TYPE_PUT);
+
+    let in_the_money = (true, false, false);
+    let out_of_the_money = (false, true, false);
+    let at_the_money = (false, false, true);
+    for (id, price, expected) in [
+        (1, 12_000, in_the_money),
+        (1, 10_000, at_the_money),
+        (1, 8_000, out_of_the_money),
+        (2, 12_000, out_of_the_money),
+        (2, 10_000, at_the_money),
+        (2, 8_000, in_the_money),
+        // One basis point either side of the strike still counts as at the money
+        (1, 10_001, at_the_money),
+        (2, 9_999, at_the_money),
+        (1, 10_002, in_the_money),
+    ] {
+        assert_eq!(
+            get_moneyness(&mut builder, contract_hash, id, price),
+            expected,
+            "option {} at {}",
+            id,
+            price
+        );
+    }
+
+    // Exercised options are still judged on their strike alone
+    exercise_option(&mut builder, contract_hash, 1);
+    assert_eq!(get_moneyness(&mut builder, contract_hash, 1, 12_000), in_the_money);
+}
+
+/// Reads `(get_intrinsic_value, get_time_value)` for option `id` through the
+/// mock quote reader
+fn get_intrinsic_and_time_value(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    current_price: u64,
+    option_price: u64,
+) -> (U256, U256) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_OPTION_VALUE,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_ID => id,
+            ARG_CURRENT_PRICE => U256::from(current_price),
+            ARG_OPTION_PRICE => U256::from(option_price),
+        },
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "option_value")
+}
+
+#[test]
+fn test_intrinsic_and_time_value_scale_with_option_amount() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    // A call and a put over 5 units each, both struck at 1000
+    let amount = U256::from(5u64);
+    let collateral = U512::from(5000u64);
+    create_collateralised_call(&mut builder, contract_hash, 1, 1735689600, amount, collateral)
+        .expect_success()
+        .commit();
+    create_sized_put(&mut builder, contract_hash, 2, amount);
+
+    for (id, price, option_price, intrinsic, time) in [
+        // In the money: 200 per unit
+        (1, 1200, 1500, 1000, 500),
+        (2, 800, 1500, 1000, 500),
+        // At the money: all time value
+        (1, 1000, 300, 0, 300),
+        (2, 1000, 300, 0, 300),
+        // Out of the money
+        (1, 800, 100, 0, 100),
+        (2, 1200, 100, 0, 100),
+        // Quoted below intrinsic value: no time value
+        (2, 800, 900, 1000, 0),
+    ] {
+        assert_eq!(
+            get_intrinsic_and_time_value(&mut builder, contract_hash, id, price, option_price),
+            (U256::from(intrinsic), U256::from(time)),
+            "option {} at {}",
+            id,
+            price
+        );
+    }
+}
+
+/// Creates a straddle of call `call_id` and put `put_id` at strike 1000 as
+/// the default account
+fn create_straddle(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    call_id: u64,
+    put_id: u64,
+    expiry: u64,
+) {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_STRADDLE,
+        runtime_args! {
+            ARG_CALL_ID => call_id,
+            ARG_PUT_ID => put_id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+/// Calls `straddle_entry_point` with `straddle_id` as the default account
+fn call_with_straddle_id<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    straddle_entry_point: &str,
+    straddle_id: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        straddle_entry_point,
+        runtime_args! { ARG_STRADDLE_ID => straddle_id },
+    )
+}
+
+#[test]
+fn test_straddle_legs_created_and_cancelled_together() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    create_straddle(&mut builder, contract_hash, 1, 2, expiry);
+    create_straddle(&mut builder, contract_hash, 3, 4, expiry);
+
+    let count: u64 = get_contract_value(&builder, contract_hash, "straddle_count");
+    assert_eq!(count, 2);
+    let legs: (u64, u64) = get_contract_value(&builder, contract_hash, "straddle_0");
+    assert_eq!(legs, (1, 2));
+    call_with_straddle_id(&mut builder, contract_hash, ENTRY_POINT_GET_STRADDLE, 0)
+        .expect_success()
+        .commit();
+
+    for (id, option_type) in [(1u64, OPTION_TYPE_CALL), (2, OPTION_TYPE_PUT)] {
+        let stored_type: bool =
+            get_contract_value(&builder, contract_hash, &format!("option_{}_type", id));
+        let strike: U256 = get_option_field(&builder, contract_hash, OPTIONS_STRIKE_DICT, id);
+        let stored_expiry: u64 = get_option_field(&builder, contract_hash, OPTIONS_EXPIRY_DICT, id);
+        assert_eq!(stored_type, option_type);
+        assert_eq!(strike, U256::from(1000u64));
+        assert_eq!(stored_expiry, expiry);
+    }
+
+    call_with_straddle_id(&mut builder, contract_hash, ENTRY_POINT_CANCEL_STRADDLE, 0)
+        .expect_success()
+        .commit();
+    for id in 1..=4u64 {
+        let cancelled: bool =
+            get_contract_value(&builder, contract_hash, &format!("option_{}_cancelled", id));
+        assert_eq!(cancelled, id <= 2, "option {}", id);
+    }
+
+    // With one leg already cancelled, neither leg is cancelled
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 4);
+    call_with_straddle_id(&mut builder, contract_hash, ENTRY_POINT_CANCEL_STRADDLE, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_OPTION_CANCELLED);
+    let cancelled: bool = get_contract_value(&builder, contract_hash, "option_3_cancelled");
+    assert!(!cancelled);
+
+    call_with_straddle_id(&mut builder, contract_hash, ENTRY_POINT_GET_STRADDLE, 2)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_STRADDLE_NOT_FOUND);
+}
+
+/// Calls `create_spread` as the default account for a spread of options
+/// `first_id` (long) and `first_id + 1` (short)
+fn create_spread(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    first_id: u64,
+    (long_strike, short_strike): (u64, u64),
+    option_type: bool,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_SPREAD,
+        runtime_args! {
+            ARG_LONG_ID => first_id,
+            ARG_SHORT_ID => first_id + 1,
+            ARG_LONG_STRIKE => U256::from(long_strike),
+            ARG_SHORT_STRIKE => U256::from(short_strike),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => option_type,
+        },
+    )
+}
+
+/// Reads `(get_spread_max_profit, get_spread_max_loss)` through the mock
+/// quote reader
+fn get_spread_bounds(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    spread_id: u64,
+) -> (U512, U512) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_SPREAD_BOUNDS,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_SPREAD_ID => spread_id,
+        },
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "spread_bounds")
+}
+
+#[test]
+fn test_vertical_spreads_bound_profit_by_strike_width() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    // Bull call spread: long the 1000 call, short the 1200 call
+    create_spread(&mut builder, contract_hash, 1, (1000, 1200), OPTION_TYPE_CALL)
+        .expect_success()
+        .commit();
+    // Bear put spread: long the 1500 put, short the 1200 put
+    create_spread(&mut builder, contract_hash, 3, (1500, 1200), OPTION_TYPE_PUT)
+        .expect_success()
+        .commit();
+
+    let legs: (u64, u64) = get_contract_value(&builder, contract_hash, "spread_0");
+    assert_eq!(legs, (1, 2));
+    let short_strike: U256 = get_option_field(&builder, contract_hash, OPTIONS_STRIKE_DICT, 2);
+    assert_eq!(short_strike, U256::from(1200u64));
+    assert_eq!(
+        get_spread_bounds(&mut builder, contract_hash, 0),
+        (U512::from(200u64), U512::zero())
+    );
+    assert_eq!(
+        get_spread_bounds(&mut builder, contract_hash, 1),
+        (U512::from(300u64), U512::zero())
+    );
+
+    // Strikes the wrong way round for the option type are rejected
+    for (long_strike, short_strike, option_type) in [
+        (1200, 1000, OPTION_TYPE_CALL),
+        (1000, 1000, OPTION_TYPE_CALL),
+        (1000, 1200, OPTION_TYPE_PUT),
+    ] {
+        create_spread(&mut builder, contract_hash, 5, (long_strike, short_strike), option_type)
+            .expect_failure()
+            .commit();
+        assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+    }
+}
+
+#[test]
+fn test_spread_long_leg_cancelled_only_after_short_leg() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    create_spread(&mut builder, contract_hash, 1, (1000, 1200), OPTION_TYPE_CALL)
+        .expect_success()
+        .commit();
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CANCEL_OPTION,
+        runtime_args! { ARG_ID => 1u64 },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_SPREAD_SHORT_LEG_OPEN);
+
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 2);
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1);
+    for id in [1u64, 2] {
+        let cancelled: bool =
+            get_contract_value(&builder, contract_hash, &format!("option_{}_cancelled", id));
+        assert!(cancelled, "option {} should be cancelled", id);
+    }
+}
+
+/// Reads `owner`'s live portfolio ids and summed delta through the quote
+/// reader
+fn get_user_portfolio(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    owner: AccountHash,
+) -> (Vec<u64>, i64) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_USER_PORTFOLIO,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_OWNER => owner,
+            ARG_CURRENT_PRICE => U256::from(1000u64),
+        },
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "user_portfolio")
+}
+
+#[test]
+fn test_user_portfolio_tracks_live_options_and_delta() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let other_account = create_funded_account(&mut builder, 1);
+    let expiry = 1735689600u64;
+
+    let deltas = [(1u64, 60_000_000i64), (2, -40_000_000), (3, 30_000_000), (4, 50_000_000)];
+    for (id, delta) in deltas {
+        let option_type = if delta > 0 { OPTION_TYPE_CALL } else { OPTION_TYPE_PUT };
+        let strike_price = U256::from(1000u64);
+        create_option_with_type(&mut builder, contract_hash, id, strike_price, expiry, option_type);
+        update_greeks(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id, [delta, 0, 0, 0])
+            .expect_success()
+            .commit();
+    }
+    // Exercised options leave the portfolio
+    exercise_option_at(&mut builder, contract_hash, 4, expiry).expect_success().commit();
+
+    assert_eq!(
+        get_user_portfolio(&mut builder, contract_hash, *DEFAULT_ACCOUNT_ADDR),
+        (vec![1, 2, 3], 50_000_000)
+    );
+
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 2, other_account);
+    assert_eq!(
+        get_user_portfolio(&mut builder, contract_hash, *DEFAULT_ACCOUNT_ADDR),
+        (vec![1, 3], 90_000_000)
+    );
+    assert_eq!(
+        get_user_portfolio(&mut builder, contract_hash, other_account),
+        (vec![2], -40_000_000)
+    );
+}
+
+/// Reads `owner`'s net `(delta, gamma, (theta, vega))` through the quote
+/// reader
+fn get_portfolio_greeks(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    owner: AccountHash,
+) -> (i64, i64, (i64, i64)) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_PORTFOLIO_GREEKS,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_OWNER => owner,
+            ARG_CURRENT_PRICE => U256::from(1000u64),
+        },
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "portfolio_greeks")
+}
+
+#[test]
+fn test_portfolio_greeks_net_long_calls_against_long_puts() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    // Put deltas are subtracted whether the oracle stored them signed or not
+    let positions = [
+        (1u64, OPTION_TYPE_CALL, [60_000_000i64, 2_000_000, -5_000_000, 10_000_000]),
+        (2, OPTION_TYPE_PUT, [-40_000_000, 3_000_000, -4_000_000, 12_000_000]),
+        (3, OPTION_TYPE_PUT, [25_000_000, 1_000_000, -2_000_000, 8_000_000]),
+        (4, OPTION_TYPE_CALL, [50_000_000, 4_000_000, -6_000_000, 9_000_000]),
+    ];
+    for (id, option_type, greeks) in positions {
+        let strike_price = U256::from(1000u64);
+        if id == 4 {
+            // Left in state Created, so it does not count
+            let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+            call_entry_point(
+                &mut builder,
+                *DEFAULT_ACCOUNT_ADDR,
+                contract_hash,
+                ENTRY_POINT_CREATE_OPTION,
+                runtime_args! {
+                    ARG_ID => id,
+                    ARG_STRIKE_PRICE => strike_price,
+                    ARG_EXPIRY => expiry,
+                    ARG_OPTION_TYPE => option_type,
+                    ARG_NONCE => nonce,
+                },
+            )
+            .expect_success()
+            .commit();
+        } else {
+            create_option_with_type(
+                &mut builder,
+                contract_hash,
+                id,
+                strike_price,
+                expiry,
+                option_type,
+            );
+        }
+        update_greeks(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id, greeks)
+            .expect_success()
+            .commit();
+    }
+
+    // delta 60 - 40 - 25, gamma 2 + 3 + 1, theta -5 - 4 - 2, vega 10 + 12 + 8
+    assert_eq!(
+        get_portfolio_greeks(&mut builder, contract_hash, *DEFAULT_ACCOUNT_ADDR),
+        (-5_000_000, 6_000_000, (-11_000_000, 30_000_000))
+    );
+
+    let other_account = create_funded_account(&mut builder, 1);
+    assert_eq!(get_portfolio_greeks(&mut builder, contract_hash, other_account), (0, 0, (0, 0)));
+}
+
+/// Calls `process_scheduled_exercises` for `ids` as `keeper` at
+/// `block_time_secs`
+fn process_scheduled_exercises(
+    builder: &mut InMemoryWasmTestBuilder,
+    keeper: AccountHash,
+    contract_hash: ContractHash,
+    ids: Vec<u64>,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        keeper,
+        contract_hash,
+        ENTRY_POINT_PROCESS_SCHEDULED_EXERCISES,
+        runtime_args! {
+            ARG_IDS => ids,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_keeper_processes_scheduled_exercises() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 4);
+    let expiry = 1735689600u64;
+
+    for id in 1..=3 {
+        create_option(&mut builder, contract_hash, id, U256::from(1000u64), expiry);
+    }
+    register_keeper(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, keeper)
+        .expect_success()
+        .commit();
+
+    // Only the owner can schedule an exercise
+    call_entry_point(
+        &mut builder,
+        keeper,
+        contract_hash,
+        ENTRY_POINT_SCHEDULE_EXERCISE,
+        runtime_args! { ARG_ID => 1u64 },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+
+    // Option 1 stays scheduled, 2 never is and 3's schedule is withdrawn
+    for (entry_point, id) in [
+        (ENTRY_POINT_SCHEDULE_EXERCISE, 1u64),
+        (ENTRY_POINT_SCHEDULE_EXERCISE, 3),
+        (ENTRY_POINT_CANCEL_SCHEDULED_EXERCISE, 3),
+    ] {
+        call_entry_point(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            entry_point,
+            runtime_args! { ARG_ID => id },
+        )
+        .expect_success()
+        .commit();
+    }
+
+    // Only registered keepers process the queue
+    process_scheduled_exercises(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, vec![1], expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_KEEPER);
+
+    process_scheduled_exercises(&mut builder, keeper, contract_hash, vec![1, 2, 3, 99], expiry)
+        .expect_success()
+        .commit();
+
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_EXERCISED);
+    assert_eq!(get_option_state(&builder, contract_hash, 2), OPTION_STATE_ACTIVE);
+    assert_eq!(get_option_state(&builder, contract_hash, 3), OPTION_STATE_ACTIVE);
+    let exercised: bool = get_option_field(&builder, contract_hash, OPTIONS_EXERCISED_DICT, 1);
+    assert!(exercised, "Scheduled option should be exercised for its owner");
+}
+
+#[test]
+fn test_keeper_settles_in_the_money_auto_exercise_options() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 4);
+    let expiry = 1735689600u64;
+    let collateral = U512::from(5_000_000_000u64);
+
+    // One unit struck at 1000, whose collateral the payout comes from
+    create_collateralised_call(&mut builder, contract_hash, 1, expiry, U256::one(), collateral)
+        .expect_success()
+        .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), expiry);
+    register_keeper(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, keeper)
+        .expect_success()
+        .commit();
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_SET_AUTO_EXERCISE,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_AUTO_EXERCISE => true,
+        },
+    )
+    .expect_success()
+    .commit();
+    set_oracle_price(&mut builder, U256::from(1600u64));
+
+    // Option 2 is just as far in the money but was never opted in
+    let owner_before = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point_at(
+        &mut builder,
+        keeper,
+        contract_hash,
+        ENTRY_POINT_PROCESS_AUTO_EXERCISES,
+        runtime_args! {
+            ARG_FROM_EXPIRY => expiry,
+            ARG_TO_EXPIRY => expiry,
+        },
+        expiry + 1,
+    )
+    .expect_success()
+    .commit();
+
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_SETTLED);
+    assert_eq!(get_option_state(&builder, contract_hash, 2), OPTION_STATE_ACTIVE);
+    let payout = U512::from(600u64);
+    assert_eq!(
+        get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR),
+        owner_before + payout
+    );
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, payout);
+    assert!(!contract_has_key(&builder, contract_hash, "auto_exercise_pending_1"));
+}
+
+/// Calls `contribute_to_insurance_fund` as `sender`, paying `amount` from its
+/// main purse
+fn contribute_to_insurance_fund(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    amount: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let purse = builder.get_expected_account(sender).main_purse();
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_CONTRIBUTE_TO_INSURANCE_FUND,
+        runtime_args! {
+            ARG_PURSE => purse,
+            ARG_AMOUNT => U512::from(amount),
+        },
+    )
+}
+
+#[test]
+fn test_insurance_fund_covers_collateral_shortfalls() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let contributor = create_funded_account(&mut builder, 4);
+    let expiry = 1735689600u64;
+
+    // Two single-unit calls struck at 1000, each backed by 1000 motes
+    for id in [1u64, 2] {
+        let collateral = U512::from(1000u64);
+        create_collateralised_call(&mut builder, contract_hash, id, expiry, U256::one(), collateral)
+            .expect_success()
+            .commit();
+        activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id)
+            .expect_success()
+            .commit();
+    }
+    contribute_to_insurance_fund(&mut builder, contributor, contract_hash, 4000)
+        .expect_success()
+        .commit();
+    let contribution = lp_dictionary_value(
+        &builder,
+        contract_hash,
+        "insurance_contributions",
+        &account_hex(contributor),
+    );
+    assert_eq!(contribution, U512::from(4000u64));
+
+    // Each call is now owed 4000 against 1000 of collateral
+    set_oracle_price(&mut builder, U256::from(5000u64));
+    for id in [1u64, 2] {
+        expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id, expiry + 1)
+            .expect_success()
+            .commit();
+        settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id, expiry + 1)
+            .expect_success()
+            .commit();
+    }
+
+    // The fund covers option 1's shortfall in full and pays out what is left to option 2
+    for (id, settlement) in [(1u64, 4000u64), (2, 2000)] {
+        let paid: U512 =
+            get_contract_value(&builder, contract_hash, &format!("option_{}_settlement", id));
+        assert_eq!(paid, U512::from(settlement));
+        let locked: U512 =
+            get_contract_value(&builder, contract_hash, &format!("option_{}_collateral", id));
+        assert_eq!(locked, U512::zero());
+    }
+    let fund: U512 = get_contract_value(&builder, contract_hash, "insurance_fund_balance");
+    assert_eq!(fund, U512::zero());
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::zero());
+}
+
+/// Reads `is_liquidatable` for option `id` at `current_price` through the
+/// quote reader
+fn is_liquidatable(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    current_price: u64,
+) -> bool {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_IS_LIQUIDATABLE,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_ID => id,
+            ARG_CURRENT_PRICE => U256::from(current_price),
+        },
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "is_liquidatable")
+}
+
+/// Calls `liquidate_option` for `id` as `sender`
+fn liquidate_option(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_LIQUIDATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+        },
+    )
+}
+
+#[test]
+fn test_under_collateralised_option_liquidated() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let liquidator = create_funded_account(&mut builder, 4);
+
+    // One call struck at 1000 backed by 1000 motes, enough up to a price of 2000
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        1735689600u64,
+        U256::one(),
+        U512::from(1000u64),
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    assert!(!is_liquidatable(&mut builder, contract_hash, 1, 1900));
+    assert!(is_liquidatable(&mut builder, contract_hash, 1, 2500));
+
+    set_oracle_price(&mut builder, U256::from(1900u64));
+    liquidate_option(&mut builder, liquidator, contract_hash, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_LIQUIDATABLE);
+
+    // The liquidator takes the default 5% bonus, the owner the other 950 of the 1500 owed
+    set_oracle_price(&mut builder, U256::from(2500u64));
+    let owner_before = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    liquidate_option(&mut builder, liquidator, contract_hash, 1)
+        .expect_success()
+        .commit();
+
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_SETTLED);
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, U512::from(950u64));
+    assert_eq!(
+        get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR),
+        owner_before + U512::from(950u64)
+    );
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::zero());
+    assert!(!is_liquidatable(&mut builder, contract_hash, 1, 2500));
+}
+
+/// Calls `approve_exercise` for option `id` as `signer`
+fn approve_exercise(
+    builder: &mut InMemoryWasmTestBuilder,
+    signer: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        signer,
+        contract_hash,
+        ENTRY_POINT_APPROVE_EXERCISE,
+        runtime_args! {
+            ARG_ID => id,
+        },
+    )
+}
+
+#[test]
+fn test_high_value_exercise_requires_signer_approvals() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let signers: Vec<AccountHash> =
+        (4..=6).map(|seed| create_funded_account(&mut builder, seed)).collect();
+    let expiry = 1735689600u64;
+
+    // Options with a notional above 1_000_000 need all three signers
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_SET_EXERCISE_APPROVAL_POLICY,
+        runtime_args! {
+            ARG_HIGH_VALUE_THRESHOLD => U256::from(1_000_000u64),
+            ARG_SIGNERS => signers.clone(),
+            ARG_EXERCISE_THRESHOLD => 3u32,
+        },
+    )
+    .expect_success()
+    .commit();
+    create_option(&mut builder, contract_hash, 1, U256::from(2_000_000u64), expiry);
+    create_option(&mut builder, contract_hash, 2, U256::from(1_000_000u64), expiry);
+
+    approve_exercise(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_EXERCISE_SIGNER);
+
+    // A repeated approval counts once
+    for signer in [signers[0], signers[1], signers[1]] {
+        approve_exercise(&mut builder, signer, contract_hash, 1)
+            .expect_success()
+            .commit();
+    }
+    exercise_option_at(&mut builder, contract_hash, 1, expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_APPROVALS);
+
+    approve_exercise(&mut builder, signers[2], contract_hash, 1)
+        .expect_success()
+        .commit();
+    exercise_option_at(&mut builder, contract_hash, 1, expiry)
+        .expect_success()
+        .commit();
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_EXERCISED);
+
+    // Option 2 is not above the threshold
+    exercise_option_at(&mut builder, contract_hash, 2, expiry)
+        .expect_success()
+        .commit();
+}
+
+/// Sets an exercise approval policy needing `signer` alone above a notional
+/// of 1_000_000
+fn set_single_signer_policy(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    signer: AccountHash,
+) {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_SET_EXERCISE_APPROVAL_POLICY,
+        runtime_args! {
+            ARG_HIGH_VALUE_THRESHOLD => U256::from(1_000_000u64),
+            ARG_SIGNERS => vec![signer],
+            ARG_EXERCISE_THRESHOLD => 1u32,
+        },
+    )
+    .expect_success()
+    .commit();
+}
+
+#[test]
+fn test_removed_signers_approvals_stop_counting() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let old_signer = create_funded_account(&mut builder, 4);
+    let new_signer = create_funded_account(&mut builder, 5);
+    let expiry = 1735689600u64;
+
+    set_single_signer_policy(&mut builder, contract_hash, old_signer);
+    create_option(&mut builder, contract_hash, 1, U256::from(2_000_000u64), expiry);
+    approve_exercise(&mut builder, old_signer, contract_hash, 1)
+        .expect_success()
+        .commit();
+
+    // Replacing the signer drops the old approval
+    set_single_signer_policy(&mut builder, contract_hash, new_signer);
+    exercise_option_at(&mut builder, contract_hash, 1, expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_APPROVALS);
+
+    approve_exercise(&mut builder, new_signer, contract_hash, 1)
+        .expect_success()
+        .commit();
+    exercise_option_at(&mut builder, contract_hash, 1, expiry)
+        .expect_success()
+        .commit();
+}
+
+/// Calls `create_syndicated_option` for a single-unit call struck at 1000,
+/// owned by the default account
+fn create_syndicated_call(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    expiry: u64,
+    syndicators: Vec<AccountHash>,
+    shares: Vec<u64>,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_SYNDICATED_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_OPTION_AMOUNT => U256::one(),
+            ARG_SYNDICATORS => syndicators,
+            ARG_SHARES => shares,
+        },
+    )
+}
+
+/// Calls `join_syndicate` for option `id` as `member`, paying `amount` from
+/// its main purse
+fn join_syndicate(
+    builder: &mut InMemoryWasmTestBuilder,
+    member: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    amount: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let purse = builder.get_expected_account(member).main_purse();
+    call_entry_point(
+        builder,
+        member,
+        contract_hash,
+        ENTRY_POINT_JOIN_SYNDICATE,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_PURSE => purse,
+            ARG_AMOUNT => U512::from(amount),
+        },
+    )
+}
+
+/// Reads `member`'s `(share_bps, collateral)` in syndicated option `id`
+fn get_syndicate_position(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    member: AccountHash,
+) -> (u64, U512) {
+    let dictionary_uref = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get("syndicates")
+        .expect("Dictionary syndicates not found")
+        .into_uref()
+        .expect("Dictionary is not a URef");
+
+    builder
+        .query_dictionary_item(None, dictionary_uref, &format!("{}_{}", id, account_hex(member)))
+        .expect("Syndicate position not found")
+        .as_cl_value()
+        .expect("Not a CLValue")
+        .clone()
+        .into_t()
+        .expect("Failed to parse syndicate position")
+}
+
+#[test]
+fn test_syndicated_option_draws_collateral_by_share() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let members: Vec<AccountHash> =
+        (4..=6).map(|seed| create_funded_account(&mut builder, seed)).collect();
+    let expiry = 1735689600u64;
+
+    let overallocated = vec![4000, 4000, 3000];
+    create_syndicated_call(&mut builder, contract_hash, 1, expiry, members.clone(), overallocated)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+
+    // Shares of 40/40/20% of the 1000 strike notional
+    let shares = vec![4000, 4000, 2000];
+    create_syndicated_call(&mut builder, contract_hash, 1, expiry, members.clone(), shares)
+        .expect_success()
+        .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_SYNDICATE_NOT_FUNDED);
+
+    for (member, amount) in members.iter().zip([400u64, 400, 200]) {
+        join_syndicate(&mut builder, *member, contract_hash, 1, amount)
+            .expect_success()
+            .commit();
+    }
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    join_syndicate(&mut builder, members[0], contract_hash, 1, 100)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_STATE_TRANSITION);
+
+    // Settling at 1500 pays out 500, split 200/200/100 across the syndicators
+    set_oracle_price(&mut builder, U256::from(1500u64));
+    expire_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, U512::from(500u64));
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(locked, U512::from(500u64));
+    let expected = [(4000u64, 200u64), (4000, 200), (2000, 100)];
+    for (member, (share, left)) in members.iter().zip(expected) {
+        assert_eq!(
+            get_syndicate_position(&builder, contract_hash, 1, *member),
+            (share, U512::from(left))
+        );
+    }
+
+    // Each syndicator withdraws only what is left of its own collateral
+    call_entry_point_at(
+        &mut builder,
+        members[2],
+        contract_hash,
+        ENTRY_POINT_WITHDRAW_COLLATERAL,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+        expiry + 1,
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(
+        get_syndicate_position(&builder, contract_hash, 1, members[2]),
+        (2000, U512::zero())
+    );
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(locked, U512::from(400u64));
+}
+
+/// Calls `place_option_order` as `placer` for one unit of a call struck at
+/// 1000, buying for at most or selling for at least `price`, with the escrow
+/// paid from its main purse
+fn place_option_order(
+    builder: &mut InMemoryWasmTestBuilder,
+    placer: AccountHash,
+    contract_hash: ContractHash,
+    expiry: u64,
+    is_buy: bool,
+    price: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let purse = builder.get_expected_account(placer).main_purse();
+    let price_arg = if is_buy { ARG_MAX_PRICE } else { ARG_MIN_PRICE };
+    let mut args = runtime_args! {
+        ARG_STRIKE => U256::from(1000u64),
+        ARG_EXPIRY => expiry,
+        ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+        ARG_IS_BUY => is_buy,
+        ARG_AMOUNT => U256::one(),
+        ARG_PURSE => purse,
+    };
+    args.insert(price_arg, U512::from(price)).expect("Failed to add price");
+    call_entry_point(builder, placer, contract_hash, ENTRY_POINT_PLACE_OPTION_ORDER, args)
+}
+
+/// Calls `match_orders` as the default account, creating option `id`
+fn match_orders(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    buy_order_id: u64,
+    sell_order_id: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_MATCH_ORDERS,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_BUY_ORDER_ID => buy_order_id,
+            ARG_SELL_ORDER_ID => sell_order_id,
+        },
+    )
+}
+
+/// Calls `cancel_order` for `order_id` as `sender`
+fn cancel_order(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    order_id: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_CANCEL_ORDER,
+        runtime_args! {
+            ARG_ORDER_ID => order_id,
+        },
+    )
+}
+
+#[test]
+fn test_matched_orders_create_option_at_agreed_price() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let seller = create_funded_account(&mut builder, 4);
+    let expiry = 1735689600u64;
+
+    // Order 0 sells for at least 100, escrowing 1000 of collateral; order 1 bids only 80
+    place_option_order(&mut builder, seller, contract_hash, expiry, false, 100)
+        .expect_success()
+        .commit();
+    place_option_order(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, expiry, true, 80)
+        .expect_success()
+        .commit();
+    match_orders(&mut builder, contract_hash, 1, 1, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_ORDERS_DO_NOT_MATCH);
+
+    // Order 2 bids 150 and trades at the resting sell order's 100
+    place_option_order(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, expiry, true, 150)
+        .expect_success()
+        .commit();
+    let order_count: u64 = get_contract_value(&builder, contract_hash, "order_count");
+    assert_eq!(order_count, 3);
+    cancel_order(&mut builder, seller, contract_hash, 2)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ORDER_OWNER);
+    match_orders(&mut builder, contract_hash, 1, 2, 0)
+        .expect_success()
+        .commit();
+
+    let owner: AccountHash = get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, 1);
+    assert_eq!(owner, *DEFAULT_ACCOUNT_ADDR);
+    let writer: AccountHash = get_contract_value(&builder, contract_hash, "option_1_writer");
+    assert_eq!(writer, seller);
+    let premium: U512 = get_contract_value(&builder, contract_hash, "option_1_premium");
+    assert_eq!(premium, U512::from(100u64));
+    let collateral: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(collateral, U512::from(1000u64));
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_CREATED);
+
+    match_orders(&mut builder, contract_hash, 2, 2, 0)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_ORDER_NOT_OPEN);
+
+    // Cancelling order 1 refunds its bid, leaving only the premium and collateral
+    cancel_order(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    let open: bool = get_contract_value(&builder, contract_hash, "order_1_open");
+    assert!(!open, "Cancelled order should be closed");
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::from(1100u64));
+}
+
+#[test]
+fn test_put_sell_order_escrows_collateral() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let seller = create_funded_account(&mut builder, 4);
+    let purse = builder.get_expected_account(seller).main_purse();
+
+    // Two puts struck at 1000 escrow 2000, the most the writer can owe
+    call_entry_point(
+        &mut builder,
+        seller,
+        contract_hash,
+        ENTRY_POINT_PLACE_OPTION_ORDER,
+        runtime_args! {
+            ARG_STRIKE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_PUT,
+            ARG_IS_BUY => false,
+            ARG_MIN_PRICE => U512::from(100u64),
+            ARG_AMOUNT => U256::from(2u64),
+            ARG_PURSE => purse,
+        },
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::from(2000u64));
+
+    cancel_order(&mut builder, seller, contract_hash, 0)
+        .expect_success()
+        .commit();
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::zero());
+}
+
+/// Reads `get_best_bid` and `get_best_ask` for calls struck at 1000 expiring
+/// at `expiry` through the quote reader
+fn get_best_bid_ask(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    expiry: u64,
+) -> (Option<U512>, Option<U512>) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_BEST_BID_ASK,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_STRIKE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+        },
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "best_bid_ask")
+}
+
+#[test]
+fn test_best_bid_and_ask_track_open_orders() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    assert_eq!(get_best_bid_ask(&mut builder, contract_hash, expiry), (None, None));
+
+    // Orders 0-2 bid 80, 120 and 100; orders 3-4 ask 300 and 200; order 5 is another expiry
+    let orders = [(true, 80u64), (true, 120), (true, 100), (false, 300), (false, 200)];
+    let placer = *DEFAULT_ACCOUNT_ADDR;
+    for (is_buy, price) in orders {
+        place_option_order(&mut builder, placer, contract_hash, expiry, is_buy, price)
+            .expect_success()
+            .commit();
+    }
+    place_option_order(&mut builder, placer, contract_hash, expiry + 1, true, 500)
+        .expect_success()
+        .commit();
+    assert_eq!(
+        get_best_bid_ask(&mut builder, contract_hash, expiry),
+        (Some(U512::from(120u64)), Some(U512::from(200u64)))
+    );
+
+    // Cancelling the best bid and ask exposes the next best prices
+    for order_id in [1u64, 4] {
+        cancel_order(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, order_id)
+            .expect_success()
+            .commit();
+    }
+    assert_eq!(
+        get_best_bid_ask(&mut builder, contract_hash, expiry),
+        (Some(U512::from(100u64)), Some(U512::from(300u64)))
+    );
+}
+
+/// Reads `get_accumulated_fees` and `get_total_fees_collected` through the
+/// quote reader
+fn get_fees(builder: &mut InMemoryWasmTestBuilder, contract_hash: ContractHash) -> (U512, U512) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_FEES,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+        },
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "fees")
+}
+
+/// Calls `withdraw_fees_to_treasury` as `sender`, sending `amount` to
+/// `destination`
+fn withdraw_fees_to_treasury(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    amount: u64,
+    destination: AccountHash,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_WITHDRAW_FEES_TO_TREASURY,
+        runtime_args! {
+            ARG_AMOUNT => U512::from(amount),
+            ARG_DESTINATION => destination,
+        },
+    )
+}
+
+#[test]
+fn test_fees_withdrawn_to_treasury_up_to_accumulated() {
+    // A 1% fee on a 1_000_000 payout
+    let (mut builder, contract_hash, _) = settle_with_fee(100, 1_000_000);
+    let treasury = create_funded_account(&mut builder, 3);
+    let fees = (U512::from(10_000u64), U512::from(10_000u64));
+    assert_eq!(get_fees(&mut builder, contract_hash), fees);
+
+    withdraw_fees_to_treasury(&mut builder, treasury, contract_hash, 4_000, treasury)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+    withdraw_fees_to_treasury(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 10_001, treasury)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_ACCUMULATED_FEES);
+
+    let treasury_before = get_account_balance(&builder, treasury);
+    withdraw_fees_to_treasury(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 4_000, treasury)
+        .expect_success()
+        .commit();
+    assert_eq!(get_account_balance(&builder, treasury), treasury_before + U512::from(4_000u64));
+
+    // The all-time total is unaffected by withdrawals
+    let fees = (U512::from(6_000u64), U512::from(10_000u64));
+    assert_eq!(get_fees(&mut builder, contract_hash), fees);
+}
+
+/// Calls `withdraw_my_collateral` for `id` as the default account
+fn withdraw_my_collateral(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_WITHDRAW_MY_COLLATERAL,
+        runtime_args! {
+            ARG_ID => id,
+        },
+    )
+}
+
+#[test]
+fn test_deprecated_registry_pays_out_fees_and_collateral() {
+    // A 1% fee on a 1_000_000 payout
+    let (mut builder, contract_hash, _) = settle_with_fee(100, 1_000_000);
+    let treasury = create_funded_account(&mut builder, 3);
+    let expiry = 1735689600u64;
+    let collateral = U512::from(1000u64);
+
+    create_collateralised_call(&mut builder, contract_hash, 2, expiry, U256::one(), collateral)
+        .expect_success()
+        .commit();
+    withdraw_my_collateral(&mut builder, contract_hash, 2)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_CONTRACT_NOT_DEPRECATED);
+
+    let treasury_before = get_account_balance(&builder, treasury);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_DEPRECATE,
+        runtime_args! {
+            ARG_DESTINATION => treasury,
+        },
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(get_account_balance(&builder, treasury), treasury_before + U512::from(10_000u64));
+    let accumulated: U512 = get_contract_value(&builder, contract_hash, "accumulated_fees");
+    assert_eq!(accumulated, U512::zero());
+    let deprecated: bool = get_contract_value(&builder, contract_hash, "deprecated");
+    assert!(deprecated, "Registry should be marked as deprecated");
+    assert!(contract_has_key(&builder, contract_hash, "deprecated_at"));
+
+    let premium = U512::from(5_000u64);
+    create_option_with_premium(&mut builder, contract_hash, 3, expiry, premium, premium)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_CONTRACT_DEPRECATED);
+
+    // The call is still open, yet its writer takes the collateral back
+    let purse_before = get_contract_purse_balance(&builder, contract_hash);
+    withdraw_my_collateral(&mut builder, contract_hash, 2)
+        .expect_success()
+        .commit();
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), purse_before - collateral);
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_2_collateral");
+    assert_eq!(locked, U512::zero());
+}
+
+#[test]
+fn test_deprecated_registry_lets_lps_and_writers_withdraw() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let lp = create_funded_account(&mut builder, 2);
+    let expiry = 1735689600u64;
+    let premium = U512::from(5_000u64);
+
+    deposit_to_pool(&mut builder, lp, contract_hash, 10_000_000)
+        .expect_success()
+        .commit();
+    // Locks half the pool behind option 1
+    write_option_from_pool(&mut builder, admin, contract_hash, 1, 5_000)
+        .expect_success()
+        .commit();
+    create_option_with_premium(&mut builder, contract_hash, 2, expiry, premium, premium)
+        .expect_success()
+        .commit();
+    call_entry_point(
+        &mut builder,
+        admin,
+        contract_hash,
+        ENTRY_POINT_DEPRECATE,
+        runtime_args! {
+            ARG_DESTINATION => admin,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    // The writer still claims the premium once option 2 expires
+    call_entry_point_at(
+        &mut builder,
+        admin,
+        contract_hash,
+        ENTRY_POINT_CLAIM_PREMIUM,
+        runtime_args! {
+            ARG_ID => 2u64,
+        },
+        expiry + 1,
+    )
+    .expect_success()
+    .commit();
+
+    // Only the free half of the pool can leave until option 1's collateral
+    // is released, which anyone may do
+    withdraw_from_pool(&mut builder, lp, contract_hash, 10_000_000)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_POOL_LIQUIDITY);
+    withdraw_my_collateral(&mut builder, contract_hash, 1)
+        .expect_success()
+        .commit();
+    let utilized: U512 = get_contract_value(&builder, contract_hash, POOL_UTILIZED_COLLATERAL_KEY);
+    assert_eq!(utilized, U512::zero());
+
+    let purse_before = get_contract_purse_balance(&builder, contract_hash);
+    withdraw_from_pool(&mut builder, lp, contract_hash, 10_000_000)
+        .expect_success()
+        .commit();
+    assert_eq!(lp_balance(&builder, contract_hash, lp), U512::zero());
+    assert_eq!(
+        get_contract_purse_balance(&builder, contract_hash),
+        purse_before - U512::from(10_000_000u64)
+    );
+}
+
+/// Calls `emergency_cancel_batch` for `ids` as the default account
+fn emergency_cancel_batch(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    ids: Vec<u64>,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EMERGENCY_CANCEL_BATCH,
+        runtime_args! {
+            ARG_IDS => ids,
+        },
+    )
+}
+
+#[test]
+fn test_emergency_cancel_batch_refunds_buyers_and_writer() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let buyers = [create_funded_account(&mut builder, 2), create_funded_account(&mut builder, 3)];
+    let expiry = 1735689600u64;
+    let premium = U512::from(500u64);
+
+    // The default account writes ten one-unit calls, locking 1000 each on
+    // top of the premium, and sells them to alternating buyers
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+    for id in 1..=10u64 {
+        let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+        call_entry_point(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            contract_hash,
+            ENTRY_POINT_CREATE_OPTION,
+            runtime_args! {
+                ARG_ID => id,
+                ARG_STRIKE_PRICE => U256::from(1000u64),
+                ARG_EXPIRY => expiry,
+                ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+                ARG_NONCE => nonce,
+                ARG_OPTION_AMOUNT => U256::one(),
+                ARG_PREMIUM => premium,
+                ARG_PURSE => purse,
+                ARG_AMOUNT => premium + U512::from(1000u64),
+            },
+        )
+        .expect_success()
+        .commit();
+        activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id)
+            .expect_success()
+            .commit();
+        let buyer = buyers[(id % 2) as usize];
+        transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id, buyer);
+    }
+
+    emergency_cancel_batch(&mut builder, contract_hash, vec![1])
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_CONTRACT_NOT_PAUSED);
+
+    pause_as(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash)
+        .expect_success()
+        .commit();
+    let balances_before = buyers.map(|buyer| get_account_balance(&builder, buyer));
+    // Option 99 does not exist and is skipped
+    emergency_cancel_batch(&mut builder, contract_hash, (1..=10).chain([99]).collect())
+        .expect_success()
+        .commit();
+
+    for (buyer, before) in buyers.iter().zip(balances_before) {
+        assert_eq!(get_account_balance(&builder, *buyer), before + premium * 5);
+    }
+    for id in 1..=10u64 {
+        assert_eq!(get_option_state(&builder, contract_hash, id), OPTION_STATE_CANCELLED);
+        let locked: U512 =
+            get_contract_value(&builder, contract_hash, &format!("option_{}_collateral", id));
+        assert_eq!(locked, U512::zero());
+    }
+    // Every premium went back to the buyers and all collateral to the writer
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::zero());
+    let cancelled: u64 = get_contract_value(&builder, contract_hash, "emergency_cancelled_count");
+    assert_eq!(cancelled, 10);
+    let refunded: U512 = get_contract_value(&builder, contract_hash, "emergency_refund_total");
+    assert_eq!(refunded, premium * 10);
+}
+
+/// Installs the mock oracle, the mock CEP-78 collection and a registry that
+/// mints option tokens into it, returning the collection's hash
+fn setup_contract_with_option_nft() -> (InMemoryWasmTestBuilder, ContractHash) {
+    let mut builder = setup_mock_oracle();
+    let oracle_hash = get_mock_oracle_hash(&builder);
+
+    let nft_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_mock_cep78_wasm_path().to_string_lossy(),
+        RuntimeArgs::new(),
+    )
+    .build();
+    builder.exec(nft_request).expect_success().commit();
+    let nft_hash = builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(MOCK_CEP78_KEY)
+        .expect("Mock CEP-78 not found")
+        .into_hash()
+        .map(ContractHash::new)
+        .expect("Invalid mock CEP-78 hash");
+
+    let install_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_wasm_path().to_string_lossy(),
+        runtime_args! {
+            ARG_ORACLE_CONTRACT_HASH => oracle_hash,
+            ARG_FEE_BPS => 0u32,
+            ARG_KEEPER_REWARD_PER_OPTION => U512::zero(),
+            ARG_OPTION_NFT_CONTRACT_HASH => Key::Hash(nft_hash.value()),
+        },
+    )
+    .build();
+    builder.exec(install_request).expect_success().commit();
+    (builder, nft_hash)
+}
+
+#[test]
+fn test_option_nft_follows_option_ownership() {
+    let (mut builder, nft_hash) = setup_contract_with_option_nft();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    create_option(&mut builder, contract_hash, 1, U256::from(1000000u64), expiry);
+
+    let token_id: u64 = get_contract_value(&builder, contract_hash, "option_1_nft_token_id");
+    assert_eq!(token_id, 0);
+    let owner: Key = get_contract_value(&builder, nft_hash, "token_owner_0");
+    assert_eq!(owner, Key::Account(*DEFAULT_ACCOUNT_ADDR));
+    let metadata: String = get_contract_value(&builder, nft_hash, "token_meta_data_0");
+    let expected = r#"{"id":"1","strike":"1000000","expiry":"1735689600","type":"call"}"#;
+    assert_eq!(metadata, expected);
+
+    let buyer = create_funded_account(&mut builder, 8);
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, buyer);
+    let owner: Key = get_contract_value(&builder, nft_hash, "token_owner_0");
+    assert_eq!(owner, Key::Account(buyer));
+
+    // Exercising the last unit burns the token
+    call_entry_point(
+        &mut builder,
+        buyer,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+        },
+    )
+    .expect_success()
+    .commit();
+    let burnt: bool = get_contract_value(&builder, nft_hash, "burnt_0");
+    assert!(burnt, "Option token should be burnt");
+}
+
+/// Creates option `id` as the default account with `metadata_uri`
+fn create_option_with_metadata<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    metadata_uri: &str,
+) -> &'a mut InMemoryWasmTestBuilder {
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_METADATA_URI => metadata_uri.to_string(),
+        },
+    )
+}
+
+/// Calls `set_metadata_uri` for option `id` as `sender`
+fn set_metadata_uri<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    id: u64,
+    metadata_uri: &str,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_SET_METADATA_URI,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_METADATA_URI => metadata_uri.to_string(),
+        },
+    )
+}
+
+#[test]
+fn test_metadata_uri_set_at_creation_and_updated() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    create_option_with_metadata(&mut builder, contract_hash, 1, "ipfs://terms-v1")
+        .expect_success()
+        .commit();
+    let uri: String = get_contract_value(&builder, contract_hash, "option_1_metadata_uri");
+    assert_eq!(uri, "ipfs://terms-v1");
+
+    create_option(&mut builder, contract_hash, 2, U256::from(1000000u64), 1735689600u64);
+    assert!(!contract_has_key(&builder, contract_hash, "option_2_metadata_uri"));
+
+    // Only the owner or the admin may change it
+    let stranger = create_funded_account(&mut builder, 8);
+    set_metadata_uri(&mut builder, stranger, contract_hash, 1, "ipfs://spoofed")
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+    set_metadata_uri(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 2, "ipfs://terms-v2")
+        .expect_success()
+        .commit();
+    let uri: String = get_contract_value(&builder, contract_hash, "option_2_metadata_uri");
+    assert_eq!(uri, "ipfs://terms-v2");
+}
+
+#[test]
+fn test_metadata_uri_longer_than_512_chars_rejected() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let too_long = "a".repeat(513);
+
+    create_option_with_metadata(&mut builder, contract_hash, 1, &too_long)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_METADATA_TOO_LONG);
+
+    create_option_with_metadata(&mut builder, contract_hash, 1, &too_long[..512])
+        .expect_success()
+        .commit();
+    set_metadata_uri(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, &too_long)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_METADATA_TOO_LONG);
+}
+
+/// Calls `update_oracle_price` as `sender` in a block at `block_time_secs`
+fn update_oracle_price_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    price: u64,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_UPDATE_ORACLE_PRICE,
+        runtime_args! {
+            ARG_PRICE => U256::from(price),
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_settlement_rejects_stale_published_price() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 2);
+    let publisher = create_funded_account(&mut builder, 3);
+    let expiry = 1735689600u64;
+    let collateral = U512::from(5_000_000_000u64);
+
+    // One unit struck at 1000, out of the money at the oracle's price
+    create_collateralised_call(&mut builder, contract_hash, 1, expiry, U256::one(), collateral)
+        .expect_success()
+        .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    set_oracle_price(&mut builder, U256::from(1000u64));
+    expire_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    update_oracle_price_at(&mut builder, publisher, contract_hash, 1_500, expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_PRICE_PUBLISHER);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_ADD_PRICE_PUBLISHER,
+        runtime_args! {
+            ARG_PUBLISHER => publisher,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    // Published more than the default hour before settlement
+    update_oracle_price_at(&mut builder, publisher, contract_hash, 1_500, expiry - 3_600)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_ORACLE_PRICE_STALE);
+
+    // A fresh price replaces the oracle's
+    update_oracle_price_at(&mut builder, publisher, contract_hash, 1_500, expiry)
+        .expect_success()
+        .commit();
+    let owner_before = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    settle_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    let received = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR) - owner_before;
+    assert_eq!(received, U512::from(500u64));
+}
+
+/// Sets the mock oracle to `price` and records it as a snapshot as `keeper`
+/// at `block_time_secs`
+fn record_price_snapshot_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    keeper: AccountHash,
+    contract_hash: ContractHash,
+    price: u64,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    set_oracle_price(builder, U256::from(price));
+    call_entry_point_at(
+        builder,
+        keeper,
+        contract_hash,
+        ENTRY_POINT_RECORD_PRICE_SNAPSHOT,
+        runtime_args! {},
+        block_time_secs,
+    )
+}
+
+/// Reads `compute_twap` over `window_seconds` through the quote reader at
+/// `block_time_secs`
+fn get_twap(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    window_seconds: u64,
+    block_time_secs: u64,
+) -> U256 {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_TWAP,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_WINDOW_SECONDS => window_seconds,
+        },
+        block_time_secs,
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "twap")
+}
+
+#[test]
+fn test_twap_weights_snapshot_prices_by_time() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 2);
+    register_keeper(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, keeper)
+        .expect_success()
+        .commit();
+
+    // Prices 1000, 1100, ..., 1900, ten minutes apart
+    let start = 1735689600u64;
+    for i in 0..10u64 {
+        let price = 1000 + 100 * i;
+        record_price_snapshot_at(&mut builder, keeper, contract_hash, price, start + 600 * i)
+            .expect_success()
+            .commit();
+    }
+    let last = start + 5_400;
+    record_price_snapshot_at(&mut builder, keeper, contract_hash, 2000, last + 299)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_SNAPSHOT_TOO_EARLY);
+
+    // Ten minutes after the last snapshot every price has held equally long
+    let now = last + 600;
+    assert_eq!(get_twap(&mut builder, contract_hash, 6_000, now), U256::from(1450u64));
+    assert_eq!(get_twap(&mut builder, contract_hash, 1_800, now), U256::from(1800u64));
+    // 1900 for 600 seconds and 1800 for the 300 before
+    assert_eq!(get_twap(&mut builder, contract_hash, 900, now), U256::from(1866u64));
+}
+
+#[test]
+fn test_settlement_uses_twap_when_enabled() {
+    let mut builder = setup_mock_oracle();
+    let oracle_hash = get_mock_oracle_hash(&builder);
+    let install_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_wasm_path().to_string_lossy(),
+        runtime_args! {
+            ARG_ORACLE_CONTRACT_HASH => oracle_hash,
+            ARG_FEE_BPS => 0u32,
+            ARG_KEEPER_REWARD_PER_OPTION => U512::zero(),
+            ARG_USE_TWAP => true,
+            ARG_TWAP_WINDOW => 1_800u64,
+        },
+    )
+    .build();
+    builder.exec(install_request).expect_success().commit();
+    let contract_hash = get_contract_hash(&builder);
+    let keeper = create_funded_account(&mut builder, 2);
+    register_keeper(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, keeper)
+        .expect_success()
+        .commit();
+
+    // One unit struck at 1000
+    let expiry = 1735689600u64;
+    let collateral = U512::from(5_000_000_000u64);
+    create_collateralised_call(&mut builder, contract_hash, 1, expiry, U256::one(), collateral)
+        .expect_success()
+        .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    let settle_time = expiry + 1;
+    for (price, age) in [(1200u64, 1_800u64), (1400, 1_200), (1600, 600)] {
+        record_price_snapshot_at(&mut builder, keeper, contract_hash, price, settle_time - age)
+            .expect_success()
+            .commit();
+    }
+
+    // The spot price is at the strike, but the half hour TWAP is 1400
+    set_oracle_price(&mut builder, U256::from(1000u64));
+    expire_option_at(&mut builder, keeper, contract_hash, 1, settle_time)
+        .expect_success()
+        .commit();
+    let owner_before = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    settle_option_at(&mut builder, keeper, contract_hash, 1, settle_time)
+        .expect_success()
+        .commit();
+    let received = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR) - owner_before;
+    assert_eq!(received, U512::from(400u64));
+}
+
+/// Reads `get_unrealized_pnl` for option `id` at `current_price` through the
+/// quote reader, as `(is_loss, magnitude)`
+fn get_unrealized_pnl(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    current_price: u64,
+) -> (bool, U512) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_UNREALIZED_PNL,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_ID => id,
+            ARG_CURRENT_PRICE => U256::from(current_price),
+        },
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "unrealized_pnl")
+}
+
+#[test]
+fn test_unrealized_pnl_nets_premium_against_intrinsic_value() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+    let premium = U512::from(150u64);
+
+    // A call struck at 1000, bought for 150 with the underlying at 1200
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => 0u64,
+            ARG_PREMIUM => premium,
+            ARG_PURSE => purse,
+            ARG_AMOUNT => premium,
+            ARG_UNDERLYING_PRICE_AT_CREATION => U256::from(1200u64),
+        },
+    )
+    .expect_success()
+    .commit();
+    let entry_price: U256 = get_contract_value(&builder, contract_hash, "option_1_entry_price");
+    assert_eq!(entry_price, U256::from(1200u64));
+
+    let pnl = |gain: u64| (false, U512::from(gain));
+    assert_eq!(get_unrealized_pnl(&mut builder, contract_hash, 1, 1500), pnl(350));
+    assert_eq!(get_unrealized_pnl(&mut builder, contract_hash, 1, 1100), (true, U512::from(50u64)));
+    assert_eq!(get_unrealized_pnl(&mut builder, contract_hash, 1, 900), (true, premium));
+
+    // Without a price or a published one zero is recorded; with no premium,
+    // out of the money is zero
+    create_option(&mut builder, contract_hash, 2, U256::from(1000u64), 1735689600u64);
+    let entry_price: U256 = get_contract_value(&builder, contract_hash, "option_2_entry_price");
+    assert_eq!(entry_price, U256::zero());
+    assert_eq!(get_unrealized_pnl(&mut builder, contract_hash, 2, 1300), pnl(300));
+    assert_eq!(get_unrealized_pnl(&mut builder, contract_hash, 2, 900), pnl(0));
+}
+
+/// Creates option `id` without an entry price as the default account in a
+/// block at `block_time_secs`
+fn create_option_without_entry_price_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_entry_price_defaults_to_fresh_published_price() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let publisher = create_funded_account(&mut builder, 2);
+    let published_at = 1_700_000_000u64;
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_ADD_PRICE_PUBLISHER,
+        runtime_args! {
+            ARG_PUBLISHER => publisher,
+        },
+    )
+    .expect_success()
+    .commit();
+    update_oracle_price_at(&mut builder, publisher, contract_hash, 1_250, published_at)
+        .expect_success()
+        .commit();
+
+    create_option_without_entry_price_at(&mut builder, contract_hash, 1, published_at + 60)
+        .expect_success()
+        .commit();
+    let entry_price: U256 = get_contract_value(&builder, contract_hash, "option_1_entry_price");
+    assert_eq!(entry_price, U256::from(1_250u64));
+
+    // A stale price is not recorded, and doesn't stop the option being created
+    create_option_without_entry_price_at(&mut builder, contract_hash, 2, published_at + 3_601)
+        .expect_success()
+        .commit();
+    let entry_price: U256 = get_contract_value(&builder, contract_hash, "option_2_entry_price");
+    assert_eq!(entry_price, U256::zero());
+}
+
+/// Calls `apply_dividend_adjustment` for `series_id` as `sender`
+fn apply_dividend_adjustment(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    series_id: u64,
+    dividend: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_APPLY_DIVIDEND_ADJUSTMENT,
+        runtime_args! {
+            ARG_SERIES_ID => series_id,
+            ARG_DIVIDEND_PER_SHARE => U256::from(dividend),
+        },
+    )
+}
+
+#[test]
+fn test_dividend_adjustment_lowers_open_series_strikes() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    for id in 1..=3 {
+        create_option_in_series(&mut builder, contract_hash, id, 5);
+    }
+    create_option(&mut builder, contract_hash, 4, U256::from(1000u64), 1735689600u64);
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 3);
+
+    let stranger = create_funded_account(&mut builder, 8);
+    apply_dividend_adjustment(&mut builder, stranger, contract_hash, 5, 50)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+    for dividend in [50, 25] {
+        apply_dividend_adjustment(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 5, dividend)
+            .expect_success()
+            .commit();
+    }
+
+    // Only the open options in the series are adjusted
+    for (id, strike) in [(1u64, 925u64), (2, 925), (3, 1000), (4, 1000)] {
+        let stored: U256 = get_option_field(&builder, contract_hash, OPTIONS_STRIKE_DICT, id);
+        assert_eq!(stored, U256::from(strike), "Unexpected strike for option {}", id);
+    }
+    let first: U256 = get_contract_value(&builder, contract_hash, "option_1_adjustment_0");
+    let second: U256 = get_contract_value(&builder, contract_hash, "option_1_adjustment_1");
+    assert_eq!((first, second), (U256::from(50u64), U256::from(25u64)));
+    assert!(!contract_has_key(&builder, contract_hash, "option_3_adjustment_count"));
+
+    // Open interest follows the options to their new strike
+    let moved: u64 = get_contract_value(&builder, contract_hash, "oi_strike_1735689600_925");
+    let left: u64 = get_contract_value(&builder, contract_hash, "oi_strike_1735689600_1000");
+    assert_eq!((moved, left), (2, 1));
+}
+
+/// Calls `calculate_max_pain` for `expiry` through the quote reader
+fn read_max_pain(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    expiry: u64,
+    low: u64,
+    high: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_MAX_PAIN,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_EXPIRY => expiry,
+            ARG_PRICE_RANGE_LOW => U256::from(low),
+            ARG_PRICE_RANGE_HIGH => U256::from(high),
+        },
+    )
+}
+
+/// Returns the max pain strike for `expiry` between `low` and `high`
+fn get_max_pain(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    expiry: u64,
+    low: u64,
+    high: u64,
+) -> U256 {
+    read_max_pain(builder, contract_hash, expiry, low, high).expect_success().commit();
+    let reader_hash = mock_quote_reader_hash(builder);
+    get_contract_value(builder, reader_hash, "max_pain")
+}
+
+#[test]
+fn test_max_pain_minimizes_total_holder_payout() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let options = [
+        (1u64, 900u64, OPTION_TYPE_CALL),
+        (2, 1000, OPTION_TYPE_CALL),
+        (3, 1000, OPTION_TYPE_CALL),
+        (4, 1100, OPTION_TYPE_PUT),
+        (5, 1000, OPTION_TYPE_PUT),
+        (6, 1100, OPTION_TYPE_PUT),
+    ];
+    for (id, strike, option_type) in options {
+        create_option_with_type(
+            &mut builder,
+            contract_hash,
+            id,
+            U256::from(strike),
+            expiry,
+            option_type,
+        );
+    }
+    // Another expiry's options must not count
+    create_option(&mut builder, contract_hash, 7, U256::from(2000u64), expiry + 86400);
+
+    // Payouts: 500 at 900, 300 at 1000 and 400 at 1100
+    assert_eq!(get_max_pain(&mut builder, contract_hash, expiry, 0, 5000), U256::from(1000u64));
+    // Only strikes in range are candidates
+    assert_eq!(get_max_pain(&mut builder, contract_hash, expiry, 1050, 5000), U256::from(1100u64));
+    assert_eq!(get_max_pain(&mut builder, contract_hash, expiry, 1200, 5000), U256::zero());
+
+    // Without the 1100 puts, 900 and 1000 tie at 100 and the lower strike wins
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 4);
+    cancel_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 6);
+    assert_eq!(get_max_pain(&mut builder, contract_hash, expiry, 0, 5000), U256::from(900u64));
+
+    read_max_pain(&mut builder, contract_hash, expiry, 1000, 900).expect_failure().commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+}
+
+/// Calls `set_max_notional_per_account` as `sender`
+fn set_max_notional_per_account(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    max_notional: U256,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_SET_MAX_NOTIONAL_PER_ACCOUNT,
+        runtime_args! {
+            ARG_MAX_NOTIONAL_PER_ACCOUNT => max_notional,
+        },
+    )
+}
+
+/// Calls `create_option` for a put over `option_amount` units struck at 1000
+/// as the default account, paying its `1000 * option_amount` collateral
+fn create_put_of_size(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    option_amount: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_PUT,
+            ARG_NONCE => nonce,
+            ARG_OPTION_AMOUNT => U256::from(option_amount),
+            ARG_PURSE => purse,
+            ARG_AMOUNT => U512::from(1000 * option_amount),
+        },
+    )
+}
+
+/// Reads the notional of the open options `account` created
+fn get_user_notional(
+    builder: &InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    account: AccountHash,
+) -> U256 {
+    let hex: String = account.value().iter().map(|byte| format!("{:02x}", byte)).collect();
+    get_contract_value(builder, contract_hash, &format!("user_notional_{}", hex))
+}
+
+#[test]
+fn test_position_limit_caps_notional_per_account() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let limit: U256 = get_contract_value(&builder, contract_hash, "max_notional_per_account");
+    assert_eq!(limit, U256::MAX, "No limit by default");
+
+    let stranger = create_funded_account(&mut builder, 8);
+    set_max_notional_per_account(&mut builder, stranger, contract_hash, U256::from(10_000u64))
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_ADMIN);
+    set_max_notional_per_account(&mut builder, admin, contract_hash, U256::from(10_000u64))
+        .expect_success()
+        .commit();
+
+    // 4 + 5 units at strike 1000 leave room for one more unit, not two
+    create_put_of_size(&mut builder, contract_hash, 1, 4).expect_success().commit();
+    create_put_of_size(&mut builder, contract_hash, 2, 5).expect_success().commit();
+    assert_eq!(get_user_notional(&builder, contract_hash, admin), U256::from(9_000u64));
+    create_put_of_size(&mut builder, contract_hash, 3, 2).expect_failure().commit();
+    assert_user_error(&builder, ERROR_POSITION_LIMIT_EXCEEDED);
+    create_put_of_size(&mut builder, contract_hash, 3, 1).expect_success().commit();
+    assert_eq!(get_user_notional(&builder, contract_hash, admin), U256::from(10_000u64));
+
+    // Exercising 3 of option 1's units frees their notional
+    activate_option(&mut builder, admin, contract_hash, 1).expect_success().commit();
+    exercise_amount_of(&mut builder, contract_hash, 1, U256::from(3u64))
+        .expect_success()
+        .commit();
+    assert_eq!(get_user_notional(&builder, contract_hash, admin), U256::from(7_000u64));
+    create_put_of_size(&mut builder, contract_hash, 4, 3).expect_success().commit();
+    assert_eq!(get_user_notional(&builder, contract_hash, admin), U256::from(10_000u64));
+}
+
+/// Calls `create_option_batch` for calls `ids` struck at 1000 as the default
+/// account
+fn create_call_batch(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    ids: Vec<u64>,
+) -> &mut InMemoryWasmTestBuilder {
+    let strike_prices = vec![U256::from(1000u64); ids.len()];
+    let expiries = vec![1735689600u64; ids.len()];
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION_BATCH,
+        runtime_args! {
+            ARG_IDS => ids,
+            ARG_STRIKE_PRICES => strike_prices,
+            ARG_EXPIRIES => expiries,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+        },
+    )
+}
+
+#[test]
+fn test_position_limit_counts_unsized_and_batched_options() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    set_max_notional_per_account(&mut builder, admin, contract_hash, U256::from(2_500u64))
+        .expect_success()
+        .commit();
+
+    // An unsized option counts as one unit
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    assert_eq!(get_user_notional(&builder, contract_hash, admin), U256::from(1_000u64));
+    create_call_batch(&mut builder, contract_hash, vec![2, 3])
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_POSITION_LIMIT_EXCEEDED);
+
+    // Exercising it frees its unit for the batch
+    exercise_option(&mut builder, contract_hash, 1);
+    assert_eq!(get_user_notional(&builder, contract_hash, admin), U256::zero());
+    create_call_batch(&mut builder, contract_hash, vec![2, 3])
+        .expect_success()
+        .commit();
+    assert_eq!(get_user_notional(&builder, contract_hash, admin), U256::from(2_000u64));
+}
+
+/// Calls `rollover_option` as `sender` at `block_time_secs`
+fn rollover_option(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    old_id: u64,
+    new_id: u64,
+    new_expiry: u64,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let nonce = next_nonce(builder, contract_hash, sender);
+    call_entry_point_at(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_ROLLOVER_OPTION,
+        runtime_args! {
+            ARG_OLD_ID => old_id,
+            ARG_NEW_ID => new_id,
+            ARG_NEW_EXPIRY => new_expiry,
+            ARG_NONCE => nonce,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_rollover_replaces_expiring_option() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let owner = *DEFAULT_ACCOUNT_ADDR;
+    let expiry = 1735689600u64;
+    let new_expiry = expiry + 30 * 86400;
+    create_option_with_type(
+        &mut builder,
+        contract_hash,
+        1,
+        U256::from(1500u64),
+        expiry,
+        OPTION_TYPE_PUT,
+    );
+
+    // Too early: more than a day before expiry
+    rollover_option(&mut builder, owner, contract_hash, 1, 2, new_expiry, expiry - 86401)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_ROLLOVER_WINDOW_NOT_OPEN);
+
+    let near_expiry = expiry - 3600;
+    let stranger = create_funded_account(&mut builder, 8);
+    rollover_option(&mut builder, stranger, contract_hash, 1, 2, new_expiry, near_expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+    // The new expiry has to be later than the old one
+    rollover_option(&mut builder, owner, contract_hash, 1, 2, expiry, near_expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+
+    rollover_option(&mut builder, owner, contract_hash, 1, 2, new_expiry, near_expiry)
+        .expect_success()
+        .commit();
+    assert_eq!(get_option_state(&builder, contract_hash, 1), OPTION_STATE_CANCELLED);
+    assert_eq!(get_option_state(&builder, contract_hash, 2), OPTION_STATE_ACTIVE);
+    let strike: U256 = get_option_field(&builder, contract_hash, OPTIONS_STRIKE_DICT, 2);
+    let stored_expiry: u64 = get_option_field(&builder, contract_hash, OPTIONS_EXPIRY_DICT, 2);
+    let new_owner: AccountHash = get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, 2);
+    let option_type: bool = get_contract_value(&builder, contract_hash, "option_2_type");
+    assert_eq!((strike, stored_expiry), (U256::from(1500u64), new_expiry));
+    assert_eq!((new_owner, option_type), (owner, OPTION_TYPE_PUT));
+
+    let rollover: (u64, u64, u64) = get_contract_value(&builder, contract_hash, "rollover_0");
+    assert_eq!(rollover, (1, 2, near_expiry));
+    let count: u64 = get_contract_value(&builder, contract_hash, "rollover_count");
+    assert_eq!(count, 1);
+
+    // Only active options can be rolled
+    create_option_from(&mut builder, owner, contract_hash, 3)
+        .expect_success()
+        .commit();
+    rollover_option(&mut builder, owner, contract_hash, 3, 4, new_expiry, near_expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_OPTION_NOT_ACTIVE);
+}
+
+#[test]
+fn test_rollover_carries_terms_and_checks_nonce() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let owner = *DEFAULT_ACCOUNT_ADDR;
+    let expiry = 1735689600u64;
+    let new_expiry = expiry + 30 * 86400;
+    let near_expiry = expiry - 3600;
+
+    call_entry_point(
+        &mut builder,
+        owner,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => 0u64,
+            ARG_SERIES_ID => 7u64,
+            ARG_IV => 5_000u64,
+            ARG_METADATA_URI => "ipfs://terms".to_string(),
+        },
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, owner, contract_hash, 1)
+        .expect_success()
+        .commit();
+
+    // A replayed nonce is rejected like any other creation
+    call_entry_point_at(
+        &mut builder,
+        owner,
+        contract_hash,
+        ENTRY_POINT_ROLLOVER_OPTION,
+        runtime_args! {
+            ARG_OLD_ID => 1u64,
+            ARG_NEW_ID => 2u64,
+            ARG_NEW_EXPIRY => new_expiry,
+            ARG_NONCE => 0u64,
+        },
+        near_expiry,
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INVALID_NONCE);
+
+    rollover_option(&mut builder, owner, contract_hash, 1, 2, new_expiry, near_expiry)
+        .expect_success()
+        .commit();
+    let series: Option<u64> = get_contract_value(&builder, contract_hash, "option_2_series");
+    let iv: u64 = get_contract_value(&builder, contract_hash, "option_2_iv");
+    let uri: String = get_contract_value(&builder, contract_hash, "option_2_metadata_uri");
+    assert_eq!((series, iv), (Some(7), 5_000));
+    assert_eq!(uri, "ipfs://terms");
+}
+
+#[test]
+fn test_rollover_moves_writer_collateral_once_approved() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let writer = *DEFAULT_ACCOUNT_ADDR;
+    let buyer = create_funded_account(&mut builder, 2);
+    let expiry = 1735689600u64;
+    let new_expiry = expiry + 30 * 86400;
+    let near_expiry = expiry - 3600;
+
+    // Two units struck at 1000, fully collateralised by the writer
+    create_collateralised_call(
+        &mut builder,
+        contract_hash,
+        1,
+        expiry,
+        U256::from(2u64),
+        U512::from(2000u64),
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, writer, contract_hash, 1)
+        .expect_success()
+        .commit();
+    transfer_option(&mut builder, writer, contract_hash, 1, buyer);
+
+    rollover_option(&mut builder, buyer, contract_hash, 1, 2, new_expiry, near_expiry)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_ROLLOVER_NOT_APPROVED);
+
+    call_entry_point(
+        &mut builder,
+        writer,
+        contract_hash,
+        ENTRY_POINT_APPROVE_ROLLOVER,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_NEW_EXPIRY => new_expiry,
+        },
+    )
+    .expect_success()
+    .commit();
+    rollover_option(&mut builder, buyer, contract_hash, 1, 2, new_expiry, near_expiry)
+        .expect_success()
+        .commit();
+
+    let old_locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    let new_locked: U512 = get_contract_value(&builder, contract_hash, "option_2_collateral");
+    assert_eq!((old_locked, new_locked), (U512::zero(), U512::from(2000u64)));
+
+    // Exercising the rolled option pays (1500 - 1000) * 2 out of the moved
+    // collateral, less the 0.5% early exercise penalty kept as a fee
+    set_oracle_price(&mut builder, U256::from(1500u64));
+    call_entry_point_at(
+        &mut builder,
+        buyer,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 2u64,
+        },
+        near_expiry,
+    )
+    .expect_success()
+    .commit();
+    let new_locked: U512 = get_contract_value(&builder, contract_hash, "option_2_collateral");
+    assert_eq!(new_locked, U512::from(1000u64));
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::from(1005u64));
+}
+
+/// Calls `create_option` for a Bermudan call exercisable around `exercise_dates`
+fn create_bermudan_option<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    expiry: u64,
+    exercise_dates: Vec<u64>,
+) -> &'a mut InMemoryWasmTestBuilder {
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => expiry,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_OPTION_STYLE => OPTION_STYLE_BERMUDAN,
+            ARG_EXERCISE_DATES => exercise_dates,
+        },
+    )
+}
+
+#[test]
+fn test_bermudan_option_exercises_only_near_its_dates() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+    let dates = vec![expiry - 60 * 86400, expiry - 30 * 86400, expiry];
+    for id in 1..=3 {
+        create_bermudan_option(&mut builder, contract_hash, id, expiry, dates.clone())
+            .expect_success()
+            .commit();
+        activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, id)
+            .expect_success()
+            .commit();
+    }
+    let count: u64 = get_contract_value(&builder, contract_hash, "option_1_exercise_date_count");
+    let second: u64 = get_contract_value(&builder, contract_hash, "option_1_exercise_date_1");
+    assert_eq!((count, second), (3, dates[1]));
+
+    // Between dates, and just outside a date's hour either side
+    for time in [expiry - 45 * 86400, dates[0] - 3601, dates[1] + 3601] {
+        exercise_option_at(&mut builder, contract_hash, 1, time)
+            .expect_failure()
+            .commit();
+        assert_user_error(&builder, ERROR_INVALID_EXERCISE_TIME);
+    }
+
+    exercise_option_at(&mut builder, contract_hash, 1, dates[0] - 3600)
+        .expect_success()
+        .commit();
+    exercise_option_at(&mut builder, contract_hash, 2, dates[1] + 3600)
+        .expect_success()
+        .commit();
+    exercise_option_at(&mut builder, contract_hash, 3, dates[2] - 1800)
+        .expect_success()
+        .commit();
+    for id in 1..=3 {
+        assert_eq!(get_option_state(&builder, contract_hash, id), OPTION_STATE_EXERCISED);
+    }
+}
+
+#[test]
+fn test_bermudan_option_requires_valid_exercise_dates() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let expiry = 1735689600u64;
+
+    let too_many: Vec<u64> = (1..=13).map(|day| expiry - day * 86400).collect();
+    for dates in [Vec::new(), too_many, vec![expiry + 1]] {
+        create_bermudan_option(&mut builder, contract_hash, 1, expiry, dates)
+            .expect_failure()
+            .commit();
+        assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+    }
+    assert!(!contract_has_key(&builder, contract_hash, "option_1"));
+}
+
+/// Calls `set_early_exercise_penalty` as `sender`
+fn set_early_exercise_penalty(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    penalty_bps: u32,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_SET_EARLY_EXERCISE_PENALTY,
+        runtime_args! {
+            ARG_EARLY_EXERCISE_PENALTY_BPS => penalty_bps,
+        },
+    )
+}
+
+#[test]
+fn test_early_exercise_penalty_is_kept_as_fee() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let admin = *DEFAULT_ACCOUNT_ADDR;
+    let expiry = 1735689600u64;
+    let penalty_key = "early_exercise_penalty_bps";
+    let penalty_bps: u32 = get_contract_value(&builder, contract_hash, penalty_key);
+    assert_eq!(penalty_bps, 50, "0.5% by default");
+
+    set_early_exercise_penalty(&mut builder, admin, contract_hash, 10_001)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+    set_early_exercise_penalty(&mut builder, admin, contract_hash, 1_000)
+        .expect_success()
+        .commit();
+
+    deposit_to_pool(&mut builder, admin, contract_hash, 10_000)
+        .expect_success()
+        .commit();
+    for id in 1..=2 {
+        write_option_from_pool(&mut builder, admin, contract_hash, id, 5)
+            .expect_success()
+            .commit();
+        activate_option(&mut builder, admin, contract_hash, id)
+            .expect_success()
+            .commit();
+    }
+    set_oracle_price(&mut builder, U256::from(1100u64));
+
+    // Two days before expiry: 10% of the 500 payout is kept
+    let purse_before = get_contract_purse_balance(&builder, contract_hash);
+    exercise_option_at(&mut builder, contract_hash, 1, expiry - 2 * 86400)
+        .expect_success()
+        .commit();
+    let paid_out = purse_before - get_contract_purse_balance(&builder, contract_hash);
+    assert_eq!(paid_out, U512::from(450u64));
+    let accumulated: U512 = get_contract_value(&builder, contract_hash, "accumulated_fees");
+    assert_eq!(accumulated, U512::from(50u64));
+
+    // Within the last day the payout is made in full
+    let purse_before = get_contract_purse_balance(&builder, contract_hash);
+    exercise_option_at(&mut builder, contract_hash, 2, expiry - 3600)
+        .expect_success()
+        .commit();
+    let paid_out = purse_before - get_contract_purse_balance(&builder, contract_hash);
+    assert_eq!(paid_out, U512::from(500u64));
+    let accumulated: U512 = get_contract_value(&builder, contract_hash, "accumulated_fees");
+    assert_eq!(accumulated, U512::from(50u64));
+}
+
+/// Calls `create_strip` or `create_strap` at strike 1000 as the default account
+fn create_bundle<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    bundle_entry_point: &str,
+    base_id: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        bundle_entry_point,
+        runtime_args! {
+            ARG_BASE_ID => base_id,
+            ARG_STRIKE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+        },
+    )
+}
+
+/// Calls `exercise_bundle` for `base_id` as the default account
+fn exercise_bundle(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    base_id: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_BUNDLE,
+        runtime_args! {
+            ARG_BASE_ID => base_id,
+        },
+    )
+}
+
+#[test]
+fn test_strip_and_strap_create_expected_legs() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    create_bundle(&mut builder, contract_hash, ENTRY_POINT_CREATE_STRIP, 1)
+        .expect_success()
+        .commit();
+    create_bundle(&mut builder, contract_hash, ENTRY_POINT_CREATE_STRAP, 10)
+        .expect_success()
+        .commit();
+
+    let legs = [
+        (1u64, OPTION_TYPE_PUT),
+        (2, OPTION_TYPE_PUT),
+        (3, OPTION_TYPE_CALL),
+        (10, OPTION_TYPE_PUT),
+        (11, OPTION_TYPE_CALL),
+        (12, OPTION_TYPE_CALL),
+    ];
+    for (id, expected_type) in legs {
+        let option_type: bool =
+            get_contract_value(&builder, contract_hash, &format!("option_{}_type", id));
+        assert_eq!(option_type, expected_type, "Unexpected type for option {}", id);
+    }
+    for (base_id, expected_type) in [(1u64, 0u8), (10, 1)] {
+        let bundle_type: u8 =
+            get_contract_value(&builder, contract_hash, &format!("bundle_{}_type", base_id));
+        let count: u8 =
+            get_contract_value(&builder, contract_hash, &format!("bundle_{}_count", base_id));
+        assert_eq!((bundle_type, count), (expected_type, 3));
+    }
+
+    // Legs can't overlap an existing option
+    create_bundle(&mut builder, contract_hash, ENTRY_POINT_CREATE_STRAP, 3)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_OPTION_ALREADY_EXISTS);
+}
+
+#[test]
+fn test_exercise_bundle_exercises_all_legs_atomically() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let owner = *DEFAULT_ACCOUNT_ADDR;
+    create_bundle(&mut builder, contract_hash, ENTRY_POINT_CREATE_STRIP, 1)
+        .expect_success()
+        .commit();
+    for id in 1..=2 {
+        activate_option(&mut builder, owner, contract_hash, id)
+            .expect_success()
+            .commit();
+    }
+
+    // The third leg is not active yet, so no leg is exercised
+    exercise_bundle(&mut builder, contract_hash, 1)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_INVALID_STATE_TRANSITION);
+    assert!(!is_option_exercised(&builder, contract_hash, 1));
+
+    activate_option(&mut builder, owner, contract_hash, 3)
+        .expect_success()
+        .commit();
+    exercise_bundle(&mut builder, contract_hash, 1)
+        .expect_success()
+        .commit();
+    for id in 1..=3 {
+        assert!(is_option_exercised(&builder, contract_hash, id));
+    }
+
+    exercise_bundle(&mut builder, contract_hash, 2)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_BUNDLE_NOT_FOUND);
+}
+
+/// `(option_id, exerciser, (timestamp, settlement_amount))`
+type ExerciseLogEntry = (u64, AccountHash, (u64, U256));
+
+/// Calls `get_exercise_history` for one page, then reads back the entries
+/// that page covers from the `exercise_log_{index}` keys
+fn get_exercise_history_page(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    offset: u64,
+    limit: u64,
+) -> Vec<ExerciseLogEntry> {
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_GET_EXERCISE_HISTORY,
+        runtime_args! {
+            ARG_OFFSET => offset,
+            ARG_LIMIT => limit,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    let total: u64 = get_contract_value(builder, contract_hash, "exercise_log_count");
+    let end = offset.saturating_add(limit).min(total);
+    (offset..end)
+        .map(|index| get_contract_value(builder, contract_hash, &format!("exercise_log_{}", index)))
+        .collect()
+}
+
+#[test]
+fn test_exercise_history_logs_each_exercise_and_pages() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let owner = *DEFAULT_ACCOUNT_ADDR;
+    let keeper = create_funded_account(&mut builder, 3);
+    let expiry = 1735689600u64;
+
+    for id in 1..=4 {
+        create_option(&mut builder, contract_hash, id, U256::from(1000u64), expiry);
+    }
+    for id in [5u64, 6] {
+        create_collateralised_call(
+            &mut builder,
+            contract_hash,
+            id,
+            expiry,
+            U256::from(2u64),
+            U512::from(2000u64),
+        )
+        .expect_success()
+        .commit();
+        activate_option(&mut builder, owner, contract_hash, id)
+            .expect_success()
+            .commit();
+    }
+    set_oracle_price(&mut builder, U256::from(1600u64));
+
+    for id in 1..=5 {
+        exercise_option_at(&mut builder, contract_hash, id, expiry - 10 + id)
+            .expect_success()
+            .commit();
+    }
+    // Settling an expired option logs it with its owner, even when a keeper settles
+    expire_option_at(&mut builder, keeper, contract_hash, 6, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, keeper, contract_hash, 6, expiry + 1)
+        .expect_success()
+        .commit();
+
+    // Only the collateralised calls pay out, (1600 - 1000) * 2 units each
+    let mut expected: Vec<ExerciseLogEntry> = (1..=4)
+        .map(|id| (id, owner, (expiry - 10 + id, U256::zero())))
+        .collect();
+    expected.push((5, owner, (expiry - 5, U256::from(1200u64))));
+    expected.push((6, owner, (expiry + 1, U256::from(1200u64))));
+    assert_eq!(get_exercise_history_page(&mut builder, contract_hash, 0, 10), expected);
+
+    assert_eq!(get_exercise_history_page(&mut builder, contract_hash, 0, 4), expected[..4]);
+    assert_eq!(get_exercise_history_page(&mut builder, contract_hash, 4, 4), expected[4..]);
+    assert_eq!(get_exercise_history_page(&mut builder, contract_hash, 6, 4), Vec::new());
+
+    for id in 1..=6 {
+        call_entry_point(
+            &mut builder,
+            owner,
+            contract_hash,
+            ENTRY_POINT_GET_EXERCISE_COUNT_FOR_OPTION,
+            runtime_args! {
+                ARG_ID => id,
+            },
+        )
+        .expect_success()
+        .commit();
+        let count_key = format!("option_{}_exercise_log_count", id);
+        let count: u64 = get_contract_value(&builder, contract_hash, &count_key);
+        assert_eq!(count, 1);
+    }
+}
+
+/// Installs the mock oracle and the registry charging `funding_rate_per_day`
+/// basis points of notional per day
+fn setup_contract_with_funding_rate(funding_rate_per_day: i64) -> InMemoryWasmTestBuilder {
+    let mut builder = setup_mock_oracle();
+    let oracle_hash = get_mock_oracle_hash(&builder);
+    let install_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_wasm_path().to_string_lossy(),
+        runtime_args! {
+            ARG_ORACLE_CONTRACT_HASH => oracle_hash,
+            ARG_FEE_BPS => 0u32,
+            ARG_KEEPER_REWARD_PER_OPTION => U512::zero(),
+            ARG_FUNDING_RATE_PER_DAY => funding_rate_per_day,
+        },
+    )
+    .build();
+    builder.exec(install_request).expect_success().commit();
+    builder
+}
+
+/// Creates option 1 at block time 0, a call on 2 units struck at 1000 with
+/// 2000 collateral, activates it and transfers it to a new `buyer`
+fn create_transferred_call(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+) -> AccountHash {
+    let buyer = create_funded_account(builder, 2);
+    create_collateralised_call(
+        builder,
+        contract_hash,
+        1,
+        1735689600u64,
+        U256::from(2u64),
+        U512::from(2000u64),
+    )
+    .expect_success()
+    .commit();
+    activate_option(builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    transfer_option(builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, buyer);
+    buyer
+}
+
+/// Reads `get_accrued_funding` for option `id` at `block_time` through the
+/// quote reader, as `(holder_pays, amount)`
+fn get_accrued_funding_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    block_time: u64,
+) -> (bool, U512) {
+    let reader_hash = mock_quote_reader_hash(builder);
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        reader_hash,
+        ENTRY_POINT_READ_ACCRUED_FUNDING,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_ID => id,
+        },
+        block_time,
+    )
+    .expect_success()
+    .commit();
+    get_contract_value(builder, reader_hash, "accrued_funding")
+}
+
+/// Calls `collect_funding` for `ids` as `sender` at `block_time`
+fn collect_funding_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+    ids: Vec<u64>,
+    block_time: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_COLLECT_FUNDING,
+        runtime_args! {
+            ARG_IDS => ids,
+        },
+        block_time,
+    )
+}
+
+#[test]
+fn test_negative_funding_rate_pays_holder_from_collateral() {
+    let mut builder = setup_contract_with_funding_rate(-50);
+    let contract_hash = get_contract_hash(&builder);
+    let buyer = create_transferred_call(&mut builder, contract_hash);
+    let keeper = create_funded_account(&mut builder, 3);
+
+    // 50 bps of the 2000 notional is 10 a day, and the part day is not due yet
+    let collect_time = 3 * 86_400 + 3_600;
+    let accrued = get_accrued_funding_at(&mut builder, contract_hash, 1, collect_time);
+    assert_eq!(accrued, (false, U512::from(30u64)));
+
+    let buyer_before = get_account_balance(&builder, buyer);
+    collect_funding_at(&mut builder, keeper, contract_hash, vec![1, 99], collect_time)
+        .expect_success()
+        .commit();
+    assert_eq!(get_account_balance(&builder, buyer), buyer_before + U512::from(30u64));
+    let locked: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(locked, U512::from(1970u64));
+    let last_funding_key = "option_1_last_funding_time";
+    let last_funding: u64 = get_contract_value(&builder, contract_hash, last_funding_key);
+    assert_eq!(last_funding, 3 * 86_400);
+
+    // The carried over hour counts towards the next day
+    let accrued = get_accrued_funding_at(&mut builder, contract_hash, 1, 4 * 86_400);
+    assert_eq!(accrued, (false, U512::from(10u64)));
+}
+
+#[test]
+fn test_positive_funding_rate_charges_holder_payout_to_writer() {
+    let mut builder = setup_contract_with_funding_rate(50);
+    let contract_hash = get_contract_hash(&builder);
+    let buyer = create_transferred_call(&mut builder, contract_hash);
+    let keeper = create_funded_account(&mut builder, 3);
+
+    let collect_time = 3 * 86_400;
+    let accrued = get_accrued_funding_at(&mut builder, contract_hash, 1, collect_time);
+    assert_eq!(accrued, (true, U512::from(30u64)));
+    collect_funding_at(&mut builder, keeper, contract_hash, vec![1], collect_time)
+        .expect_success()
+        .commit();
+    let owed: U512 = get_contract_value(&builder, contract_hash, "option_1_funding_owed");
+    assert_eq!(owed, U512::from(30u64));
+    let accrued = get_accrued_funding_at(&mut builder, contract_hash, 1, collect_time);
+    assert_eq!(accrued, (true, U512::zero()));
+
+    // The owed funding comes out of the (1600 - 1000) * 2 settlement payout
+    let expiry = 1735689600u64;
+    set_oracle_price(&mut builder, U256::from(1600u64));
+    let buyer_before = get_account_balance(&builder, buyer);
+    let writer_before = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    expire_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, keeper, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    assert_eq!(get_account_balance(&builder, buyer), buyer_before + U512::from(1170u64));
+    let writer_after = get_account_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    assert_eq!(writer_after, writer_before + U512::from(30u64));
+    let owed: U512 = get_contract_value(&builder, contract_hash, "option_1_funding_owed");
+    assert!(owed.is_zero());
+}
+
+/// Installs the mock DEX, sets it as the registry's collateral DEX pricing
+/// `token` at `price` motes, and accepts `token` as collateral at an 80%
+/// collateral factor
+fn setup_token_collateral(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    token_hash: ContractHash,
+    price: U512,
+) {
+    let install_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        &get_mock_dex_wasm_path().to_string_lossy(),
+        RuntimeArgs::new(),
+    )
+    .build();
+    builder.exec(install_request).expect_success().commit();
+    let dex_key = *builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(MOCK_DEX_KEY)
+        .expect("Mock DEX not found");
+    let dex_hash = dex_key
+        .into_hash()
+        .map(ContractHash::new)
+        .expect("Invalid mock DEX hash");
+
+    let token = Key::Hash(token_hash.value());
+    let calls = [
+        (dex_hash, ENTRY_POINT_SET_PRICE, runtime_args! { ARG_TOKEN => token, ARG_PRICE => price }),
+        (contract_hash, ENTRY_POINT_SET_COLLATERAL_DEX, runtime_args! { ARG_DEX => dex_key }),
+        (
+            contract_hash,
+            ENTRY_POINT_ADD_COLLATERAL_TYPE,
+            runtime_args! { ARG_TOKEN => token, ARG_COLLATERAL_FACTOR_BPS => 8_000u32 },
+        ),
+    ];
+    for (hash, entry_point, args) in calls {
+        call_entry_point(builder, *DEFAULT_ACCOUNT_ADDR, hash, entry_point, args)
+            .expect_success()
+            .commit();
+    }
+}
+
+/// Writes option `id` as the default account, a call on 2 units struck at
+/// 1000 backed by `collateral_amount` of mock CEP-18 tokens and no CSPR
+fn create_token_collateralised_call(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    token_hash: ContractHash,
+    id: u64,
+    collateral_amount: U256,
+) -> &mut InMemoryWasmTestBuilder {
+    let nonce = next_nonce(builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+            ARG_NONCE => nonce,
+            ARG_OPTION_AMOUNT => U256::from(2u64),
+            ARG_COLLATERAL_TOKEN => Key::Hash(token_hash.value()),
+            ARG_COLLATERAL_AMOUNT => collateral_amount,
+        },
+    )
+}
+
+#[test]
+fn test_token_collateral_liquidated_to_holder_on_settlement() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let token_hash = setup_mock_cep18(&mut builder, U256::from(100u64));
+    setup_token_collateral(&mut builder, contract_hash, token_hash, U512::from(100u64));
+    let buyer = create_funded_account(&mut builder, 2);
+    let expiry = 1735689600u64;
+
+    // 25 tokens at 100 motes less the 20% haircut cover the 2000 a call needs
+    create_token_collateralised_call(&mut builder, contract_hash, token_hash, 1, U256::from(25u64))
+        .expect_success()
+        .commit();
+    let registry_balance_key =
+        format!("balance_{}", Key::Hash(contract_hash.value()).to_formatted_string());
+    let held: U256 = get_contract_value(&builder, token_hash, &registry_balance_key);
+    assert_eq!(held, U256::from(25u64));
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::zero());
+
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, buyer);
+    set_oracle_price(&mut builder, U256::from(1600u64));
+    expire_option_at(&mut builder, buyer, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    settle_option_at(&mut builder, buyer, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+
+    // The (1600 - 1000) * 2 payout is worth 12 tokens at the DEX's price
+    assert_eq!(get_token_balance(&builder, token_hash, buyer), U256::from(12u64));
+    let settlement: U512 = get_contract_value(&builder, contract_hash, "option_1_settlement");
+    assert_eq!(settlement, U512::from(1200u64));
+    let locked: U256 = get_contract_value(&builder, contract_hash, "option_1_token_collateral");
+    assert_eq!(locked, U256::from(13u64));
+
+    withdraw_collateral_at(&mut builder, contract_hash, 1, expiry + 1)
+        .expect_success()
+        .commit();
+    assert_eq!(
+        get_token_balance(&builder, token_hash, *DEFAULT_ACCOUNT_ADDR),
+        U256::from(88u64)
+    );
+    let held: U256 = get_contract_value(&builder, token_hash, &registry_balance_key);
+    assert!(held.is_zero());
+}
+
+#[test]
+fn test_early_exercise_penalty_applies_to_token_covered_payout() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let token_hash = setup_mock_cep18(&mut builder, U256::from(100u64));
+    setup_token_collateral(&mut builder, contract_hash, token_hash, U512::from(100u64));
+    let expiry = 1735689600u64;
+
+    set_early_exercise_penalty(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1_000)
+        .expect_success()
+        .commit();
+    create_token_collateralised_call(&mut builder, contract_hash, token_hash, 1, U256::from(25u64))
+        .expect_success()
+        .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+    set_oracle_price(&mut builder, U256::from(1600u64));
+    exercise_option_at(&mut builder, contract_hash, 1, expiry - 2 * 86400)
+        .expect_success()
+        .commit();
+
+    // 10% of the 1200 payout is withheld, so only 1080 is sold: 11 tokens
+    assert_eq!(
+        get_token_balance(&builder, token_hash, *DEFAULT_ACCOUNT_ADDR),
+        U256::from(86u64)
+    );
+    let locked: U256 = get_contract_value(&builder, contract_hash, "option_1_token_collateral");
+    assert_eq!(locked, U256::from(14u64));
+}
+
+#[test]
+fn test_removed_collateral_type_is_rejected() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let token_hash = setup_mock_cep18(&mut builder, U256::from(100u64));
+    setup_token_collateral(&mut builder, contract_hash, token_hash, U512::from(100u64));
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_REMOVE_COLLATERAL_TYPE,
+        runtime_args! {
+            ARG_TOKEN => Key::Hash(token_hash.value()),
+        },
+    )
+    .expect_success()
+    .commit();
+    create_token_collateralised_call(&mut builder, contract_hash, token_hash, 1, U256::from(25u64))
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_COLLATERAL_NOT_ACCEPTED);
+    assert_eq!(
+        get_token_balance(&builder, token_hash, *DEFAULT_ACCOUNT_ADDR),
+        U256::from(100u64)
+    );
+}
+
+#[test]
+fn test_option_tokens_minted_on_create_and_burnt_on_exercise() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let buyer = create_funded_account(&mut builder, 2);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_DEPLOY_OPTION_TOKEN,
+        runtime_args! {
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_PUT,
+        },
+    )
+    .expect_success()
+    .commit();
+    let token_hash = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get("token_cspr_1000_1735689600_put")
+        .expect("Option token not deployed")
+        .into_hash()
+        .map(ContractHash::new)
+        .expect("Invalid option token hash");
+
+    create_sized_put(&mut builder, contract_hash, 1, U256::from(100u64));
+    assert_eq!(
+        get_token_balance(&builder, token_hash, *DEFAULT_ACCOUNT_ADDR),
+        U256::from(100u64)
+    );
+
+    // Holding the class's tokens does not make the buyer the option's owner
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        token_hash,
+        ENTRY_POINT_TRANSFER,
+        runtime_args! {
+            ARG_RECIPIENT => Key::Account(buyer),
+            ARG_AMOUNT => U256::from(50u64),
+        },
+    )
+    .expect_success()
+    .commit();
+    call_entry_point(
+        &mut builder,
+        buyer,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_EXERCISE_AMOUNT => U256::from(50u64),
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_EXERCISE_AMOUNT => U256::from(50u64),
+        },
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(
+        get_token_balance(&builder, token_hash, *DEFAULT_ACCOUNT_ADDR),
+        U256::zero()
+    );
+    assert_eq!(get_token_balance(&builder, token_hash, buyer), U256::from(50u64));
+    let total_supply: U256 = get_contract_value(&builder, token_hash, "total_supply");
+    assert_eq!(total_supply, U256::from(50u64));
+    let exercised_amount: U256 =
+        get_contract_value(&builder, contract_hash, "option_1_exercised_amount");
+    assert_eq!(exercised_amount, U256::from(50u64));
+}
+
+#[test]
+fn test_transfer_option_moves_its_option_tokens() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let buyer = create_funded_account(&mut builder, 2);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_DEPLOY_OPTION_TOKEN,
+        runtime_args! {
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_PUT,
+        },
+    )
+    .expect_success()
+    .commit();
+    let token_hash = builder
+        .get_contract(contract_hash)
+        .expect("Contract not found")
+        .named_keys()
+        .get("token_cspr_1000_1735689600_put")
+        .expect("Option token not deployed")
+        .into_hash()
+        .map(ContractHash::new)
+        .expect("Invalid option token hash");
+    create_sized_put(&mut builder, contract_hash, 1, U256::from(100u64));
+
+    transfer_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1, buyer);
+    assert_eq!(
+        get_token_balance(&builder, token_hash, *DEFAULT_ACCOUNT_ADDR),
+        U256::zero()
+    );
+    assert_eq!(get_token_balance(&builder, token_hash, buyer), U256::from(100u64));
+}
+
+/// Exercises 4 units of option 1 as `sender`
+fn exercise_four_units_as(
+    builder: &mut InMemoryWasmTestBuilder,
+    sender: AccountHash,
+    contract_hash: ContractHash,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point(
+        builder,
+        sender,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_EXERCISE_AMOUNT => U256::from(4u64),
+        },
+    )
+}
+
+#[test]
+fn test_approved_account_exercises_until_revoked() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let bot = create_funded_account(&mut builder, 2);
+    create_sized_put(&mut builder, contract_hash, 1, U256::from(10u64));
+
+    exercise_four_units_as(&mut builder, bot, contract_hash).expect_failure().commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_APPROVE_EXERCISE_FOR,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_APPROVED => bot,
+        },
+    )
+    .expect_success()
+    .commit();
+    exercise_four_units_as(&mut builder, bot, contract_hash).expect_success().commit();
+    let exercised_amount: U256 =
+        get_contract_value(&builder, contract_hash, "option_1_exercised_amount");
+    assert_eq!(exercised_amount, U256::from(4u64));
+
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_REVOKE_EXERCISE_APPROVAL,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_APPROVED => bot,
+        },
+    )
+    .expect_success()
+    .commit();
+    exercise_four_units_as(&mut builder, bot, contract_hash).expect_failure().commit();
+    assert_user_error(&builder, ERROR_NOT_OPTION_OWNER);
+    let exercised_amount: U256 =
+        get_contract_value(&builder, contract_hash, "option_1_exercised_amount");
+    assert_eq!(exercised_amount, U256::from(4u64));
+}
+
+/// Arguments for call `id` on `option_amount` units (unsized when zero) at
+/// strike 1000 expiring at 1735689600 that the holder of `secret_key` signed
+/// with `permit_nonce`, as a writer would off-chain, every other term at its
+/// default
+fn permit_args(
+    contract_hash: ContractHash,
+    secret_key: &SecretKey,
+    id: u64,
+    permit_nonce: u64,
+    option_amount: U256,
+) -> RuntimeArgs {
+    let public_key = PublicKey::from(secret_key);
+    let writer = AccountHash::from(&public_key);
+    let (strike_price, expiry, permit_deadline) = (U256::from(1000u64), 1735689600u64, 1000u64);
+    let terms = (
+        id,
+        strike_price,
+        expiry,
+        OPTION_TYPE_CALL,
+        OPTION_STYLE_AMERICAN,
+        Vec::<u64>::new(),
+        0u64,
+        U512::zero(),
+        option_amount,
+        0u64,
+    );
+    let extras = (
+        None::<Key>,
+        U256::zero(),
+        None::<u64>,
+        None::<String>,
+        None::<U256>,
+        None::<Key>,
+        U256::zero(),
+        None::<u64>,
+        0u64,
+        0u64,
+    );
+    let mut message = (writer, permit_nonce, permit_deadline, contract_hash)
+        .to_bytes()
+        .expect("Failed to serialize message");
+    message.extend((terms, extras).to_bytes().expect("Failed to serialize terms"));
+    let signature: [u8; 64] =
+        Vec::<u8>::from(&crypto::sign(crypto::blake2b(message), secret_key, &public_key))
+            .try_into()
+            .expect("Not an ed25519 signature");
+
+    let mut args = runtime_args! {
+        ARG_ID => id,
+        ARG_STRIKE_PRICE => strike_price,
+        ARG_EXPIRY => expiry,
+        ARG_OPTION_TYPE => OPTION_TYPE_CALL,
+        ARG_PERMIT_SIGNATURE => signature,
+        ARG_SIGNER => signer_bytes(&public_key),
+        ARG_PERMIT_NONCE => permit_nonce,
+        ARG_PERMIT_DEADLINE => permit_deadline,
+    };
+    if !option_amount.is_zero() {
+        args.insert(ARG_OPTION_AMOUNT, option_amount).expect("Failed to add amount");
+    }
+    args
+}
+
+/// Submits the writer's permit for unsized call `id` from `relayer` at block
+/// time 0
+
+fn create_option_with_permit<'a>(
+    builder: &'a mut InMemoryWasmTestBuilder,
+    relayer: AccountHash,
+    contract_hash: ContractHash,
+    secret_key: &SecretKey,
+    id: u64,
+    permit_nonce: u64,
+) -> &'a mut InMemoryWasmTestBuilder {
+    let mut args = permit_args(contract_hash, secret_key, id, permit_nonce, U256::zero());
+    let nonce = next_nonce(builder, contract_hash, relayer);
+    args.insert(ARG_NONCE, nonce).expect("Failed to add nonce");
+    call_entry_point(builder, relayer, contract_hash, ENTRY_POINT_CREATE_OPTION_WITH_PERMIT, args)
+}
+
+#[test]
+fn test_relayer_creates_option_with_writer_permit() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let relayer = create_funded_account(&mut builder, 2);
+    let (secret_key, public_key) = ed25519_key_pair(9);
+
+    create_option_with_permit(&mut builder, relayer, contract_hash, &secret_key, 1, 7)
+        .expect_success()
+        .commit();
+    let writer: AccountHash = get_contract_value(&builder, contract_hash, "option_1_writer");
+    assert_eq!(writer, AccountHash::from(&public_key));
+    let owner: AccountHash = get_option_field(&builder, contract_hash, OPTIONS_OWNER_DICT, 1);
+    assert_eq!(owner, relayer);
+
+    // A fresh signature over another option cannot reuse the permit nonce
+    create_option_with_permit(&mut builder, relayer, contract_hash, &secret_key, 2, 7)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_PERMIT_NONCE_USED);
+}
+
+#[test]
+fn test_relayer_cannot_change_signed_permit_terms() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let relayer = create_funded_account(&mut builder, 2);
+    let (secret_key, _) = ed25519_key_pair(9);
+
+    // The writer signed an unsized call; the relayer sizes it at 5 units
+    let mut args = permit_args(contract_hash, &secret_key, 1, 7, U256::zero());
+    let nonce = next_nonce(&builder, contract_hash, relayer);
+    args.insert(ARG_NONCE, nonce).expect("Failed to add nonce");
+    args.insert(ARG_OPTION_AMOUNT, U256::from(5u64)).expect("Failed to add amount");
+    call_entry_point(
+        &mut builder,
+        relayer,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION_WITH_PERMIT,
+        args,
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INVALID_SIGNATURE);
+    assert!(!contract_has_key(&builder, contract_hash, "option_1_writer"));
+}
+
+/// Calls `deposit_permit_collateral` as `writer`, paying `amount` from its
+/// main purse
+fn deposit_permit_collateral(
+    builder: &mut InMemoryWasmTestBuilder,
+    writer: AccountHash,
+    contract_hash: ContractHash,
+    amount: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    let purse = builder.get_expected_account(writer).main_purse();
+    call_entry_point(
+        builder,
+        writer,
+        contract_hash,
+        ENTRY_POINT_DEPOSIT_PERMIT_COLLATERAL,
+        runtime_args! {
+            ARG_PURSE => purse,
+            ARG_AMOUNT => U512::from(amount),
+        },
+    )
+}
+
+#[test]
+fn test_permit_collateral_comes_from_writer_deposit() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    let relayer = create_funded_account(&mut builder, 2);
+    let (secret_key, public_key) = ed25519_key_pair(9);
+    let writer = AccountHash::from(&public_key);
+    fund_account(&mut builder, writer);
+
+    // 5 units at strike 1000 lock 5000, one more than the writer deposited
+    deposit_permit_collateral(&mut builder, writer, contract_hash, 4999)
+        .expect_success()
+        .commit();
+    let mut args = permit_args(contract_hash, &secret_key, 1, 7, U256::from(5u64));
+    let nonce = next_nonce(&builder, contract_hash, relayer);
+    args.insert(ARG_NONCE, nonce).expect("Failed to add nonce");
+    call_entry_point(
+        &mut builder,
+        relayer,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION_WITH_PERMIT,
+        args.clone(),
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_COLLATERAL);
+
+    // The relayer passes no purse: it owes no premium and the deposit the rest
+    deposit_permit_collateral(&mut builder, writer, contract_hash, 1)
+        .expect_success()
+        .commit();
+    call_entry_point(
+        &mut builder,
+        relayer,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION_WITH_PERMIT,
+        args,
+    )
+    .expect_success()
+    .commit();
+    let collateral: U512 = get_contract_value(&builder, contract_hash, "option_1_collateral");
+    assert_eq!(collateral, U512::from(5000u64));
+    assert_eq!(get_contract_purse_balance(&builder, contract_hash), U512::from(5000u64));
+
+    // Locked collateral is no longer the writer's to withdraw
+    call_entry_point(
+        &mut builder,
+        writer,
+        contract_hash,
+        ENTRY_POINT_WITHDRAW_PERMIT_COLLATERAL,
+        runtime_args! {
+            ARG_AMOUNT => U512::one(),
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INSUFFICIENT_COLLATERAL);
+}
+
+#[test]
+fn test_clone_option_overriding_expiry() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+
+    let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CLONE_OPTION,
+        runtime_args! {
+            ARG_SOURCE_ID => 1u64,
+            ARG_NEW_ID => 2u64,
+            ARG_NEW_EXPIRY => Some(1767225600u64),
+            ARG_NONCE => nonce,
+        },
+    )
+    .expect_success()
+    .commit();
+
+    let strike: U256 = get_option_field(&builder, contract_hash, OPTIONS_STRIKE_DICT, 2);
+    assert_eq!(strike, U256::from(1000u64));
+    let expiry: u64 = get_option_field(&builder, contract_hash, OPTIONS_EXPIRY_DICT, 2);
+    assert_eq!(expiry, 1767225600);
+    let option_type: bool = get_contract_value(&builder, contract_hash, "option_2_type");
+    assert_eq!(option_type, OPTION_TYPE_CALL);
+    let source_expiry: u64 = get_option_field(&builder, contract_hash, OPTIONS_EXPIRY_DICT, 1);
+    assert_eq!(source_expiry, 1735689600);
+    let count: u64 = get_contract_value(&builder, contract_hash, OPTION_COUNT_KEY);
+    assert_eq!(count, 2);
+
+    let entry_price: U256 = get_contract_value(&builder, contract_hash, "option_2_entry_price");
+    assert_eq!(entry_price, U256::zero());
+
+    // The clone's id is now taken
+    let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CLONE_OPTION,
+        runtime_args! {
+            ARG_SOURCE_ID => 1u64,
+            ARG_NEW_ID => 2u64,
+            ARG_NONCE => nonce,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_OPTION_ALREADY_EXISTS);
+}
+
+#[test]
+fn test_clone_option_is_validated_like_create_option() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+    create_option(&mut builder, contract_hash, 1, U256::from(1000u64), 1735689600u64);
+    let last_nonce = get_last_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR)
+        .expect("Creating the source should have used a nonce");
+
+    // An expiry that has already passed
+    call_entry_point_at(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CLONE_OPTION,
+        runtime_args! {
+            ARG_SOURCE_ID => 1u64,
+            ARG_NEW_ID => 2u64,
+            ARG_NEW_EXPIRY => Some(1767225600u64),
+            ARG_NONCE => last_nonce + 1,
+        },
+        1767225600,
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INVALID_PARAMETER);
+
+    // A replayed nonce
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CLONE_OPTION,
+        runtime_args! {
+            ARG_SOURCE_ID => 1u64,
+            ARG_NEW_ID => 2u64,
+            ARG_NONCE => last_nonce,
+        },
+    )
+    .expect_failure()
+    .commit();
+    assert_user_error(&builder, ERROR_INVALID_NONCE);
+    let count: u64 = get_contract_value(&builder, contract_hash, OPTION_COUNT_KEY);
+    assert_eq!(count, 1);
+}
+
+/// Exercises `exercise_amount` units of option `id` as the default account at
+/// `block_time_secs`
+fn exercise_amount_at(
+    builder: &mut InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+    id: u64,
+    exercise_amount: U256,
+    block_time_secs: u64,
+) -> &mut InMemoryWasmTestBuilder {
+    call_entry_point_at(
+        builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_EXERCISE_OPTION,
+        runtime_args! {
+            ARG_ID => id,
+            ARG_EXERCISE_AMOUNT => exercise_amount,
+        },
+        block_time_secs,
+    )
+}
+
+#[test]
+fn test_vesting_option_exercises_only_vested_units() {
+    let mut builder = setup_contract();
+    let contract_hash = get_contract_hash(&builder);
+
+    // 10 units vesting over 1000s once a 100s cliff after t=1000 has passed
+    let purse = builder.get_expected_account(*DEFAULT_ACCOUNT_ADDR).main_purse();
+    let nonce = next_nonce(&builder, contract_hash, *DEFAULT_ACCOUNT_ADDR);
+    call_entry_point(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_CREATE_OPTION,
+        runtime_args! {
+            ARG_ID => 1u64,
+            ARG_STRIKE_PRICE => U256::from(1000u64),
+            ARG_EXPIRY => 1735689600u64,
+            ARG_OPTION_TYPE => OPTION_TYPE_PUT,
+            ARG_NONCE => nonce,
+            ARG_OPTION_AMOUNT => U256::from(10u64),
+            ARG_PURSE => purse,
+            ARG_AMOUNT => U512::from(10_000u64),
+            ARG_VESTING_START => 1000u64,
+            ARG_CLIFF_SECONDS => 100u64,
+            ARG_VESTING_DURATION_SECONDS => 1000u64,
+        },
+    )
+    .expect_success()
+    .commit();
+    activate_option(&mut builder, *DEFAULT_ACCOUNT_ADDR, contract_hash, 1)
+        .expect_success()
+        .commit();
+
+    exercise_amount_at(&mut builder, contract_hash, 1, U256::one(), 1050)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_EXCEEDS_VESTED_AMOUNT);
+
+    // Half vested: 5 units, and no more
+    exercise_amount_at(&mut builder, contract_hash, 1, U256::from(5u64), 1600)
+        .expect_success()
+        .commit();
+    exercise_amount_at(&mut builder, contract_hash, 1, U256::one(), 1600)
+        .expect_failure()
+        .commit();
+    assert_user_error(&builder, ERROR_EXCEEDS_VESTED_AMOUNT);
+    assert!(!is_option_exercised(&builder, contract_hash, 1));
+
+    exercise_option_at(&mut builder, contract_hash, 1, 2100)
+        .expect_success()
+        .commit();
+    let exercised_amount: U256 =
+        get_contract_value(&builder, contract_hash, "option_1_exercised_amount");
+    assert_eq!(exercised_amount, U256::from(10u64));
+    assert!(is_option_exercised(&builder, contract_hash, 1));
+}
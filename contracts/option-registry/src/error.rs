@@ -0,0 +1,194 @@
+//! Error codes returned by the option registry contract.
+//!
+//! Each variant maps to `ApiError::User(code)` so off-chain clients can match
+//! on the exact failure reason of a reverted deploy.
+
+use casper_types::ApiError;
+
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// An option with the given id has already been created.
+    OptionAlreadyExists = 1,
+    /// No option exists with the given id.
+    OptionNotFound = 2,
+    /// The caller does not own the option.
+    NotOptionOwner = 3,
+    /// The option has already been exercised.
+    OptionAlreadyExercised = 4,
+    /// The option's expiry has passed.
+    OptionExpired = 5,
+    /// The option has been cancelled.
+    OptionCancelled = 6,
+    /// A runtime argument is out of range or inconsistent with the others.
+    InvalidParameter = 7,
+    /// The option's activation time has not been reached.
+    OptionNotActive = 8,
+    /// A named key required by the contract is missing.
+    MissingNamedKey = 9,
+    /// A stored value is not of the expected key or CL type.
+    InvalidStorageValue = 10,
+    /// A return value could not be converted into a `CLValue`.
+    CLValueConversion = 11,
+    /// A European option was exercised before its expiry, or a Bermudan
+    /// option more than an hour from any of its exercise dates.
+    InvalidExerciseTime = 12,
+    /// The amount paid does not equal the option's premium.
+    IncorrectPremium = 13,
+    /// A purse-to-purse or purse-to-account transfer failed.
+    TransferFailed = 14,
+    /// The caller is not the option's writer.
+    NotOptionWriter = 15,
+    /// The option can still be exercised or has not reached expiry.
+    OptionNotExpired = 16,
+    /// The premium has already been paid out to the writer.
+    PremiumAlreadyClaimed = 17,
+    /// The caller is not the registry admin.
+    NotAdmin = 18,
+    /// The registry is paused and rejects state changes.
+    ContractPaused = 19,
+    /// The caller is not the proposed admin.
+    NotPendingAdmin = 20,
+    /// No event has been recorded at the given index.
+    EventNotFound = 21,
+    /// The option has already been cash-settled.
+    OptionAlreadySettled = 22,
+    /// The collateral paid by a call writer is below `strike_price * option_amount`.
+    InsufficientCollateral = 23,
+    /// The option expired unexercised and must be settled first.
+    OptionNotSettled = 24,
+    /// The exercise amount is larger than the option's unexercised amount.
+    ExceedsOptionAmount = 25,
+    /// The nonce is not above the caller's last used nonce.
+    InvalidNonce = 26,
+    /// The signature or signer key does not authorise the signed exercise.
+    InvalidSignature = 27,
+    /// The option's lifecycle state does not allow this operation.
+    InvalidStateTransition = 28,
+    /// `option_count` did not advance by exactly one for a created option.
+    CountInvariantViolated = 29,
+    /// A guarded entry point was re-entered through an external contract call.
+    Reentrancy = 30,
+    /// No pending parameter change has the given nonce.
+    ProposalNotFound = 31,
+    /// The parameter change's 48-hour delay has not passed yet, or the
+    /// governance proposal does not have enough votes to start it.
+    ProposalNotReady = 32,
+    /// The caller is not a registered keeper.
+    NotKeeper = 33,
+    /// The writer whitelist is enabled and the caller is not on it, or a
+    /// governance caller is not on it.
+    NotWhitelisted = 34,
+    /// Writing the option would lock more than `max_utilization_bps` of the
+    /// LP pool's value as collateral.
+    PoolUtilizationExceeded = 35,
+    /// The account the LP shares come from holds fewer than requested.
+    InsufficientLpShares = 36,
+    /// The LP pool lacks the free, unlocked value the withdrawal needs, or was
+    /// drained while shares remain outstanding.
+    InsufficientPoolLiquidity = 37,
+    /// The caller's LP share allowance from the owner is smaller than requested.
+    InsufficientLpAllowance = 38,
+    /// The option has not been exercised in full.
+    NotExercised = 39,
+    /// A settlement price for the expiry has already been finalized.
+    SettlementPriceAlreadySet = 40,
+    /// No settlement price has been finalized for the expiry.
+    SettlementPriceNotSet = 41,
+    /// The expiry's settlement price is disputed or still in its dispute
+    /// window.
+    SettlementPriceNotFinal = 42,
+    /// The settlement price's dispute window has closed.
+    DisputeWindowClosed = 43,
+    /// The expiry's settlement price is not under dispute.
+    NoOpenDispute = 44,
+    /// No straddle has the given id.
+    StraddleNotFound = 45,
+    /// No spread has the given id.
+    SpreadNotFound = 46,
+    /// The long leg of a spread cannot be cancelled while its short leg is
+    /// still open.
+    SpreadShortLegOpen = 47,
+    /// The option's collateral still covers its liquidation threshold.
+    NotLiquidatable = 48,
+    /// The high-value option has fewer exercise approvals than the policy
+    /// requires.
+    InsufficientApprovals = 49,
+    /// The caller is not one of the exercise signers.
+    NotExerciseSigner = 50,
+    /// The caller is not one of the syndicated option's syndicators, or has
+    /// left the syndicate.
+    NotSyndicator = 51,
+    /// A syndicator has not put up collateral covering its share, or has
+    /// left the syndicate.
+    SyndicateNotFunded = 52,
+    /// No order has the given id.
+    OrderNotFound = 53,
+    /// The caller did not place the order.
+    NotOrderOwner = 54,
+    /// The order has already been cancelled or matched.
+    OrderNotOpen = 55,
+    /// The orders are not a buy and a sell for the same option terms with
+    /// crossing prices.
+    OrdersDoNotMatch = 56,
+    /// The fee withdrawal is larger than the accumulated fees.
+    InsufficientAccumulatedFees = 57,
+    /// The metadata URI is longer than 512 characters.
+    MetadataTooLong = 58,
+    /// The cached oracle price is older than the staleness threshold.
+    OraclePriceStale = 59,
+    /// The caller is not a registered price publisher.
+    NotPricePublisher = 60,
+    /// Less than `snapshot_interval_seconds` has passed since the last price
+    /// snapshot.
+    SnapshotTooEarly = 61,
+    /// No price snapshot has been recorded to compute a TWAP from.
+    NoPriceSnapshots = 62,
+    /// The option would take the notional of the caller's open options above
+    /// `max_notional_per_account`.
+    PositionLimitExceeded = 63,
+    /// The option is not yet within `ROLLOVER_WINDOW_SECS` of its expiry.
+    RolloverWindowNotOpen = 64,
+    /// No strip or strap was created with the given base id.
+    BundleNotFound = 65,
+    /// The caller has already voted for this governance proposal.
+    AlreadyVoted = 66,
+    /// The collateral token is not on the collateral whitelist.
+    CollateralNotAccepted = 67,
+    /// No DEX has been set to price collateral tokens.
+    NoCollateralDex = 68,
+    /// An option token has already been deployed for the option class.
+    OptionTokenExists = 69,
+    /// The account holds fewer option tokens than the transfer, burn or
+    /// exercise needs.
+    InsufficientOptionTokens = 70,
+    /// The caller's option token allowance from the owner is smaller than
+    /// requested.
+    InsufficientOptionTokenAllowance = 71,
+    /// Only the registry may mint and burn option tokens.
+    NotOptionTokenMinter = 72,
+    /// The creation permit's deadline has passed.
+    PermitExpired = 73,
+    /// The writer has already used this permit nonce.
+    PermitNonceUsed = 74,
+    /// The exercise asks for more units than have vested and are still
+    /// unexercised.
+    ExceedsVestedAmount = 75,
+    /// Price validation is on and the premium is further from the option's
+    /// theoretical premium than `max_price_deviation_bps` allows.
+    PremiumDeviationTooHigh = 76,
+    /// The registry has been deprecated and rejects state changes.
+    ContractDeprecated = 77,
+    /// `withdraw_my_collateral` is only open once the registry is deprecated.
+    ContractNotDeprecated = 78,
+    /// `emergency_cancel_batch` is only open while the registry is paused.
+    ContractNotPaused = 79,
+    /// The option's writer has not approved rolling it out to the new expiry.
+    RolloverNotApproved = 80,
+}
+
+impl From<ErrorCode> for ApiError {
+    fn from(error: ErrorCode) -> Self {
+        ApiError::User(error as u16)
+    }
+}
@@ -0,0 +1,51 @@
+//! Lifecycle events recorded in contract named keys for off-chain indexers.
+//!
+//! Event `n` is stored under `event_{n}` as
+//! `(event_type, option_id, (timestamp, actor))`, and `event_counter` holds the
+//! number of events emitted so far.
+
+use alloc::string::{String, ToString};
+
+use casper_contract::contract_api::{runtime, storage};
+use casper_types::account::AccountHash;
+
+use crate::error::ErrorCode;
+use crate::storage_utils::increment_u64;
+use crate::{current_timestamp, named_uref, read_uref};
+
+pub const EVENT_COUNTER_KEY: &str = "event_counter";
+
+/// `(event_type, option_id, (timestamp, actor))`
+pub type EventRecord = (u8, u64, (u64, AccountHash));
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventType {
+    OptionCreated = 0,
+    OptionExercised = 1,
+    OptionCancelled = 2,
+    OptionTransferred = 3,
+    PremiumClaimed = 4,
+    OptionSettled = 5,
+    OptionLiquidated = 6,
+}
+
+pub fn event_key(index: u64) -> String {
+    let mut key = String::from("event_");
+    key.push_str(&index.to_string());
+    key
+}
+
+/// Records `event_type` for `option_id` with the caller as actor.
+pub fn emit(event_type: EventType, option_id: u64) {
+    let counter_uref = named_uref(EVENT_COUNTER_KEY, ErrorCode::MissingNamedKey);
+    let index: u64 = read_uref(counter_uref);
+    
+    let record: EventRecord = (
+        event_type as u8,
+        option_id,
+        (current_timestamp(), runtime::get_caller()),
+    );
+    runtime::put_key(&event_key(index), storage::new_uref(record).into());
+    increment_u64(counter_uref);
+}
@@ -3,29 +3,578 @@
 
 extern crate alloc;
 
+mod error;
+mod events;
+mod option_token;
+mod pricing;
+mod storage_utils;
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
 use alloc::string::{String, ToString};
 use alloc::vec;
+use alloc::vec::Vec;
 
 use casper_contract::{
-    contract_api::{runtime, storage},
+    contract_api::{runtime, storage, system},
     unwrap_or_revert::UnwrapOrRevert,
 };
 
 use casper_types::{
-    CLType, EntryPointAccess, EntryPointType, EntryPoints, Parameter,
-    contracts::{EntryPoint, NamedKeys},
+    AsymmetricType, CLType, CLTyped, CLValue, EntryPointAccess, EntryPointType, EntryPoints, Key,
+    Parameter, PublicKey, Signature, URef, U256, U512,
+    account::AccountHash,
+    bytesrepr::{Bytes, FromBytes, ToBytes},
+    contracts::{ContractHash, ContractPackageHash, EntryPoint, NamedKeys},
+    crypto, runtime_args,
 };
 
+use error::ErrorCode;
+use events::{EventRecord, EventType, EVENT_COUNTER_KEY};
+use storage_utils::{decrement_u64, increment_u64, named_counter};
+
 const CONTRACT_KEY: &str = "option_registry";
 const CONTRACT_PACKAGE_KEY: &str = "option_registry_package";
 const CONTRACT_ACCESS_KEY: &str = "option_registry_access";
+const OPTION_COUNT_KEY: &str = "option_count";
+const CONTRACT_PURSE_KEY: &str = "contract_purse";
+const PAUSED_KEY: &str = "paused";
+const DEPRECATED_KEY: &str = "deprecated";
+const DEPRECATED_AT_KEY: &str = "deprecated_at";
+const IN_PROGRESS_KEY: &str = "in_progress";
+const ADMIN_KEY: &str = "option_registry_admin";
+const PENDING_ADMIN_KEY: &str = "option_registry_pending_admin";
+const ORACLE_KEY: &str = "option_registry_oracle";
+const GREEKS_ORACLE_KEY: &str = "option_registry_greeks_oracle";
+const VOL_ORACLE_KEY: &str = "option_registry_vol_oracle";
+const CONTRACT_HASH_KEY: &str = "option_registry_contract_hash";
+const CONTRACT_VERSION_KEY: &str = "option_registry_contract_version";
+const FEE_BPS_KEY: &str = "option_registry_fee_bps";
+const ACCUMULATED_FEES_KEY: &str = "accumulated_fees";
+const TOTAL_FEES_COLLECTED_KEY: &str = "total_fees_collected";
+const ORACLE_PRICE_CACHE_KEY: &str = "oracle_price_cache";
+const LAST_ORACLE_UPDATE_TIME_KEY: &str = "last_oracle_update_time";
+const ORACLE_STALENESS_THRESHOLD_KEY: &str = "oracle_staleness_threshold_seconds";
+const SNAPSHOT_COUNT_KEY: &str = "snapshot_count";
+const SNAPSHOT_INTERVAL_KEY: &str = "snapshot_interval_seconds";
+const USE_TWAP_KEY: &str = "use_twap";
+const TWAP_WINDOW_KEY: &str = "twap_window_seconds";
+const MAX_NOTIONAL_PER_ACCOUNT_KEY: &str = "max_notional_per_account";
+/// The count-based limit `max_notional_per_account` replaced, removed on upgrade.
+const LEGACY_MAX_OPTIONS_PER_ACCOUNT_KEY: &str = "max_options_per_account";
+const EARLY_EXERCISE_PENALTY_BPS_KEY: &str = "early_exercise_penalty_bps";
+const PRICE_VALIDATION_ENABLED_KEY: &str = "price_validation_enabled";
+const MAX_PRICE_DEVIATION_BPS_KEY: &str = "max_price_deviation_bps";
+const GOVERNANCE_VOTE_THRESHOLD_KEY: &str = "governance_vote_threshold";
+const FUNDING_RATE_PER_DAY_KEY: &str = "funding_rate_per_day";
+const WHITELIST_ENABLED_KEY: &str = "whitelist_enabled";
+const KEEPER_REWARD_KEY: &str = "option_registry_keeper_reward";
+const KEEPER_EXERCISE_REWARD_KEY: &str = "option_registry_keeper_exercise_reward";
+const MAX_UTILIZATION_BPS_KEY: &str = "option_registry_max_utilization_bps";
+const AMM_LAMBDA_BPS_KEY: &str = "option_registry_amm_lambda_bps";
+const LIQUIDATION_THRESHOLD_BPS_KEY: &str = "option_registry_liquidation_threshold_bps";
+const LIQUIDATION_BONUS_BPS_KEY: &str = "option_registry_liquidation_bonus_bps";
+const NFT_CONTRACT_KEY: &str = "option_nft_contract_hash";
+const COLLATERAL_DEX_KEY: &str = "collateral_dex";
+const POOL_TOTAL_VALUE_KEY: &str = "pool_total_value";
+const POOL_TOTAL_SHARES_KEY: &str = "pool_total_shares";
+const POOL_UTILIZED_COLLATERAL_KEY: &str = "pool_utilized_collateral";
+const INSURANCE_FUND_BALANCE_KEY: &str = "insurance_fund_balance";
+const EMERGENCY_CANCELLED_COUNT_KEY: &str = "emergency_cancelled_count";
+const EMERGENCY_REFUND_TOTAL_KEY: &str = "emergency_refund_total";
+const HIGH_VALUE_THRESHOLD_KEY: &str = "high_value_threshold";
+const EXERCISE_SIGNERS_KEY: &str = "exercise_signers";
+const EXERCISE_THRESHOLD_KEY: &str = "exercise_threshold";
+const EXPIRY_INDEX_KEY: &str = "expiry_index";
+const EXPIRY_SET_COUNT_KEY: &str = "expiry_set_count";
+const ROLLOVER_COUNT_KEY: &str = "rollover_count";
+const EXERCISE_LOG_COUNT_KEY: &str = "exercise_log_count";
+const STRADDLE_COUNT_KEY: &str = "straddle_count";
+const ORDER_COUNT_KEY: &str = "order_count";
+const SPREAD_COUNT_KEY: &str = "spread_count";
+const TOTAL_OPEN_INTEREST_KEY: &str = "total_open_interest";
+const TOTAL_CALLS_CREATED_KEY: &str = "total_calls_created";
+const TOTAL_PUTS_CREATED_KEY: &str = "total_puts_created";
+const VOLUME_CALL_EXERCISED_KEY: &str = "volume_call_exercised";
+const VOLUME_PUT_EXERCISED_KEY: &str = "volume_put_exercised";
+const EXPIRY_SET_MEMBERS_DICT: &str = "expiry_set_members";
+const OPTION_CHAIN_DICT: &str = "option_chain";
+const OPTIONS_STRIKE_DICT: &str = "options_strike";
+const OPTIONS_EXPIRY_DICT: &str = "options_expiry";
+const OPTIONS_EXERCISED_DICT: &str = "options_exercised";
+const OPTIONS_OWNER_DICT: &str = "options_owner";
+const NONCES_DICT: &str = "nonces";
+const MIGRATION_CURSOR_KEY: &str = "migration_cursor";
+const PROPOSALS_DICT: &str = "proposals";
+const PROPOSAL_COUNT_KEY: &str = "proposal_count";
+const STATE_INDEX_KEY: &str = "state_index";
+/// Counters of options in each state, indexed by state.
+const STATE_COUNT_KEYS: [&str; 6] = [
+    "created_option_count",
+    "active_option_count",
+    "exercised_option_count",
+    "expired_option_count",
+    "cancelled_option_count",
+    "settled_option_count",
+];
+const KEEPER_REGISTRY_DICT: &str = "keeper_registry";
+const WRITER_WHITELIST_DICT: &str = "writer_whitelist";
+const LP_BALANCES_DICT: &str = "lp_balances";
+const LP_ALLOWANCES_DICT: &str = "lp_allowances";
+const INSURANCE_CONTRIBUTIONS_DICT: &str = "insurance_contributions";
+const EXERCISE_APPROVALS_DICT: &str = "exercise_approvals";
+const EXERCISE_DELEGATES_DICT: &str = "exercise_delegates";
+const SYNDICATES_DICT: &str = "syndicates";
+const ORDER_BOOK_DICT: &str = "order_book";
+const PRICE_PUBLISHERS_DICT: &str = "price_publishers";
+const VOL_SURFACE_DICT: &str = "vol_surface";
+const COLLATERAL_WHITELIST_DICT: &str = "collateral_whitelist";
+const USED_PERMITS_DICT: &str = "used_permits";
+const PERMIT_COLLATERAL_DICT: &str = "permit_collateral";
 
 const ENTRY_POINT_CREATE_OPTION: &str = "create_option";
+const ENTRY_POINT_CREATE_OPTION_BATCH: &str = "create_option_batch";
+const ENTRY_POINT_CREATE_OPTION_WITH_PERMIT: &str = "create_option_with_permit";
+const ENTRY_POINT_DEPOSIT_PERMIT_COLLATERAL: &str = "deposit_permit_collateral";
+const ENTRY_POINT_WITHDRAW_PERMIT_COLLATERAL: &str = "withdraw_permit_collateral";
 const ENTRY_POINT_EXERCISE_OPTION: &str = "exercise_option";
+const ENTRY_POINT_EXERCISE_OPTIONS_BATCH: &str = "exercise_options_batch";
+const ENTRY_POINT_EXERCISE_WITH_SIGNATURE: &str = "exercise_with_signature";
+const ENTRY_POINT_SET_EXERCISE_APPROVAL_POLICY: &str = "set_exercise_approval_policy";
+const ENTRY_POINT_APPROVE_EXERCISE: &str = "approve_exercise";
+const ENTRY_POINT_APPROVE_EXERCISE_FOR: &str = "approve_exercise_for";
+const ENTRY_POINT_REVOKE_EXERCISE_APPROVAL: &str = "revoke_exercise_approval";
+const ENTRY_POINT_CREATE_SYNDICATED_OPTION: &str = "create_syndicated_option";
+const ENTRY_POINT_JOIN_SYNDICATE: &str = "join_syndicate";
+const ENTRY_POINT_LEAVE_SYNDICATE: &str = "leave_syndicate";
+const ENTRY_POINT_PLACE_OPTION_ORDER: &str = "place_option_order";
+const ENTRY_POINT_CANCEL_ORDER: &str = "cancel_order";
+const ENTRY_POINT_MATCH_ORDERS: &str = "match_orders";
+const ENTRY_POINT_GET_BEST_BID: &str = "get_best_bid";
+const ENTRY_POINT_GET_BEST_ASK: &str = "get_best_ask";
+const ENTRY_POINT_ACTIVATE_OPTION: &str = "activate_option";
+const ENTRY_POINT_EXPIRE_OPTION: &str = "expire_option";
+const ENTRY_POINT_CANCEL_OPTION: &str = "cancel_option";
+const ENTRY_POINT_CANCEL_SERIES: &str = "cancel_series";
+const ENTRY_POINT_ROLLOVER_OPTION: &str = "rollover_option";
+const ENTRY_POINT_APPROVE_ROLLOVER: &str = "approve_rollover";
+const ENTRY_POINT_GET_ROLLOVER_HISTORY: &str = "get_rollover_history";
+const ENTRY_POINT_GET_EXERCISE_HISTORY: &str = "get_exercise_history";
+const ENTRY_POINT_GET_EXERCISE_COUNT_FOR_OPTION: &str = "get_exercise_count_for_option";
+const ENTRY_POINT_APPLY_DIVIDEND_ADJUSTMENT: &str = "apply_dividend_adjustment";
+const ENTRY_POINT_CREATE_STRADDLE: &str = "create_straddle";
+const ENTRY_POINT_CLONE_OPTION: &str = "clone_option";
+const ENTRY_POINT_GET_STRADDLE: &str = "get_straddle";
+const ENTRY_POINT_CANCEL_STRADDLE: &str = "cancel_straddle";
+const ENTRY_POINT_CREATE_STRIP: &str = "create_strip";
+const ENTRY_POINT_CREATE_STRAP: &str = "create_strap";
+const ENTRY_POINT_GET_BUNDLE_OPTIONS: &str = "get_bundle_options";
+const ENTRY_POINT_EXERCISE_BUNDLE: &str = "exercise_bundle";
+const ENTRY_POINT_CREATE_SPREAD: &str = "create_spread";
+const ENTRY_POINT_GET_SPREAD_MAX_PROFIT: &str = "get_spread_max_profit";
+const ENTRY_POINT_GET_SPREAD_MAX_LOSS: &str = "get_spread_max_loss";
+const ENTRY_POINT_TRANSFER_OPTION: &str = "transfer_option";
+const ENTRY_POINT_CLAIM_PREMIUM: &str = "claim_premium";
+const ENTRY_POINT_SETTLE_OPTION: &str = "settle_option";
+const ENTRY_POINT_CONTRIBUTE_TO_INSURANCE_FUND: &str = "contribute_to_insurance_fund";
+const ENTRY_POINT_LIQUIDATE_OPTION: &str = "liquidate_option";
+const ENTRY_POINT_ADD_COLLATERAL: &str = "add_collateral";
+const ENTRY_POINT_WITHDRAW_COLLATERAL: &str = "withdraw_collateral";
+const ENTRY_POINT_PAUSE: &str = "pause";
+const ENTRY_POINT_UNPAUSE: &str = "unpause";
+const ENTRY_POINT_TRANSFER_ADMIN: &str = "transfer_admin";
+const ENTRY_POINT_ACCEPT_ADMIN: &str = "accept_admin";
+const ENTRY_POINT_COLLECT_FEES: &str = "collect_fees";
+const ENTRY_POINT_WITHDRAW_FEES_TO_TREASURY: &str = "withdraw_fees_to_treasury";
+const ENTRY_POINT_DEPRECATE: &str = "deprecate";
+const ENTRY_POINT_EMERGENCY_CANCEL_BATCH: &str = "emergency_cancel_batch";
+const ENTRY_POINT_WITHDRAW_MY_COLLATERAL: &str = "withdraw_my_collateral";
+const ENTRY_POINT_GET_ACCUMULATED_FEES: &str = "get_accumulated_fees";
+const ENTRY_POINT_GET_TOTAL_FEES_COLLECTED: &str = "get_total_fees_collected";
+const ENTRY_POINT_SET_MAX_NOTIONAL_PER_ACCOUNT: &str = "set_max_notional_per_account";
+const ENTRY_POINT_SET_EARLY_EXERCISE_PENALTY: &str = "set_early_exercise_penalty";
+const ENTRY_POINT_SET_PRICE_VALIDATION: &str = "set_price_validation";
+const ENTRY_POINT_SET_MAX_PRICE_DEVIATION: &str = "set_max_price_deviation";
+const ENTRY_POINT_GET_POSITION_LIMIT: &str = "get_position_limit";
+const ENTRY_POINT_GET_USER_NOTIONAL: &str = "get_user_notional";
+const ENTRY_POINT_REGISTER_KEEPER: &str = "register_keeper";
+const ENTRY_POINT_DEREGISTER_KEEPER: &str = "deregister_keeper";
+const ENTRY_POINT_KEEPER_EXPIRE_OPTIONS: &str = "keeper_expire_options";
+const ENTRY_POINT_COLLECT_FUNDING: &str = "collect_funding";
+const ENTRY_POINT_GET_ACCRUED_FUNDING: &str = "get_accrued_funding";
+const ENTRY_POINT_SCHEDULE_EXERCISE: &str = "schedule_exercise";
+const ENTRY_POINT_CANCEL_SCHEDULED_EXERCISE: &str = "cancel_scheduled_exercise";
+const ENTRY_POINT_PROCESS_SCHEDULED_EXERCISES: &str = "process_scheduled_exercises";
+const ENTRY_POINT_SET_AUTO_EXERCISE: &str = "set_auto_exercise";
+const ENTRY_POINT_PROCESS_AUTO_EXERCISES: &str = "process_auto_exercises";
+const ENTRY_POINT_ADD_WRITER: &str = "add_writer";
+const ENTRY_POINT_REMOVE_WRITER: &str = "remove_writer";
+const ENTRY_POINT_ADD_PRICE_PUBLISHER: &str = "add_price_publisher";
+const ENTRY_POINT_REMOVE_PRICE_PUBLISHER: &str = "remove_price_publisher";
+const ENTRY_POINT_UPDATE_ORACLE_PRICE: &str = "update_oracle_price";
+const ENTRY_POINT_RECORD_PRICE_SNAPSHOT: &str = "record_price_snapshot";
+const ENTRY_POINT_COMPUTE_TWAP: &str = "compute_twap";
+const ENTRY_POINT_SET_GREEKS_ORACLE: &str = "set_greeks_oracle";
+const ENTRY_POINT_UPDATE_GREEKS: &str = "update_greeks";
+const ENTRY_POINT_SET_VOL_ORACLE: &str = "set_vol_oracle";
+const ENTRY_POINT_UPDATE_IV: &str = "update_iv";
+const ENTRY_POINT_UPDATE_VOLATILITY_SURFACE_POINT: &str = "update_volatility_surface_point";
+const ENTRY_POINT_UPDATE_VOLATILITY_SURFACE_BATCH: &str = "update_volatility_surface_batch";
+const ENTRY_POINT_ADD_COLLATERAL_TYPE: &str = "add_collateral_type";
+const ENTRY_POINT_REMOVE_COLLATERAL_TYPE: &str = "remove_collateral_type";
+const ENTRY_POINT_SET_COLLATERAL_DEX: &str = "set_collateral_dex";
+const ENTRY_POINT_DEPLOY_OPTION_TOKEN: &str = "deploy_option_token";
+const ENTRY_POINT_GET_OPTION_TOKEN_ADDRESS: &str = "get_option_token_address";
+const ENTRY_POINT_DEPOSIT_TO_POOL: &str = "deposit_to_pool";
+const ENTRY_POINT_WITHDRAW_FROM_POOL: &str = "withdraw_from_pool";
+const ENTRY_POINT_WRITE_OPTION_FROM_POOL: &str = "write_option_from_pool";
+const ENTRY_POINT_BUY_OPTION_FROM_POOL: &str = "buy_option_from_pool";
+const ENTRY_POINT_TRANSFER_LP_SHARES: &str = "transfer_lp_shares";
+const ENTRY_POINT_APPROVE_LP_SHARES: &str = "approve_lp_shares";
+const ENTRY_POINT_TRANSFER_LP_SHARES_FROM: &str = "transfer_lp_shares_from";
+const ENTRY_POINT_PROPOSE_PARAMETER_CHANGE: &str = "propose_parameter_change";
+const ENTRY_POINT_EXECUTE_PARAMETER_CHANGE: &str = "execute_parameter_change";
+const ENTRY_POINT_CANCEL_PARAMETER_CHANGE: &str = "cancel_parameter_change";
+const ENTRY_POINT_CREATE_GOVERNANCE_PROPOSAL: &str = "create_governance_proposal";
+const ENTRY_POINT_VOTE_FOR_PROPOSAL: &str = "vote_for_proposal";
+const ENTRY_POINT_EXECUTE_PROPOSAL: &str = "execute_proposal";
+const ENTRY_POINT_CANCEL_PROPOSAL: &str = "cancel_proposal";
+const ENTRY_POINT_UPGRADE: &str = "upgrade";
+const ENTRY_POINT_MIGRATE_OPTIONS: &str = "migrate_options";
+const ENTRY_POINT_GET_OPTION: &str = "get_option";
+const ENTRY_POINT_GET_OPTION_FULL: &str = "get_option_full";
+const ENTRY_POINT_GET_OPTION_TYPE: &str = "get_option_type";
+const ENTRY_POINT_GET_OPTION_COUNT: &str = "get_option_count";
+const ENTRY_POINT_GET_COLLATERAL: &str = "get_collateral";
+const ENTRY_POINT_GET_SERIES_OPTIONS: &str = "get_series_options";
+const ENTRY_POINT_GET_ADJUSTMENT_HISTORY: &str = "get_adjustment_history";
+const ENTRY_POINT_GET_ALL_OPTIONS: &str = "get_all_options";
+const ENTRY_POINT_GET_OPTIONS_EXPIRING_BETWEEN: &str = "get_options_expiring_between";
+const ENTRY_POINT_GET_ALL_EXPIRIES: &str = "get_all_expiries";
+const ENTRY_POINT_GET_STRIKES_FOR_EXPIRY: &str = "get_strikes_for_expiry";
+const ENTRY_POINT_GET_OPTION_CHAIN: &str = "get_option_chain";
+const ENTRY_POINT_CALCULATE_MAX_PAIN: &str = "calculate_max_pain";
+const ENTRY_POINT_GET_OPEN_INTEREST_BY_STRIKE: &str = "get_open_interest_by_strike";
+const ENTRY_POINT_GET_OPEN_INTEREST_BY_EXPIRY: &str = "get_open_interest_by_expiry";
+const ENTRY_POINT_GET_TOTAL_OPEN_INTEREST: &str = "get_total_open_interest";
+const ENTRY_POINT_GET_TOTAL_VOLUME: &str = "get_total_volume";
+const ENTRY_POINT_GET_VOLUME_BY_STRIKE: &str = "get_volume_by_strike";
+const ENTRY_POINT_GET_VOLUME_BY_EXPIRY: &str = "get_volume_by_expiry";
+const ENTRY_POINT_GET_PUT_CALL_RATIO: &str = "get_put_call_ratio";
+const ENTRY_POINT_GET_PUT_CALL_RATIO_FOR_EXPIRY: &str = "get_put_call_ratio_for_expiry";
+const ENTRY_POINT_GET_OPTIONS_BY_OWNER: &str = "get_options_by_owner";
+const ENTRY_POINT_GET_USER_PORTFOLIO: &str = "get_user_portfolio";
+const ENTRY_POINT_GET_USER_PORTFOLIO_DELTA: &str = "get_user_portfolio_delta";
+const ENTRY_POINT_GET_PORTFOLIO_GREEKS: &str = "get_portfolio_greeks";
+const ENTRY_POINT_GET_OPTION_STATE: &str = "get_option_state";
+const ENTRY_POINT_GET_CREATION_TIMESTAMP: &str = "get_creation_timestamp";
+const ENTRY_POINT_GET_EXERCISE_TIMESTAMP: &str = "get_exercise_timestamp";
+const ENTRY_POINT_GET_TIME_TO_EXPIRY: &str = "get_time_to_expiry";
+const ENTRY_POINT_IS_EXPIRED: &str = "is_expired";
+const ENTRY_POINT_FINALIZE_SETTLEMENT_PRICE: &str = "finalize_settlement_price";
+const ENTRY_POINT_DISPUTE_SETTLEMENT_PRICE: &str = "dispute_settlement_price";
+const ENTRY_POINT_RESOLVE_DISPUTE: &str = "resolve_dispute";
+const ENTRY_POINT_IS_SETTLEMENT_FINAL: &str = "is_settlement_final";
+const ENTRY_POINT_IS_IN_THE_MONEY: &str = "is_in_the_money";
+const ENTRY_POINT_IS_OUT_OF_THE_MONEY: &str = "is_out_of_the_money";
+const ENTRY_POINT_IS_AT_THE_MONEY: &str = "is_at_the_money";
+const ENTRY_POINT_IS_LIQUIDATABLE: &str = "is_liquidatable";
+const ENTRY_POINT_GET_INTRINSIC_VALUE: &str = "get_intrinsic_value";
+const ENTRY_POINT_GET_TIME_VALUE: &str = "get_time_value";
+const ENTRY_POINT_GET_UNREALIZED_PNL: &str = "get_unrealized_pnl";
+const ENTRY_POINT_GET_GREEKS: &str = "get_greeks";
+const ENTRY_POINT_GET_IV: &str = "get_iv";
+const ENTRY_POINT_GET_VOLATILITY_SURFACE_POINT: &str = "get_volatility_surface_point";
+const ENTRY_POINT_ESTIMATE_CALL_PRICE: &str = "estimate_call_price";
+const ENTRY_POINT_GET_AMM_OPTION_PRICE: &str = "get_amm_option_price";
+const ENTRY_POINT_GET_OPTIONS_IN_STATE: &str = "get_options_in_state";
+const ENTRY_POINT_GET_ACTIVE_OPTION_COUNT: &str = "get_active_option_count";
+const ENTRY_POINT_GET_EXERCISED_OPTION_COUNT: &str = "get_exercised_option_count";
+const ENTRY_POINT_GET_EXPIRED_OPTION_COUNT: &str = "get_expired_option_count";
+const ENTRY_POINT_GET_CANCELLED_OPTION_COUNT: &str = "get_cancelled_option_count";
+const ENTRY_POINT_GET_SETTLED_OPTION_COUNT: &str = "get_settled_option_count";
+const ENTRY_POINT_GET_TOTAL_OPTION_COUNT: &str = "get_total_option_count";
+const ENTRY_POINT_GET_CONTRACT_VERSION: &str = "get_contract_version";
+const ENTRY_POINT_IS_MIGRATION_COMPLETE: &str = "is_migration_complete";
+const ENTRY_POINT_IS_EXERCISABLE: &str = "is_exercisable";
+const ENTRY_POINT_EXERCISE_REVERT_REASON: &str = "exercise_revert_reason";
+const ENTRY_POINT_IS_WHITELISTED: &str = "is_whitelisted";
+const ENTRY_POINT_GET_LP_BALANCE: &str = "get_lp_balance";
+const ENTRY_POINT_GET_LP_ALLOWANCE: &str = "get_lp_allowance";
+const ENTRY_POINT_GET_INSURANCE_FUND_BALANCE: &str = "get_insurance_fund_balance";
+const ENTRY_POINT_GET_INSURANCE_FUND_CONTRIBUTION: &str = "get_insurance_fund_contribution";
+const ENTRY_POINT_GET_EVENT: &str = "get_event";
+const ENTRY_POINT_GET_EVENT_COUNT: &str = "get_event_count";
+const ENTRY_POINT_GET_OPTION_NFT_TOKEN_ID: &str = "get_option_nft_token_id";
+const ENTRY_POINT_SET_METADATA_URI: &str = "set_metadata_uri";
+const ENTRY_POINT_GET_METADATA_URI: &str = "get_metadata_uri";
 
 const ARG_ID: &str = "id";
 const ARG_STRIKE_PRICE: &str = "strike_price";
 const ARG_EXPIRY: &str = "expiry";
+const ARG_OPTION_TYPE: &str = "option_type";
+const ARG_NEW_OWNER: &str = "new_owner";
+const ARG_ACTIVATION: &str = "activation";
+const ARG_IDS: &str = "ids";
+const ARG_STRIKE_PRICES: &str = "strike_prices";
+const ARG_EXPIRIES: &str = "expiries";
+const ARG_OPTION_STYLE: &str = "option_style";
+const ARG_PREMIUM: &str = "premium";
+const ARG_PURSE: &str = "purse";
+const ARG_AMOUNT: &str = "amount";
+const ARG_WRITER: &str = "writer";
+const ARG_NEW_ADMIN: &str = "new_admin";
+const ARG_INDEX: &str = "index";
+const ARG_OPTION_AMOUNT: &str = "option_amount";
+const ARG_UNDERLYING_TOKEN: &str = "underlying_token";
+const ARG_UNDERLYING_AMOUNT: &str = "underlying_amount";
+const ARG_EXERCISE_AMOUNT: &str = "exercise_amount";
+const ARG_SERIES_ID: &str = "series_id";
+const ARG_DIVIDEND_PER_SHARE: &str = "dividend_per_share";
+const ARG_OLD_ID: &str = "old_id";
+const ARG_NEW_ID: &str = "new_id";
+const ARG_NEW_EXPIRY: &str = "new_expiry";
+const ARG_CALL_ID: &str = "call_id";
+const ARG_SOURCE_ID: &str = "source_id";
+const ARG_NEW_STRIKE: &str = "new_strike";
+const ARG_PUT_ID: &str = "put_id";
+const ARG_STRADDLE_ID: &str = "straddle_id";
+const ARG_BASE_ID: &str = "base_id";
+const ARG_LONG_ID: &str = "long_id";
+const ARG_SHORT_ID: &str = "short_id";
+const ARG_LONG_STRIKE: &str = "long_strike";
+const ARG_SHORT_STRIKE: &str = "short_strike";
+const ARG_SPREAD_ID: &str = "spread_id";
+const ARG_OFFSET: &str = "offset";
+const ARG_LIMIT: &str = "limit";
+const ARG_FROM_EXPIRY: &str = "from_expiry";
+const ARG_TO_EXPIRY: &str = "to_expiry";
+const ARG_AUTO_EXERCISE: &str = "auto_exercise";
+const ARG_OWNER: &str = "owner";
+const ARG_ORACLE_CONTRACT_HASH: &str = "oracle_contract_hash";
+const ARG_FEE_BPS: &str = "fee_bps";
+const ARG_KEEPER_REWARD_PER_OPTION: &str = "keeper_reward_per_option";
+const ARG_KEEPER_REWARD_PER_EXERCISE: &str = "keeper_reward_per_exercise";
+const ARG_KEEPER: &str = "keeper";
+const ARG_PUBLISHER: &str = "publisher";
+const ARG_ORACLE_STALENESS_THRESHOLD: &str = "oracle_staleness_threshold_seconds";
+const ARG_SNAPSHOT_INTERVAL: &str = "snapshot_interval_seconds";
+const ARG_USE_TWAP: &str = "use_twap";
+const ARG_TWAP_WINDOW: &str = "twap_window_seconds";
+const ARG_WINDOW_SECONDS: &str = "window_seconds";
+const ARG_PRICE_RANGE_LOW: &str = "price_range_low";
+const ARG_PRICE_RANGE_HIGH: &str = "price_range_high";
+const ARG_WHITELIST_ENABLED: &str = "whitelist_enabled";
+const ARG_MAX_UTILIZATION_BPS: &str = "max_utilization_bps";
+const ARG_AMM_LAMBDA_BPS: &str = "amm_lambda_bps";
+const ARG_LIQUIDATION_THRESHOLD_BPS: &str = "liquidation_threshold_bps";
+const ARG_LIQUIDATION_BONUS_BPS: &str = "liquidation_bonus_bps";
+const ARG_OPTION_NFT_CONTRACT_HASH: &str = "option_nft_contract_hash";
+const ARG_MAX_NOTIONAL_PER_ACCOUNT: &str = "max_notional_per_account";
+const ARG_EARLY_EXERCISE_PENALTY_BPS: &str = "early_exercise_penalty_bps";
+const ARG_PRICE_VALIDATION_ENABLED: &str = "price_validation_enabled";
+const ARG_MAX_PRICE_DEVIATION_BPS: &str = "max_price_deviation_bps";
+const ARG_GOVERNANCE_VOTE_THRESHOLD: &str = "governance_vote_threshold";
+const ARG_FUNDING_RATE_PER_DAY: &str = "funding_rate_per_day";
+const ARG_SHARES: &str = "shares";
+const ARG_RECIPIENT: &str = "recipient";
+const ARG_DESTINATION: &str = "destination";
+const ARG_SPENDER: &str = "spender";
+const ARG_ACCOUNT: &str = "account";
+const ARG_GREEKS_ORACLE: &str = "greeks_oracle";
+const ARG_HIGH_VALUE_THRESHOLD: &str = "high_value_threshold";
+const ARG_SIGNERS: &str = "signers";
+const ARG_EXERCISE_THRESHOLD: &str = "exercise_threshold";
+const ARG_SYNDICATORS: &str = "syndicators";
+const ARG_STRIKE: &str = "strike";
+const ARG_IS_BUY: &str = "is_buy";
+const ARG_MAX_PRICE: &str = "max_price";
+const ARG_MIN_PRICE: &str = "min_price";
+const ARG_ORDER_ID: &str = "order_id";
+const ARG_BUY_ORDER_ID: &str = "buy_order_id";
+const ARG_SELL_ORDER_ID: &str = "sell_order_id";
+const ARG_DELTA: &str = "delta";
+const ARG_GAMMA: &str = "gamma";
+const ARG_THETA: &str = "theta";
+const ARG_VEGA: &str = "vega";
+const ARG_VOL_ORACLE: &str = "vol_oracle";
+const ARG_IV: &str = "iv";
+const ARG_IV_BPS: &str = "iv_bps";
+const ARG_COLLATERAL_TOKEN: &str = "collateral_token";
+const ARG_COLLATERAL_AMOUNT: &str = "collateral_amount";
+const ARG_VESTING_START: &str = "vesting_start";
+const ARG_CLIFF_SECONDS: &str = "cliff_seconds";
+const ARG_VESTING_DURATION_SECONDS: &str = "vesting_duration_seconds";
+const ARG_COLLATERAL_FACTOR_BPS: &str = "collateral_factor_bps";
+const ARG_TOKEN: &str = "token";
+const ARG_DEX: &str = "dex";
+const ARG_IVS_BPS: &str = "ivs_bps";
+const ARG_METADATA_URI: &str = "metadata_uri";
+const ARG_UNDERLYING_PRICE_AT_CREATION: &str = "underlying_price_at_creation";
+const ARG_EXERCISE_DATES: &str = "exercise_dates";
+const ARG_UNDERLYING_PRICE: &str = "underlying_price";
+const ARG_PRICE: &str = "price";
+const ARG_CURRENT_PRICE: &str = "current_price";
+const ARG_OPTION_PRICE: &str = "option_price";
+const ARG_CONTRACT_HASH: &str = "contract_hash";
+const ARG_CONTRACT_VERSION: &str = "contract_version";
+const ARG_PARAM_KEY: &str = "param_key";
+const ARG_PARAM_VALUE: &str = "param_value";
+const ARG_PARAM_NAME: &str = "param_name";
+const ARG_NEW_VALUE: &str = "new_value";
+const ARG_DESCRIPTION: &str = "description";
+const ARG_PROPOSAL_ID: &str = "proposal_id";
+const ARG_NONCE: &str = "nonce";
+const ARG_SIGNATURE: &str = "signature";
+const ARG_PERMIT_SIGNATURE: &str = "permit_signature";
+const ARG_PERMIT_NONCE: &str = "permit_nonce";
+const ARG_PERMIT_DEADLINE: &str = "permit_deadline";
+const ARG_SIGNER: &str = "signer";
+const ARG_APPROVED: &str = "approved";
+const ARG_STATE: &str = "state";
+const ARG_CALLER: &str = "caller";
+
+const ORACLE_ENTRY_POINT_GET_PRICE: &str = "get_price";
+
+const CEP18_ENTRY_POINT_TRANSFER: &str = "transfer";
+const CEP18_ENTRY_POINT_TRANSFER_FROM: &str = "transfer_from";
+const CEP18_ARG_OWNER: &str = "owner";
+const CEP18_ARG_RECIPIENT: &str = "recipient";
+const CEP18_ARG_AMOUNT: &str = "amount";
+
+const DEX_ENTRY_POINT_GET_AMOUNT_OUT: &str = "get_amount_out";
+const DEX_ENTRY_POINT_GET_AMOUNT_IN: &str = "get_amount_in";
+const DEX_ARG_TOKEN: &str = "token";
+const DEX_ARG_AMOUNT_IN: &str = "amount_in";
+const DEX_ARG_AMOUNT_OUT: &str = "amount_out";
+
+const CEP78_ENTRY_POINT_MINT: &str = "mint";
+const CEP78_ENTRY_POINT_TRANSFER: &str = "transfer";
+const CEP78_ENTRY_POINT_BURN: &str = "burn";
+const CEP78_ARG_TOKEN_OWNER: &str = "token_owner";
+const CEP78_ARG_TOKEN_META_DATA: &str = "token_meta_data";
+const CEP78_ARG_TOKEN_ID: &str = "token_id";
+const CEP78_ARG_SOURCE_KEY: &str = "source_key";
+const CEP78_ARG_TARGET_KEY: &str = "target_key";
+
+const OPTION_STYLE_EUROPEAN: u8 = 0;
+const OPTION_STYLE_AMERICAN: u8 = 1;
+const OPTION_STYLE_BERMUDAN: u8 = 2;
+
+/// Bundle types stored under `bundle_{base_id}_type`.
+const BUNDLE_TYPE_STRIP: u8 = 0;
+const BUNDLE_TYPE_STRAP: u8 = 1;
+
+/// Lifecycle states stored under `option_{id}_state`.
+const OPTION_STATE_CREATED: u8 = 0;
+const OPTION_STATE_ACTIVE: u8 = 1;
+const OPTION_STATE_EXERCISED: u8 = 2;
+const OPTION_STATE_EXPIRED: u8 = 3;
+const OPTION_STATE_CANCELLED: u8 = 4;
+const OPTION_STATE_SETTLED: u8 = 5;
+
+/// Length of the window, starting at expiry, in which a European option can
+/// be exercised.
+const EUROPEAN_EXERCISE_WINDOW_SECS: u64 = 86_400;
+
+/// How long before its expiry an option can be rolled over.
+const ROLLOVER_WINDOW_SECS: u64 = 86_400;
+
+/// Shortest time to expiry an option bought from the LP pool can have.
+const MIN_POOL_OPTION_TERM_SECS: u64 = 86_400;
+
+/// How long holders can dispute a finalized settlement price.
+const DISPUTE_WINDOW_SECS: u64 = 86_400;
+
+/// Parameters `propose_parameter_change` accepts.
+const PARAM_FEE_BPS: &str = "fee_bps";
+const PARAM_ORACLE_STALENESS_THRESHOLD: &str = "oracle_staleness_threshold_seconds";
+
+/// How long a parameter change waits between proposal and execution.
+const GOVERNANCE_DELAY_SECS: u64 = 48 * 3600;
+
+/// Denominator of `option_registry_fee_bps` and
+/// `option_registry_max_utilization_bps`.
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// `option_registry_amm_lambda_bps` for registries installed without
+/// `amm_lambda_bps`.
+const DEFAULT_AMM_LAMBDA_BPS: u32 = 1_000;
+
+/// `option_registry_liquidation_threshold_bps` for registries installed
+/// without `liquidation_threshold_bps`: liquidatable once collateral no
+/// longer covers the payout in full.
+const DEFAULT_LIQUIDATION_THRESHOLD_BPS: u32 = 10_000;
+
+/// `option_registry_liquidation_bonus_bps` for registries installed without
+/// `liquidation_bonus_bps`.
+const DEFAULT_LIQUIDATION_BONUS_BPS: u32 = 500;
+
+/// `oracle_staleness_threshold_seconds` for registries installed without it.
+const DEFAULT_ORACLE_STALENESS_THRESHOLD_SECS: u64 = 3600;
+
+/// `snapshot_interval_seconds` for registries installed without it.
+const DEFAULT_SNAPSHOT_INTERVAL_SECS: u64 = 300;
+
+/// `twap_window_seconds` for registries installed without it.
+const DEFAULT_TWAP_WINDOW_SECS: u64 = 3600;
+
+/// `max_notional_per_account` for registries installed without it: no limit.
+const DEFAULT_MAX_NOTIONAL_PER_ACCOUNT: U256 = U256::MAX;
+
+/// `early_exercise_penalty_bps` for registries installed without it.
+const DEFAULT_EARLY_EXERCISE_PENALTY_BPS: u32 = 50;
+
+/// `max_price_deviation_bps` until the admin sets it.
+const DEFAULT_MAX_PRICE_DEVIATION_BPS: u32 = 1_000;
+
+/// `governance_vote_threshold` for registries installed without it.
+const DEFAULT_GOVERNANCE_VOTE_THRESHOLD: u32 = 3;
+
+/// Eta of a governance proposal short of `governance_vote_threshold` votes.
+/// No block time reaches it, so the proposal can't be executed yet.
+const AWAITING_VOTES_ETA: u64 = u64::MAX;
+
+/// Longest description `create_governance_proposal` accepts, in characters.
+const MAX_PROPOSAL_DESCRIPTION_LEN: usize = 1024;
+
+/// Funding accrues once per whole period of this length.
+const FUNDING_PERIOD_SECS: u64 = 86_400;
+
+/// An American option exercised earlier than this before its expiry pays the
+/// early exercise penalty.
+const EARLY_EXERCISE_WINDOW_SECS: u64 = 86_400;
+
+/// Most snapshots, newest first, a TWAP is computed over.
+const MAX_TWAP_SNAPSHOTS: u64 = 100;
+
+/// Writer recorded for options written by the LP pool. No account has this
+/// hash, so writer-only entry points can't touch pool funds.
+const POOL_WRITER: AccountHash = AccountHash::new([0; 32]);
+
+/// Writer recorded for syndicated options, whose collateral is split across
+/// the syndicators instead. Like `POOL_WRITER`, no account has this hash.
+const SYNDICATE_WRITER: AccountHash = AccountHash::new([0xff; 32]);
+
+/// Most options `migrate_options` moves, and most it advances
+/// `migration_cursor` past, in one call.
+const MAX_MIGRATION_BATCH: usize = 50;
+
+/// Most options `emergency_cancel_batch` takes in one call.
+const MAX_EMERGENCY_CANCEL_BATCH: usize = 100;
+
+/// Longest `option_{id}_metadata_uri` accepted, in characters.
+const MAX_METADATA_URI_LEN: usize = 512;
+
+/// Most exercise dates a Bermudan option can have.
+const MAX_EXERCISE_DATES: usize = 12;
+
+/// How far either side of one of its exercise dates a Bermudan option can be
+/// exercised.
+const EXERCISE_TOLERANCE_SECS: u64 = 3600;
+
+/// Width of an `expiry_index` bucket.
+const DAY_IN_SECONDS: u64 = 86_400;
 
 fn option_key(id: u64) -> String {
     let mut key = String::from("option_");
@@ -33,86 +582,8212 @@ fn option_key(id: u64) -> String {
     key
 }
 
-fn option_exercised_key(id: u64) -> String {
+fn option_cancelled_key(id: u64) -> String {
     let mut key = String::from("option_");
     key.push_str(&id.to_string());
-    key.push_str("_exercised");
+    key.push_str("_cancelled");
     key
 }
 
-#[no_mangle]
-pub extern "C" fn create_option() {
-    let id: u64 = runtime::get_named_arg(ARG_ID);
-    let strike_price: u64 = runtime::get_named_arg(ARG_STRIKE_PRICE);
-    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
-    
-    let key_name = option_key(id);
-    
-    let id_uref = storage::new_uref(id);
-    let strike_uref = storage::new_uref(strike_price);
-    let expiry_uref = storage::new_uref(expiry);
-    
-    runtime::put_key(&key_name, id_uref.into());
-    runtime::put_key(&(key_name.clone() + "_strike"), strike_uref.into());
-    runtime::put_key(&(key_name.clone() + "_expiry"), expiry_uref.into());
-    
-    let exercised_key = option_exercised_key(id);
-    let exercised_uref = storage::new_uref(false);
-    runtime::put_key(&exercised_key, exercised_uref.into());
+fn option_state_key(id: u64) -> String {
+    let mut key = String::from("option_");
+    key.push_str(&id.to_string());
+    key.push_str("_state");
+    key
 }
 
-#[no_mangle]
-pub extern "C" fn exercise_option() {
-    let id: u64 = runtime::get_named_arg(ARG_ID);
-    let exercised_key = option_exercised_key(id);
-    
-    match runtime::get_key(&exercised_key) {
-        Some(key) => {
-            let uref = key.into_uref().unwrap_or_revert();
-            storage::write(uref, true);
-        }
-        None => {
-            let exercised_uref = storage::new_uref(true);
-            runtime::put_key(&exercised_key, exercised_uref.into());
-        }
+fn option_writer_key(id: u64) -> String {
+    let mut key = String::from("option_");
+    key.push_str(&id.to_string());
+    key.push_str("_writer");
+    key
+}
+
+/// `option_{id}_{greek}`, where `greek` is `delta`, `gamma`, `theta` or `vega`.
+fn option_greek_key(id: u64, greek: &str) -> String {
+    let mut key = option_key(id);
+    key.push('_');
+    key.push_str(greek);
+    key
+}
+
+fn option_greeks_stale_key(id: u64) -> String {
+    option_key(id) + "_greeks_stale"
+}
+
+fn option_iv_key(id: u64) -> String {
+    option_key(id) + "_iv"
+}
+
+fn option_created_at_key(id: u64) -> String {
+    option_key(id) + "_created_at"
+}
+
+fn option_collateral_token_key(id: u64) -> String {
+    option_key(id) + "_collateral_token"
+}
+
+fn option_token_collateral_key(id: u64) -> String {
+    option_key(id) + "_token_collateral"
+}
+
+fn option_vesting_key(id: u64) -> String {
+    option_key(id) + "_vesting"
+}
+
+fn option_notional_account_key(id: u64) -> String {
+    option_key(id) + "_notional_account"
+}
+
+/// `option_{id}_rollover_approved_until`, the latest expiry the writer lets
+/// the option be rolled out to.
+fn option_rollover_approval_key(id: u64) -> String {
+    option_key(id) + "_rollover_approved_until"
+}
+
+fn option_token_key(id: u64) -> String {
+    option_key(id) + "_option_token"
+}
+
+/// `token_{underlying}_{strike}_{expiry}_{call|put}`, where the option token
+/// of that class is stored; `underlying` is the underlying token's hash in
+/// hex, or `cspr` for cash-settled options.
+fn option_token_class_key(
+    underlying: Option<Key>,
+    strike_price: U256,
+    expiry: u64,
+    option_type: bool,
+) -> String {
+    let underlying = match underlying {
+        Some(token) => bytes_hex(&contract_key_hash(token).value()),
+        None => String::from("cspr"),
+    };
+    let mut key = String::from("token_") + &underlying;
+    key.push('_');
+    key.push_str(&strike_price.to_string());
+    key.push('_');
+    key.push_str(&expiry.to_string());
+    key.push_str(if option_type { "_call" } else { "_put" });
+    key
+}
+
+fn option_last_funding_time_key(id: u64) -> String {
+    option_key(id) + "_last_funding_time"
+}
+
+fn option_funding_owed_key(id: u64) -> String {
+    option_key(id) + "_funding_owed"
+}
+
+fn option_exercised_at_key(id: u64) -> String {
+    option_key(id) + "_exercised_at"
+}
+
+fn option_exercise_log_count_key(id: u64) -> String {
+    option_key(id) + "_exercise_log_count"
+}
+
+fn option_exercise_date_count_key(id: u64) -> String {
+    option_key(id) + "_exercise_date_count"
+}
+
+fn option_exercise_date_key(id: u64, index: u64) -> String {
+    let mut key = option_key(id) + "_exercise_date_";
+    key.push_str(&index.to_string());
+    key
+}
+
+fn option_collateral_key(id: u64) -> String {
+    let mut key = String::from("option_");
+    key.push_str(&id.to_string());
+    key.push_str("_collateral");
+    key
+}
+
+fn option_approval_count_key(id: u64) -> String {
+    option_key(id) + "_approval_count"
+}
+
+/// `{id}_{account_hex}`, the `exercise_approvals` and `exercise_delegates`
+/// item for the pair.
+fn exercise_approval_key(id: u64, account: &AccountHash) -> String {
+    let mut key = id.to_string();
+    key.push('_');
+    key.push_str(&account_hex(account));
+    key
+}
+
+fn option_syndicators_key(id: u64) -> String {
+    option_key(id) + "_syndicators"
+}
+
+/// `{id}_{account_hex}`, the `syndicates` item for the pair.
+fn syndicate_key(id: u64, account: &AccountHash) -> String {
+    let mut key = id.to_string();
+    key.push('_');
+    key.push_str(&account_hex(account));
+    key
+}
+
+fn option_settled_key(id: u64) -> String {
+    let mut key = String::from("option_");
+    key.push_str(&id.to_string());
+    key.push_str("_settled");
+    key
+}
+
+fn signed_exercise_nonce_key(id: u64) -> String {
+    let mut key = String::from("signed_exercise_nonce_");
+    key.push_str(&id.to_string());
+    key
+}
+
+fn expiry_set_key(index: u64) -> String {
+    let mut key = String::from("expiry_set_");
+    key.push_str(&index.to_string());
+    key
+}
+
+fn rollover_key(index: u64) -> String {
+    let mut key = String::from("rollover_");
+    key.push_str(&index.to_string());
+    key
+}
+
+fn exercise_log_key(index: u64) -> String {
+    let mut key = String::from("exercise_log_");
+    key.push_str(&index.to_string());
+    key
+}
+
+fn expiry_strikes_count_key(expiry: u64) -> String {
+    let mut key = String::from("expiry_");
+    key.push_str(&expiry.to_string());
+    key.push_str("_strikes_count");
+    key
+}
+
+fn expiry_strike_key(expiry: u64, index: u64) -> String {
+    let mut key = String::from("expiry_");
+    key.push_str(&expiry.to_string());
+    key.push_str("_strikes_");
+    key.push_str(&index.to_string());
+    key
+}
+
+/// `{expiry}_{strike_price}`, the `option_chain` item for one strike.
+fn option_chain_key(expiry: u64, strike_price: U256) -> String {
+    let mut key = expiry.to_string();
+    key.push('_');
+    key.push_str(&strike_price.to_string());
+    key
+}
+
+/// `{strike}_{expiry}`, the `vol_surface` item for one point.
+fn vol_surface_key(strike: U256, expiry: u64) -> String {
+    let mut key = strike.to_string();
+    key.push('_');
+    key.push_str(&expiry.to_string());
+    key
+}
+
+fn oi_strike_key(expiry: u64, strike_price: U256) -> String {
+    let mut key = String::from("oi_strike_");
+    key.push_str(&option_chain_key(expiry, strike_price));
+    key
+}
+
+fn oi_expiry_key(expiry: u64) -> String {
+    let mut key = String::from("oi_expiry_");
+    key.push_str(&expiry.to_string());
+    key
+}
+
+/// `volume_call_{strike_price}` or `volume_put_{strike_price}`.
+fn volume_strike_key(strike_price: U256, option_type: bool) -> String {
+    let mut key = String::from(if option_type { "volume_call_" } else { "volume_put_" });
+    key.push_str(&strike_price.to_string());
+    key
+}
+
+fn volume_expiry_key(expiry: u64) -> String {
+    let mut key = String::from("volume_expiry_");
+    key.push_str(&expiry.to_string());
+    key
+}
+
+/// `expiry_{expiry}_calls_created` or `expiry_{expiry}_puts_created`.
+fn expiry_created_key(expiry: u64, option_type: bool) -> String {
+    let mut key = String::from("expiry_");
+    key.push_str(&expiry.to_string());
+    key.push_str(if option_type { "_calls_created" } else { "_puts_created" });
+    key
+}
+
+fn settlement_price_key(expiry: u64) -> String {
+    let mut key = String::from("settlement_price_");
+    key.push_str(&expiry.to_string());
+    key
+}
+
+fn price_snapshot_key(timestamp: u64) -> String {
+    let mut key = String::from("oracle_price_snapshot_");
+    key.push_str(&timestamp.to_string());
+    key
+}
+
+fn price_snapshot_time_key(index: u64) -> String {
+    let mut key = String::from("oracle_price_snapshot_time_");
+    key.push_str(&index.to_string());
+    key
+}
+
+fn settlement_finalized_at_key(expiry: u64) -> String {
+    settlement_price_key(expiry) + "_finalized_at"
+}
+
+fn settlement_disputed_key(expiry: u64) -> String {
+    settlement_price_key(expiry) + "_disputed"
+}
+
+fn settlement_resolved_key(expiry: u64) -> String {
+    settlement_price_key(expiry) + "_resolved"
+}
+
+fn straddle_key(straddle_id: u64) -> String {
+    let mut key = String::from("straddle_");
+    key.push_str(&straddle_id.to_string());
+    key
+}
+
+fn bundle_key(base_id: u64) -> String {
+    let mut key = String::from("bundle_");
+    key.push_str(&base_id.to_string());
+    key
+}
+
+fn order_key(order_id: u64) -> String {
+    let mut key = String::from("order_");
+    key.push_str(&order_id.to_string());
+    key
+}
+
+fn order_open_key(order_id: u64) -> String {
+    order_key(order_id) + "_open"
+}
+
+/// `{expiry}_{strike}_{call|put}_{bids|asks}`, the `order_book` item for one
+/// side of the book.
+fn order_book_key(expiry: u64, strike_price: U256, option_type: bool, is_buy: bool) -> String {
+    let mut key = option_chain_key(expiry, strike_price);
+    key.push_str(if option_type { "_call" } else { "_put" });
+    key.push_str(if is_buy { "_bids" } else { "_asks" });
+    key
+}
+
+fn spread_key(spread_id: u64) -> String {
+    let mut key = String::from("spread_");
+    key.push_str(&spread_id.to_string());
+    key
+}
+
+fn option_spread_key(id: u64) -> String {
+    option_key(id) + "_spread"
+}
+
+fn option_nft_token_id_key(id: u64) -> String {
+    option_key(id) + "_nft_token_id"
+}
+
+fn option_adjustment_count_key(id: u64) -> String {
+    option_key(id) + "_adjustment_count"
+}
+
+fn option_adjustment_key(id: u64, index: u64) -> String {
+    let mut key = option_key(id) + "_adjustment_";
+    key.push_str(&index.to_string());
+    key
+}
+
+fn option_entry_price_key(id: u64) -> String {
+    option_key(id) + "_entry_price"
+}
+
+fn option_metadata_uri_key(id: u64) -> String {
+    option_key(id) + "_metadata_uri"
+}
+
+/// `auto_exercise_pending_{id}`, set by `set_auto_exercise` for
+/// `process_auto_exercises` to exercise the option at expiry.
+fn auto_exercise_pending_key(id: u64) -> String {
+    let mut key = String::from("auto_exercise_pending_");
+    key.push_str(&id.to_string());
+    key
+}
+
+/// `option_{id}_auto_exercise`, set by `schedule_exercise` for keepers to
+/// exercise the option through `process_scheduled_exercises`.
+fn option_auto_exercise_key(id: u64) -> String {
+    option_key(id) + "_auto_exercise"
+}
+
+/// `option_{id}_state_slot`, the option's slot in its state's bucket.
+fn option_state_slot_key(id: u64) -> String {
+    option_key(id) + "_state_slot"
+}
+
+/// `{state}_{slot}`, the `state_index` item holding one id of `state`'s
+/// bucket.
+fn state_slot_key(state: u8, slot: u64) -> String {
+    let mut key = state.to_string();
+    key.push('_');
+    key.push_str(&slot.to_string());
+    key
+}
+
+fn all_options_key(index: u64) -> String {
+    let mut key = String::from("all_options_");
+    key.push_str(&index.to_string());
+    key
+}
+
+fn series_count_key(series_id: u64) -> String {
+    let mut key = String::from("series_");
+    key.push_str(&series_id.to_string());
+    key.push_str("_count");
+    key
+}
+
+fn series_entry_key(series_id: u64, index: u64) -> String {
+    let mut key = String::from("series_");
+    key.push_str(&series_id.to_string());
+    key.push('_');
+    key.push_str(&index.to_string());
+    key
+}
+
+/// Lowercase hex of `account`, used to build per-account keys.
+fn account_hex(account: &AccountHash) -> String {
+    bytes_hex(&account.value())
+}
+
+/// Lowercase hex of `bytes`.
+fn bytes_hex(bytes: &[u8]) -> String {
+    const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for &byte in bytes {
+        hex.push(HEX_DIGITS[usize::from(byte >> 4)] as char);
+        hex.push(HEX_DIGITS[usize::from(byte & 0x0f)] as char);
     }
+    hex
 }
 
-#[no_mangle]
-pub extern "C" fn call() {
-    let mut entry_points = EntryPoints::new();
-    
-    entry_points.add_entry_point(EntryPoint::new(
-        ENTRY_POINT_CREATE_OPTION,
-        vec![
-            Parameter::new(ARG_ID, CLType::U64),
-            Parameter::new(ARG_STRIKE_PRICE, CLType::U64),
-            Parameter::new(ARG_EXPIRY, CLType::U64),
-        ],
-        CLType::Unit,
-        EntryPointAccess::Public,
-        EntryPointType::Called,
-    ).into());
-    
-    entry_points.add_entry_point(EntryPoint::new(
-        ENTRY_POINT_EXERCISE_OPTION,
-        vec![
-            Parameter::new(ARG_ID, CLType::U64),
-        ],
-        CLType::Unit,
-        EntryPointAccess::Public,
-        EntryPointType::Called,
-    ).into());
-    
-    let named_keys = NamedKeys::new();
+/// `owner_{hex}` prefix shared by `owner`'s portfolio keys.
+fn owner_key_prefix(owner: &AccountHash) -> String {
+    String::from("owner_") + &account_hex(owner)
+}
+
+/// `user_notional_{account_hex}`, the strike times units of the options
+/// `account` created that are still open.
+fn user_notional_key(account: &AccountHash) -> String {
+    String::from("user_notional_") + &account_hex(account)
+}
+
+fn nonce_key(account: &AccountHash) -> String {
+    String::from("nonce_") + &account_hex(account)
+}
+
+/// `{signer_hex}_{nonce}`, the `used_permits` item for the pair.
+fn permit_nonce_key(signer: &AccountHash, nonce: u64) -> String {
+    let mut key = account_hex(signer);
+    key.push('_');
+    key.push_str(&nonce.to_string());
+    key
+}
+
+fn owner_count_key(owner: &AccountHash) -> String {
+    owner_key_prefix(owner) + "_count"
+}
+
+fn owner_entry_key(owner: &AccountHash, index: u64) -> String {
+    let mut key = owner_key_prefix(owner);
+    key.push('_');
+    key.push_str(&index.to_string());
+    key
+}
+
+/// Current block time in unix seconds, matching the unit used for `expiry`.
+fn current_timestamp() -> u64 {
+    u64::from(runtime::get_blocktime()) / 1000
+}
+
+fn named_uref(name: &str, missing: ErrorCode) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert_with(missing)
+        .into_uref()
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+}
+
+fn read_uref<T: CLTyped + FromBytes>(uref: URef) -> T {
+    storage::read(uref)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+}
+
+/// Reads a contract-level named key, reverting if the contract was not
+/// installed with it.
+fn read_named_value<T: CLTyped + FromBytes>(name: &str) -> T {
+    read_uref(named_uref(name, ErrorCode::MissingNamedKey))
+}
+
+/// Reads a per-option named key, reverting with `OptionNotFound` if the
+/// option was never created.
+fn read_option_value<T: CLTyped + FromBytes>(name: &str) -> T {
+    read_uref(named_uref(name, ErrorCode::OptionNotFound))
+}
+
+/// Whether `account` is marked in the account-keyed dictionary `dictionary`,
+/// such as the keeper registry; unmarked accounts read as false.
+fn account_listed(dictionary: &str, account: &AccountHash) -> bool {
+    let dictionary_uref = named_uref(dictionary, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(dictionary_uref, &account_hex(account))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or(false)
+}
+
+fn set_account_listed(dictionary: &str, account: &AccountHash, listed: bool) {
+    let dictionary_uref = named_uref(dictionary, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(dictionary_uref, &account_hex(account), listed);
+}
+
+/// Reads a per-option named key that options created before it was
+/// introduced lack, returning the default for those.
+fn read_option_value_or_default<T: CLTyped + FromBytes + Default>(name: &str) -> T {
+    match runtime::get_key(name) {
+        Some(_) => read_option_value(name),
+        None => T::default(),
+    }
+}
+
+/// Reverts with `OptionNotFound` unless option `id` has been created.
+fn require_option_exists(id: u64) {
+    if !runtime::has_key(&option_state_key(id)) {
+        runtime::revert(ErrorCode::OptionNotFound);
+    }
+}
+
+/// Reads option `id`'s entry in the per-field dictionary `dictionary`,
+/// reverting with `OptionNotFound` if the option was never created.
+fn read_option_field<T: CLTyped + FromBytes>(dictionary: &str, id: u64) -> T {
+    let dictionary_uref = named_uref(dictionary, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(dictionary_uref, &id.to_string())
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_revert_with(ErrorCode::OptionNotFound)
+}
+
+fn write_option_field<T: CLTyped + ToBytes>(dictionary: &str, id: u64, value: T) {
+    let dictionary_uref = named_uref(dictionary, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(dictionary_uref, &id.to_string(), value);
+}
+
+/// Moves option `id` to state `to`, reverting with `InvalidStateTransition`
+/// unless its current state is one of `from`. Returns the previous state.
+fn transition(id: u64, from: &[u8], to: u8) -> u8 {
+    let state_uref = named_uref(&option_state_key(id), ErrorCode::OptionNotFound);
+    let state: u8 = read_uref(state_uref);
+    if !from.contains(&state) {
+        runtime::revert(ErrorCode::InvalidStateTransition);
+    }
+    storage::write(state_uref, to);
+    if state != to {
+        unindex_state(id, state);
+        index_state(id, to);
+    }
+    if is_open_state(state) && !is_open_state(to) {
+        close_open_interest(id);
+        // `exercise` releases the units it exercises itself
+        if to != OPTION_STATE_EXERCISED {
+            release_notional(id, remaining_amount(id));
+        }
+    }
+    state
+}
+
+/// Strike times units of the open options `account` created.
+fn user_notional(account: &AccountHash) -> U256 {
+    let key = user_notional_key(account);
+    if runtime::has_key(&key) {
+        read_named_value(&key)
+    } else {
+        U256::zero()
+    }
+}
+
+/// Adds option `id`'s notional, `strike_price` times its `amount` units (one
+/// when unsized), to the open notional of `account`, which created it,
+/// reverting with `PositionLimitExceeded` if that takes it above
+/// `max_notional_per_account`. Every path that creates an option counts it.
+fn count_notional(id: u64, account: AccountHash, strike_price: U256, amount: U256) {
+    let units = if amount.is_zero() { U256::one() } else { amount };
+    let notional = user_notional(&account).saturating_add(strike_price.saturating_mul(units));
+    let max_notional: U256 = read_named_value(MAX_NOTIONAL_PER_ACCOUNT_KEY);
+    if notional > max_notional {
+        runtime::revert(ErrorCode::PositionLimitExceeded);
+    }
+    put_named_value(&user_notional_key(&account), notional);
+    put_named_value(&option_notional_account_key(id), account);
+}
+
+/// Takes `units` of option `id` off the notional of the account that created
+/// it; options created before notional was counted are skipped. An unsized
+/// option, only ever closed whole, releases its one unit.
+fn release_notional(id: u64, units: U256) {
+    if !runtime::has_key(&option_notional_account_key(id)) {
+        return;
+    }
+    let account: AccountHash = read_named_value(&option_notional_account_key(id));
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let amount: U256 = read_option_value(&(option_key(id) + "_amount"));
+    let units = if amount.is_zero() { U256::one() } else { units };
+    let released = strike_price.saturating_mul(units);
+    put_named_value(&user_notional_key(&account), user_notional(&account).saturating_sub(released));
+}
+
+/// Counts a newly stored call or put in the registry and `expiry` totals.
+fn count_created(expiry: u64, option_type: bool) {
+    let total_key = if option_type {
+        TOTAL_CALLS_CREATED_KEY
+    } else {
+        TOTAL_PUTS_CREATED_KEY
+    };
+    increment_u64(named_counter(total_key));
+    increment_u64(named_counter(&expiry_created_key(expiry, option_type)));
+}
+
+/// Whether an option in `state` counts towards open interest.
+fn is_open_state(state: u8) -> bool {
+    state == OPTION_STATE_CREATED || state == OPTION_STATE_ACTIVE
+}
+
+/// The open interest counters of an option struck at `strike_price` and
+/// expiring at `expiry`: per strike and expiry, per expiry, and the total.
+fn open_interest_keys(expiry: u64, strike_price: U256) -> [String; 3] {
+    [
+        oi_strike_key(expiry, strike_price),
+        oi_expiry_key(expiry),
+        TOTAL_OPEN_INTEREST_KEY.to_string(),
+    ]
+}
+
+/// Counts a newly stored option in the open interest at its strike and expiry.
+fn open_open_interest(expiry: u64, strike_price: U256) {
+    for key in open_interest_keys(expiry, strike_price) {
+        increment_u64(named_counter(&key));
+    }
+}
+
+/// Removes option `id` from open interest once it is exercised, expired or
+/// cancelled. Counters saturate at 0 for options created before they existed.
+fn close_open_interest(id: u64) {
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    for key in open_interest_keys(expiry, strike_price) {
+        decrement_u64(named_counter(&key));
+    }
+}
+
+/// Adds the notional of `units` exercised units of option `id`, `units *
+/// strike`, to the exercised volume of its type, strike and expiry. An
+/// unsized option counts as one unit.
+fn record_volume(id: u64, units: U256) {
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let option_type: bool = read_option_value(&(option_key(id) + "_type"));
+    let units = if units.is_zero() { U256::one() } else { units };
+    let notional = units.saturating_mul(strike_price);
+    
+    let total_key = if option_type {
+        VOLUME_CALL_EXERCISED_KEY
+    } else {
+        VOLUME_PUT_EXERCISED_KEY
+    };
+    let total: U256 = read_named_value(total_key);
+    put_named_value(total_key, total.saturating_add(notional));
+    // A strike or expiry's volume is created the first time it is exercised
+    for key in [volume_strike_key(strike_price, option_type), volume_expiry_key(expiry)] {
+        let volume: U256 = read_option_value_or_default(&key);
+        put_named_value(&key, volume.saturating_add(notional));
+    }
+}
+
+/// The `u64` count stored under the named key `key`; 0 if nothing was ever
+/// counted there.
+fn stored_count(key: &str) -> u64 {
+    if runtime::has_key(key) {
+        read_named_value(key)
+    } else {
+        0
+    }
+}
+
+/// The option id in slot `slot` of `state`'s `state_index` bucket.
+fn state_slot(state: u8, slot: u64) -> u64 {
+    let index_uref = named_uref(STATE_INDEX_KEY, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(index_uref, &state_slot_key(state, slot))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+}
+
+/// Puts option `id` in slot `slot` of `state`'s bucket and records the slot
+/// in `option_{id}_state_slot`.
+fn put_state_slot(state: u8, slot: u64, id: u64) {
+    let index_uref = named_uref(STATE_INDEX_KEY, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(index_uref, &state_slot_key(state, slot), id);
+    put_named_value(&option_state_slot_key(id), slot);
+}
+
+/// Appends option `id` to `state`'s bucket. The bucket's length is the
+/// state's count, so each transition costs the same however many options
+/// there are.
+fn index_state(id: u64, state: u8) {
+    let count_uref = named_counter(STATE_COUNT_KEYS[usize::from(state)]);
+    let slot: u64 = read_uref(count_uref);
+    put_state_slot(state, slot, id);
+    increment_u64(count_uref);
+}
+
+/// Removes option `id` from `state`'s bucket by moving the bucket's last id
+/// into its slot. Options that were never indexed are left alone.
+fn unindex_state(id: u64, state: u8) {
+    if !runtime::has_key(&option_state_slot_key(id)) {
+        return;
+    }
+    let count_uref = named_counter(STATE_COUNT_KEYS[usize::from(state)]);
+    let count: u64 = read_uref(count_uref);
+    let Some(last) = count.checked_sub(1) else {
+        return;
+    };
+    let slot: u64 = read_named_value(&option_state_slot_key(id));
+    put_state_slot(state, slot, state_slot(state, last));
+    storage::write(count_uref, last);
+}
+
+/// How many options are in `state`.
+fn state_count(state: u8) -> u64 {
+    stored_count(STATE_COUNT_KEYS[usize::from(state)])
+}
+
+/// Writes `value` to the named URef `name`, creating it if the contract has
+/// no such key yet.
+fn put_named_value<T: CLTyped + ToBytes>(name: &str, value: T) {
+    match runtime::get_key(name) {
+        Some(_) => storage::write(named_uref(name, ErrorCode::MissingNamedKey), value),
+        None => runtime::put_key(name, storage::new_uref(value).into()),
+    }
+}
+
+fn ret<T: CLTyped + ToBytes>(value: T) -> ! {
+    runtime::ret(CLValue::from_t(value).unwrap_or_revert_with(ErrorCode::CLValueConversion))
+}
+
+/// Reverts unless the caller is the current registry admin.
+fn require_admin() {
+    let admin: AccountHash = read_named_value(ADMIN_KEY);
+    if admin != runtime::get_caller() {
+        runtime::revert(ErrorCode::NotAdmin);
+    }
+}
+
+/// Reverts with `NotAdmin` unless the caller is the admin or the account
+/// stored as an `Option<AccountHash>` under `delegate_key`.
+fn require_admin_or_delegate(delegate_key: &str) {
+    let delegate: Option<AccountHash> = read_named_value(delegate_key);
+    if delegate != Some(runtime::get_caller()) {
+        require_admin();
+    }
+}
+
+/// Reverts unless the caller is the writer of option `id`, returning the writer.
+fn require_writer(id: u64) -> AccountHash {
+    let writer: AccountHash = read_option_value(&option_writer_key(id));
+    if writer != runtime::get_caller() {
+        runtime::revert(ErrorCode::NotOptionWriter);
+    }
+    writer
+}
+
+/// Reverts unless the caller is the owner of option `id`.
+fn require_option_owner(id: u64) {
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    if owner != runtime::get_caller() {
+        runtime::revert(ErrorCode::NotOptionOwner);
+    }
+}
+
+/// Reverts if the registry has been deprecated or paused by the admin.
+fn require_not_paused() {
+    let deprecated: bool = read_named_value(DEPRECATED_KEY);
+    if deprecated {
+        runtime::revert(ErrorCode::ContractDeprecated);
+    }
+    require_unpaused();
+}
+
+/// Reverts if the registry is paused by the admin. Paths that only pay
+/// funds out use this instead of `require_not_paused`, so they keep working
+/// once the registry is deprecated.
+fn require_unpaused() {
+    let paused: bool = read_named_value(PAUSED_KEY);
+    if paused {
+        runtime::revert(ErrorCode::ContractPaused);
+    }
+}
+
+/// Reverts with `NotWhitelisted` if the writer whitelist is enabled and the
+/// caller is not on it.
+fn require_whitelisted_writer() {
+    require_whitelisted(&runtime::get_caller());
+}
+
+/// Reverts with `NotWhitelisted` if the writer whitelist is enabled and
+/// `writer` is not on it.
+fn require_whitelisted(writer: &AccountHash) {
+    let enabled: bool = read_named_value(WHITELIST_ENABLED_KEY);
+    if enabled && !account_listed(WRITER_WHITELIST_DICT, writer) {
+        runtime::revert(ErrorCode::NotWhitelisted);
+    }
+}
+
+/// Marks a guarded entry point as running, reverting with `Reentrancy` if one
+/// already is: an oracle or token call made by the outer entry point has
+/// called back into the registry.
+fn enter_guard() {
+    let guard_uref = named_uref(IN_PROGRESS_KEY, ErrorCode::MissingNamedKey);
+    let in_progress: bool = read_uref(guard_uref);
+    if in_progress {
+        runtime::revert(ErrorCode::Reentrancy);
+    }
+    storage::write(guard_uref, true);
+}
+
+/// Clears the flag set by `enter_guard` once every external call is done.
+fn exit_guard() {
+    storage::write(named_uref(IN_PROGRESS_KEY, ErrorCode::MissingNamedKey), false);
+}
+
+/// Current price of the underlying. Once a price publisher has called
+/// `update_oracle_price` this is the cached price, reverting with
+/// `OraclePriceStale` if it is older than the staleness threshold; until
+/// then the oracle configured at install is queried directly.
+fn fetch_oracle_price() -> U256 {
+    let last_update: u64 = read_named_value(LAST_ORACLE_UPDATE_TIME_KEY);
+    if last_update == 0 {
+        let oracle_hash: ContractHash = read_named_value(ORACLE_KEY);
+        return runtime::call_contract::<U256>(
+            oracle_hash,
+            ORACLE_ENTRY_POINT_GET_PRICE,
+            runtime_args! {},
+        );
+    }
+    let threshold: u64 = read_named_value(ORACLE_STALENESS_THRESHOLD_KEY);
+    if current_timestamp().saturating_sub(last_update) > threshold {
+        runtime::revert(ErrorCode::OraclePriceStale);
+    }
+    read_named_value(ORACLE_PRICE_CACHE_KEY)
+}
+
+/// The price a publisher cached with `update_oracle_price`, or `None` if none
+/// was or it is older than the staleness threshold. Unlike
+/// `fetch_oracle_price` this never reverts or calls the oracle.
+fn fresh_cached_oracle_price() -> Option<U256> {
+    let last_update: u64 = read_named_value(LAST_ORACLE_UPDATE_TIME_KEY);
+    let threshold: u64 = read_named_value(ORACLE_STALENESS_THRESHOLD_KEY);
+    if last_update == 0 || current_timestamp().saturating_sub(last_update) > threshold {
+        return None;
+    }
+    Some(read_named_value(ORACLE_PRICE_CACHE_KEY))
+}
+
+/// Cash value of the option at `price`: `price - strike` for a call and
+/// `strike - price` for a put, floored at zero.
+fn intrinsic_value(option_type: bool, strike_price: U256, price: U256) -> U256 {
+    if option_type {
+        price.saturating_sub(strike_price)
+    } else {
+        strike_price.saturating_sub(price)
+    }
+}
+
+/// Whether `price` is within one basis point of `strike_price`.
+fn at_the_money(strike_price: U256, price: U256) -> bool {
+    let distance = price.max(strike_price) - price.min(strike_price);
+    distance.saturating_mul(U256::from(BPS_DENOMINATOR)) <= strike_price
+}
+
+/// `(in_the_money, at_the_money)` for option `id` with the underlying at
+/// `ARG_CURRENT_PRICE`. An option within one basis point of its strike is at
+/// the money rather than in or out of it.
+fn moneyness() -> (bool, bool) {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let option_type: bool = read_option_value(&(option_key(id) + "_type"));
+    
+    let at_the_money = at_the_money(strike_price, price);
+    let pays_out = !intrinsic_value(option_type, strike_price, price).is_zero();
+    (pays_out && !at_the_money, at_the_money)
+}
+
+/// Intrinsic value of all of option `id`'s `option_amount` units, exercised
+/// or not, with the underlying at `ARG_CURRENT_PRICE`. Unsized options count
+/// as one unit.
+fn total_intrinsic_value() -> U256 {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    let key_name = option_key(id);
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let option_type: bool = read_option_value(&(key_name.clone() + "_type"));
+    let amount: U256 = read_option_value(&(key_name + "_amount"));
+    let units = if amount.is_zero() { U256::one() } else { amount };
+    
+    intrinsic_value(option_type, strike_price, price).saturating_mul(units)
+}
+
+/// Cash value of `units` of option `id` with the underlying at `price`.
+/// Unsized options, created without an `option_amount`, are valued as one
+/// unit.
+fn option_payout(id: u64, units: U256, price: U256) -> U512 {
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let option_type: bool = read_option_value(&(option_key(id) + "_type"));
+    let units = if units.is_zero() { U512::one() } else { u256_to_u512(units) };
+    
+    u256_to_u512(intrinsic_value(option_type, strike_price, price)) * units
+}
+
+/// Whether the settlement price finalized for `expiry` can no longer change:
+/// its dispute was resolved, or its dispute window closed undisputed.
+fn settlement_final(expiry: u64) -> bool {
+    if !runtime::has_key(&settlement_price_key(expiry)) {
+        return false;
+    }
+    let disputed: bool = read_named_value(&settlement_disputed_key(expiry));
+    if disputed {
+        return false;
+    }
+    let resolved: bool = read_named_value(&settlement_resolved_key(expiry));
+    let finalized_at: u64 = read_named_value(&settlement_finalized_at_key(expiry));
+    resolved || current_timestamp() > finalized_at.saturating_add(DISPUTE_WINDOW_SECS)
+}
+
+/// Time-weighted average of the snapshot prices over the last
+/// `window_seconds`. Each snapshot's price holds until the next one, or until
+/// now for the newest; a snapshot older than the window counts from the
+/// window's start. Only the newest `MAX_TWAP_SNAPSHOTS` are read. Reverts
+/// with `NoPriceSnapshots` before the first snapshot.
+fn twap(window_seconds: u64) -> U256 {
+    let count: u64 = read_named_value(SNAPSHOT_COUNT_KEY);
+    if count == 0 {
+        runtime::revert(ErrorCode::NoPriceSnapshots);
+    }
+    let now = current_timestamp();
+    let window_start = now.saturating_sub(window_seconds);
+    
+    let mut weighted_sum = U256::zero();
+    let mut total_seconds = 0u64;
+    let mut latest_price = None;
+    let mut segment_end = now;
+    for index in (count.saturating_sub(MAX_TWAP_SNAPSHOTS)..count).rev() {
+        let timestamp: u64 = read_named_value(&price_snapshot_time_key(index));
+        let price: U256 = read_named_value(&price_snapshot_key(timestamp));
+        latest_price.get_or_insert(price);
+        let segment_start = timestamp.max(window_start);
+        if segment_end > segment_start {
+            let seconds = segment_end - segment_start;
+            weighted_sum = weighted_sum.saturating_add(price.saturating_mul(U256::from(seconds)));
+            total_seconds += seconds;
+        }
+        if timestamp <= window_start {
+            break;
+        }
+        segment_end = segment_end.min(timestamp);
+    }
+    
+    // A zero-length window is just the newest snapshot
+    if total_seconds == 0 {
+        return latest_price.unwrap_or_default();
+    }
+    weighted_sum / U256::from(total_seconds)
+}
+
+/// Price option `id` settles at: the settlement price finalized for its
+/// expiry, or, if none was, the TWAP over `twap_window_seconds` when
+/// `use_twap` is set and the current oracle price otherwise. Reverts with
+/// `SettlementPriceNotFinal` while a finalized price can still be disputed.
+fn settlement_price(id: u64) -> U256 {
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let price_key = settlement_price_key(expiry);
+    if !runtime::has_key(&price_key) {
+        let use_twap: bool = read_named_value(USE_TWAP_KEY);
+        if use_twap {
+            return twap(read_named_value(TWAP_WINDOW_KEY));
+        }
+        return fetch_oracle_price();
+    }
+    if !settlement_final(expiry) {
+        runtime::revert(ErrorCode::SettlementPriceNotFinal);
+    }
+    read_named_value(&price_key)
+}
+
+/// Takes up to `payout` out of the collateral still locked for option `id`
+/// and returns the amount taken. For a pool-backed option the amount leaves
+/// the pool, lowering its value along with its utilized collateral; for a
+/// syndicated one it comes out of the syndicators' positions.
+fn draw_collateral(id: u64, payout: U512) -> U512 {
+    let collateral_uref = named_uref(&option_collateral_key(id), ErrorCode::OptionNotFound);
+    let locked: U512 = read_uref(collateral_uref);
+    let drawn = payout.min(locked);
+    storage::write(collateral_uref, locked - drawn);
+    if is_pool_backed(id) {
+        release_pool_collateral(drawn);
+        let value_uref = named_uref(POOL_TOTAL_VALUE_KEY, ErrorCode::MissingNamedKey);
+        let total_value: U512 = read_uref(value_uref);
+        storage::write(value_uref, total_value - drawn);
+    } else if is_syndicated(id) {
+        draw_syndicate_collateral(id, drawn);
+    }
+    drawn
+}
+
+/// Whether option `id` was created by `create_syndicated_option`.
+fn is_syndicated(id: u64) -> bool {
+    runtime::has_key(&option_syndicators_key(id))
+}
+
+fn syndicators(id: u64) -> Vec<AccountHash> {
+    read_option_value(&option_syndicators_key(id))
+}
+
+/// `(share_bps, collateral)` of `member` in syndicated option `id`; zeroes
+/// once it has left.
+fn syndicate_position(id: u64, member: &AccountHash) -> (u64, U512) {
+    let syndicates_uref = named_uref(SYNDICATES_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(syndicates_uref, &syndicate_key(id, member))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_default()
+}
+
+fn set_syndicate_position(id: u64, member: &AccountHash, position: (u64, U512)) {
+    let syndicates_uref = named_uref(SYNDICATES_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(syndicates_uref, &syndicate_key(id, member), position);
+}
+
+/// Returns the caller, reverting with `NotSyndicator` unless it is one of
+/// syndicated option `id`'s syndicators.
+fn require_syndicator(id: u64) -> AccountHash {
+    let caller = runtime::get_caller();
+    if !is_syndicated(id) || !syndicators(id).contains(&caller) {
+        runtime::revert(ErrorCode::NotSyndicator);
+    }
+    caller
+}
+
+/// Whether every syndicator of option `id` has joined with collateral
+/// covering its share of the option's full payout at the strike, and none
+/// has left.
+fn syndicate_funded(id: u64) -> bool {
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let amount: U256 = read_option_value(&(option_key(id) + "_amount"));
+    let notional = u256_to_u512(strike_price.saturating_mul(amount));
+    let mut total_share = 0u64;
+    for member in syndicators(id) {
+        let (share, collateral) = syndicate_position(id, &member);
+        if collateral < notional * U512::from(share) / U512::from(BPS_DENOMINATOR) {
+            return false;
+        }
+        total_share += share;
+    }
+    total_share == BPS_DENOMINATOR
+}
+
+/// Deducts `drawn` from the positions of syndicated option `id`'s remaining
+/// syndicators in proportion to their shares. The rounding remainder comes
+/// out of the first positions that can still cover it.
+fn draw_syndicate_collateral(id: u64, drawn: U512) {
+    let members = syndicators(id);
+    let mut positions: Vec<(u64, U512)> =
+        members.iter().map(|member| syndicate_position(id, member)).collect();
+    let total_share: u64 = positions.iter().map(|(share, _)| share).sum();
+    if total_share == 0 {
+        return;
+    }
+    
+    let mut left = drawn;
+    for (share, collateral) in positions.iter_mut() {
+        let proportional = drawn * U512::from(*share) / U512::from(total_share);
+        let portion = proportional.min(*collateral).min(left);
+        *collateral -= portion;
+        left -= portion;
+    }
+    for (_, collateral) in positions.iter_mut() {
+        let portion = left.min(*collateral);
+        *collateral -= portion;
+        left -= portion;
+    }
+    for (member, position) in members.iter().zip(positions) {
+        set_syndicate_position(id, member, position);
+    }
+}
+
+/// Zeroes `member`'s collateral in syndicated option `id`, taking it out of
+/// the option's locked collateral, and returns the amount.
+fn take_syndicate_collateral(id: u64, member: &AccountHash) -> U512 {
+    let (share, collateral) = syndicate_position(id, member);
+    set_syndicate_position(id, member, (share, U512::zero()));
+    let collateral_uref = named_uref(&option_collateral_key(id), ErrorCode::OptionNotFound);
+    let locked: U512 = read_uref(collateral_uref);
+    storage::write(collateral_uref, locked - collateral);
+    collateral
+}
+
+/// Takes up to `shortfall` out of the insurance fund and returns the amount
+/// taken, so a fund that cannot cover it pays out everything it holds.
+fn draw_insurance_fund(shortfall: U512) -> U512 {
+    let fund_uref = named_uref(INSURANCE_FUND_BALANCE_KEY, ErrorCode::MissingNamedKey);
+    let balance: U512 = read_uref(fund_uref);
+    let drawn = shortfall.min(balance);
+    storage::write(fund_uref, balance - drawn);
+    drawn
+}
+
+/// Whether Active option `id`'s locked collateral covers less than
+/// `option_registry_liquidation_threshold_bps` of what its remaining units
+/// would pay with the underlying at `price`. Options without collateral are
+/// never liquidatable.
+fn liquidatable(id: u64, price: U256) -> bool {
+    let state: u8 = read_option_value(&option_state_key(id));
+    let collateral: U512 = read_option_value(&option_collateral_key(id));
+    if state != OPTION_STATE_ACTIVE || collateral.is_zero() {
+        return false;
+    }
+    let liability = option_payout(id, remaining_amount(id), price);
+    let threshold_bps: u32 = read_named_value(LIQUIDATION_THRESHOLD_BPS_KEY);
+    collateral * U512::from(BPS_DENOMINATOR) < liability * U512::from(threshold_bps)
+}
+
+/// CSPR `account` has contributed to the insurance fund; zero if none.
+fn insurance_contribution(account: &AccountHash) -> U512 {
+    let contributions_uref = named_uref(INSURANCE_CONTRIBUTIONS_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(contributions_uref, &account_hex(account))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_default()
+}
+
+/// Whether option `id` was written by the LP pool.
+fn is_pool_backed(id: u64) -> bool {
+    let writer: AccountHash = read_option_value(&option_writer_key(id));
+    writer == POOL_WRITER
+}
+
+/// Returns `amount` of pool collateral to the pool's free liquidity.
+fn release_pool_collateral(amount: U512) {
+    let utilized_uref = named_uref(POOL_UTILIZED_COLLATERAL_KEY, ErrorCode::MissingNamedKey);
+    let utilized: U512 = read_uref(utilized_uref);
+    storage::write(utilized_uref, utilized - amount);
+}
+
+/// `account`'s LP shares in the pool; zero if it never held any.
+fn lp_balance(account: &AccountHash) -> U512 {
+    let balances_uref = named_uref(LP_BALANCES_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(balances_uref, &account_hex(account))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_default()
+}
+
+fn set_lp_balance(account: &AccountHash, shares: U512) {
+    let balances_uref = named_uref(LP_BALANCES_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(balances_uref, &account_hex(account), shares);
+}
+
+/// Moves `shares` LP shares from `owner` to `recipient`, reverting with
+/// `InsufficientLpShares` if `owner` holds fewer.
+fn move_lp_shares(owner: &AccountHash, recipient: &AccountHash, shares: U512) {
+    let balance = lp_balance(owner);
+    if shares > balance {
+        runtime::revert(ErrorCode::InsufficientLpShares);
+    }
+    set_lp_balance(owner, balance - shares);
+    set_lp_balance(recipient, lp_balance(recipient) + shares);
+}
+
+/// `{owner_hex}_{spender_hex}`, the `lp_allowances` item for the pair.
+fn lp_allowance_key(owner: &AccountHash, spender: &AccountHash) -> String {
+    let mut key = account_hex(owner);
+    key.push('_');
+    key.push_str(&account_hex(spender));
+    key
+}
+
+/// LP shares `spender` may still move out of `owner`'s balance.
+fn lp_allowance(owner: &AccountHash, spender: &AccountHash) -> U512 {
+    let allowances_uref = named_uref(LP_ALLOWANCES_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(allowances_uref, &lp_allowance_key(owner, spender))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_default()
+}
+
+fn set_lp_allowance(owner: &AccountHash, spender: &AccountHash, shares: U512) {
+    let allowances_uref = named_uref(LP_ALLOWANCES_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(allowances_uref, &lp_allowance_key(owner, spender), shares);
+}
+
+/// Deducts the protocol fee from a CSPR settlement `payout`, adding it to
+/// `accumulated_fees` and `total_fees_collected`, and returns what is left
+/// for the owner.
+fn take_fee(payout: U512) -> U512 {
+    let fee_bps: u32 = read_named_value(FEE_BPS_KEY);
+    let fee = payout * U512::from(fee_bps) / U512::from(BPS_DENOMINATOR);
+    collect_fee(fee);
+    payout - fee
+}
+
+/// Adds `fee`, already in the contract purse, to `accumulated_fees` and
+/// `total_fees_collected`.
+fn collect_fee(fee: U512) {
+    if fee.is_zero() {
+        return;
+    }
+    for fees_key in [ACCUMULATED_FEES_KEY, TOTAL_FEES_COLLECTED_KEY] {
+        let fees_uref = named_uref(fees_key, ErrorCode::MissingNamedKey);
+        let fees: U512 = read_uref(fees_uref);
+        storage::write(fees_uref, fees + fee);
+    }
+}
+
+/// The `early_exercise_penalty_bps` share of the `payout` of American option
+/// `id` when it is exercised more than `EARLY_EXERCISE_WINDOW_SECS` before
+/// expiry, and zero otherwise.
+fn early_exercise_penalty(id: u64, payout: U512) -> U512 {
+    let option_style: u8 = read_option_value(&(option_key(id) + "_style"));
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let early = current_timestamp() < expiry.saturating_sub(EARLY_EXERCISE_WINDOW_SECS);
+    if option_style != OPTION_STYLE_AMERICAN || !early {
+        return U512::zero();
+    }
+    let penalty_bps: u32 = read_named_value(EARLY_EXERCISE_PENALTY_BPS_KEY);
+    payout * U512::from(penalty_bps) / U512::from(BPS_DENOMINATOR)
+}
+
+/// Sends `amount` from the contract purse to `recipient`'s main purse.
+fn pay_from_contract_purse(recipient: AccountHash, amount: U512) {
+    if amount.is_zero() {
+        return;
+    }
+    let contract_purse = named_uref(CONTRACT_PURSE_KEY, ErrorCode::MissingNamedKey);
+    system::transfer_from_purse_to_account(contract_purse, recipient, amount, None)
+        .unwrap_or_revert_with(ErrorCode::TransferFailed);
+}
+
+/// Moves option `id`'s underlying CEP-18 tokens from the writer to
+/// `recipient`. The writer must have approved this contract to spend at least
+/// `underlying_amount` on the token.
+fn deliver_underlying(id: u64, token: Key, recipient: AccountHash) {
+    let writer: AccountHash = read_option_value(&option_writer_key(id));
+    let amount: U256 = read_option_value(&(option_key(id) + "_underlying_amount"));
+    runtime::call_contract::<()>(
+        contract_key_hash(token),
+        CEP18_ENTRY_POINT_TRANSFER_FROM,
+        runtime_args! {
+            CEP18_ARG_OWNER => Key::Account(writer),
+            CEP18_ARG_RECIPIENT => Key::Account(recipient),
+            CEP18_ARG_AMOUNT => amount,
+        },
+    );
+}
+
+/// The contract a `Key::Hash` names, as token and DEX arguments do.
+fn contract_key_hash(key: Key) -> ContractHash {
+    key.into_hash_addr()
+        .map(ContractHash::new)
+        .unwrap_or_revert_with(ErrorCode::InvalidParameter)
+}
+
+/// The key CEP-18 tokens credit this contract's balance to.
+fn registry_token_key() -> Key {
+    let contract_hash: ContractHash = read_named_value(CONTRACT_HASH_KEY);
+    Key::Hash(contract_hash.value())
+}
+
+/// The DEX collateral tokens are priced and liquidated against.
+fn collateral_dex() -> ContractHash {
+    let dex: Option<Key> = read_named_value(COLLATERAL_DEX_KEY);
+    dex.map(contract_key_hash).unwrap_or_revert_with(ErrorCode::NoCollateralDex)
+}
+
+/// `(accepted, collateral_factor_bps)` of `token` on the collateral
+/// whitelist; `(false, 0)` if it was never added.
+fn collateral_type(token: Key) -> (bool, u32) {
+    let whitelist_uref = named_uref(COLLATERAL_WHITELIST_DICT, ErrorCode::MissingNamedKey);
+    let token_hash = contract_key_hash(token);
+    storage::dictionary_get(whitelist_uref, &bytes_hex(&token_hash.value()))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or((false, 0))
+}
+
+/// What `amount` of collateral `token` counts for in motes: its DEX value
+/// less the token's haircut. Reverts unless the token is accepted.
+fn token_collateral_value(token: Key, amount: U256) -> U512 {
+    let (accepted, collateral_factor_bps) = collateral_type(token);
+    if !accepted {
+        runtime::revert(ErrorCode::CollateralNotAccepted);
+    }
+    if amount.is_zero() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    let value: U512 = runtime::call_contract(
+        collateral_dex(),
+        DEX_ENTRY_POINT_GET_AMOUNT_OUT,
+        runtime_args! {
+            DEX_ARG_TOKEN => token,
+            DEX_ARG_AMOUNT_IN => amount,
+        },
+    );
+    value * U512::from(collateral_factor_bps) / U512::from(BPS_DENOMINATOR)
+}
+
+/// Pulls `amount` of `token` from `from` into this contract as option `id`'s
+/// token collateral.
+fn lock_token_collateral(id: u64, token: Key, amount: U256, from: AccountHash) {
+    runtime::call_contract::<()>(
+        contract_key_hash(token),
+        CEP18_ENTRY_POINT_TRANSFER_FROM,
+        runtime_args! {
+            CEP18_ARG_OWNER => Key::Account(from),
+            CEP18_ARG_RECIPIENT => registry_token_key(),
+            CEP18_ARG_AMOUNT => amount,
+        },
+    );
+    record_token_collateral(id, token, amount);
+}
+
+/// Records `amount` of `token`, already held by this contract, as option
+/// `id`'s token collateral.
+fn record_token_collateral(id: u64, token: Key, amount: U256) {
+    runtime::put_key(&option_collateral_token_key(id), storage::new_uref(token).into());
+    runtime::put_key(&option_token_collateral_key(id), storage::new_uref(amount).into());
+}
+
+fn send_tokens(token: Key, recipient: AccountHash, amount: U256) {
+    runtime::call_contract::<()>(
+        contract_key_hash(token),
+        CEP18_ENTRY_POINT_TRANSFER,
+        runtime_args! {
+            CEP18_ARG_RECIPIENT => Key::Account(recipient),
+            CEP18_ARG_AMOUNT => amount,
+        },
+    );
+}
+
+/// Covers as much of `payout` as option `id`'s token collateral can: the
+/// DEX prices how many tokens `payout` is worth, and up to that many are
+/// sent to `recipient`. Returns the part of `payout` the tokens covered;
+/// options without token collateral cover nothing.
+fn liquidate_token_collateral(id: u64, recipient: AccountHash, payout: U512) -> U512 {
+    let token_key = option_collateral_token_key(id);
+    if payout.is_zero() || !runtime::has_key(&token_key) {
+        return U512::zero();
+    }
+    let token: Key = read_named_value(&token_key);
+    let locked_uref = named_uref(&option_token_collateral_key(id), ErrorCode::OptionNotFound);
+    let locked: U256 = read_uref(locked_uref);
+    let dex = collateral_dex();
+    let needed: U256 = runtime::call_contract(
+        dex,
+        DEX_ENTRY_POINT_GET_AMOUNT_IN,
+        runtime_args! {
+            DEX_ARG_TOKEN => token,
+            DEX_ARG_AMOUNT_OUT => payout,
+        },
+    );
+    
+    let sold = needed.min(locked);
+    let covered = if sold == needed {
+        payout
+    } else {
+        runtime::call_contract(
+            dex,
+            DEX_ENTRY_POINT_GET_AMOUNT_OUT,
+            runtime_args! {
+                DEX_ARG_TOKEN => token,
+                DEX_ARG_AMOUNT_IN => sold,
+            },
+        )
+    };
+    storage::write(locked_uref, locked - sold);
+    if !sold.is_zero() {
+        send_tokens(token, recipient, sold);
+    }
+    covered.min(payout)
+}
+
+/// Sends the writer whatever token collateral option `id` still holds.
+fn release_token_collateral(id: u64, writer: AccountHash) {
+    let token_key = option_collateral_token_key(id);
+    if !runtime::has_key(&token_key) {
+        return;
+    }
+    let token: Key = read_named_value(&token_key);
+    let locked_uref = named_uref(&option_token_collateral_key(id), ErrorCode::OptionNotFound);
+    let locked: U256 = read_uref(locked_uref);
+    storage::write(locked_uref, U256::zero());
+    if !locked.is_zero() {
+        send_tokens(token, writer, locked);
+    }
+}
+
+/// The CEP-78 collection options are minted into, if one was configured at
+/// install.
+fn nft_contract() -> Option<ContractHash> {
+    let nft_contract: Option<Key> = read_named_value(NFT_CONTRACT_KEY);
+    nft_contract.map(contract_key_hash)
+}
+
+/// CEP-78 token metadata for an option:
+/// `{"id":"..","strike":"..","expiry":"..","type":"call"|"put"}`.
+fn nft_metadata(id: u64, strike_price: U256, expiry: u64, option_type: bool) -> String {
+    let mut metadata = String::from("{\"id\":\"");
+    metadata.push_str(&id.to_string());
+    metadata.push_str("\",\"strike\":\"");
+    metadata.push_str(&strike_price.to_string());
+    metadata.push_str("\",\"expiry\":\"");
+    metadata.push_str(&expiry.to_string());
+    metadata.push_str("\",\"type\":\"");
+    metadata.push_str(if option_type { "call" } else { "put" });
+    metadata.push_str("\"}");
+    metadata
+}
+
+/// Mints option `id`'s ownership token to `owner` when an NFT collection is
+/// configured, recording the token id under `option_{id}_nft_token_id`.
+fn mint_option_nft(id: u64, owner: AccountHash) {
+    let Some(nft_contract) = nft_contract() else {
+        return;
+    };
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let option_type: bool = read_option_value(&(option_key(id) + "_type"));
+    
+    // CEP-78 returns the receipt name, the owned tokens dictionary and the token id
+    let (_, _, token_id): (String, Key, String) = runtime::call_contract(
+        nft_contract,
+        CEP78_ENTRY_POINT_MINT,
+        runtime_args! {
+            CEP78_ARG_TOKEN_OWNER => Key::Account(owner),
+            CEP78_ARG_TOKEN_META_DATA => nft_metadata(id, strike_price, expiry, option_type),
+        },
+    );
+    let token_id: u64 = token_id.parse().ok().unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    runtime::put_key(&option_nft_token_id_key(id), storage::new_uref(token_id).into());
+}
+
+/// Mints option `id`'s units to `owner` in its class's option token, if
+/// `deploy_option_token` has deployed one, and records the token against the
+/// option. Unsized options get no tokens.
+fn mint_option_tokens(id: u64, owner: AccountHash) {
+    let amount: U256 = read_option_value(&(option_key(id) + "_amount"));
+    let class_key = option_token_class_key(
+        read_option_value(&(option_key(id) + "_underlying")),
+        read_option_field(OPTIONS_STRIKE_DICT, id),
+        read_option_field(OPTIONS_EXPIRY_DICT, id),
+        read_option_value(&(option_key(id) + "_type")),
+    );
+    let Some(token) = runtime::get_key(&class_key) else {
+        return;
+    };
+    if amount.is_zero() {
+        return;
+    }
+    put_named_value(&option_token_key(id), token);
+    option_token::mint_to(contract_key_hash(token), owner, amount);
+}
+
+/// Moves option `id`'s unexercised units in its option token, if it has one,
+/// from `owner` to `new_owner`, reverting with `InsufficientOptionTokens` if
+/// `owner` holds fewer. Only the owner exercises, so its tokens follow it.
+fn move_option_tokens(id: u64, owner: AccountHash, new_owner: AccountHash) {
+    if !runtime::has_key(&option_token_key(id)) {
+        return;
+    }
+    let token: Key = read_named_value(&option_token_key(id));
+    let units = remaining_amount(id);
+    option_token::burn_from(contract_key_hash(token), owner, units);
+    option_token::mint_to(contract_key_hash(token), new_owner, units);
+}
+
+/// Deploys the CEP-18 option token for options on `underlying_token` (cash
+/// settled when absent) struck at `strike_price` and expiring at `expiry`,
+/// of `option_type`, and stores its hash under
+/// `token_{underlying}_{strike}_{expiry}_{call|put}`. Options of that class
+/// created afterwards mint their units in it, and exercising them burns the
+/// exercised units from the option's owner (admin only).
+#[no_mangle]
+pub extern "C" fn deploy_option_token() {
+    require_admin();
+    
+    let underlying: Option<Key> = runtime::try_get_named_arg(ARG_UNDERLYING_TOKEN);
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    let class_key = option_token_class_key(underlying, strike_price, expiry, option_type);
+    if runtime::has_key(&class_key) {
+        runtime::revert(ErrorCode::OptionTokenExists);
+    }
+    
+    let token_hash = option_token::deploy(class_key.clone(), registry_token_key());
+    runtime::put_key(&class_key, Key::Hash(token_hash.value()));
+}
+
+/// Returns the option token deployed for the class, if any.
+#[no_mangle]
+pub extern "C" fn get_option_token_address() {
+    let underlying: Option<Key> = runtime::try_get_named_arg(ARG_UNDERLYING_TOKEN);
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    ret(runtime::get_key(&option_token_class_key(underlying, strike_price, expiry, option_type)));
+}
+
+/// Option `id`'s ownership token id; `None` if none was minted for it.
+fn option_nft_token_id(id: u64) -> Option<u64> {
+    let key_name = option_nft_token_id_key(id);
+    if runtime::has_key(&key_name) {
+        Some(read_named_value(&key_name))
+    } else {
+        None
+    }
+}
+
+/// Moves option `id`'s ownership token, if it has one, from `owner` to
+/// `new_owner`.
+fn transfer_option_nft(id: u64, owner: AccountHash, new_owner: AccountHash) {
+    let (Some(nft_contract), Some(token_id)) = (nft_contract(), option_nft_token_id(id)) else {
+        return;
+    };
+    runtime::call_contract::<(String, Key)>(
+        nft_contract,
+        CEP78_ENTRY_POINT_TRANSFER,
+        runtime_args! {
+            CEP78_ARG_TOKEN_ID => token_id,
+            CEP78_ARG_SOURCE_KEY => Key::Account(owner),
+            CEP78_ARG_TARGET_KEY => Key::Account(new_owner),
+        },
+    );
+}
+
+/// Burns option `id`'s ownership token, if it has one.
+fn burn_option_nft(id: u64) {
+    let (Some(nft_contract), Some(token_id)) = (nft_contract(), option_nft_token_id(id)) else {
+        return;
+    };
+    runtime::call_contract::<()>(
+        nft_contract,
+        CEP78_ENTRY_POINT_BURN,
+        runtime_args! {
+            CEP78_ARG_TOKEN_ID => token_id,
+        },
+    );
+}
+
+/// Returns the CEP-78 token id representing option `id`, or `None` if no
+/// token was minted for it.
+#[no_mangle]
+pub extern "C" fn get_option_nft_token_id() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    ret(option_nft_token_id(id));
+}
+
+/// Stores `metadata_uri` as option `id`'s metadata link, reverting with
+/// `MetadataTooLong` past `MAX_METADATA_URI_LEN` characters.
+fn store_metadata_uri(id: u64, metadata_uri: String) {
+    if metadata_uri.chars().count() > MAX_METADATA_URI_LEN {
+        runtime::revert(ErrorCode::MetadataTooLong);
+    }
+    put_named_value(&option_metadata_uri_key(id), metadata_uri);
+}
+
+/// Replaces option `id`'s metadata URI. Callable by the option's owner or
+/// the admin.
+#[no_mangle]
+pub extern "C" fn set_metadata_uri() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let metadata_uri: String = runtime::get_named_arg(ARG_METADATA_URI);
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    let admin: AccountHash = read_named_value(ADMIN_KEY);
+    let caller = runtime::get_caller();
+    if caller != owner && caller != admin {
+        runtime::revert(ErrorCode::NotOptionOwner);
+    }
+    store_metadata_uri(id, metadata_uri);
+}
+
+/// Returns option `id`'s metadata URI, or `None` if it has none.
+#[no_mangle]
+pub extern "C" fn get_metadata_uri() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    let key_name = option_metadata_uri_key(id);
+    let metadata_uri: Option<String> = if runtime::has_key(&key_name) {
+        Some(read_named_value(&key_name))
+    } else {
+        None
+    };
+    ret(metadata_uri);
+}
+
+/// Widens a `U256` amount to the `U512` used for CSPR motes.
+fn u256_to_u512(value: U256) -> U512 {
+    let mut bytes = [0u8; 32];
+    value.to_little_endian(&mut bytes);
+    U512::from_little_endian(&bytes)
+}
+
+/// Narrows a `U512` amount of motes to `U256`, saturating at `U256::MAX`.
+fn u512_to_u256(value: U512) -> U256 {
+    if value.bits() > 256 {
+        return U256::MAX;
+    }
+    let mut bytes = [0u8; 64];
+    value.to_little_endian(&mut bytes);
+    U256::from_little_endian(&bytes[..32])
+}
+
+/// Moves the `amount` argument from the caller-supplied `purse` into the
+/// contract purse and returns the amount received. Casper has no native
+/// attached value for stored contract calls, so this stands in for it.
+fn transferred_value() -> U512 {
+    let source: URef = runtime::get_named_arg(ARG_PURSE);
+    let amount: U512 = runtime::get_named_arg(ARG_AMOUNT);
+    let contract_purse = named_uref(CONTRACT_PURSE_KEY, ErrorCode::MissingNamedKey);
+    system::transfer_from_purse_to_purse(source, contract_purse, amount, None)
+        .unwrap_or_revert_with(ErrorCode::TransferFailed);
+    amount
+}
+
+/// Returns true once `now` is past the last moment the option can be exercised.
+fn exercise_window_closed(expiry: u64, option_style: u8, now: u64) -> bool {
+    if option_style == OPTION_STYLE_EUROPEAN {
+        now >= expiry.saturating_add(EUROPEAN_EXERCISE_WINDOW_SECS)
+    } else {
+        now > expiry
+    }
+}
+
+/// Per-option parameters supplied at creation.
+struct OptionTerms {
+    strike_price: U256,
+    expiry: u64,
+    option_type: bool,
+    option_style: u8,
+    activation: u64,
+    premium: U512,
+    /// Units of the underlying; zero for an unsized option.
+    amount: U256,
+    /// CSPR locked by the writer, already moved into the contract purse.
+    collateral: U512,
+    /// CEP-18 contract delivered on settlement of a call; `None` for cash settlement.
+    underlying: Option<Key>,
+    underlying_amount: U256,
+    series_id: Option<u64>,
+    /// Implied volatility in basis points; zero when unknown.
+    iv: u64,
+    /// Timestamps a Bermudan option can be exercised around; empty otherwise.
+    exercise_dates: Vec<u64>,
+}
+
+/// Where `issue_option` takes a new option's collateral from.
+#[derive(Clone, Copy)]
+enum CollateralSource {
+    /// CSPR paid in with the premium, or out of the writer's permit
+    /// collateral deposit when a relayer submits its permit, and optional
+    /// token collateral pulled from the writer's approval.
+    Paid(Option<(Key, U256)>),
+    /// CSPR and token collateral the contract already holds, moved over
+    /// from a rolled option.
+    Held(U512, Option<(Key, U256)>),
+}
+
+/// Writes all named keys for a new option with `owner` as the buyer and
+/// `writer` the seller. Does not touch `option_count`; callers register the
+/// options they store.
+fn store_option(id: u64, terms: OptionTerms, owner: AccountHash, writer: AccountHash) {
+    let OptionTerms {
+        strike_price,
+        expiry,
+        option_type,
+        option_style,
+        activation,
+        premium,
+        amount,
+        collateral,
+        underlying,
+        underlying_amount,
+        series_id,
+        iv,
+        exercise_dates,
+    } = terms;
+    
+    if activation > expiry {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    if option_style > OPTION_STYLE_BERMUDAN {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    // Bermudan options, and only they, need between one and twelve dates by expiry
+    let bermudan = option_style == OPTION_STYLE_BERMUDAN;
+    if bermudan == exercise_dates.is_empty()
+        || exercise_dates.len() > MAX_EXERCISE_DATES
+        || exercise_dates.iter().any(|&date| date > expiry)
+    {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    if let Some(token) = underlying {
+        if token.into_hash_addr().is_none() || underlying_amount.is_zero() {
+            runtime::revert(ErrorCode::InvalidParameter);
+        }
+    }
+    
+    let key_name = option_key(id);
+    if runtime::get_key(&key_name).is_some() {
+        runtime::revert(ErrorCode::OptionAlreadyExists);
+    }
+    
+    let id_uref = storage::new_uref(id);
+    let type_uref = storage::new_uref(option_type);
+    let style_uref = storage::new_uref(option_style);
+    let activation_uref = storage::new_uref(activation);
+    let premium_uref = storage::new_uref(premium);
+    
+    runtime::put_key(&key_name, id_uref.into());
+    write_option_field(OPTIONS_STRIKE_DICT, id, strike_price);
+    write_option_field(OPTIONS_EXPIRY_DICT, id, expiry);
+    runtime::put_key(&(key_name.clone() + "_type"), type_uref.into());
+    runtime::put_key(&(key_name.clone() + "_style"), style_uref.into());
+    runtime::put_key(&(key_name.clone() + "_activation"), activation_uref.into());
+    runtime::put_key(&(key_name.clone() + "_premium"), premium_uref.into());
+    runtime::put_key(&(key_name.clone() + "_premium_claimed"), storage::new_uref(false).into());
+    runtime::put_key(&(key_name.clone() + "_amount"), storage::new_uref(amount).into());
+    runtime::put_key(
+        &(key_name.clone() + "_exercised_amount"),
+        storage::new_uref(U256::zero()).into(),
+    );
+    runtime::put_key(&(key_name.clone() + "_underlying"), storage::new_uref(underlying).into());
+    runtime::put_key(
+        &(key_name.clone() + "_underlying_amount"),
+        storage::new_uref(underlying_amount).into(),
+    );
+    runtime::put_key(&option_iv_key(id), storage::new_uref(iv).into());
+    runtime::put_key(&option_greeks_stale_key(id), storage::new_uref(false).into());
+    runtime::put_key(&option_created_at_key(id), storage::new_uref(current_timestamp()).into());
+    if bermudan {
+        let date_count = exercise_dates.len() as u64;
+        for (index, date) in (0..date_count).zip(exercise_dates) {
+            put_named_value(&option_exercise_date_key(id, index), date);
+        }
+        put_named_value(&option_exercise_date_count_key(id), date_count);
+    }
+    
+    index_expiry(id, expiry);
+    add_to_expiry_set(expiry);
+    add_to_option_chain(id, expiry, strike_price, option_type);
+    open_open_interest(expiry, strike_price);
+    count_created(expiry, option_type);
+    runtime::put_key(&(key_name.clone() + "_series"), storage::new_uref(series_id).into());
+    if let Some(series_id) = series_id {
+        add_to_series(series_id, id);
+    }
+    
+    let contract_purse = named_uref(CONTRACT_PURSE_KEY, ErrorCode::MissingNamedKey);
+    runtime::put_key(&option_collateral_key(id), storage::new_uref(collateral).into());
+    runtime::put_key(&(key_name.clone() + "_collateral_purse"), contract_purse.into());
+    
+    write_option_field(OPTIONS_EXERCISED_DICT, id, false);
+    runtime::put_key(&option_state_key(id), storage::new_uref(OPTION_STATE_CREATED).into());
+    index_state(id, OPTION_STATE_CREATED);
+    
+    let cancelled_uref = storage::new_uref(false);
+    runtime::put_key(&option_cancelled_key(id), cancelled_uref.into());
+    
+    runtime::put_key(&option_settled_key(id), storage::new_uref(false).into());
+    
+    write_option_field(OPTIONS_OWNER_DICT, id, owner);
+    add_to_portfolio(&owner, id);
+    
+    let writer_uref = storage::new_uref(writer);
+    runtime::put_key(&option_writer_key(id), writer_uref.into());
+}
+
+/// Appends option `id` to the creation-order index and bumps `option_count`.
+fn register_option(id: u64) {
+    let count_uref = named_uref(OPTION_COUNT_KEY, ErrorCode::MissingNamedKey);
+    let index: u64 = read_uref(count_uref);
+    runtime::put_key(&all_options_key(index), storage::new_uref(id).into());
+    increment_u64(count_uref);
+}
+
+/// Appends option `id` to `series_id`, creating the series on first use.
+fn add_to_series(series_id: u64, id: u64) {
+    let count_uref = named_counter(&series_count_key(series_id));
+    let index: u64 = read_uref(count_uref);
+    runtime::put_key(&series_entry_key(series_id, index), storage::new_uref(id).into());
+    increment_u64(count_uref);
+}
+
+/// Appends option `id` to `owner`'s portfolio and records the slot in
+/// `option_{id}_owner_index` so a later transfer can tombstone it.
+fn add_to_portfolio(owner: &AccountHash, id: u64) {
+    let count_uref = named_counter(&owner_count_key(owner));
+    let index: u64 = read_uref(count_uref);
+    runtime::put_key(&owner_entry_key(owner, index), storage::new_uref(Some(id)).into());
+    increment_u64(count_uref);
+    
+    let index_key = option_key(id) + "_owner_index";
+    match runtime::get_key(&index_key) {
+        Some(_) => storage::write(named_uref(&index_key, ErrorCode::OptionNotFound), index),
+        None => runtime::put_key(&index_key, storage::new_uref(index).into()),
+    }
+}
+
+/// Tombstones option `id`'s slot in `owner`'s portfolio.
+fn remove_from_portfolio(owner: &AccountHash, id: u64) {
+    let index: u64 = read_option_value(&(option_key(id) + "_owner_index"));
+    let slot_uref = named_uref(&owner_entry_key(owner, index), ErrorCode::InvalidStorageValue);
+    storage::write(slot_uref, Option::<u64>::None);
+}
+
+/// Number of options in `series_id`; zero for an unknown series.
+fn series_count(series_id: u64) -> u64 {
+    let count_key = series_count_key(series_id);
+    if runtime::get_key(&count_key).is_some() {
+        read_named_value(&count_key)
+    } else {
+        0
+    }
+}
+
+/// Option ids whose expiry falls on `bucket` (days since the unix epoch).
+fn expiry_bucket_options(bucket: u64) -> Vec<u64> {
+    let index_uref = named_uref(EXPIRY_INDEX_KEY, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(index_uref, &bucket.to_string())
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_default()
+}
+
+/// Adds option `id` to the `expiry_index` bucket for `expiry`.
+fn index_expiry(id: u64, expiry: u64) {
+    let bucket = expiry / DAY_IN_SECONDS;
+    let mut ids = expiry_bucket_options(bucket);
+    ids.push(id);
+    let index_uref = named_uref(EXPIRY_INDEX_KEY, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(index_uref, &bucket.to_string(), ids);
+}
+
+/// Appends `expiry` to the `expiry_set_{index}` list of distinct expiries
+/// the first time an option with that expiry is stored.
+fn add_to_expiry_set(expiry: u64) {
+    let members_uref = named_uref(EXPIRY_SET_MEMBERS_DICT, ErrorCode::MissingNamedKey);
+    let seen: Option<bool> = storage::dictionary_get(members_uref, &expiry.to_string())
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    if seen.is_some() {
+        return;
+    }
+    storage::dictionary_put(members_uref, &expiry.to_string(), true);
+    
+    let count_uref = named_uref(EXPIRY_SET_COUNT_KEY, ErrorCode::MissingNamedKey);
+    let index: u64 = read_uref(count_uref);
+    runtime::put_key(&expiry_set_key(index), storage::new_uref(expiry).into());
+    increment_u64(count_uref);
+}
+
+/// Call and put option ids struck at `strike_price` and expiring at
+/// `expiry`, in creation order; `None` before the first such option.
+fn option_chain_entry(expiry: u64, strike_price: U256) -> Option<(Vec<u64>, Vec<u64>)> {
+    let chain_uref = named_uref(OPTION_CHAIN_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(chain_uref, &option_chain_key(expiry, strike_price))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+}
+
+/// Adds option `id` to the calls or puts at its strike in the `option_chain`
+/// for `expiry`, appending the strike to `expiry_{expiry}_strikes_{index}`
+/// the first time it is used at that expiry.
+fn add_to_option_chain(id: u64, expiry: u64, strike_price: U256, option_type: bool) {
+    let (mut calls, mut puts) = match option_chain_entry(expiry, strike_price) {
+        Some(entry) => entry,
+        None => {
+            let count_uref = named_counter(&expiry_strikes_count_key(expiry));
+            let index: u64 = read_uref(count_uref);
+            let strike_uref = storage::new_uref(strike_price);
+            runtime::put_key(&expiry_strike_key(expiry, index), strike_uref.into());
+            increment_u64(count_uref);
+            (Vec::new(), Vec::new())
+        }
+    };
+    if option_type {
+        calls.push(id);
+    } else {
+        puts.push(id);
+    }
+    let chain_uref = named_uref(OPTION_CHAIN_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(chain_uref, &option_chain_key(expiry, strike_price), (calls, puts));
+}
+
+/// Moves option `id` from `old_strike` to `new_strike` in the `option_chain`
+/// for `expiry`.
+fn move_in_option_chain(
+    id: u64,
+    expiry: u64,
+    old_strike: U256,
+    new_strike: U256,
+    option_type: bool,
+) {
+    let (mut calls, mut puts) = option_chain_entry(expiry, old_strike).unwrap_or_default();
+    calls.retain(|&other| other != id);
+    puts.retain(|&other| other != id);
+    let chain_uref = named_uref(OPTION_CHAIN_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(chain_uref, &option_chain_key(expiry, old_strike), (calls, puts));
+    add_to_option_chain(id, expiry, new_strike, option_type);
+}
+
+/// Distinct strikes of options expiring at `expiry`, ascending.
+fn strikes_for_expiry(expiry: u64) -> Vec<U256> {
+    let count_key = expiry_strikes_count_key(expiry);
+    let count: u64 = if runtime::has_key(&count_key) {
+        read_named_value(&count_key)
+    } else {
+        0
+    };
+    let mut strikes: Vec<U256> = (0..count)
+        .map(|index| read_named_value(&expiry_strike_key(expiry, index)))
+        .collect();
+    strikes.sort_unstable();
+    strikes
+}
+
+/// Strike in `[low, high]` at which the open options expiring at `expiry`
+/// would pay their holders least in total, the lowest on a tie; zero when no
+/// strike at that expiry is in range.
+fn max_pain(expiry: u64, low: U256, high: U256) -> U256 {
+    let strikes = strikes_for_expiry(expiry);
+    let mut open_units: Vec<(bool, U256, U512)> = Vec::new();
+    for &strike_price in &strikes {
+        let (calls, puts) = option_chain_entry(expiry, strike_price).unwrap_or_default();
+        for (option_type, ids) in [(true, calls), (false, puts)] {
+            for id in ids {
+                let state: u8 = read_option_value(&option_state_key(id));
+                if !is_open_state(state) {
+                    continue;
+                }
+                let units = remaining_amount(id);
+                let units = if units.is_zero() { U512::one() } else { u256_to_u512(units) };
+                open_units.push((option_type, strike_price, units));
+            }
+        }
+    }
+    
+    let total_payout = |price: U256| -> U512 {
+        open_units
+            .iter()
+            .map(|&(option_type, strike_price, units)| {
+                u256_to_u512(intrinsic_value(option_type, strike_price, price)) * units
+            })
+            .fold(U512::zero(), |total, payout| total + payout)
+    };
+    strikes
+        .into_iter()
+        .filter(|strike_price| (low..=high).contains(strike_price))
+        .map(|strike_price| (total_payout(strike_price), strike_price))
+        .min()
+        .map_or(U256::zero(), |(_, strike_price)| strike_price)
+}
+
+/// Option ids in `series_id` in creation order.
+fn series_options(series_id: u64) -> Vec<u64> {
+    (0..series_count(series_id))
+        .map(|index| read_named_value(&series_entry_key(series_id, index)))
+        .collect()
+}
+
+/// Consumes `nonce` for the caller, reverting with `InvalidNonce` unless it is
+/// above the last nonce the caller used.
+fn consume_nonce(nonce: u64) {
+    let nonces = named_uref(NONCES_DICT, ErrorCode::MissingNamedKey);
+    let key = nonce_key(&runtime::get_caller());
+    let last: Option<u64> = storage::dictionary_get(nonces, &key)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    if last.is_some_and(|last| nonce <= last) {
+        runtime::revert(ErrorCode::InvalidNonce);
+    }
+    storage::dictionary_put(nonces, &key, nonce);
+}
+
+/// Loads at most `limit` ids from positions `offset..` of a `total`-long
+/// list, where `entry` reads the id at a position. Returns the page with
+/// `total` so callers know when to stop paging.
+fn page(total: u64, offset: u64, limit: u64, entry: impl Fn(u64) -> u64) -> (Vec<u64>, u64) {
+    let end = offset.saturating_add(limit).min(total);
+    ((offset..end).map(entry).collect(), total)
+}
+
+#[no_mangle]
+pub extern "C" fn create_option() {
+    require_not_paused();
+    require_whitelisted_writer();
+    
+    // Seller of the option; only a signed permit can name someone else
+    let writer: Option<AccountHash> = runtime::try_get_named_arg(ARG_WRITER);
+    if writer.is_some_and(|writer| writer != runtime::get_caller()) {
+        runtime::revert(ErrorCode::NotOptionWriter);
+    }
+    create_option_for(runtime::get_caller());
+}
+
+/// Creates an option written by `writer` as `create_option` does from the
+/// same arguments, the signer of `signature` being the writer, so a relayer
+/// can submit a writer's pre-signed option. `permit_signature` is the
+/// writer's ed25519 signature over `blake2b` of `(writer, permit_nonce,
+/// permit_deadline, contract_hash)` followed by `permit_terms_bytes()`, made
+/// with the key passed as `signer`. Each of a writer's permit nonces can be used once,
+/// and the permit is rejected after `permit_deadline`. The relayer pays only
+/// the premium; the option's CSPR collateral comes out of what the writer
+/// put up with `deposit_permit_collateral`.
+#[no_mangle]
+pub extern "C" fn create_option_with_permit() {
+    require_not_paused();
+    
+    let signature: [u8; 64] = runtime::get_named_arg(ARG_PERMIT_SIGNATURE);
+    let signer: [u8; 32] = runtime::get_named_arg(ARG_SIGNER);
+    let permit_nonce: u64 = runtime::get_named_arg(ARG_PERMIT_NONCE);
+    let permit_deadline: u64 = runtime::get_named_arg(ARG_PERMIT_DEADLINE);
+    if current_timestamp() > permit_deadline {
+        runtime::revert(ErrorCode::PermitExpired);
+    }
+    
+    let public_key = PublicKey::ed25519_from_bytes(signer)
+        .ok()
+        .unwrap_or_revert_with(ErrorCode::InvalidSignature);
+    let signature = Signature::ed25519(signature)
+        .ok()
+        .unwrap_or_revert_with(ErrorCode::InvalidSignature);
+    let writer = AccountHash::from(&public_key);
+    let contract_hash: ContractHash = read_named_value(CONTRACT_HASH_KEY);
+    let mut message = (writer, permit_nonce, permit_deadline, contract_hash)
+        .to_bytes()
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    message.extend(permit_terms_bytes());
+    if crypto::verify(runtime::blake2b(message), &signature, &public_key).is_err() {
+        runtime::revert(ErrorCode::InvalidSignature);
+    }
+    
+    let permits_uref = named_uref(USED_PERMITS_DICT, ErrorCode::MissingNamedKey);
+    let permit_key = permit_nonce_key(&writer, permit_nonce);
+    let used: bool = storage::dictionary_get(permits_uref, &permit_key)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or(false);
+    if used {
+        runtime::revert(ErrorCode::PermitNonceUsed);
+    }
+    storage::dictionary_put(permits_uref, &permit_key, true);
+    
+    require_whitelisted(&writer);
+    create_option_for(writer);
+}
+
+/// CSPR `writer` has deposited to collateralise options written with its
+/// permits; zero if it never deposited.
+fn permit_collateral(writer: &AccountHash) -> U512 {
+    let deposits_uref = named_uref(PERMIT_COLLATERAL_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(deposits_uref, &account_hex(writer))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_default()
+}
+
+/// Takes `amount` out of `writer`'s permit collateral deposit, reverting with
+/// `InsufficientCollateral` if it holds less.
+fn take_permit_collateral(writer: &AccountHash, amount: U512) {
+    let deposit = permit_collateral(writer);
+    if amount > deposit {
+        runtime::revert(ErrorCode::InsufficientCollateral);
+    }
+    let deposits_uref = named_uref(PERMIT_COLLATERAL_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(deposits_uref, &account_hex(writer), deposit - amount);
+}
+
+/// Adds CSPR, moved in via `purse` and `amount`, to the caller's deposit for
+/// collateralising the options relayers create with its permits.
+#[no_mangle]
+pub extern "C" fn deposit_permit_collateral() {
+    require_not_paused();
+    
+    let writer = runtime::get_caller();
+    let deposit = permit_collateral(&writer) + transferred_value();
+    let deposits_uref = named_uref(PERMIT_COLLATERAL_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(deposits_uref, &account_hex(&writer), deposit);
+}
+
+/// Pays `amount` of the caller's unused permit collateral deposit back to it.
+#[no_mangle]
+pub extern "C" fn withdraw_permit_collateral() {
+    let amount: U512 = runtime::get_named_arg(ARG_AMOUNT);
+    let writer = runtime::get_caller();
+    take_permit_collateral(&writer, amount);
+    pay_from_contract_purse(writer, amount);
+}
+
+/// Serializes every `create_option` argument that shapes the option, absent
+/// ones at their defaults, so a permit's signature covers all of its terms:
+/// `((id, strike_price, expiry, option_type, option_style, exercise_dates,
+/// activation, premium, option_amount, iv), (underlying_token,
+/// underlying_amount, series_id, metadata_uri, underlying_price_at_creation,
+/// collateral_token, collateral_amount, vesting_start, cliff_seconds,
+/// vesting_duration_seconds))`.
+fn permit_terms_bytes() -> Vec<u8> {
+    let terms = (
+        runtime::get_named_arg::<u64>(ARG_ID),
+        runtime::get_named_arg::<U256>(ARG_STRIKE_PRICE),
+        runtime::get_named_arg::<u64>(ARG_EXPIRY),
+        runtime::get_named_arg::<bool>(ARG_OPTION_TYPE),
+        runtime::try_get_named_arg::<u8>(ARG_OPTION_STYLE).unwrap_or(OPTION_STYLE_AMERICAN),
+        runtime::try_get_named_arg::<Vec<u64>>(ARG_EXERCISE_DATES).unwrap_or_default(),
+        runtime::try_get_named_arg::<u64>(ARG_ACTIVATION).unwrap_or(0),
+        runtime::try_get_named_arg::<U512>(ARG_PREMIUM).unwrap_or_default(),
+        runtime::try_get_named_arg::<U256>(ARG_OPTION_AMOUNT).unwrap_or_default(),
+        runtime::try_get_named_arg::<u64>(ARG_IV).unwrap_or(0),
+    );
+    let extras = (
+        runtime::try_get_named_arg::<Key>(ARG_UNDERLYING_TOKEN),
+        runtime::try_get_named_arg::<U256>(ARG_UNDERLYING_AMOUNT).unwrap_or_default(),
+        runtime::try_get_named_arg::<u64>(ARG_SERIES_ID),
+        runtime::try_get_named_arg::<String>(ARG_METADATA_URI),
+        runtime::try_get_named_arg::<U256>(ARG_UNDERLYING_PRICE_AT_CREATION),
+        runtime::try_get_named_arg::<Key>(ARG_COLLATERAL_TOKEN),
+        runtime::try_get_named_arg::<U256>(ARG_COLLATERAL_AMOUNT).unwrap_or_default(),
+        runtime::try_get_named_arg::<u64>(ARG_VESTING_START),
+        runtime::try_get_named_arg::<u64>(ARG_CLIFF_SECONDS).unwrap_or(0),
+        runtime::try_get_named_arg::<u64>(ARG_VESTING_DURATION_SECONDS).unwrap_or(0),
+    );
+    (terms, extras).to_bytes().unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+}
+
+/// Creates the option described by `create_option`'s arguments, owned by the
+/// caller and written by `writer`.
+fn create_option_for(writer: AccountHash) {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    // true = Call, false = Put
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    // 0 = European, 1 = American, 2 = Bermudan; defaults to American
+    let option_style: u8 = runtime::try_get_named_arg(ARG_OPTION_STYLE).unwrap_or(OPTION_STYLE_AMERICAN);
+    // When a Bermudan option can be exercised, give or take an hour
+    let exercise_dates: Vec<u64> =
+        runtime::try_get_named_arg(ARG_EXERCISE_DATES).unwrap_or_default();
+    // Earliest exercise timestamp; 0 means exercisable immediately
+    let activation: u64 = runtime::try_get_named_arg(ARG_ACTIVATION).unwrap_or(0);
+    // Escrowed until the writer claims it; zero means no payment is expected
+    let premium: U512 = runtime::try_get_named_arg(ARG_PREMIUM).unwrap_or_default();
+    // Units of the underlying; zero leaves the option unsized and uncollateralised
+    let amount: U256 = runtime::try_get_named_arg(ARG_OPTION_AMOUNT).unwrap_or_default();
+    // CEP-18 token a call delivers on settlement; cash-settled when absent
+    let underlying: Option<Key> = runtime::try_get_named_arg(ARG_UNDERLYING_TOKEN);
+    let underlying_amount: U256 = runtime::try_get_named_arg(ARG_UNDERLYING_AMOUNT).unwrap_or_default();
+    // Groups related options; none by default
+    let series_id: Option<u64> = runtime::try_get_named_arg(ARG_SERIES_ID);
+    // Implied volatility in basis points (5000 = 50%); the surface's by default
+    let mut iv: u64 = runtime::try_get_named_arg(ARG_IV).unwrap_or(0);
+    // Link to off-chain terms or documents; none by default
+    let metadata_uri: Option<String> = runtime::try_get_named_arg(ARG_METADATA_URI);
+    // Whitelisted CEP-18 token locked alongside or instead of CSPR; none by default
+    let collateral_token: Option<Key> = runtime::try_get_named_arg(ARG_COLLATERAL_TOKEN);
+    let collateral_amount: U256 =
+        runtime::try_get_named_arg(ARG_COLLATERAL_AMOUNT).unwrap_or_default();
+    // Units vest linearly over the duration once the cliff after the start passes
+    let vesting_start: Option<u64> = runtime::try_get_named_arg(ARG_VESTING_START);
+    let cliff_seconds: u64 = runtime::try_get_named_arg(ARG_CLIFF_SECONDS).unwrap_or(0);
+    let vesting_duration: u64 =
+        runtime::try_get_named_arg(ARG_VESTING_DURATION_SECONDS).unwrap_or(0);
+    if vesting_start.is_some() && amount.is_zero() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    if iv == 0 {
+        iv = vol_surface_point(strike_price, expiry);
+    }
+    
+    let terms = OptionTerms {
+        strike_price,
+        expiry,
+        option_type,
+        option_style,
+        activation,
+        premium,
+        amount,
+        collateral: U512::zero(),
+        underlying,
+        underlying_amount,
+        series_id,
+        iv,
+        exercise_dates,
+    };
+    let token_collateral = collateral_token.map(|token| (token, collateral_amount));
+    issue_option(id, terms, writer, CollateralSource::Paid(token_collateral));
+    if let Some(vesting_start) = vesting_start {
+        put_named_value(&option_vesting_key(id), (vesting_start, cliff_seconds, vesting_duration));
+    }
+    if let Some(metadata_uri) = metadata_uri {
+        store_metadata_uri(id, metadata_uri);
+    }
+}
+
+/// Validates and stores option `id` with `terms`, owned by the caller and
+/// written by `writer`, as `create_option`, `clone_option` and
+/// `rollover_option` do: checks the premium against the market while price
+/// validation is on, takes the premium from `purse` and `strike * amount`
+/// collateral, less what its token collateral covers, from `collateral`,
+/// enforces `max_notional_per_account`, consumes
+/// the caller's `nonce`, records the entry price and mints the option's
+/// tokens and NFT.
+fn issue_option(
+    id: u64,
+    mut terms: OptionTerms,
+    writer: AccountHash,
+    collateral: CollateralSource,
+) {
+    // Must exceed the caller's previous nonce so a replayed deploy is rejected
+    let nonce: u64 = runtime::get_named_arg(ARG_NONCE);
+    // Underlying price the option was bought at; by default a fresh published
+    // price, or zero, as a stale oracle mustn't block creation
+    let entry_price: U256 = runtime::try_get_named_arg(ARG_UNDERLYING_PRICE_AT_CREATION)
+        .or_else(fresh_cached_oracle_price)
+        .unwrap_or_default();
+    let OptionTerms { strike_price, expiry, option_type, premium, amount, .. } = terms;
+    let validate_price: bool = read_named_value(PRICE_VALIDATION_ENABLED_KEY);
+    if validate_price {
+        require_market_premium(premium, option_type, strike_price, expiry, amount);
+    }
+    
+    // Both types lock strike * amount, which covers a put's whole payout
+    let mut required_collateral = u256_to_u512(strike_price) * u256_to_u512(amount);
+    let (CollateralSource::Paid(token_collateral) | CollateralSource::Held(_, token_collateral)) =
+        collateral;
+    // Token collateral covers its haircut value of that and CSPR the rest
+    if let Some((token, collateral_amount)) = token_collateral {
+        let token_value = token_collateral_value(token, collateral_amount);
+        required_collateral = required_collateral.saturating_sub(token_value);
+    }
+    
+    // The premium is escrowed exactly; anything paid on top is the writer's
+    // collateral. A relayer pays only the premium, and the writer's deposit
+    // the collateral, as does the owner rolling an option over
+    terms.collateral = if let CollateralSource::Held(held, _) = collateral {
+        if held < required_collateral {
+            runtime::revert(ErrorCode::InsufficientCollateral);
+        }
+        if !premium.is_zero() && transferred_value() != premium {
+            runtime::revert(ErrorCode::IncorrectPremium);
+        }
+        held
+    } else if writer != runtime::get_caller() {
+        take_permit_collateral(&writer, required_collateral);
+        if !premium.is_zero() && transferred_value() != premium {
+            runtime::revert(ErrorCode::IncorrectPremium);
+        }
+        required_collateral
+    } else if premium.is_zero() && required_collateral.is_zero() {
+        U512::zero()
+    } else {
+        let received = transferred_value();
+        if received < premium || (required_collateral.is_zero() && received != premium) {
+            runtime::revert(ErrorCode::IncorrectPremium);
+        }
+        let collateral = received - premium;
+        if collateral < required_collateral {
+            runtime::revert(ErrorCode::InsufficientCollateral);
+        }
+        collateral
+    };
+    
+    // The notional of every open option the writer wrote counts towards the limit
+    count_notional(id, writer, strike_price, amount);
+    
+    consume_nonce(nonce);
+    let old_count: u64 = read_named_value(OPTION_COUNT_KEY);
+    store_option(id, terms, runtime::get_caller(), writer);
+    register_option(id);
+    
+    // Post-condition: exactly one option was registered
+    let new_count: u64 = read_named_value(OPTION_COUNT_KEY);
+    if old_count.checked_add(1) != Some(new_count) {
+        runtime::revert(ErrorCode::CountInvariantViolated);
+    }
+    put_named_value(&option_entry_price_key(id), entry_price);
+    // The writer approves token collateral, so a permit's signer funds it
+    match collateral {
+        CollateralSource::Paid(Some((token, collateral_amount))) => {
+            lock_token_collateral(id, token, collateral_amount, writer);
+        }
+        CollateralSource::Held(_, Some((token, collateral_amount))) => {
+            record_token_collateral(id, token, collateral_amount);
+        }
+        _ => {}
+    }
+    mint_option_tokens(id, runtime::get_caller());
+    mint_option_nft(id, runtime::get_caller());
+    events::emit(EventType::OptionCreated, id);
+}
+
+/// Reverts with `PremiumDeviationTooHigh` unless `premium` is within
+/// `max_price_deviation_bps` of the option's theoretical premium: its
+/// Black-Scholes value per unit, puts through put-call parity, at the oracle
+/// price and the volatility surface's implied volatility, times its units.
+/// The surface is used even when the caller passes `iv`, so the check can't
+/// be talked down.
+fn require_market_premium(
+    premium: U512,
+    option_type: bool,
+    strike_price: U256,
+    expiry: u64,
+    amount: U256,
+) {
+    let spot = fetch_oracle_price();
+    let iv = vol_surface_point(strike_price, expiry);
+    let seconds_to_expiry = expiry.saturating_sub(current_timestamp());
+    let unit_price = if option_type {
+        pricing::call_price(spot, strike_price, iv, seconds_to_expiry)
+    } else {
+        pricing::put_price(spot, strike_price, iv, seconds_to_expiry)
+    };
+    let units = if amount.is_zero() { U256::one() } else { amount };
+    let theoretical = u256_to_u512(unit_price) * u256_to_u512(units);
+    
+    let deviation = if premium > theoretical {
+        premium - theoretical
+    } else {
+        theoretical - premium
+    };
+    let max_deviation_bps: u32 = read_named_value(MAX_PRICE_DEVIATION_BPS_KEY);
+    if deviation * U512::from(BPS_DENOMINATOR) > theoretical * U512::from(max_deviation_bps) {
+        runtime::revert(ErrorCode::PremiumDeviationTooHigh);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn create_option_batch() {
+    require_not_paused();
+    require_whitelisted_writer();
+    
+    let ids: Vec<u64> = runtime::get_named_arg(ARG_IDS);
+    let strike_prices: Vec<U256> = runtime::get_named_arg(ARG_STRIKE_PRICES);
+    let expiries: Vec<u64> = runtime::get_named_arg(ARG_EXPIRIES);
+    // Applies to every option in the batch
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    
+    if ids.len() != strike_prices.len() || ids.len() != expiries.len() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    let old_count: u64 = read_named_value(OPTION_COUNT_KEY);
+    let created = ids.len() as u64;
+    for ((id, strike_price), expiry) in ids.into_iter().zip(strike_prices).zip(expiries) {
+        create_plain_option(id, strike_price, expiry, option_type);
+    }
+    
+    // Post-condition: exactly one option was registered per id
+    let new_count: u64 = read_named_value(OPTION_COUNT_KEY);
+    if old_count.checked_add(created) != Some(new_count) {
+        runtime::revert(ErrorCode::CountInvariantViolated);
+    }
+}
+
+/// Creates an unsized, uncollateralised American option without premium,
+/// written and owned by the caller, as `create_option_batch` and
+/// `create_straddle` do for each option.
+fn create_plain_option(id: u64, strike_price: U256, expiry: u64, option_type: bool) {
+    let terms = OptionTerms {
+        strike_price,
+        expiry,
+        option_type,
+        option_style: OPTION_STYLE_AMERICAN,
+        activation: 0,
+        premium: U512::zero(),
+        amount: U256::zero(),
+        collateral: U512::zero(),
+        underlying: None,
+        underlying_amount: U256::zero(),
+        series_id: None,
+        iv: 0,
+        exercise_dates: Vec::new(),
+    };
+    count_notional(id, runtime::get_caller(), strike_price, U256::zero());
+    store_option(id, terms, runtime::get_caller(), runtime::get_caller());
+    register_option(id);
+    events::emit(EventType::OptionCreated, id);
+}
+
+/// Creates option `new_id` with option `source_id`'s terms, replacing its
+/// strike with `new_strike` and its expiry with `new_expiry` where given,
+/// written and owned by the caller. `new_strike` must be non-zero and
+/// `new_expiry` in the future. The premium is not copied and neither is
+/// collateral: the clone takes the optional `premium`, `nonce` and
+/// `underlying_price_at_creation` and is validated, collateralised and
+/// minted as `create_option` does.
+#[no_mangle]
+pub extern "C" fn clone_option() {
+    require_not_paused();
+    require_whitelisted_writer();
+    
+    let source_id: u64 = runtime::get_named_arg(ARG_SOURCE_ID);
+    let new_id: u64 = runtime::get_named_arg(ARG_NEW_ID);
+    let new_strike: Option<U256> = runtime::try_get_named_arg(ARG_NEW_STRIKE).flatten();
+    let new_expiry: Option<u64> = runtime::try_get_named_arg(ARG_NEW_EXPIRY).flatten();
+    let premium: U512 = runtime::try_get_named_arg(ARG_PREMIUM).unwrap_or_default();
+    require_option_exists(source_id);
+    if new_strike.is_some_and(|strike| strike.is_zero())
+        || new_expiry.is_some_and(|expiry| expiry <= current_timestamp())
+    {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    let terms = OptionTerms {
+        strike_price: new_strike
+            .unwrap_or_else(|| read_option_field(OPTIONS_STRIKE_DICT, source_id)),
+        expiry: new_expiry.unwrap_or_else(|| read_option_field(OPTIONS_EXPIRY_DICT, source_id)),
+        option_type: read_option_value(&(option_key(source_id) + "_type")),
+        option_style: read_option_value(&(option_key(source_id) + "_style")),
+        activation: read_option_value(&(option_key(source_id) + "_activation")),
+        premium,
+        amount: read_option_value(&(option_key(source_id) + "_amount")),
+        collateral: U512::zero(),
+        underlying: read_option_value(&(option_key(source_id) + "_underlying")),
+        underlying_amount: read_option_value(&(option_key(source_id) + "_underlying_amount")),
+        series_id: read_option_value(&(option_key(source_id) + "_series")),
+        iv: read_option_value(&option_iv_key(source_id)),
+        exercise_dates: (0..stored_count(&option_exercise_date_count_key(source_id)))
+            .map(|index| read_named_value(&option_exercise_date_key(source_id, index)))
+            .collect(),
+    };
+    issue_option(new_id, terms, runtime::get_caller(), CollateralSource::Paid(None));
+}
+
+/// `(call_id, put_id)` of straddle `straddle_id`.
+fn straddle_legs(straddle_id: u64) -> (u64, u64) {
+    read_uref(named_uref(&straddle_key(straddle_id), ErrorCode::StraddleNotFound))
+}
+
+/// Creates a straddle: call `call_id` and put `put_id`, both struck at
+/// `strike_price` and expiring at `expiry`, created as by
+/// `create_option_batch` and linked under the next straddle id, which is
+/// returned.
+#[no_mangle]
+pub extern "C" fn create_straddle() {
+    require_not_paused();
+    require_whitelisted_writer();
+    
+    let call_id: u64 = runtime::get_named_arg(ARG_CALL_ID);
+    let put_id: u64 = runtime::get_named_arg(ARG_PUT_ID);
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    
+    create_plain_option(call_id, strike_price, expiry, true);
+    create_plain_option(put_id, strike_price, expiry, false);
+    
+    let count_uref = named_counter(STRADDLE_COUNT_KEY);
+    let straddle_id: u64 = read_uref(count_uref);
+    runtime::put_key(&straddle_key(straddle_id), storage::new_uref((call_id, put_id)).into());
+    increment_u64(count_uref);
+    ret(straddle_id);
+}
+
+/// Returns `(call_id, put_id)`, the legs of straddle `straddle_id`.
+#[no_mangle]
+pub extern "C" fn get_straddle() {
+    let straddle_id: u64 = runtime::get_named_arg(ARG_STRADDLE_ID);
+    ret(straddle_legs(straddle_id));
+}
+
+/// Cancels both legs of straddle `straddle_id`; if either cannot be
+/// cancelled, neither is.
+#[no_mangle]
+pub extern "C" fn cancel_straddle() {
+    require_not_paused();
+    
+    let straddle_id: u64 = runtime::get_named_arg(ARG_STRADDLE_ID);
+    let (call_id, put_id) = straddle_legs(straddle_id);
+    cancel(call_id);
+    cancel(put_id);
+}
+
+/// Creates options `base_id`, `base_id + 1` and `base_id + 2` as by
+/// `create_option_batch`, `puts` puts followed by calls, all struck at
+/// `ARG_STRIKE` and expiring at `ARG_EXPIRY`, and records them as a bundle of
+/// `bundle_type` under `base_id`.
+fn create_bundle(bundle_type: u8, puts: u64) {
+    require_not_paused();
+    require_whitelisted_writer();
+    
+    let base_id: u64 = runtime::get_named_arg(ARG_BASE_ID);
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    base_id.checked_add(2).unwrap_or_revert_with(ErrorCode::InvalidParameter);
+    
+    for offset in 0..3 {
+        create_plain_option(base_id + offset, strike_price, expiry, offset >= puts);
+    }
+    put_named_value(&(bundle_key(base_id) + "_type"), bundle_type);
+    put_named_value(&(bundle_key(base_id) + "_count"), 3u8);
+}
+
+/// Option ids in the bundle created under `base_id`.
+fn bundle_options(base_id: u64) -> Vec<u64> {
+    let count_key = bundle_key(base_id) + "_count";
+    if !runtime::has_key(&count_key) {
+        runtime::revert(ErrorCode::BundleNotFound);
+    }
+    let count: u8 = read_named_value(&count_key);
+    (0..u64::from(count)).map(|offset| base_id + offset).collect()
+}
+
+/// Creates a strip: puts `base_id` and `base_id + 1` and call `base_id + 2`,
+/// all struck at `strike` and expiring at `expiry`.
+#[no_mangle]
+pub extern "C" fn create_strip() {
+    create_bundle(BUNDLE_TYPE_STRIP, 2);
+}
+
+/// Creates a strap: put `base_id` and calls `base_id + 1` and `base_id + 2`,
+/// all struck at `strike` and expiring at `expiry`.
+#[no_mangle]
+pub extern "C" fn create_strap() {
+    create_bundle(BUNDLE_TYPE_STRAP, 1);
+}
+
+/// Returns the option ids in the strip or strap created under `base_id`.
+#[no_mangle]
+pub extern "C" fn get_bundle_options() {
+    let base_id: u64 = runtime::get_named_arg(ARG_BASE_ID);
+    ret(bundle_options(base_id));
+}
+
+/// Exercises every leg of the bundle created under `base_id`; if any leg
+/// cannot be exercised, none is.
+#[no_mangle]
+pub extern "C" fn exercise_bundle() {
+    require_not_paused();
+    
+    let base_id: u64 = runtime::get_named_arg(ARG_BASE_ID);
+    enter_guard();
+    for id in bundle_options(base_id) {
+        exercise(id, runtime::get_caller(), None);
+    }
+    exit_guard();
+}
+
+/// `(long_id, short_id)` of spread `spread_id`.
+fn spread_legs(spread_id: u64) -> (u64, u64) {
+    read_uref(named_uref(&spread_key(spread_id), ErrorCode::SpreadNotFound))
+}
+
+/// `(width, net_premium)` of spread `spread_id`: the distance between its
+/// strikes, and the long leg's premium less the short leg's.
+fn spread_width_and_net_premium(spread_id: u64) -> (U512, U512) {
+    let (long_id, short_id) = spread_legs(spread_id);
+    let long_strike: U256 = read_option_field(OPTIONS_STRIKE_DICT, long_id);
+    let short_strike: U256 = read_option_field(OPTIONS_STRIKE_DICT, short_id);
+    let long_premium: U512 = read_option_value(&(option_key(long_id) + "_premium"));
+    let short_premium: U512 = read_option_value(&(option_key(short_id) + "_premium"));
+    
+    let width = long_strike.max(short_strike) - long_strike.min(short_strike);
+    (u256_to_u512(width), long_premium.saturating_sub(short_premium))
+}
+
+/// Creates a vertical spread expiring at `expiry`: long leg `long_id` struck
+/// at `long_strike` and short leg `short_id` at `short_strike`, both calls or
+/// both puts and created as by `create_option_batch`. A call spread must be
+/// long the lower strike and a put spread long the higher one. Links the legs
+/// under the next spread id, which is returned.
+#[no_mangle]
+pub extern "C" fn create_spread() {
+    require_not_paused();
+    require_whitelisted_writer();
+    
+    let long_id: u64 = runtime::get_named_arg(ARG_LONG_ID);
+    let short_id: u64 = runtime::get_named_arg(ARG_SHORT_ID);
+    let long_strike: U256 = runtime::get_named_arg(ARG_LONG_STRIKE);
+    let short_strike: U256 = runtime::get_named_arg(ARG_SHORT_STRIKE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    
+    let strikes_ordered = if option_type {
+        long_strike < short_strike
+    } else {
+        long_strike > short_strike
+    };
+    if !strikes_ordered {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    create_plain_option(long_id, long_strike, expiry, option_type);
+    create_plain_option(short_id, short_strike, expiry, option_type);
+    
+    let count_uref = named_counter(SPREAD_COUNT_KEY);
+    let spread_id: u64 = read_uref(count_uref);
+    runtime::put_key(&spread_key(spread_id), storage::new_uref((long_id, short_id)).into());
+    runtime::put_key(&option_spread_key(long_id), storage::new_uref(spread_id).into());
+    runtime::put_key(&option_spread_key(short_id), storage::new_uref(spread_id).into());
+    increment_u64(count_uref);
+    ret(spread_id);
+}
+
+/// Returns the most spread `spread_id` can make per unit at expiry: the
+/// distance between its strikes less the net premium paid for it.
+#[no_mangle]
+pub extern "C" fn get_spread_max_profit() {
+    let spread_id: u64 = runtime::get_named_arg(ARG_SPREAD_ID);
+    let (width, net_premium) = spread_width_and_net_premium(spread_id);
+    ret(width.saturating_sub(net_premium));
+}
+
+/// Returns the most spread `spread_id` can lose per unit: the net premium
+/// paid for it, capped at the distance between its strikes.
+#[no_mangle]
+pub extern "C" fn get_spread_max_loss() {
+    let spread_id: u64 = runtime::get_named_arg(ARG_SPREAD_ID);
+    let (width, net_premium) = spread_width_and_net_premium(spread_id);
+    ret(net_premium.min(width));
+}
+
+/// Creates option `id` owned by the caller, without premium, whose collateral
+/// is put up by `syndicators` in the matching `shares` (basis points summing
+/// to 10000) through `join_syndicate`. Payouts are drawn from the
+/// syndicators' collateral in proportion to their shares.
+#[no_mangle]
+pub extern "C" fn create_syndicated_option() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    let amount: U256 = runtime::get_named_arg(ARG_OPTION_AMOUNT);
+    let syndicators: Vec<AccountHash> = runtime::get_named_arg(ARG_SYNDICATORS);
+    let shares: Vec<u64> = runtime::get_named_arg(ARG_SHARES);
+    
+    if syndicators.is_empty() || syndicators.len() != shares.len() || amount.is_zero() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    let total_share = shares.iter().try_fold(0u64, |total, &share| total.checked_add(share));
+    if total_share != Some(BPS_DENOMINATOR) {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    for (index, member) in syndicators.iter().enumerate() {
+        if syndicators[..index].contains(member) {
+            runtime::revert(ErrorCode::InvalidParameter);
+        }
+    }
+    
+    let terms = OptionTerms {
+        strike_price,
+        expiry,
+        option_type,
+        option_style: OPTION_STYLE_AMERICAN,
+        activation: 0,
+        premium: U512::zero(),
+        amount,
+        collateral: U512::zero(),
+        underlying: None,
+        underlying_amount: U256::zero(),
+        series_id: None,
+        iv: 0,
+        exercise_dates: Vec::new(),
+    };
+    count_notional(id, runtime::get_caller(), strike_price, amount);
+    store_option(id, terms, runtime::get_caller(), SYNDICATE_WRITER);
+    for (member, &share) in syndicators.iter().zip(shares.iter()) {
+        set_syndicate_position(id, member, (share, U512::zero()));
+    }
+    runtime::put_key(&option_syndicators_key(id), storage::new_uref(syndicators).into());
+    register_option(id);
+    events::emit(EventType::OptionCreated, id);
+}
+
+/// Adds the CSPR moved from `purse` to the calling syndicator's collateral
+/// in option `id`, which must not be activated yet (syndicators only).
+#[no_mangle]
+pub extern "C" fn join_syndicate() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let member = require_syndicator(id);
+    let state: u8 = read_option_value(&option_state_key(id));
+    if state != OPTION_STATE_CREATED {
+        runtime::revert(ErrorCode::InvalidStateTransition);
+    }
+    let (share, collateral) = syndicate_position(id, &member);
+    if share == 0 {
+        runtime::revert(ErrorCode::NotSyndicator);
+    }
+    
+    let amount = transferred_value();
+    set_syndicate_position(id, &member, (share, collateral + amount));
+    let collateral_uref = named_uref(&option_collateral_key(id), ErrorCode::OptionNotFound);
+    let locked: U512 = read_uref(collateral_uref);
+    storage::write(collateral_uref, locked + amount);
+}
+
+/// Refunds the calling syndicator's collateral in option `id` and gives up
+/// its share, as long as no unit has been exercised (syndicators only). The
+/// remaining syndicators then cover payouts between them; an option left
+/// before activation can no longer be activated.
+#[no_mangle]
+pub extern "C" fn leave_syndicate() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    enter_guard();
+    
+    let member = require_syndicator(id);
+    let state: u8 = read_option_value(&option_state_key(id));
+    let exercised_amount: U256 = read_option_value(&(option_key(id) + "_exercised_amount"));
+    if !is_open_state(state) || !exercised_amount.is_zero() {
+        runtime::revert(ErrorCode::InvalidStateTransition);
+    }
+    
+    let refund = take_syndicate_collateral(id, &member);
+    set_syndicate_position(id, &member, (0, U512::zero()));
+    pay_from_contract_purse(member, refund);
+    
+    exit_guard();
+}
+
+/// Returns how many units of option `id` have not been exercised yet.
+fn remaining_amount(id: u64) -> U256 {
+    let key_name = option_key(id);
+    let amount: U256 = read_option_value(&(key_name.clone() + "_amount"));
+    let exercised_amount: U256 = read_option_value(&(key_name + "_exercised_amount"));
+    amount - exercised_amount
+}
+
+/// The first precondition that stops `holder` exercising option `id` right
+/// now, checked in the order `exercise` reverts on them, or `None` if it can.
+fn exercise_blocker(id: u64, holder: AccountHash) -> Option<ErrorCode> {
+    if !runtime::has_key(&option_state_key(id)) {
+        return Some(ErrorCode::OptionNotFound);
+    }
+    
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    if owner != holder {
+        return Some(ErrorCode::NotOptionOwner);
+    }
+    
+    let cancelled: bool = read_option_value(&option_cancelled_key(id));
+    if cancelled {
+        return Some(ErrorCode::OptionCancelled);
+    }
+    
+    let exercised: bool = read_option_field(OPTIONS_EXERCISED_DICT, id);
+    if exercised {
+        return Some(ErrorCode::OptionAlreadyExercised);
+    }
+    
+    let settled: bool = read_option_value(&option_settled_key(id));
+    if settled {
+        return Some(ErrorCode::OptionAlreadySettled);
+    }
+    
+    let state: u8 = read_option_value(&option_state_key(id));
+    if state != OPTION_STATE_ACTIVE {
+        return Some(ErrorCode::InvalidStateTransition);
+    }
+    
+    let key_name = option_key(id);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let option_style: u8 = read_option_value(&(key_name.clone() + "_style"));
+    let activation: u64 = read_option_value(&(key_name + "_activation"));
+    let now = current_timestamp();
+    if option_style == OPTION_STYLE_EUROPEAN && now < expiry {
+        return Some(ErrorCode::InvalidExerciseTime);
+    }
+    if option_style == OPTION_STYLE_BERMUDAN && !on_exercise_date(id, now) {
+        return Some(ErrorCode::InvalidExerciseTime);
+    }
+    if exercise_window_closed(expiry, option_style, now) {
+        return Some(ErrorCode::OptionExpired);
+    }
+    if now < activation {
+        return Some(ErrorCode::OptionNotActive);
+    }
+    
+    if needs_more_approvals(id) {
+        return Some(ErrorCode::InsufficientApprovals);
+    }
+    
+    None
+}
+
+/// Whether `now` is within `EXERCISE_TOLERANCE_SECS` of one of Bermudan
+/// option `id`'s exercise dates.
+fn on_exercise_date(id: u64, now: u64) -> bool {
+    (0..stored_count(&option_exercise_date_count_key(id))).any(|index| {
+        let date: u64 = read_named_value(&option_exercise_date_key(id, index));
+        now.abs_diff(date) <= EXERCISE_TOLERANCE_SECS
+    })
+}
+
+/// Whether option `id`'s notional, strike times units, is above the
+/// `high_value_threshold` set by `set_exercise_approval_policy` while it has
+/// fewer than `exercise_threshold` approvals from its current signers.
+/// Always false until a policy is set.
+fn needs_more_approvals(id: u64) -> bool {
+    if !runtime::has_key(HIGH_VALUE_THRESHOLD_KEY) {
+        return false;
+    }
+    let high_value_threshold: U256 = read_named_value(HIGH_VALUE_THRESHOLD_KEY);
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let amount: U256 = read_option_value(&(option_key(id) + "_amount"));
+    let units = if amount.is_zero() { U256::one() } else { amount };
+    if strike_price.saturating_mul(units) <= high_value_threshold {
+        return false;
+    }
+    
+    let exercise_threshold: u32 = read_named_value(EXERCISE_THRESHOLD_KEY);
+    let signers: Vec<AccountHash> = read_named_value(EXERCISE_SIGNERS_KEY);
+    let approvals = signers.iter().filter(|signer| approved_exercise(id, signer)).count();
+    approvals < exercise_threshold as usize
+}
+
+/// Whether `signer` has approved the exercise of option `id`.
+fn approved_exercise(id: u64, signer: &AccountHash) -> bool {
+    let approvals_uref = named_uref(EXERCISE_APPROVALS_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(approvals_uref, &exercise_approval_key(id, signer))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or(false)
+}
+
+/// Units of option `id` still unvested at `now`. Vesting options, created
+/// with `vesting_start`, vest nothing until `cliff_seconds` after it and then
+/// `amount * (now - vesting_start - cliff_seconds) / vesting_duration_seconds`
+/// up to `amount`; other options are fully vested.
+fn unvested_amount(id: u64, now: u64) -> U256 {
+    if !runtime::has_key(&option_vesting_key(id)) {
+        return U256::zero();
+    }
+    let (vesting_start, cliff_seconds, vesting_duration): (u64, u64, u64) =
+        read_named_value(&option_vesting_key(id));
+    let amount: U256 = read_option_value(&(option_key(id) + "_amount"));
+    let cliff_end = vesting_start.saturating_add(cliff_seconds);
+    if now < cliff_end {
+        return amount;
+    }
+    let elapsed = now - cliff_end;
+    if vesting_duration == 0 || elapsed >= vesting_duration {
+        return U256::zero();
+    }
+    amount - amount.saturating_mul(U256::from(elapsed)) / U256::from(vesting_duration)
+}
+
+/// Checks every exercise precondition for `id` and exercises `requested`
+/// units on behalf of `holder`, or everything left when `None`, reverting with
+/// the first failed check. The option is marked exercised once no units
+/// remain; unsized options are always exercised in full.
+fn exercise(id: u64, holder: AccountHash, requested: Option<U256>) {
+    if let Some(error) = exercise_blocker(id, holder) {
+        runtime::revert(error);
+    }
+    
+    // Stays Active until the last unit is exercised. Vesting options can only
+    // exercise, and default to, the vested units not yet exercised
+    let remaining = remaining_amount(id);
+    let exercisable = remaining.saturating_sub(unvested_amount(id, current_timestamp()));
+    let exercise_amount = requested.unwrap_or(exercisable);
+    let next_state = if exercise_amount == remaining {
+        OPTION_STATE_EXERCISED
+    } else {
+        OPTION_STATE_ACTIVE
+    };
+    transition(id, &[OPTION_STATE_ACTIVE], next_state);
+    
+    if exercise_amount > remaining {
+        runtime::revert(ErrorCode::ExceedsOptionAmount);
+    }
+    if exercise_amount > exercisable || (exercisable.is_zero() && !remaining.is_zero()) {
+        runtime::revert(ErrorCode::ExceedsVestedAmount);
+    }
+    if exercise_amount.is_zero() && !remaining.is_zero() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    if runtime::has_key(&option_token_key(id)) {
+        let token: Key = read_named_value(&option_token_key(id));
+        option_token::burn_from(contract_key_hash(token), holder, exercise_amount);
+    }
+    release_notional(id, exercise_amount);
+    
+    let exercised_amount_uref =
+        named_uref(&(option_key(id) + "_exercised_amount"), ErrorCode::OptionNotFound);
+    let exercised_amount: U256 = read_uref(exercised_amount_uref);
+    storage::write(exercised_amount_uref, exercised_amount + exercise_amount);
+    record_volume(id, exercise_amount);
+    if exercise_amount == remaining {
+        write_option_field(OPTIONS_EXERCISED_DICT, id, true);
+        put_named_value(&option_exercised_at_key(id), current_timestamp());
+        burn_option_nft(id);
+    }
+    
+    // Collateralised options pay out the exercised units' cash value, less
+    // any early exercise penalty on all of it, to the owner in collateral
+    // tokens first. The penalty is then collected out of the CSPR collateral
+    let collateral: U512 = read_option_value(&option_collateral_key(id));
+    let mut paid = U512::zero();
+    if !collateral.is_zero() || runtime::has_key(&option_collateral_token_key(id)) {
+        let payout = option_payout(id, exercise_amount, fetch_oracle_price());
+        let owed = payout - early_exercise_penalty(id, payout);
+        let covered = liquidate_token_collateral(id, holder, owed);
+        let drawn = draw_collateral(id, payout - covered);
+        let owed_in_cspr = owed - covered;
+        collect_fee(drawn.saturating_sub(owed_in_cspr));
+        paid = pay_owed_funding(id, drawn.min(owed_in_cspr));
+        pay_from_contract_purse(holder, paid);
+    }
+    
+    log_exercise(id, holder, paid);
+    events::emit(EventType::OptionExercised, id);
+}
+
+/// Appends `(id, exerciser, (now, settlement_amount))` to the exercise log
+/// under `exercise_log_{index}` and counts it against option `id`. Entries
+/// are never rewritten.
+fn log_exercise(id: u64, exerciser: AccountHash, settlement_amount: U512) {
+    let entry = (id, exerciser, (current_timestamp(), u512_to_u256(settlement_amount)));
+    let count_uref = named_counter(EXERCISE_LOG_COUNT_KEY);
+    let index: u64 = read_uref(count_uref);
+    runtime::put_key(&exercise_log_key(index), storage::new_uref(entry).into());
+    increment_u64(count_uref);
+    increment_u64(named_counter(&option_exercise_log_count_key(id)));
+}
+
+/// Returns up to `limit` exercise log entries from position `offset`, oldest
+/// first, as `(option_id, exerciser, (timestamp, settlement_amount))`.
+#[no_mangle]
+pub extern "C" fn get_exercise_history() {
+    let offset: u64 = runtime::get_named_arg(ARG_OFFSET);
+    let limit: u64 = runtime::get_named_arg(ARG_LIMIT);
+    let end = offset.saturating_add(limit).min(stored_count(EXERCISE_LOG_COUNT_KEY));
+    let history: Vec<(u64, AccountHash, (u64, U256))> = (offset..end)
+        .map(|index| read_named_value(&exercise_log_key(index)))
+        .collect();
+    ret(history);
+}
+
+/// Returns how many exercise log entries option `id` has: one per partial
+/// exercise, plus one if it was settled after expiring.
+#[no_mangle]
+pub extern "C" fn get_exercise_count_for_option() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    ret(stored_count(&option_exercise_log_count_key(id)));
+}
+
+#[no_mangle]
+pub extern "C" fn exercise_option() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    // Units to exercise; defaults to everything still unexercised
+    let exercise_amount: Option<U256> = runtime::try_get_named_arg(ARG_EXERCISE_AMOUNT);
+    enter_guard();
+    exercise(id, exercise_principal(id, runtime::get_caller()), exercise_amount);
+    exit_guard();
+}
+
+/// Exercises every option in `ids`. A revert on any id unwinds the writes for
+/// the whole batch. Casper reverts carry only the `ErrorCode`, so the failing id
+/// is not part of the revert; ids are checked in list order.
+#[no_mangle]
+pub extern "C" fn exercise_options_batch() {
+    require_not_paused();
+    
+    let ids: Vec<u64> = runtime::get_named_arg(ARG_IDS);
+    enter_guard();
+    for id in ids {
+        exercise(id, exercise_principal(id, runtime::get_caller()), None);
+    }
+    exit_guard();
+}
+
+/// Exercises all remaining units of option `id` for its owner `signer`, so a
+/// keeper can submit and pay for the deploy. `signature` is the signer's
+/// ed25519 signature over `blake2b((id, nonce, contract_hash))`, where `nonce`
+/// is the option's `signed_exercise_nonce_{id}`; each accepted signature bumps
+/// the nonce so it cannot be replayed.
+#[no_mangle]
+pub extern "C" fn exercise_with_signature() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let signature: [u8; 64] = runtime::get_named_arg(ARG_SIGNATURE);
+    let signer: [u8; 32] = runtime::get_named_arg(ARG_SIGNER);
+    
+    let public_key = PublicKey::ed25519_from_bytes(signer)
+        .ok()
+        .unwrap_or_revert_with(ErrorCode::InvalidSignature);
+    let signature = Signature::ed25519(signature)
+        .ok()
+        .unwrap_or_revert_with(ErrorCode::InvalidSignature);
+    
+    let nonce_uref = named_counter(&signed_exercise_nonce_key(id));
+    let nonce: u64 = read_uref(nonce_uref);
+    let contract_hash: ContractHash = read_named_value(CONTRACT_HASH_KEY);
+    let message = (id, nonce, contract_hash)
+        .to_bytes()
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    if crypto::verify(runtime::blake2b(message), &signature, &public_key).is_err() {
+        runtime::revert(ErrorCode::InvalidSignature);
+    }
+    increment_u64(nonce_uref);
+    
+    enter_guard();
+    exercise(id, AccountHash::from(&public_key), None);
+    exit_guard();
+}
+
+/// Requires `exercise_threshold` of `signers` to approve, through
+/// `approve_exercise`, the exercise of any option whose notional (strike
+/// times units) is above `high_value_threshold` (admin only). Only approvals
+/// from the new `signers` count, so removed signers' approvals lapse.
+#[no_mangle]
+pub extern "C" fn set_exercise_approval_policy() {
+    require_admin();
+    
+    let high_value_threshold: U256 = runtime::get_named_arg(ARG_HIGH_VALUE_THRESHOLD);
+    let signers: Vec<AccountHash> = runtime::get_named_arg(ARG_SIGNERS);
+    let exercise_threshold: u32 = runtime::get_named_arg(ARG_EXERCISE_THRESHOLD);
+    if exercise_threshold == 0 || exercise_threshold as usize > signers.len() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    put_named_value(HIGH_VALUE_THRESHOLD_KEY, high_value_threshold);
+    put_named_value(EXERCISE_SIGNERS_KEY, signers);
+    put_named_value(EXERCISE_THRESHOLD_KEY, exercise_threshold);
+}
+
+/// Records the calling signer's approval to exercise option `id`; approving
+/// twice counts once (exercise signers only).
+#[no_mangle]
+pub extern "C" fn approve_exercise() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    let signer = runtime::get_caller();
+    let signers: Vec<AccountHash> = if runtime::has_key(EXERCISE_SIGNERS_KEY) {
+        read_named_value(EXERCISE_SIGNERS_KEY)
+    } else {
+        Vec::new()
+    };
+    if !signers.contains(&signer) {
+        runtime::revert(ErrorCode::NotExerciseSigner);
+    }
+    
+    if !approved_exercise(id, &signer) {
+        let approvals_uref = named_uref(EXERCISE_APPROVALS_DICT, ErrorCode::MissingNamedKey);
+        storage::dictionary_put(approvals_uref, &exercise_approval_key(id, &signer), true);
+        increment_u64(named_counter(&option_approval_count_key(id)));
+    }
+}
+
+/// The account `caller` exercises option `id` for: its owner, if the owner
+/// approved `caller` with `approve_exercise_for` and still owns the option,
+/// and `caller` itself otherwise. Delegated exercises pay the owner.
+fn exercise_principal(id: u64, caller: AccountHash) -> AccountHash {
+    if !runtime::has_key(&option_state_key(id)) {
+        return caller;
+    }
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    let delegates_uref = named_uref(EXERCISE_DELEGATES_DICT, ErrorCode::MissingNamedKey);
+    let granted_by: Option<AccountHash> =
+        storage::dictionary_get(delegates_uref, &exercise_approval_key(id, &caller))
+            .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+            .flatten();
+    if granted_by == Some(owner) {
+        owner
+    } else {
+        caller
+    }
+}
+
+/// Records, as option `id`'s owner, who granted `approved` exercise rights,
+/// or clears them with `None`. A grant lapses once the option changes hands.
+fn set_exercise_delegate(id: u64, approved: &AccountHash, granted_by: Option<AccountHash>) {
+    let delegates_uref = named_uref(EXERCISE_DELEGATES_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(delegates_uref, &exercise_approval_key(id, approved), granted_by);
+}
+
+/// Lets `approved`, such as a liquidation bot, exercise option `id` on the
+/// owner's behalf until revoked or the option is transferred (owner only).
+#[no_mangle]
+pub extern "C" fn approve_exercise_for() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let approved: AccountHash = runtime::get_named_arg(ARG_APPROVED);
+    require_option_owner(id);
+    set_exercise_delegate(id, &approved, Some(runtime::get_caller()));
+}
+
+/// Withdraws `approved`'s right to exercise option `id` (owner only).
+#[no_mangle]
+pub extern "C" fn revoke_exercise_approval() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let approved: AccountHash = runtime::get_named_arg(ARG_APPROVED);
+    require_option_owner(id);
+    set_exercise_delegate(id, &approved, None);
+}
+
+/// Checks every cancellation precondition for `id` and marks it cancelled,
+/// reverting with the first failed check.
+fn cancel(id: u64) {
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    if owner != runtime::get_caller() {
+        runtime::revert(ErrorCode::NotOptionOwner);
+    }
+    
+    // Cancelling a spread's long leg alone would leave its short leg uncovered
+    if runtime::has_key(&option_spread_key(id)) {
+        let spread_id: u64 = read_option_value(&option_spread_key(id));
+        let (long_id, short_id) = spread_legs(spread_id);
+        let short_cancelled: bool = read_option_value(&option_cancelled_key(short_id));
+        if id == long_id && !short_cancelled {
+            runtime::revert(ErrorCode::SpreadShortLegOpen);
+        }
+    }
+    
+    let cancelled_uref = named_uref(&option_cancelled_key(id), ErrorCode::OptionNotFound);
+    let cancelled: bool = read_uref(cancelled_uref);
+    if cancelled {
+        runtime::revert(ErrorCode::OptionCancelled);
+    }
+    
+    let exercised: bool = read_option_field(OPTIONS_EXERCISED_DICT, id);
+    if exercised {
+        runtime::revert(ErrorCode::OptionAlreadyExercised);
+    }
+    
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    if current_timestamp() > expiry {
+        runtime::revert(ErrorCode::OptionExpired);
+    }
+    
+    transition(id, &[OPTION_STATE_CREATED, OPTION_STATE_ACTIVE], OPTION_STATE_CANCELLED);
+    storage::write(cancelled_uref, true);
+    events::emit(EventType::OptionCancelled, id);
+}
+
+#[no_mangle]
+pub extern "C" fn cancel_option() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    cancel(id);
+}
+
+/// Cancels every listed Created or Active option while the registry is
+/// paused, skipping the rest, whatever their owners or expiries. Each
+/// option's unclaimed escrowed premium is refunded to its owner and its
+/// writer's collateral returned: to the writer, CSPR and tokens, or to the
+/// pool's free liquidity for pool-backed options. Syndicators take their
+/// shares back with `withdraw_collateral`. Counts the options cancelled in
+/// `emergency_cancelled_count` and the premiums refunded in
+/// `emergency_refund_total`. Takes at most `MAX_EMERGENCY_CANCEL_BATCH` ids
+/// (admin only).
+#[no_mangle]
+pub extern "C" fn emergency_cancel_batch() {
+    require_admin();
+    let paused: bool = read_named_value(PAUSED_KEY);
+    if !paused {
+        runtime::revert(ErrorCode::ContractNotPaused);
+    }
+    
+    let ids: Vec<u64> = runtime::get_named_arg(ARG_IDS);
+    if ids.len() > MAX_EMERGENCY_CANCEL_BATCH {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    enter_guard();
+    let mut refunded = U512::zero();
+    for id in ids {
+        if !runtime::has_key(&option_state_key(id)) {
+            continue;
+        }
+        let state: u8 = read_option_value(&option_state_key(id));
+        if !is_open_state(state) {
+            continue;
+        }
+        transition(id, &[OPTION_STATE_CREATED, OPTION_STATE_ACTIVE], OPTION_STATE_CANCELLED);
+        storage::write(named_uref(&option_cancelled_key(id), ErrorCode::OptionNotFound), true);
+        
+        let key_name = option_key(id);
+        let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+        let claimed_uref =
+            named_uref(&(key_name.clone() + "_premium_claimed"), ErrorCode::OptionNotFound);
+        let claimed: bool = read_uref(claimed_uref);
+        if !claimed {
+            let premium: U512 = read_option_value(&(key_name + "_premium"));
+            storage::write(claimed_uref, true);
+            pay_from_contract_purse(owner, premium);
+            refunded += premium;
+        }
+        
+        if !is_syndicated(id) {
+            let collateral_uref = named_uref(&option_collateral_key(id), ErrorCode::OptionNotFound);
+            let locked: U512 = read_uref(collateral_uref);
+            storage::write(collateral_uref, U512::zero());
+            if is_pool_backed(id) {
+                release_pool_collateral(locked);
+            } else {
+                let writer: AccountHash = read_option_value(&option_writer_key(id));
+                pay_from_contract_purse(writer, locked);
+                release_token_collateral(id, writer);
+            }
+        }
+        increment_u64(named_counter(EMERGENCY_CANCELLED_COUNT_KEY));
+        events::emit(EventType::OptionCancelled, id);
+    }
+    let total: U512 = read_named_value(EMERGENCY_REFUND_TOTAL_KEY);
+    put_named_value(EMERGENCY_REFUND_TOTAL_KEY, total + refunded);
+    exit_guard();
+}
+
+/// Lets the owner of option `id` roll it out to any expiry up to
+/// `new_expiry`, keeping the writer's collateral locked until then. Replaces
+/// any earlier approval (writer only).
+#[no_mangle]
+pub extern "C" fn approve_rollover() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let new_expiry: u64 = runtime::get_named_arg(ARG_NEW_EXPIRY);
+    require_writer(id);
+    put_named_value(&option_rollover_approval_key(id), new_expiry);
+}
+
+/// Rolls the caller's Active option `old_id`, within `ROLLOVER_WINDOW_SECS`
+/// of its expiry, into option `new_id` expiring at `new_expiry`: `old_id` is
+/// cancelled, its option tokens and NFT burnt, and `new_id` issued as
+/// `create_option` does with its strike, type, style, unexercised units,
+/// underlying, series, implied volatility, vesting, metadata and writer,
+/// active and owned by the caller. The writer's CSPR and token collateral
+/// moves over to `new_id`, so unless the caller is the writer it must have
+/// approved `new_expiry` with `approve_rollover`. An optional `premium` for
+/// the new option is paid in via `purse` and `amount` and escrowed for the
+/// writer, `nonce` must be greater than the caller's previous nonce, and the
+/// writer whitelist, `max_notional_per_account` and price validation apply.
+/// The roll is recorded as `(old_id, new_id, timestamp)` under
+/// `rollover_{index}`. Bermudan options can't be rolled, as their exercise
+/// dates don't carry over.
+#[no_mangle]
+pub extern "C" fn rollover_option() {
+    require_not_paused();
+    
+    let old_id: u64 = runtime::get_named_arg(ARG_OLD_ID);
+    let new_id: u64 = runtime::get_named_arg(ARG_NEW_ID);
+    let new_expiry: u64 = runtime::get_named_arg(ARG_NEW_EXPIRY);
+    let premium: U512 = runtime::try_get_named_arg(ARG_PREMIUM).unwrap_or_default();
+    
+    let state: u8 = read_option_value(&option_state_key(old_id));
+    if state != OPTION_STATE_ACTIVE {
+        runtime::revert(ErrorCode::OptionNotActive);
+    }
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, old_id);
+    let now = current_timestamp();
+    if now.saturating_add(ROLLOVER_WINDOW_SECS) < expiry {
+        runtime::revert(ErrorCode::RolloverWindowNotOpen);
+    }
+    // Pool and syndicate writers can't take on a new option unasked
+    if new_expiry <= expiry || is_pool_backed(old_id) || is_syndicated(old_id) {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    let amount = remaining_amount(old_id);
+    cancel(old_id);
+    let writer: AccountHash = read_option_value(&option_writer_key(old_id));
+    let approved_until: u64 = read_option_value_or_default(&option_rollover_approval_key(old_id));
+    if writer != runtime::get_caller() && approved_until < new_expiry {
+        runtime::revert(ErrorCode::RolloverNotApproved);
+    }
+    require_whitelisted(&writer);
+    
+    // The old option's units live on in the new one, which mints its own
+    if runtime::has_key(&option_token_key(old_id)) {
+        let token: Key = read_named_value(&option_token_key(old_id));
+        option_token::burn_from(contract_key_hash(token), runtime::get_caller(), amount);
+    }
+    burn_option_nft(old_id);
+    
+    // The collateral backing the old option backs the new one instead
+    let collateral_uref = named_uref(&option_collateral_key(old_id), ErrorCode::OptionNotFound);
+    let held: U512 = read_uref(collateral_uref);
+    storage::write(collateral_uref, U512::zero());
+    let held_tokens = if runtime::has_key(&option_collateral_token_key(old_id)) {
+        let token: Key = read_named_value(&option_collateral_token_key(old_id));
+        let locked_uref =
+            named_uref(&option_token_collateral_key(old_id), ErrorCode::OptionNotFound);
+        let locked: U256 = read_uref(locked_uref);
+        storage::write(locked_uref, U256::zero());
+        Some((token, locked))
+    } else {
+        None
+    };
+    
+    let key_name = option_key(old_id);
+    let terms = OptionTerms {
+        strike_price: read_option_field(OPTIONS_STRIKE_DICT, old_id),
+        expiry: new_expiry,
+        option_type: read_option_value(&(key_name.clone() + "_type")),
+        option_style: read_option_value(&(key_name.clone() + "_style")),
+        activation: 0,
+        premium,
+        amount,
+        collateral: U512::zero(),
+        underlying: read_option_value(&(key_name.clone() + "_underlying")),
+        underlying_amount: read_option_value(&(key_name.clone() + "_underlying_amount")),
+        series_id: read_option_value(&(key_name + "_series")),
+        iv: read_option_value(&option_iv_key(old_id)),
+        exercise_dates: Vec::new(),
+    };
+    issue_option(new_id, terms, writer, CollateralSource::Held(held, held_tokens));
+    transition(new_id, &[OPTION_STATE_CREATED], OPTION_STATE_ACTIVE);
+    if runtime::has_key(&option_vesting_key(old_id)) {
+        let vesting: (u64, u64, u64) = read_named_value(&option_vesting_key(old_id));
+        put_named_value(&option_vesting_key(new_id), vesting);
+    }
+    if runtime::has_key(&option_metadata_uri_key(old_id)) {
+        let metadata_uri: String = read_named_value(&option_metadata_uri_key(old_id));
+        store_metadata_uri(new_id, metadata_uri);
+    }
+    
+    let count_uref = named_counter(ROLLOVER_COUNT_KEY);
+    let index: u64 = read_uref(count_uref);
+    let rollover_uref = storage::new_uref((old_id, new_id, now));
+    runtime::put_key(&rollover_key(index), rollover_uref.into());
+    increment_u64(count_uref);
+}
+
+/// Returns up to `limit` rollovers from position `offset`, oldest first, as
+/// `(old_id, new_id, timestamp)`.
+#[no_mangle]
+pub extern "C" fn get_rollover_history() {
+    let offset: u64 = runtime::get_named_arg(ARG_OFFSET);
+    let limit: u64 = runtime::get_named_arg(ARG_LIMIT);
+    let end = offset.saturating_add(limit).min(stored_count(ROLLOVER_COUNT_KEY));
+    let history: Vec<(u64, u64, u64)> = (offset..end)
+        .map(|index| read_named_value(&rollover_key(index)))
+        .collect();
+    ret(history);
+}
+
+/// Cancels every option in the series that is not already exercised or
+/// cancelled. Like `exercise_options_batch`, a revert on any option unwinds
+/// the whole call, so the caller must own all of them.
+#[no_mangle]
+pub extern "C" fn cancel_series() {
+    require_not_paused();
+    
+    let series_id: u64 = runtime::get_named_arg(ARG_SERIES_ID);
+    for id in series_options(series_id) {
+        let exercised: bool = read_option_field(OPTIONS_EXERCISED_DICT, id);
+        let cancelled: bool = read_option_value(&option_cancelled_key(id));
+        if !exercised && !cancelled {
+            cancel(id);
+        }
+    }
+}
+
+/// Lowers the strike of every Created or Active option in the series by
+/// `dividend_per_share`, recording the dividend in each option's adjustment
+/// history. Options already exercised, expired or cancelled keep their
+/// strike (admin only).
+#[no_mangle]
+pub extern "C" fn apply_dividend_adjustment() {
+    require_admin();
+    
+    let series_id: u64 = runtime::get_named_arg(ARG_SERIES_ID);
+    let dividend: U256 = runtime::get_named_arg(ARG_DIVIDEND_PER_SHARE);
+    for id in series_options(series_id) {
+        let state: u8 = read_option_value(&option_state_key(id));
+        if !is_open_state(state) {
+            continue;
+        }
+        let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+        let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+        let option_type: bool = read_option_value(&(option_key(id) + "_type"));
+        let adjusted = strike_price
+            .checked_sub(dividend)
+            .unwrap_or_revert_with(ErrorCode::InvalidParameter);
+        write_option_field(OPTIONS_STRIKE_DICT, id, adjusted);
+        move_in_option_chain(id, expiry, strike_price, adjusted, option_type);
+        // Open interest stays counted at the strike the option now has
+        decrement_u64(named_counter(&oi_strike_key(expiry, strike_price)));
+        increment_u64(named_counter(&oi_strike_key(expiry, adjusted)));
+        
+        let count_uref = named_counter(&option_adjustment_count_key(id));
+        let index: u64 = read_uref(count_uref);
+        runtime::put_key(&option_adjustment_key(id, index), storage::new_uref(dividend).into());
+        increment_u64(count_uref);
+    }
+}
+
+/// Returns the dividends option `id`'s strike has been lowered by, oldest
+/// first.
+#[no_mangle]
+pub extern "C" fn get_adjustment_history() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    let count = stored_count(&option_adjustment_count_key(id));
+    let history: Vec<U256> = (0..count)
+        .map(|index| read_named_value(&option_adjustment_key(id, index)))
+        .collect();
+    ret(history);
+}
+
+/// Lets the buyer, who paid the premium at creation, confirm option `id` so it
+/// can be exercised: Created -> Active.
+#[no_mangle]
+pub extern "C" fn activate_option() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    if owner != runtime::get_caller() {
+        runtime::revert(ErrorCode::NotOptionOwner);
+    }
+    if is_syndicated(id) && !syndicate_funded(id) {
+        runtime::revert(ErrorCode::SyndicateNotFunded);
+    }
+    
+    transition(id, &[OPTION_STATE_CREATED], OPTION_STATE_ACTIVE);
+}
+
+/// Marks an Active option whose exercise window has closed as Expired so it
+/// can be settled. Meant for keepers; anyone may call it.
+#[no_mangle]
+pub extern "C" fn expire_option() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let option_style: u8 = read_option_value(&(option_key(id) + "_style"));
+    if !exercise_window_closed(expiry, option_style, current_timestamp()) {
+        runtime::revert(ErrorCode::OptionNotExpired);
+    }
+    
+    transition(id, &[OPTION_STATE_ACTIVE], OPTION_STATE_EXPIRED);
+}
+
+/// Pays the escrowed premium out to the writer once the option can no longer
+/// be exercised.
+#[no_mangle]
+pub extern "C" fn claim_premium() {
+    require_unpaused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    enter_guard();
+    
+    let writer = require_writer(id);
+    
+    let exercised: bool = read_option_field(OPTIONS_EXERCISED_DICT, id);
+    if exercised {
+        runtime::revert(ErrorCode::OptionAlreadyExercised);
+    }
+    
+    let key_name = option_key(id);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let option_style: u8 = read_option_value(&(key_name.clone() + "_style"));
+    if !exercise_window_closed(expiry, option_style, current_timestamp()) {
+        runtime::revert(ErrorCode::OptionNotExpired);
+    }
+    
+    let claimed_uref = named_uref(&(key_name.clone() + "_premium_claimed"), ErrorCode::OptionNotFound);
+    let claimed: bool = read_uref(claimed_uref);
+    if claimed {
+        runtime::revert(ErrorCode::PremiumAlreadyClaimed);
+    }
+    
+    let premium: U512 = read_option_value(&(key_name + "_premium"));
+    pay_from_contract_purse(writer, premium);
+    
+    storage::write(claimed_uref, true);
+    events::emit(EventType::PremiumClaimed, id);
+    exit_guard();
+}
+
+/// Closes out an Exercised or Expired option. An Exercised option was paid at
+/// exercise, so settling it only records a zero settlement. An Expired option
+/// is settled for its unexercised units at its expiry's finalized settlement
+/// price once that is final, or else the oracle price. Calls with an
+/// underlying token are settled physically: if in the money, the writer's
+/// `underlying_amount` tokens are moved to the owner and no CSPR is paid.
+/// Everything else is cash-settled, paying the intrinsic value to the current
+/// owner out of the option's collateral tokens and then its locked CSPR
+/// collateral, with any shortfall covered from the insurance fund as far as
+/// it can. Nothing beyond that is paid, so an option without collateral
+/// settles for nothing. Anyone may trigger settlement;
+/// the proceeds always go to the owner.
+#[no_mangle]
+pub extern "C" fn settle_option() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    enter_guard();
+    settle(id);
+    exit_guard();
+}
+
+/// Checks every settlement precondition for `id` and settles it as
+/// `settle_option` describes, reverting with the first failed check.
+fn settle(id: u64) {
+    let key_name = option_key(id);
+    let settled_uref = named_uref(&option_settled_key(id), ErrorCode::OptionNotFound);
+    let settled: bool = read_uref(settled_uref);
+    if settled {
+        runtime::revert(ErrorCode::OptionAlreadySettled);
+    }
+    
+    let cancelled: bool = read_option_value(&option_cancelled_key(id));
+    if cancelled {
+        runtime::revert(ErrorCode::OptionCancelled);
+    }
+    
+    let previous_state = transition(
+        id,
+        &[OPTION_STATE_EXERCISED, OPTION_STATE_EXPIRED],
+        OPTION_STATE_SETTLED,
+    );
+    
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    let option_type: bool = read_option_value(&(key_name.clone() + "_type"));
+    let underlying: Option<Key> = read_option_value(&(key_name.clone() + "_underlying"));
+    
+    // Exercise already paid out; an unsized option has no units left to settle
+    let units = remaining_amount(id);
+    let in_the_money_payout = if previous_state == OPTION_STATE_EXERCISED {
+        U512::zero()
+    } else {
+        option_payout(id, units, settlement_price(id))
+    };
+    let payout = match underlying {
+        _ if previous_state == OPTION_STATE_EXERCISED => U512::zero(),
+        Some(token) if option_type => {
+            if !in_the_money_payout.is_zero() {
+                deliver_underlying(id, token, owner);
+            }
+            U512::zero()
+        }
+        _ => {
+            let mut payout = in_the_money_payout;
+            // Token collateral is liquidated to the owner first
+            let covered = liquidate_token_collateral(id, owner, payout);
+            payout -= covered;
+            // The rest comes out of the option's own collateral, its shortfall
+            // out of the insurance fund; an uncollateralised option pays nothing
+            let collateral: U512 = read_option_value(&option_collateral_key(id));
+            payout = if collateral.is_zero() {
+                U512::zero()
+            } else {
+                let drawn = draw_collateral(id, payout);
+                drawn + draw_insurance_fund(payout - drawn)
+            };
+            // The fee stays in the contract purse until the admin collects it
+            let net_payout = pay_owed_funding(id, take_fee(payout));
+            pay_from_contract_purse(owner, net_payout);
+            covered + net_payout
+        }
+    };
+    
+    // Exercised options were logged when exercised; expired ones only add
+    // to the volume if they settle in the money
+    if previous_state == OPTION_STATE_EXPIRED {
+        log_exercise(id, owner, payout);
+        if !in_the_money_payout.is_zero() {
+            record_volume(id, units);
+        }
+    }
+    runtime::put_key(&(key_name + "_settlement"), storage::new_uref(payout).into());
+    storage::write(settled_uref, true);
+    events::emit(EventType::OptionSettled, id);
+}
+
+/// Adds CSPR, moved in via `purse` and `amount`, to the insurance fund that
+/// covers settlement payouts a writer's collateral falls short of. Nothing
+/// is paid back to contributors.
+#[no_mangle]
+pub extern "C" fn contribute_to_insurance_fund() {
+    require_not_paused();
+    
+    let amount = transferred_value();
+    if amount.is_zero() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    let fund_uref = named_uref(INSURANCE_FUND_BALANCE_KEY, ErrorCode::MissingNamedKey);
+    let balance: U512 = read_uref(fund_uref);
+    storage::write(fund_uref, balance + amount);
+    
+    let contributor = runtime::get_caller();
+    let contribution = insurance_contribution(&contributor) + amount;
+    let contributions_uref = named_uref(INSURANCE_CONTRIBUTIONS_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(contributions_uref, &account_hex(&contributor), contribution);
+}
+
+/// Returns whether option `id` could be liquidated with the underlying at
+/// `current_price`.
+#[no_mangle]
+pub extern "C" fn is_liquidatable() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let current_price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    require_option_exists(id);
+    ret(liquidatable(id, current_price));
+}
+
+/// Closes option `id` as Settled once it is liquidatable at the oracle
+/// price. The caller receives `option_registry_liquidation_bonus_bps` of the
+/// locked collateral, and the owner the rest as a partial settlement, capped
+/// at the option's payout and less the protocol fee. Collateral beyond that
+/// stays locked for the writer to withdraw. Callable by anyone.
+#[no_mangle]
+pub extern "C" fn liquidate_option() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    enter_guard();
+    
+    let price = fetch_oracle_price();
+    if !liquidatable(id, price) {
+        runtime::revert(ErrorCode::NotLiquidatable);
+    }
+    let liability = option_payout(id, remaining_amount(id), price);
+    transition(id, &[OPTION_STATE_ACTIVE], OPTION_STATE_SETTLED);
+    
+    let locked: U512 = read_option_value(&option_collateral_key(id));
+    let bonus_bps: u32 = read_named_value(LIQUIDATION_BONUS_BPS_KEY);
+    let bonus = locked * U512::from(bonus_bps) / U512::from(BPS_DENOMINATOR);
+    let payout = (locked - bonus).min(liability);
+    draw_collateral(id, bonus + payout);
+    
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    let net_payout = take_fee(payout);
+    pay_from_contract_purse(runtime::get_caller(), bonus);
+    pay_from_contract_purse(owner, net_payout);
+    
+    runtime::put_key(&(option_key(id) + "_settlement"), storage::new_uref(net_payout).into());
+    storage::write(named_uref(&option_settled_key(id), ErrorCode::OptionNotFound), true);
+    events::emit(EventType::OptionLiquidated, id);
+    exit_guard();
+}
+
+/// Admin-only: records `price` as the settlement price of options expiring
+/// at `expiry`, once that expiry has been reached. Holders can dispute it for
+/// `DISPUTE_WINDOW_SECS`; corrections go through `resolve_dispute`.
+#[no_mangle]
+pub extern "C" fn finalize_settlement_price() {
+    require_admin();
+    
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let price: U256 = runtime::get_named_arg(ARG_PRICE);
+    if current_timestamp() < expiry {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    let price_key = settlement_price_key(expiry);
+    if runtime::has_key(&price_key) {
+        runtime::revert(ErrorCode::SettlementPriceAlreadySet);
+    }
+    
+    runtime::put_key(&price_key, storage::new_uref(price).into());
+    put_named_value(&settlement_finalized_at_key(expiry), current_timestamp());
+    put_named_value(&settlement_disputed_key(expiry), false);
+    put_named_value(&settlement_resolved_key(expiry), false);
+}
+
+/// Disputes the settlement price of option `id`'s expiry on behalf of its
+/// owner, holding settlement at that expiry until the admin resolves it.
+#[no_mangle]
+pub extern "C" fn dispute_settlement_price() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    if owner != runtime::get_caller() {
+        runtime::revert(ErrorCode::NotOptionOwner);
+    }
+    
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    if !runtime::has_key(&settlement_price_key(expiry)) {
+        runtime::revert(ErrorCode::SettlementPriceNotSet);
+    }
+    let resolved: bool = read_named_value(&settlement_resolved_key(expiry));
+    let finalized_at: u64 = read_named_value(&settlement_finalized_at_key(expiry));
+    if resolved || current_timestamp() > finalized_at.saturating_add(DISPUTE_WINDOW_SECS) {
+        runtime::revert(ErrorCode::DisputeWindowClosed);
+    }
+    
+    put_named_value(&settlement_disputed_key(expiry), true);
+}
+
+/// Admin-only: settles a dispute over `expiry`'s settlement price by setting
+/// it to `price`, which is final immediately.
+#[no_mangle]
+pub extern "C" fn resolve_dispute() {
+    require_admin();
+    
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let price: U256 = runtime::get_named_arg(ARG_PRICE);
+    let disputed_key = settlement_disputed_key(expiry);
+    if !runtime::has_key(&disputed_key) || !read_named_value::<bool>(&disputed_key) {
+        runtime::revert(ErrorCode::NoOpenDispute);
+    }
+    
+    put_named_value(&settlement_price_key(expiry), price);
+    put_named_value(&disputed_key, false);
+    put_named_value(&settlement_resolved_key(expiry), true);
+}
+
+/// Returns whether `expiry`'s settlement price is finalized and past dispute.
+#[no_mangle]
+pub extern "C" fn is_settlement_final() {
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    ret(settlement_final(expiry));
+}
+
+/// Locks more CSPR, moved in via `purse` and `amount`, behind option `id`.
+#[no_mangle]
+pub extern "C" fn add_collateral() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_writer(id);
+    
+    let collateral_uref = named_uref(&option_collateral_key(id), ErrorCode::OptionNotFound);
+    let locked: U512 = read_uref(collateral_uref);
+    storage::write(collateral_uref, locked + transferred_value());
+}
+
+/// Returns the writer's remaining collateral once the option can no longer
+/// draw on it: after exercise, settlement or cancellation. An option that
+/// expired unexercised has to be settled first so the owner is paid. For a
+/// pool-backed option anyone may call it, and the collateral goes back to the
+/// pool's free liquidity instead.
+#[no_mangle]
+pub extern "C" fn withdraw_collateral() {
+    require_unpaused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let pool_backed = is_pool_backed(id);
+    let syndicated = is_syndicated(id);
+    let writer = if pool_backed {
+        POOL_WRITER
+    } else if syndicated {
+        require_syndicator(id)
+    } else {
+        require_writer(id)
+    };
+    
+    let exercised: bool = read_option_field(OPTIONS_EXERCISED_DICT, id);
+    let settled: bool = read_option_value(&option_settled_key(id));
+    let cancelled: bool = read_option_value(&option_cancelled_key(id));
+    if !exercised && !settled && !cancelled {
+        let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+        if current_timestamp() < expiry {
+            runtime::revert(ErrorCode::OptionNotExpired);
+        }
+        runtime::revert(ErrorCode::OptionNotSettled);
+    }
+    
+    if syndicated {
+        let withdrawn = take_syndicate_collateral(id, &writer);
+        pay_from_contract_purse(writer, withdrawn);
+        return;
+    }
+    
+    let collateral_uref = named_uref(&option_collateral_key(id), ErrorCode::OptionNotFound);
+    let locked: U512 = read_uref(collateral_uref);
+    storage::write(collateral_uref, U512::zero());
+    if pool_backed {
+        release_pool_collateral(locked);
+    } else {
+        pay_from_contract_purse(writer, locked);
+        release_token_collateral(id, writer);
+    }
+}
+
+/// Accepts CEP-18 `token` as option collateral, counting
+/// `collateral_factor_bps` of its DEX value, or updates its haircut (admin
+/// only).
+#[no_mangle]
+pub extern "C" fn add_collateral_type() {
+    require_admin();
+    
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let collateral_factor_bps: u32 = runtime::get_named_arg(ARG_COLLATERAL_FACTOR_BPS);
+    if u64::from(collateral_factor_bps) > BPS_DENOMINATOR {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    let whitelist_uref = named_uref(COLLATERAL_WHITELIST_DICT, ErrorCode::MissingNamedKey);
+    let token_hex = bytes_hex(&contract_key_hash(token).value());
+    storage::dictionary_put(whitelist_uref, &token_hex, (true, collateral_factor_bps));
+}
+
+/// Stops accepting `token` as collateral for new options. Options already
+/// holding it keep it (admin only).
+#[no_mangle]
+pub extern "C" fn remove_collateral_type() {
+    require_admin();
+    
+    let token: Key = runtime::get_named_arg(ARG_TOKEN);
+    let (_, collateral_factor_bps) = collateral_type(token);
+    let whitelist_uref = named_uref(COLLATERAL_WHITELIST_DICT, ErrorCode::MissingNamedKey);
+    let token_hex = bytes_hex(&contract_key_hash(token).value());
+    storage::dictionary_put(whitelist_uref, &token_hex, (false, collateral_factor_bps));
+}
+
+/// Sets the DEX that prices collateral tokens and quotes their liquidation
+/// (admin only).
+#[no_mangle]
+pub extern "C" fn set_collateral_dex() {
+    require_admin();
+    
+    let dex: Key = runtime::get_named_arg(ARG_DEX);
+    contract_key_hash(dex);
+    storage::write(named_uref(COLLATERAL_DEX_KEY, ErrorCode::MissingNamedKey), Some(dex));
+}
+
+/// Hands the buyer role to `new_owner`, along with the option tokens of its
+/// unexercised units; the writer is unaffected.
+#[no_mangle]
+pub extern "C" fn transfer_option() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let new_owner: AccountHash = runtime::get_named_arg(ARG_NEW_OWNER);
+    
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    if owner != runtime::get_caller() {
+        runtime::revert(ErrorCode::NotOptionOwner);
+    }
+    
+    write_option_field(OPTIONS_OWNER_DICT, id, new_owner);
+    remove_from_portfolio(&owner, id);
+    add_to_portfolio(&new_owner, id);
+    transfer_option_nft(id, owner, new_owner);
+    move_option_tokens(id, owner, new_owner);
+    // A scheduled or automatic exercise was the previous owner's choice
+    runtime::remove_key(&option_auto_exercise_key(id));
+    runtime::remove_key(&auto_exercise_pending_key(id));
+    events::emit(EventType::OptionTransferred, id);
+}
+
+#[no_mangle]
+pub extern "C" fn pause() {
+    require_admin();
+    storage::write(named_uref(PAUSED_KEY, ErrorCode::MissingNamedKey), true);
+}
+
+#[no_mangle]
+pub extern "C" fn unpause() {
+    require_admin();
+    storage::write(named_uref(PAUSED_KEY, ErrorCode::MissingNamedKey), false);
+}
+
+/// Permanently retires the registry ahead of a migration: every entry point
+/// that checks for a pause reverts with `ContractDeprecated` from now on, the
+/// accumulated fees are paid to `destination`, and writers take their
+/// collateral back with `withdraw_my_collateral` (admin only).
+#[no_mangle]
+pub extern "C" fn deprecate() {
+    require_admin();
+    
+    // A paused registry can still be deprecated, but only once
+    let deprecated: bool = read_named_value(DEPRECATED_KEY);
+    if deprecated {
+        runtime::revert(ErrorCode::ContractDeprecated);
+    }
+    let destination: AccountHash = runtime::get_named_arg(ARG_DESTINATION);
+    put_named_value(DEPRECATED_KEY, true);
+    put_named_value(DEPRECATED_AT_KEY, current_timestamp());
+    
+    let fees_uref = named_uref(ACCUMULATED_FEES_KEY, ErrorCode::MissingNamedKey);
+    let fees: U512 = read_uref(fees_uref);
+    storage::write(fees_uref, U512::zero());
+    pay_from_contract_purse(destination, fees);
+}
+
+/// Returns the caller's remaining collateral on option `id`, CSPR and
+/// tokens, whatever the option's state, once the registry is deprecated.
+/// Syndicators of a syndicated option each take back their share, and for a
+/// pool-backed option anyone may call it to return the collateral to the
+/// pool's free liquidity, where LPs withdraw it. Reverts with
+/// `ContractNotDeprecated` before then.
+#[no_mangle]
+pub extern "C" fn withdraw_my_collateral() {
+    let deprecated: bool = read_named_value(DEPRECATED_KEY);
+    if !deprecated {
+        runtime::revert(ErrorCode::ContractNotDeprecated);
+    }
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    if is_syndicated(id) {
+        let syndicator = require_syndicator(id);
+        let withdrawn = take_syndicate_collateral(id, &syndicator);
+        pay_from_contract_purse(syndicator, withdrawn);
+        return;
+    }
+    
+    let pool_backed = is_pool_backed(id);
+    let writer = if pool_backed { POOL_WRITER } else { require_writer(id) };
+    let collateral_uref = named_uref(&option_collateral_key(id), ErrorCode::OptionNotFound);
+    let locked: U512 = read_uref(collateral_uref);
+    storage::write(collateral_uref, U512::zero());
+    if pool_backed {
+        release_pool_collateral(locked);
+    } else {
+        pay_from_contract_purse(writer, locked);
+        release_token_collateral(id, writer);
+    }
+}
+
+/// Authorises `greeks_oracle` to call `update_greeks` alongside the admin, or
+/// revokes the current one when `None` (admin only).
+#[no_mangle]
+pub extern "C" fn set_greeks_oracle() {
+    require_admin();
+    
+    let greeks_oracle: Option<AccountHash> = runtime::get_named_arg(ARG_GREEKS_ORACLE);
+    storage::write(named_uref(GREEKS_ORACLE_KEY, ErrorCode::MissingNamedKey), greeks_oracle);
+}
+
+/// Stores option `id`'s delta, gamma, theta and vega, each scaled by 1e8,
+/// under `option_{id}_delta` and so on (admin or greeks oracle only).
+#[no_mangle]
+pub extern "C" fn update_greeks() {
+    require_not_paused();
+    require_admin_or_delegate(GREEKS_ORACLE_KEY);
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    for greek in [ARG_DELTA, ARG_GAMMA, ARG_THETA, ARG_VEGA] {
+        let value: i64 = runtime::get_named_arg(greek);
+        put_named_value(&option_greek_key(id, greek), value);
+    }
+    put_named_value(&option_greeks_stale_key(id), false);
+}
+
+/// Authorises `vol_oracle` to call `update_iv` and update the volatility
+/// surface alongside the admin, or revokes the current one when `None`
+/// (admin only).
+#[no_mangle]
+pub extern "C" fn set_vol_oracle() {
+    require_admin();
+    
+    let vol_oracle: Option<AccountHash> = runtime::get_named_arg(ARG_VOL_ORACLE);
+    storage::write(named_uref(VOL_ORACLE_KEY, ErrorCode::MissingNamedKey), vol_oracle);
+}
+
+/// Sets option `id`'s implied volatility in basis points and flags its
+/// greeks as stale until the next `update_greeks` (admin or volatility
+/// oracle only).
+#[no_mangle]
+pub extern "C" fn update_iv() {
+    require_not_paused();
+    require_admin_or_delegate(VOL_ORACLE_KEY);
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let iv: u64 = runtime::get_named_arg(ARG_IV);
+    require_option_exists(id);
+    put_named_value(&option_iv_key(id), iv);
+    put_named_value(&option_greeks_stale_key(id), true);
+}
+
+/// The implied volatility in basis points on the volatility surface at
+/// `strike` and `expiry`, or 0 if that point has not been set.
+fn vol_surface_point(strike: U256, expiry: u64) -> u64 {
+    let surface_uref = named_uref(VOL_SURFACE_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(surface_uref, &vol_surface_key(strike, expiry))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or(0)
+}
+
+fn set_vol_surface_point(strike: U256, expiry: u64, iv_bps: u64) {
+    let surface_uref = named_uref(VOL_SURFACE_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(surface_uref, &vol_surface_key(strike, expiry), iv_bps);
+}
+
+/// Sets the volatility surface's implied volatility in basis points at
+/// `strike` and `expiry`. Options created there without an `iv` take it
+/// (admin or volatility oracle only).
+#[no_mangle]
+pub extern "C" fn update_volatility_surface_point() {
+    require_not_paused();
+    require_admin_or_delegate(VOL_ORACLE_KEY);
+    
+    let strike: U256 = runtime::get_named_arg(ARG_STRIKE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let iv_bps: u64 = runtime::get_named_arg(ARG_IV_BPS);
+    set_vol_surface_point(strike, expiry, iv_bps);
+}
+
+/// Sets one volatility surface point per index of `strike_prices`,
+/// `expiries` and `ivs_bps`, which must be the same length (admin or
+/// volatility oracle only).
+#[no_mangle]
+pub extern "C" fn update_volatility_surface_batch() {
+    require_not_paused();
+    require_admin_or_delegate(VOL_ORACLE_KEY);
+    
+    let strike_prices: Vec<U256> = runtime::get_named_arg(ARG_STRIKE_PRICES);
+    let expiries: Vec<u64> = runtime::get_named_arg(ARG_EXPIRIES);
+    let ivs_bps: Vec<u64> = runtime::get_named_arg(ARG_IVS_BPS);
+    if strike_prices.len() != expiries.len() || strike_prices.len() != ivs_bps.len() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    for ((strike, expiry), iv_bps) in strike_prices.into_iter().zip(expiries).zip(ivs_bps) {
+        set_vol_surface_point(strike, expiry, iv_bps);
+    }
+}
+
+/// Proposes `new_admin` as the next admin. Takes effect only once the
+/// proposed account calls `accept_admin`.
+#[no_mangle]
+pub extern "C" fn transfer_admin() {
+    require_admin();
+    
+    let new_admin: AccountHash = runtime::get_named_arg(ARG_NEW_ADMIN);
+    storage::write(named_uref(PENDING_ADMIN_KEY, ErrorCode::MissingNamedKey), Some(new_admin));
+}
+
+#[no_mangle]
+pub extern "C" fn accept_admin() {
+    let pending_uref = named_uref(PENDING_ADMIN_KEY, ErrorCode::MissingNamedKey);
+    let pending: Option<AccountHash> = read_uref(pending_uref);
+    let caller = runtime::get_caller();
+    if pending != Some(caller) {
+        runtime::revert(ErrorCode::NotPendingAdmin);
+    }
+    
+    storage::write(named_uref(ADMIN_KEY, ErrorCode::MissingNamedKey), caller);
+    storage::write(pending_uref, Option::<AccountHash>::None);
+}
+
+/// Pays every fee taken on settlements so far to the admin.
+#[no_mangle]
+pub extern "C" fn collect_fees() {
+    require_admin();
+    
+    let fees_uref = named_uref(ACCUMULATED_FEES_KEY, ErrorCode::MissingNamedKey);
+    let fees: U512 = read_uref(fees_uref);
+    storage::write(fees_uref, U512::zero());
+    pay_from_contract_purse(runtime::get_caller(), fees);
+}
+
+/// Pays `amount` of the accumulated fees to `destination`, reverting with
+/// `InsufficientAccumulatedFees` if more than `accumulated_fees` is asked
+/// for (admin only).
+#[no_mangle]
+pub extern "C" fn withdraw_fees_to_treasury() {
+    require_admin();
+    
+    let amount: U512 = runtime::get_named_arg(ARG_AMOUNT);
+    let destination: AccountHash = runtime::get_named_arg(ARG_DESTINATION);
+    let fees_uref = named_uref(ACCUMULATED_FEES_KEY, ErrorCode::MissingNamedKey);
+    let fees: U512 = read_uref(fees_uref);
+    if amount > fees {
+        runtime::revert(ErrorCode::InsufficientAccumulatedFees);
+    }
+    storage::write(fees_uref, fees - amount);
+    pay_from_contract_purse(destination, amount);
+}
+
+/// Returns the fees taken on settlements that have not been paid out yet.
+#[no_mangle]
+pub extern "C" fn get_accumulated_fees() {
+    let fees: U512 = read_named_value(ACCUMULATED_FEES_KEY);
+    ret(fees);
+}
+
+/// Returns every settlement fee and early exercise penalty taken since
+/// `total_fees_collected` was created, including those already paid out.
+#[no_mangle]
+pub extern "C" fn get_total_fees_collected() {
+    let fees: U512 = read_named_value(TOTAL_FEES_COLLECTED_KEY);
+    ret(fees);
+}
+
+/// Sets the most notional, strike times units, of open options that
+/// `create_option` lets a single account have created (admin only).
+/// Lowering it below an account's notional only stops that account creating
+/// more.
+#[no_mangle]
+pub extern "C" fn set_max_notional_per_account() {
+    require_admin();
+    
+    let max_notional: U256 = runtime::get_named_arg(ARG_MAX_NOTIONAL_PER_ACCOUNT);
+    put_named_value(MAX_NOTIONAL_PER_ACCOUNT_KEY, max_notional);
+}
+
+/// Returns `max_notional_per_account`.
+#[no_mangle]
+pub extern "C" fn get_position_limit() {
+    let max_notional: U256 = read_named_value(MAX_NOTIONAL_PER_ACCOUNT_KEY);
+    ret(max_notional);
+}
+
+/// Returns the notional of the open options `account` created with
+/// `create_option`.
+#[no_mangle]
+pub extern "C" fn get_user_notional() {
+    let account: AccountHash = runtime::get_named_arg(ARG_ACCOUNT);
+    ret(user_notional(&account));
+}
+
+/// Sets the share of the payout, in basis points, kept as a fee when an
+/// American option is exercised early (admin only).
+#[no_mangle]
+pub extern "C" fn set_early_exercise_penalty() {
+    require_admin();
+    
+    let penalty_bps: u32 = runtime::get_named_arg(ARG_EARLY_EXERCISE_PENALTY_BPS);
+    if u64::from(penalty_bps) > BPS_DENOMINATOR {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    put_named_value(EARLY_EXERCISE_PENALTY_BPS_KEY, penalty_bps);
+}
+
+/// Turns checking `create_option` premiums against their theoretical value
+/// on or off (admin only).
+#[no_mangle]
+pub extern "C" fn set_price_validation() {
+    require_admin();
+    
+    let enabled: bool = runtime::get_named_arg(ARG_PRICE_VALIDATION_ENABLED);
+    put_named_value(PRICE_VALIDATION_ENABLED_KEY, enabled);
+}
+
+/// Sets how far, in basis points of the theoretical premium, a
+/// `create_option` premium may be from it while price validation is on
+/// (admin only).
+#[no_mangle]
+pub extern "C" fn set_max_price_deviation() {
+    require_admin();
+    
+    let max_deviation_bps: u32 = runtime::get_named_arg(ARG_MAX_PRICE_DEVIATION_BPS);
+    put_named_value(MAX_PRICE_DEVIATION_BPS_KEY, max_deviation_bps);
+}
+
+/// Allows `keeper` to call `keeper_expire_options` and
+/// `process_scheduled_exercises` (admin only).
+#[no_mangle]
+pub extern "C" fn register_keeper() {
+    require_admin();
+    
+    let keeper: AccountHash = runtime::get_named_arg(ARG_KEEPER);
+    set_account_listed(KEEPER_REGISTRY_DICT, &keeper, true);
+}
+
+/// Removes `keeper` from the keeper registry. The admin can remove any
+/// keeper; a keeper can remove itself.
+#[no_mangle]
+pub extern "C" fn deregister_keeper() {
+    let keeper: AccountHash = runtime::get_named_arg(ARG_KEEPER);
+    if keeper != runtime::get_caller() {
+        require_admin();
+    }
+    set_account_listed(KEEPER_REGISTRY_DICT, &keeper, false);
+}
+
+/// Adds `writer` to the writer whitelist (admin only).
+#[no_mangle]
+pub extern "C" fn add_writer() {
+    require_admin();
+    
+    let writer: AccountHash = runtime::get_named_arg(ARG_WRITER);
+    set_account_listed(WRITER_WHITELIST_DICT, &writer, true);
+}
+
+/// Removes `writer` from the writer whitelist (admin only). Options it has
+/// already written are unaffected.
+#[no_mangle]
+pub extern "C" fn remove_writer() {
+    require_admin();
+    
+    let writer: AccountHash = runtime::get_named_arg(ARG_WRITER);
+    set_account_listed(WRITER_WHITELIST_DICT, &writer, false);
+}
+
+/// Allows `publisher` to push prices with `update_oracle_price` (admin only).
+#[no_mangle]
+pub extern "C" fn add_price_publisher() {
+    require_admin();
+    
+    let publisher: AccountHash = runtime::get_named_arg(ARG_PUBLISHER);
+    set_account_listed(PRICE_PUBLISHERS_DICT, &publisher, true);
+}
+
+/// Removes `publisher` from the price publishers (admin only). The cached
+/// price it last pushed stays in use until it goes stale.
+#[no_mangle]
+pub extern "C" fn remove_price_publisher() {
+    require_admin();
+    
+    let publisher: AccountHash = runtime::get_named_arg(ARG_PUBLISHER);
+    set_account_listed(PRICE_PUBLISHERS_DICT, &publisher, false);
+}
+
+/// Caches `price` as the underlying's current price, stamped with the block
+/// time (price publishers only). From the first update on, entry points
+/// read this price instead of querying the oracle.
+#[no_mangle]
+pub extern "C" fn update_oracle_price() {
+    if !account_listed(PRICE_PUBLISHERS_DICT, &runtime::get_caller()) {
+        runtime::revert(ErrorCode::NotPricePublisher);
+    }
+    
+    let price: U256 = runtime::get_named_arg(ARG_PRICE);
+    put_named_value(ORACLE_PRICE_CACHE_KEY, price);
+    put_named_value(LAST_ORACLE_UPDATE_TIME_KEY, current_timestamp());
+}
+
+/// Records the current oracle price as a snapshot stamped with the block
+/// time, at most once per `snapshot_interval_seconds` (registered keepers
+/// only).
+#[no_mangle]
+pub extern "C" fn record_price_snapshot() {
+    require_not_paused();
+    
+    if !account_listed(KEEPER_REGISTRY_DICT, &runtime::get_caller()) {
+        runtime::revert(ErrorCode::NotKeeper);
+    }
+    
+    let count_uref = named_uref(SNAPSHOT_COUNT_KEY, ErrorCode::MissingNamedKey);
+    let count: u64 = read_uref(count_uref);
+    let now = current_timestamp();
+    if count > 0 {
+        let last: u64 = read_named_value(&price_snapshot_time_key(count - 1));
+        let interval: u64 = read_named_value(SNAPSHOT_INTERVAL_KEY);
+        if now < last.saturating_add(interval) {
+            runtime::revert(ErrorCode::SnapshotTooEarly);
+        }
+    }
+    
+    let price = fetch_oracle_price();
+    runtime::put_key(&price_snapshot_key(now), storage::new_uref(price).into());
+    runtime::put_key(&price_snapshot_time_key(count), storage::new_uref(now).into());
+    increment_u64(count_uref);
+}
+
+/// Returns the time-weighted average oracle price over the last
+/// `window_seconds`, from the recorded snapshots.
+#[no_mangle]
+pub extern "C" fn compute_twap() {
+    let window_seconds: u64 = runtime::get_named_arg(ARG_WINDOW_SECONDS);
+    ret(twap(window_seconds));
+}
+
+/// Moves every listed Active option whose exercise window has closed to
+/// Expired, skipping the rest, and pays the calling keeper
+/// `option_registry_keeper_reward` per option expired. Rewards come out of
+/// `accumulated_fees` and stop once it runs dry (registered keepers only).
+#[no_mangle]
+pub extern "C" fn keeper_expire_options() {
+    require_not_paused();
+    
+    let keeper = runtime::get_caller();
+    if !account_listed(KEEPER_REGISTRY_DICT, &keeper) {
+        runtime::revert(ErrorCode::NotKeeper);
+    }
+    
+    let ids: Vec<u64> = runtime::get_named_arg(ARG_IDS);
+    let now = current_timestamp();
+    let mut expired = 0u64;
+    for id in ids {
+        if !runtime::has_key(&option_state_key(id)) {
+            continue;
+        }
+        let state: u8 = read_option_value(&option_state_key(id));
+        let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+        let option_style: u8 = read_option_value(&(option_key(id) + "_style"));
+        if state == OPTION_STATE_ACTIVE && exercise_window_closed(expiry, option_style, now) {
+            transition(id, &[OPTION_STATE_ACTIVE], OPTION_STATE_EXPIRED);
+            expired += 1;
+        }
+    }
+    
+    pay_keeper_reward(keeper, KEEPER_REWARD_KEY, expired);
+}
+
+/// When funding for option `id` last accrued: its last collection, or its
+/// creation before the first.
+fn last_funding_time(id: u64) -> u64 {
+    let last_funding_key = option_last_funding_time_key(id);
+    if runtime::has_key(&last_funding_key) {
+        read_named_value(&last_funding_key)
+    } else {
+        read_option_value_or_default(&option_created_at_key(id))
+    }
+}
+
+/// Funding option `id` has accrued over the whole days since
+/// `last_funding_time` as `(holder_pays, amount, days)`: `funding_rate_per_day`
+/// basis points of its notional, strike times units, per day. Holders pay
+/// writers at a positive rate and writers pay holders at a negative one.
+fn accrued_funding(id: u64, now: u64) -> (bool, U512, u64) {
+    let rate: i64 = read_named_value(FUNDING_RATE_PER_DAY_KEY);
+    let days = now.saturating_sub(last_funding_time(id)) / FUNDING_PERIOD_SECS;
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let amount: U256 = read_option_value(&(option_key(id) + "_amount"));
+    let units = if amount.is_zero() { U256::one() } else { amount };
+    
+    let notional = u256_to_u512(strike_price.saturating_mul(units));
+    let per_day = notional * U512::from(rate.unsigned_abs()) / U512::from(BPS_DENOMINATOR);
+    (rate > 0, per_day * U512::from(days), days)
+}
+
+/// Settles the funding each listed Active option has accrued over whole
+/// days, skipping the rest. Funding a writer owes is drawn from the option's
+/// collateral and paid to the holder at once; funding a holder owes is added
+/// to `option_{id}_funding_owed` and paid to the writer out of the holder's
+/// next exercise or settlement payout. Anyone may call it.
+#[no_mangle]
+pub extern "C" fn collect_funding() {
+    require_not_paused();
+    
+    let ids: Vec<u64> = runtime::get_named_arg(ARG_IDS);
+    let now = current_timestamp();
+    enter_guard();
+    for id in ids {
+        if !runtime::has_key(&option_state_key(id)) {
+            continue;
+        }
+        let state: u8 = read_option_value(&option_state_key(id));
+        let (holder_pays, amount, days) = accrued_funding(id, now);
+        if state != OPTION_STATE_ACTIVE || days == 0 {
+            continue;
+        }
+        // Part days carry over to the next collection
+        let collected_until = last_funding_time(id) + days * FUNDING_PERIOD_SECS;
+        put_named_value(&option_last_funding_time_key(id), collected_until);
+        
+        if holder_pays {
+            let owed_key = option_funding_owed_key(id);
+            let owed: U512 = read_option_value_or_default(&owed_key);
+            put_named_value(&owed_key, owed + amount);
+        } else {
+            let holder: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+            pay_from_contract_purse(holder, draw_collateral(id, amount));
+        }
+    }
+    exit_guard();
+}
+
+/// Pays the writer of option `id` the funding its holder owes, up to
+/// `payout`, and returns what is left of `payout` for the holder. The pool's
+/// share is added to its value.
+fn pay_owed_funding(id: u64, payout: U512) -> U512 {
+    let owed_key = option_funding_owed_key(id);
+    let owed: U512 = read_option_value_or_default(&owed_key);
+    let paid = owed.min(payout);
+    if paid.is_zero() {
+        return payout;
+    }
+    put_named_value(&owed_key, owed - paid);
+    
+    if is_pool_backed(id) {
+        let value_uref = named_uref(POOL_TOTAL_VALUE_KEY, ErrorCode::MissingNamedKey);
+        let total_value: U512 = read_uref(value_uref);
+        storage::write(value_uref, total_value + paid);
+    } else {
+        let writer: AccountHash = read_option_value(&option_writer_key(id));
+        pay_from_contract_purse(writer, paid);
+    }
+    payout - paid
+}
+
+/// Returns the funding option `id` has accrued since it was last collected
+/// as `(holder_pays, amount)`. Only whole days count.
+#[no_mangle]
+pub extern "C" fn get_accrued_funding() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    let (holder_pays, amount, _) = accrued_funding(id, current_timestamp());
+    ret((holder_pays, amount));
+}
+
+/// Pays `keeper` the per-option reward stored under `reward_key` for `count`
+/// options, out of `accumulated_fees` and capped at what it holds.
+fn pay_keeper_reward(keeper: AccountHash, reward_key: &str, count: u64) {
+    let reward_per_option: U512 = read_named_value(reward_key);
+    let fees_uref = named_uref(ACCUMULATED_FEES_KEY, ErrorCode::MissingNamedKey);
+    let fees: U512 = read_uref(fees_uref);
+    let reward = (reward_per_option * U512::from(count)).min(fees);
+    storage::write(fees_uref, fees - reward);
+    pay_from_contract_purse(keeper, reward);
+}
+
+/// Lets keepers exercise option `id` for its owner through
+/// `process_scheduled_exercises`. The schedule is dropped if the option is
+/// transferred (owner only).
+#[no_mangle]
+pub extern "C" fn schedule_exercise() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_owner(id);
+    put_named_value(&option_auto_exercise_key(id), true);
+}
+
+/// Withdraws a `schedule_exercise` for option `id` (owner only).
+#[no_mangle]
+pub extern "C" fn cancel_scheduled_exercise() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_owner(id);
+    runtime::remove_key(&option_auto_exercise_key(id));
+}
+
+/// Exercises in full, for their owners, every listed option scheduled with
+/// `schedule_exercise` that its owner could exercise right now, skipping the
+/// rest, and pays the calling keeper
+/// `option_registry_keeper_exercise_reward` per option exercised. Rewards
+/// come out of `accumulated_fees` like `keeper_expire_options`' do
+/// (registered keepers only).
+#[no_mangle]
+pub extern "C" fn process_scheduled_exercises() {
+    require_not_paused();
+    
+    let keeper = runtime::get_caller();
+    if !account_listed(KEEPER_REGISTRY_DICT, &keeper) {
+        runtime::revert(ErrorCode::NotKeeper);
+    }
+    
+    let ids: Vec<u64> = runtime::get_named_arg(ARG_IDS);
+    let mut exercised = 0u64;
+    enter_guard();
+    for id in ids {
+        let scheduled: bool = runtime::has_key(&option_auto_exercise_key(id))
+            && read_named_value(&option_auto_exercise_key(id));
+        if !scheduled {
+            continue;
+        }
+        let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+        if exercise_blocker(id, owner).is_none() {
+            exercise(id, owner, None);
+            exercised += 1;
+        }
+    }
+    exit_guard();
+    
+    pay_keeper_reward(keeper, KEEPER_EXERCISE_REWARD_KEY, exercised);
+}
+
+/// Opts option `id` in or out of automatic exercise at expiry through
+/// `process_auto_exercises`. The choice is dropped if the option is
+/// transferred (owner only).
+#[no_mangle]
+pub extern "C" fn set_auto_exercise() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let auto_exercise: bool = runtime::get_named_arg(ARG_AUTO_EXERCISE);
+    require_option_owner(id);
+    put_named_value(&auto_exercise_pending_key(id), auto_exercise);
+}
+
+/// Settles, for their owners, the Active options expiring between
+/// `from_expiry` and `to_expiry` that were opted in with `set_auto_exercise`,
+/// whose exercise window has closed and that are in the money at their
+/// settlement price, skipping the rest; out-of-the-money options are left to
+/// expire normally. Options are found through the expiry index, and their
+/// expiry's settlement price is skipped while it can still be disputed. Pays
+/// the calling keeper `option_registry_keeper_reward` per option settled,
+/// out of `accumulated_fees` like `keeper_expire_options` (registered
+/// keepers only).
+#[no_mangle]
+pub extern "C" fn process_auto_exercises() {
+    require_not_paused();
+    
+    let keeper = runtime::get_caller();
+    if !account_listed(KEEPER_REGISTRY_DICT, &keeper) {
+        runtime::revert(ErrorCode::NotKeeper);
+    }
+    
+    let from_expiry: u64 = runtime::get_named_arg(ARG_FROM_EXPIRY);
+    let to_expiry: u64 = runtime::get_named_arg(ARG_TO_EXPIRY);
+    let now = current_timestamp();
+    let mut settled = 0u64;
+    enter_guard();
+    for bucket in (from_expiry / DAY_IN_SECONDS)..=(to_expiry / DAY_IN_SECONDS) {
+        for id in expiry_bucket_options(bucket) {
+            let pending: bool = runtime::has_key(&auto_exercise_pending_key(id))
+                && read_named_value(&auto_exercise_pending_key(id));
+            let state: u8 = read_option_value(&option_state_key(id));
+            if !pending || state != OPTION_STATE_ACTIVE {
+                continue;
+            }
+            let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+            let option_style: u8 = read_option_value(&(option_key(id) + "_style"));
+            let price_pending = runtime::has_key(&settlement_price_key(expiry))
+                && !settlement_final(expiry);
+            if !(from_expiry..=to_expiry).contains(&expiry)
+                || !exercise_window_closed(expiry, option_style, now)
+                || price_pending
+                || option_payout(id, remaining_amount(id), settlement_price(id)).is_zero()
+            {
+                continue;
+            }
+            
+            transition(id, &[OPTION_STATE_ACTIVE], OPTION_STATE_EXPIRED);
+            settle(id);
+            runtime::remove_key(&auto_exercise_pending_key(id));
+            settled += 1;
+        }
+    }
+    exit_guard();
+    
+    pay_keeper_reward(keeper, KEEPER_REWARD_KEY, settled);
+}
+
+/// Adds CSPR, moved in via `purse` and `amount`, to the LP pool and mints
+/// the caller shares in proportion to the pool's current value. The first
+/// deposit mints one share per mote.
+#[no_mangle]
+pub extern "C" fn deposit_to_pool() {
+    require_not_paused();
+    
+    let value_uref = named_uref(POOL_TOTAL_VALUE_KEY, ErrorCode::MissingNamedKey);
+    let shares_uref = named_uref(POOL_TOTAL_SHARES_KEY, ErrorCode::MissingNamedKey);
+    let total_value: U512 = read_uref(value_uref);
+    let total_shares: U512 = read_uref(shares_uref);
+    // Shares left after payouts drained the pool are worthless; minting
+    // against them would hand part of the deposit to their holders
+    if !total_shares.is_zero() && total_value.is_zero() {
+        runtime::revert(ErrorCode::InsufficientPoolLiquidity);
+    }
+    
+    let amount = transferred_value();
+    let shares = if total_shares.is_zero() {
+        amount
+    } else {
+        amount * total_shares / total_value
+    };
+    if shares.is_zero() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    let depositor = runtime::get_caller();
+    set_lp_balance(&depositor, lp_balance(&depositor) + shares);
+    storage::write(value_uref, total_value + amount);
+    storage::write(shares_uref, total_shares + shares);
+}
+
+/// Burns `shares` of the caller's LP shares and pays out their part of the
+/// pool's value. Reverts with `InsufficientPoolLiquidity` if that is more than
+/// the pool has free, i.e. not locked behind options it has written.
+#[no_mangle]
+pub extern "C" fn withdraw_from_pool() {
+    require_unpaused();
+    
+    let shares: U512 = runtime::get_named_arg(ARG_SHARES);
+    let provider = runtime::get_caller();
+    let balance = lp_balance(&provider);
+    if shares.is_zero() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    if shares > balance {
+        runtime::revert(ErrorCode::InsufficientLpShares);
+    }
+    
+    let value_uref = named_uref(POOL_TOTAL_VALUE_KEY, ErrorCode::MissingNamedKey);
+    let shares_uref = named_uref(POOL_TOTAL_SHARES_KEY, ErrorCode::MissingNamedKey);
+    let total_value: U512 = read_uref(value_uref);
+    let total_shares: U512 = read_uref(shares_uref);
+    let utilized: U512 = read_named_value(POOL_UTILIZED_COLLATERAL_KEY);
+    let amount = shares * total_value / total_shares;
+    if amount > total_value - utilized {
+        runtime::revert(ErrorCode::InsufficientPoolLiquidity);
+    }
+    
+    set_lp_balance(&provider, balance - shares);
+    storage::write(value_uref, total_value - amount);
+    storage::write(shares_uref, total_shares - shares);
+    pay_from_contract_purse(provider, amount);
+}
+
+/// Moves `amount` of the caller's LP shares to `recipient`.
+#[no_mangle]
+pub extern "C" fn transfer_lp_shares() {
+    require_not_paused();
+    
+    let recipient: AccountHash = runtime::get_named_arg(ARG_RECIPIENT);
+    let amount: U512 = runtime::get_named_arg(ARG_AMOUNT);
+    move_lp_shares(&runtime::get_caller(), &recipient, amount);
+}
+
+/// Lets `spender` move up to `amount` of the caller's LP shares with
+/// `transfer_lp_shares_from`, replacing any earlier allowance.
+#[no_mangle]
+pub extern "C" fn approve_lp_shares() {
+    require_not_paused();
+    
+    let spender: AccountHash = runtime::get_named_arg(ARG_SPENDER);
+    let amount: U512 = runtime::get_named_arg(ARG_AMOUNT);
+    set_lp_allowance(&runtime::get_caller(), &spender, amount);
+}
+
+/// Moves `amount` of `owner`'s LP shares to `recipient` out of the caller's
+/// allowance, reverting with `InsufficientLpAllowance` if it is too small.
+#[no_mangle]
+pub extern "C" fn transfer_lp_shares_from() {
+    require_not_paused();
+    
+    let owner: AccountHash = runtime::get_named_arg(ARG_OWNER);
+    let recipient: AccountHash = runtime::get_named_arg(ARG_RECIPIENT);
+    let amount: U512 = runtime::get_named_arg(ARG_AMOUNT);
+    let spender = runtime::get_caller();
+    let allowance = lp_allowance(&owner, &spender);
+    if amount > allowance {
+        runtime::revert(ErrorCode::InsufficientLpAllowance);
+    }
+    set_lp_allowance(&owner, &spender, allowance - amount);
+    move_lp_shares(&owner, &recipient, amount);
+}
+
+/// Reads the terms of an option the LP pool writes from the named args
+/// shared by `write_option_from_pool` and `buy_option_from_pool`, returning
+/// its id and terms. Its collateral is `strike_price * option_amount`.
+fn pool_option_args() -> (u64, OptionTerms) {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    // true = Call, false = Put
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    let amount: U256 = runtime::get_named_arg(ARG_OPTION_AMOUNT);
+    // 0 = European, 1 = American; defaults to American
+    let option_style: u8 = runtime::try_get_named_arg(ARG_OPTION_STYLE).unwrap_or(OPTION_STYLE_AMERICAN);
+    // Implied volatility in basis points (5000 = 50%); unknown by default
+    let iv: u64 = runtime::try_get_named_arg(ARG_IV).unwrap_or(0);
+    
+    let collateral = u256_to_u512(strike_price) * u256_to_u512(amount);
+    if collateral.is_zero() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    let terms = OptionTerms {
+        strike_price,
+        expiry,
+        option_type,
+        option_style,
+        activation: 0,
+        premium: U512::zero(),
+        amount,
+        collateral,
+        underlying: None,
+        underlying_amount: U256::zero(),
+        series_id: None,
+        iv,
+        exercise_dates: Vec::new(),
+    };
+    (id, terms)
+}
+
+/// Adds `premium`, already in the contract purse, to the pool's value, locks
+/// `terms.collateral` of pool value and stores option `id` for `owner` with
+/// the pool as writer. Reverts with `PoolUtilizationExceeded` if that would
+/// take the pool's utilized collateral above
+/// `option_registry_max_utilization_bps` of its value.
+fn write_pool_option(id: u64, terms: OptionTerms, owner: AccountHash, premium: U512) {
+    let value_uref = named_uref(POOL_TOTAL_VALUE_KEY, ErrorCode::MissingNamedKey);
+    let total_value: U512 = read_uref(value_uref);
+    let total_value = total_value + premium;
+    storage::write(value_uref, total_value);
+    
+    let utilized_uref = named_uref(POOL_UTILIZED_COLLATERAL_KEY, ErrorCode::MissingNamedKey);
+    let utilized: U512 = read_uref(utilized_uref);
+    let max_utilization_bps: u32 = read_named_value(MAX_UTILIZATION_BPS_KEY);
+    let cap = total_value * U512::from(max_utilization_bps) / U512::from(BPS_DENOMINATOR);
+    if utilized + terms.collateral > cap {
+        runtime::revert(ErrorCode::PoolUtilizationExceeded);
+    }
+    storage::write(utilized_uref, utilized + terms.collateral);
+    
+    store_option(id, terms, owner, POOL_WRITER);
+    register_option(id);
+    events::emit(EventType::OptionCreated, id);
+}
+
+/// The LP pool's AMM premium for an option struck at `strike_price` on
+/// `amount` units expiring at `expiry`, given the pool's current value and
+/// utilization, on top of its intrinsic value at the oracle price.
+fn amm_option_price(option_type: bool, strike_price: U256, amount: U256, expiry: u64) -> U512 {
+    let total_value: U512 = read_named_value(POOL_TOTAL_VALUE_KEY);
+    let utilized: U512 = read_named_value(POOL_UTILIZED_COLLATERAL_KEY);
+    let lambda_bps: u32 = read_named_value(AMM_LAMBDA_BPS_KEY);
+    let time_value = pricing::amm_premium(
+        total_value,
+        u256_to_u512(strike_price) * u256_to_u512(amount),
+        total_value - utilized,
+        lambda_bps,
+        expiry.saturating_sub(current_timestamp()),
+    );
+    let intrinsic = intrinsic_value(option_type, strike_price, fetch_oracle_price());
+    time_value + u256_to_u512(intrinsic) * u256_to_u512(amount)
+}
+
+/// Writes option `id` for `owner` (the caller by default) with the LP pool as
+/// writer, locking `strike_price * option_amount` of pool value as its
+/// collateral while utilization stays within
+/// `option_registry_max_utilization_bps`. A premium, paid in via `purse` and
+/// `amount`, goes straight to the pool (admin or registered keepers only).
+#[no_mangle]
+pub extern "C" fn write_option_from_pool() {
+    require_not_paused();
+    
+    let caller = runtime::get_caller();
+    if !account_listed(KEEPER_REGISTRY_DICT, &caller) {
+        require_admin();
+    }
+    
+    let (id, terms) = pool_option_args();
+    // Buyer of the option; defaults to the caller
+    let owner: AccountHash = runtime::try_get_named_arg(ARG_OWNER).unwrap_or(caller);
+    let premium: U512 = runtime::try_get_named_arg(ARG_PREMIUM).unwrap_or_default();
+    if !premium.is_zero() && transferred_value() != premium {
+        runtime::revert(ErrorCode::IncorrectPremium);
+    }
+    write_pool_option(id, terms, owner, premium);
+}
+
+/// Sells the caller option `id`, written by the LP pool, for exactly the
+/// AMM price `get_amm_option_price` quotes, paid in via `purse` and `amount`.
+/// The premium goes to the pool. The option must expire at least
+/// `MIN_POOL_OPTION_TERM_SECS` from now.
+#[no_mangle]
+pub extern "C" fn buy_option_from_pool() {
+    require_not_paused();
+    
+    let (id, terms) = pool_option_args();
+    if terms.expiry < current_timestamp().saturating_add(MIN_POOL_OPTION_TERM_SECS) {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    let price = amm_option_price(terms.option_type, terms.strike_price, terms.amount, terms.expiry);
+    let paid = if price.is_zero() { U512::zero() } else { transferred_value() };
+    if paid != price {
+        runtime::revert(ErrorCode::IncorrectPremium);
+    }
+    write_pool_option(id, terms, runtime::get_caller(), paid);
+}
+
+/// An order book entry: intent to buy or sell `amount` units of an option at
+/// `price` in total, at most for a buy and at least for a sell.
+struct Order {
+    placer: AccountHash,
+    strike_price: U256,
+    expiry: u64,
+    option_type: bool,
+    is_buy: bool,
+    price: U512,
+    amount: U256,
+}
+
+impl Order {
+    /// CSPR held by the contract while the order is open: the whole price
+    /// for a buy, and for a sell the `strike * amount` collateral
+    /// `create_option` would lock for a call or a put.
+    fn escrow(&self) -> U512 {
+        if self.is_buy {
+            self.price
+        } else {
+            u256_to_u512(self.strike_price) * u256_to_u512(self.amount)
+        }
+    }
+}
+
+fn store_order(order_id: u64, order: &Order) {
+    let key_name = order_key(order_id);
+    runtime::put_key(&key_name, storage::new_uref(order.placer).into());
+    runtime::put_key(&(key_name.clone() + "_strike"), storage::new_uref(order.strike_price).into());
+    runtime::put_key(&(key_name.clone() + "_expiry"), storage::new_uref(order.expiry).into());
+    runtime::put_key(&(key_name.clone() + "_type"), storage::new_uref(order.option_type).into());
+    runtime::put_key(&(key_name.clone() + "_is_buy"), storage::new_uref(order.is_buy).into());
+    runtime::put_key(&(key_name.clone() + "_price"), storage::new_uref(order.price).into());
+    runtime::put_key(&(key_name + "_amount"), storage::new_uref(order.amount).into());
+    runtime::put_key(&order_open_key(order_id), storage::new_uref(true).into());
+}
+
+/// Loads order `order_id`, reverting with `OrderNotFound` if it was never
+/// placed and `OrderNotOpen` if it was cancelled or matched.
+fn read_open_order(order_id: u64) -> Order {
+    let key_name = order_key(order_id);
+    let placer: AccountHash = read_uref(named_uref(&key_name, ErrorCode::OrderNotFound));
+    let open: bool = read_named_value(&order_open_key(order_id));
+    if !open {
+        runtime::revert(ErrorCode::OrderNotOpen);
+    }
+    Order {
+        placer,
+        strike_price: read_named_value(&(key_name.clone() + "_strike")),
+        expiry: read_named_value(&(key_name.clone() + "_expiry")),
+        option_type: read_named_value(&(key_name.clone() + "_type")),
+        is_buy: read_named_value(&(key_name.clone() + "_is_buy")),
+        price: read_named_value(&(key_name.clone() + "_price")),
+        amount: read_named_value(&(key_name + "_amount")),
+    }
+}
+
+/// Open order ids on one side of the book, best price first (highest bid,
+/// lowest ask) and earliest first at the same price.
+fn order_book_side(key: &str) -> Vec<u64> {
+    let book_uref = named_uref(ORDER_BOOK_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(book_uref, key)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_default()
+}
+
+fn order_price(order_id: u64) -> U512 {
+    read_named_value(&(order_key(order_id) + "_price"))
+}
+
+/// Inserts order `order_id` into its side of the book behind every order
+/// priced at least as well, so the best price is always read first.
+fn add_to_order_book(order_id: u64, order: &Order) {
+    let key = order_book_key(order.expiry, order.strike_price, order.option_type, order.is_buy);
+    let mut ids = order_book_side(&key);
+    let position = ids.partition_point(|&resting| {
+        let price = order_price(resting);
+        if order.is_buy { price >= order.price } else { price <= order.price }
+    });
+    ids.insert(position, order_id);
+    let book_uref = named_uref(ORDER_BOOK_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(book_uref, &key, ids);
+}
+
+/// Closes order `order_id` and takes it off the book.
+fn close_order(order_id: u64, order: &Order) {
+    storage::write(named_uref(&order_open_key(order_id), ErrorCode::OrderNotFound), false);
+    let key = order_book_key(order.expiry, order.strike_price, order.option_type, order.is_buy);
+    let mut ids = order_book_side(&key);
+    ids.retain(|&resting| resting != order_id);
+    let book_uref = named_uref(ORDER_BOOK_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(book_uref, &key, ids);
+}
+
+/// Price of the best open order on one side of the book for options struck
+/// at the `strike` argument and expiring at `expiry`; `None` when empty.
+fn best_price(is_buy: bool) -> Option<U512> {
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    let ids = order_book_side(&order_book_key(expiry, strike_price, option_type, is_buy));
+    ids.first().map(|&order_id| order_price(order_id))
+}
+
+/// Returns the highest open buy price for the given strike, expiry and
+/// option type, or `None` if there are no bids.
+#[no_mangle]
+pub extern "C" fn get_best_bid() {
+    ret(best_price(true));
+}
+
+/// Returns the lowest open sell price for the given strike, expiry and
+/// option type, or `None` if there are no asks.
+#[no_mangle]
+pub extern "C" fn get_best_ask() {
+    ret(best_price(false));
+}
+
+/// Posts an order to buy (`is_buy`, paying up to `max_price`) or sell (for
+/// at least `min_price`) `amount` units of an option struck at `strike`
+/// expiring at `expiry`, and returns its order id. The order's escrow, the
+/// whole `max_price` for a buy or `strike * amount` collateral for a sell,
+/// is moved in from `purse`. Sellers must be whitelisted writers while
+/// the whitelist is enabled.
+#[no_mangle]
+pub extern "C" fn place_option_order() {
+    require_not_paused();
+    
+    let is_buy: bool = runtime::get_named_arg(ARG_IS_BUY);
+    let price: U512 = if is_buy {
+        runtime::get_named_arg(ARG_MAX_PRICE)
+    } else {
+        require_whitelisted_writer();
+        runtime::get_named_arg(ARG_MIN_PRICE)
+    };
+    let order = Order {
+        placer: runtime::get_caller(),
+        strike_price: runtime::get_named_arg(ARG_STRIKE),
+        expiry: runtime::get_named_arg(ARG_EXPIRY),
+        option_type: runtime::get_named_arg(ARG_OPTION_TYPE),
+        is_buy,
+        price,
+        amount: runtime::get_named_arg(ARG_AMOUNT),
+    };
+    if order.amount.is_zero() {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    let escrow = order.escrow();
+    if !escrow.is_zero() {
+        let source: URef = runtime::get_named_arg(ARG_PURSE);
+        let contract_purse = named_uref(CONTRACT_PURSE_KEY, ErrorCode::MissingNamedKey);
+        system::transfer_from_purse_to_purse(source, contract_purse, escrow, None)
+            .unwrap_or_revert_with(ErrorCode::TransferFailed);
+    }
+    
+    let count_uref = named_counter(ORDER_COUNT_KEY);
+    let order_id: u64 = read_uref(count_uref);
+    store_order(order_id, &order);
+    add_to_order_book(order_id, &order);
+    increment_u64(count_uref);
+    ret(order_id);
+}
+
+/// Withdraws open order `order_id` and refunds its escrow (placer only).
+#[no_mangle]
+pub extern "C" fn cancel_order() {
+    require_not_paused();
+    
+    let order_id: u64 = runtime::get_named_arg(ARG_ORDER_ID);
+    enter_guard();
+    
+    let order = read_open_order(order_id);
+    if order.placer != runtime::get_caller() {
+        runtime::revert(ErrorCode::NotOrderOwner);
+    }
+    close_order(order_id, &order);
+    pay_from_contract_purse(order.placer, order.escrow());
+    
+    exit_guard();
+}
+
+/// Matches open buy order `buy_order_id` with open sell order
+/// `sell_order_id` for the same option terms, reverting with
+/// `OrdersDoNotMatch` unless the buyer's maximum reaches the seller's
+/// minimum. Creates option `id` owned by the buyer and written by the seller,
+/// at the price of whichever order was placed first; that premium is
+/// escrowed for the writer, the sell escrow becomes the collateral and the
+/// rest of the buy escrow goes back to the buyer. Anyone may call it.
+#[no_mangle]
+pub extern "C" fn match_orders() {
+    require_not_paused();
+    
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let buy_order_id: u64 = runtime::get_named_arg(ARG_BUY_ORDER_ID);
+    let sell_order_id: u64 = runtime::get_named_arg(ARG_SELL_ORDER_ID);
+    enter_guard();
+    
+    let buy = read_open_order(buy_order_id);
+    let sell = read_open_order(sell_order_id);
+    let same_terms = buy.strike_price == sell.strike_price
+        && buy.expiry == sell.expiry
+        && buy.option_type == sell.option_type
+        && buy.amount == sell.amount;
+    if !buy.is_buy || sell.is_buy || !same_terms || buy.price < sell.price {
+        runtime::revert(ErrorCode::OrdersDoNotMatch);
+    }
+    close_order(buy_order_id, &buy);
+    close_order(sell_order_id, &sell);
+    
+    let price = if buy_order_id < sell_order_id { buy.price } else { sell.price };
+    let terms = OptionTerms {
+        strike_price: sell.strike_price,
+        expiry: sell.expiry,
+        option_type: sell.option_type,
+        option_style: OPTION_STYLE_AMERICAN,
+        activation: 0,
+        premium: price,
+        amount: sell.amount,
+        collateral: sell.escrow(),
+        underlying: None,
+        underlying_amount: U256::zero(),
+        series_id: None,
+        iv: 0,
+        exercise_dates: Vec::new(),
+    };
+    count_notional(id, sell.placer, sell.strike_price, sell.amount);
+    store_option(id, terms, buy.placer, sell.placer);
+    register_option(id);
+    events::emit(EventType::OptionCreated, id);
+    pay_from_contract_purse(buy.placer, buy.price - price);
+    
+    exit_guard();
+}
+
+fn proposal_key(nonce: u64) -> String {
+    let mut key = String::from("proposal_");
+    key.push_str(&nonce.to_string());
+    key
+}
+
+fn proposal_eta_key(nonce: u64) -> String {
+    proposal_key(nonce) + "_eta"
+}
+
+fn proposal_description_key(nonce: u64) -> String {
+    proposal_key(nonce) + "_description"
+}
+
+fn proposal_votes_key(nonce: u64) -> String {
+    proposal_key(nonce) + "_votes"
+}
+
+fn proposal_voter_key(nonce: u64, voter: &AccountHash) -> String {
+    let mut key = proposal_key(nonce);
+    key.push_str("_voter_");
+    key.push_str(&account_hex(voter));
+    key
+}
+
+/// Reverts with `InvalidParameter` unless `value` is allowed for the
+/// governed parameter `param_key`.
+fn validate_parameter(param_key: &str, value: u64) {
+    let valid = match param_key {
+        PARAM_FEE_BPS => value <= BPS_DENOMINATOR,
+        PARAM_ORACLE_STALENESS_THRESHOLD => value > 0,
+        _ => false,
+    };
+    if !valid {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+}
+
+/// The eta of pending proposal `nonce`, reverting with `ProposalNotFound` if
+/// it was never made or has been executed or cancelled.
+fn pending_proposal_eta(nonce: u64) -> u64 {
+    let proposals = named_uref(PROPOSALS_DICT, ErrorCode::MissingNamedKey);
+    let eta: Option<Option<u64>> = storage::dictionary_get(proposals, &proposal_eta_key(nonce))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    eta.flatten().unwrap_or_revert_with(ErrorCode::ProposalNotFound)
+}
+
+/// Queues a change of `param_key` to `param_value`, executable 48 hours from
+/// now. The proposal's nonce is the `proposal_count` before the call (admin
+/// only). Only `fee_bps` and `oracle_staleness_threshold_seconds` can be
+/// governed.
+#[no_mangle]
+pub extern "C" fn propose_parameter_change() {
+    require_admin();
+    
+    let param_key: String = runtime::get_named_arg(ARG_PARAM_KEY);
+    let param_value: u64 = runtime::get_named_arg(ARG_PARAM_VALUE);
+    validate_parameter(&param_key, param_value);
+    
+    let count_uref = named_counter(PROPOSAL_COUNT_KEY);
+    let nonce: u64 = read_uref(count_uref);
+    increment_u64(count_uref);
+    
+    let proposals = named_uref(PROPOSALS_DICT, ErrorCode::MissingNamedKey);
+    let eta = current_timestamp().saturating_add(GOVERNANCE_DELAY_SECS);
+    storage::dictionary_put(proposals, &proposal_key(nonce), (param_key, param_value));
+    storage::dictionary_put(proposals, &proposal_eta_key(nonce), Some(eta));
+}
+
+/// Applies pending proposal `nonce` once its eta has passed (admin only).
+#[no_mangle]
+pub extern "C" fn execute_parameter_change() {
+    require_admin();
+    
+    let nonce: u64 = runtime::get_named_arg(ARG_NONCE);
+    apply_proposal(nonce);
+}
+
+/// Applies pending proposal `nonce`'s parameter change and clears its eta,
+/// reverting with `ProposalNotReady` before the eta.
+fn apply_proposal(nonce: u64) {
+    if current_timestamp() < pending_proposal_eta(nonce) {
+        runtime::revert(ErrorCode::ProposalNotReady);
+    }
+    
+    let proposals = named_uref(PROPOSALS_DICT, ErrorCode::MissingNamedKey);
+    let (param_key, param_value): (String, u64) =
+        storage::dictionary_get(proposals, &proposal_key(nonce))
+            .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+            .unwrap_or_revert_with(ErrorCode::ProposalNotFound);
+    match param_key.as_str() {
+        PARAM_FEE_BPS => {
+            let fee_bps = u32::try_from(param_value).ok().unwrap_or_revert_with(ErrorCode::InvalidParameter);
+            storage::write(named_uref(FEE_BPS_KEY, ErrorCode::MissingNamedKey), fee_bps);
+        }
+        PARAM_ORACLE_STALENESS_THRESHOLD => {
+            put_named_value(ORACLE_STALENESS_THRESHOLD_KEY, param_value);
+        }
+        _ => runtime::revert(ErrorCode::InvalidParameter),
+    }
+    storage::dictionary_put(proposals, &proposal_eta_key(nonce), Option::<u64>::None);
+}
+
+/// Drops pending proposal `nonce` without applying it (admin only).
+#[no_mangle]
+pub extern "C" fn cancel_parameter_change() {
+    require_admin();
+    
+    let nonce: u64 = runtime::get_named_arg(ARG_NONCE);
+    drop_proposal(nonce);
+}
+
+fn drop_proposal(nonce: u64) {
+    pending_proposal_eta(nonce);
+    let proposals = named_uref(PROPOSALS_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(proposals, &proposal_eta_key(nonce), Option::<u64>::None);
+}
+
+/// Reverts with `NotWhitelisted` unless the caller is on the writer
+/// whitelist, which stands in for governance token holders. Unlike
+/// `require_whitelisted_writer` this applies even while the whitelist is
+/// disabled.
+fn require_governance_voter() -> AccountHash {
+    let voter = runtime::get_caller();
+    if !account_listed(WRITER_WHITELIST_DICT, &voter) {
+        runtime::revert(ErrorCode::NotWhitelisted);
+    }
+    voter
+}
+
+/// The votes pending governance proposal `nonce` has so far, reverting with
+/// `ProposalNotFound` if it is not pending or was queued by the admin through
+/// `propose_parameter_change`.
+fn governance_proposal_votes(nonce: u64) -> u32 {
+    pending_proposal_eta(nonce);
+    let proposals = named_uref(PROPOSALS_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_get(proposals, &proposal_votes_key(nonce))
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_revert_with(ErrorCode::ProposalNotFound)
+}
+
+/// Proposes changing `param_name` to `new_value` under the next proposal
+/// nonce, for governance voters to vote on (governance voters only). The
+/// parameters `propose_parameter_change` accepts can be governed.
+#[no_mangle]
+pub extern "C" fn create_governance_proposal() {
+    require_not_paused();
+    require_governance_voter();
+    
+    let param_name: String = runtime::get_named_arg(ARG_PARAM_NAME);
+    let new_value: u64 = runtime::get_named_arg(ARG_NEW_VALUE);
+    let description: String = runtime::get_named_arg(ARG_DESCRIPTION);
+    validate_parameter(&param_name, new_value);
+    if description.chars().count() > MAX_PROPOSAL_DESCRIPTION_LEN {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    let count_uref = named_counter(PROPOSAL_COUNT_KEY);
+    let nonce: u64 = read_uref(count_uref);
+    increment_u64(count_uref);
+    
+    let proposals = named_uref(PROPOSALS_DICT, ErrorCode::MissingNamedKey);
+    storage::dictionary_put(proposals, &proposal_key(nonce), (param_name, new_value));
+    storage::dictionary_put(proposals, &proposal_description_key(nonce), description);
+    storage::dictionary_put(proposals, &proposal_votes_key(nonce), 0u32);
+    storage::dictionary_put(proposals, &proposal_eta_key(nonce), Some(AWAITING_VOTES_ETA));
+}
+
+/// Records the caller's vote for pending governance proposal `proposal_id`.
+/// The vote that brings it to `governance_vote_threshold` starts its 48-hour
+/// timelock (governance voters only, once per proposal).
+#[no_mangle]
+pub extern "C" fn vote_for_proposal() {
+    require_not_paused();
+    let voter = require_governance_voter();
+    
+    let nonce: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    let votes = governance_proposal_votes(nonce) + 1;
+    let proposals = named_uref(PROPOSALS_DICT, ErrorCode::MissingNamedKey);
+    let voter_key = proposal_voter_key(nonce, &voter);
+    let voted: Option<bool> = storage::dictionary_get(proposals, &voter_key)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    if voted.is_some() {
+        runtime::revert(ErrorCode::AlreadyVoted);
+    }
+    storage::dictionary_put(proposals, &voter_key, true);
+    storage::dictionary_put(proposals, &proposal_votes_key(nonce), votes);
+    
+    let threshold: u32 = read_named_value(GOVERNANCE_VOTE_THRESHOLD_KEY);
+    if votes >= threshold && pending_proposal_eta(nonce) == AWAITING_VOTES_ETA {
+        let eta = current_timestamp().saturating_add(GOVERNANCE_DELAY_SECS);
+        storage::dictionary_put(proposals, &proposal_eta_key(nonce), Some(eta));
+    }
+}
+
+/// Applies governance proposal `proposal_id` once it has the votes and its
+/// timelock has passed, reverting with `ProposalNotReady` until then. Anyone
+/// may call it.
+#[no_mangle]
+pub extern "C" fn execute_proposal() {
+    require_not_paused();
+    
+    let nonce: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    governance_proposal_votes(nonce);
+    apply_proposal(nonce);
+}
+
+/// Drops pending governance proposal `proposal_id` without applying it
+/// (admin only).
+#[no_mangle]
+pub extern "C" fn cancel_proposal() {
+    require_admin();
+    
+    let nonce: u64 = runtime::get_named_arg(ARG_PROPOSAL_ID);
+    governance_proposal_votes(nonce);
+    drop_proposal(nonce);
+}
+
+/// Records the hash and version of a newly added contract version and creates
+/// the named keys the previous version predates. Called by the upgrading
+/// session on the new version, after `add_contract_version` has carried the
+/// previous version's named keys over (admin only).
+#[no_mangle]
+pub extern "C" fn upgrade() {
+    require_admin();
+    
+    let contract_hash: ContractHash = runtime::get_named_arg(ARG_CONTRACT_HASH);
+    let version: u32 = runtime::get_named_arg(ARG_CONTRACT_VERSION);
+    // Contracts installed before signed exercises or versioning lack these keys
+    put_named_value(CONTRACT_HASH_KEY, contract_hash);
+    put_named_value(CONTRACT_VERSION_KEY, version);
+    put_named_value(IN_PROGRESS_KEY, false);
+    for dictionary in [
+        PROPOSALS_DICT,
+        KEEPER_REGISTRY_DICT,
+        WRITER_WHITELIST_DICT,
+        LP_BALANCES_DICT,
+        LP_ALLOWANCES_DICT,
+        EXPIRY_SET_MEMBERS_DICT,
+        OPTION_CHAIN_DICT,
+        INSURANCE_CONTRIBUTIONS_DICT,
+        EXERCISE_APPROVALS_DICT,
+        EXERCISE_DELEGATES_DICT,
+        SYNDICATES_DICT,
+        ORDER_BOOK_DICT,
+        PRICE_PUBLISHERS_DICT,
+        VOL_SURFACE_DICT,
+        COLLATERAL_WHITELIST_DICT,
+        USED_PERMITS_DICT,
+        PERMIT_COLLATERAL_DICT,
+    ] {
+        if !runtime::has_key(dictionary) {
+            storage::new_dictionary(dictionary).unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+        }
+    }
+    for reward_key in [KEEPER_REWARD_KEY, KEEPER_EXERCISE_REWARD_KEY] {
+        if !runtime::has_key(reward_key) {
+            runtime::put_key(reward_key, storage::new_uref(U512::zero()).into());
+        }
+    }
+    if !runtime::has_key(WHITELIST_ENABLED_KEY) {
+        runtime::put_key(WHITELIST_ENABLED_KEY, storage::new_uref(false).into());
+    }
+    if !runtime::has_key(DEPRECATED_KEY) {
+        runtime::put_key(DEPRECATED_KEY, storage::new_uref(false).into());
+    }
+    if !runtime::has_key(AMM_LAMBDA_BPS_KEY) {
+        runtime::put_key(AMM_LAMBDA_BPS_KEY, storage::new_uref(DEFAULT_AMM_LAMBDA_BPS).into());
+    }
+    for (key, default_bps) in [
+        (LIQUIDATION_THRESHOLD_BPS_KEY, DEFAULT_LIQUIDATION_THRESHOLD_BPS),
+        (LIQUIDATION_BONUS_BPS_KEY, DEFAULT_LIQUIDATION_BONUS_BPS),
+    ] {
+        if !runtime::has_key(key) {
+            runtime::put_key(key, storage::new_uref(default_bps).into());
+        }
+    }
+    if !runtime::has_key(MAX_UTILIZATION_BPS_KEY) {
+        let max_utilization_bps = BPS_DENOMINATOR as u32;
+        runtime::put_key(MAX_UTILIZATION_BPS_KEY, storage::new_uref(max_utilization_bps).into());
+    }
+    for pool_key in [POOL_TOTAL_VALUE_KEY, POOL_TOTAL_SHARES_KEY, POOL_UTILIZED_COLLATERAL_KEY] {
+        if !runtime::has_key(pool_key) {
+            runtime::put_key(pool_key, storage::new_uref(U512::zero()).into());
+        }
+    }
+    if !runtime::has_key(INSURANCE_FUND_BALANCE_KEY) {
+        runtime::put_key(INSURANCE_FUND_BALANCE_KEY, storage::new_uref(U512::zero()).into());
+    }
+    if !runtime::has_key(EMERGENCY_REFUND_TOTAL_KEY) {
+        runtime::put_key(EMERGENCY_CANCELLED_COUNT_KEY, storage::new_uref(0u64).into());
+        runtime::put_key(EMERGENCY_REFUND_TOTAL_KEY, storage::new_uref(U512::zero()).into());
+    }
+    // Fees taken before this key existed are not counted
+    if !runtime::has_key(TOTAL_FEES_COLLECTED_KEY) {
+        runtime::put_key(TOTAL_FEES_COLLECTED_KEY, storage::new_uref(U512::zero()).into());
+    }
+    // Expiries of options created before this key existed are not listed
+    if !runtime::has_key(EXPIRY_SET_COUNT_KEY) {
+        runtime::put_key(EXPIRY_SET_COUNT_KEY, storage::new_uref(0u64).into());
+    }
+    // State buckets kept as one list per state move into slots, and the
+    // per-state counts become their lengths. The emptied lists stay behind
+    let index_uref = named_uref(STATE_INDEX_KEY, ErrorCode::MissingNamedKey);
+    for state in OPTION_STATE_CREATED..=OPTION_STATE_SETTLED {
+        let count_key = STATE_COUNT_KEYS[usize::from(state)];
+        let legacy: Vec<u64> = storage::dictionary_get(index_uref, &state.to_string())
+            .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+            .unwrap_or_default();
+        if !legacy.is_empty() {
+            for (slot, &id) in legacy.iter().enumerate() {
+                put_state_slot(state, slot as u64, id);
+            }
+            put_named_value(count_key, legacy.len() as u64);
+            storage::dictionary_put(index_uref, &state.to_string(), Vec::<u64>::new());
+        } else if !runtime::has_key(count_key) {
+            runtime::put_key(count_key, storage::new_uref(0u64).into());
+        }
+    }
+    for oracle_key in [GREEKS_ORACLE_KEY, VOL_ORACLE_KEY] {
+        if !runtime::has_key(oracle_key) {
+            runtime::put_key(oracle_key, storage::new_uref(Option::<AccountHash>::None).into());
+        }
+    }
+    // Registries keep querying the oracle until a publisher pushes a price
+    if !runtime::has_key(LAST_ORACLE_UPDATE_TIME_KEY) {
+        runtime::put_key(ORACLE_PRICE_CACHE_KEY, storage::new_uref(U256::zero()).into());
+        runtime::put_key(LAST_ORACLE_UPDATE_TIME_KEY, storage::new_uref(0u64).into());
+    }
+    if !runtime::has_key(ORACLE_STALENESS_THRESHOLD_KEY) {
+        let threshold = DEFAULT_ORACLE_STALENESS_THRESHOLD_SECS;
+        runtime::put_key(ORACLE_STALENESS_THRESHOLD_KEY, storage::new_uref(threshold).into());
+    }
+    // Upgraded registries keep creating options without a position limit; the
+    // count-based limit is gone
+    if !runtime::has_key(MAX_NOTIONAL_PER_ACCOUNT_KEY) {
+        let max_notional = DEFAULT_MAX_NOTIONAL_PER_ACCOUNT;
+        runtime::put_key(MAX_NOTIONAL_PER_ACCOUNT_KEY, storage::new_uref(max_notional).into());
+    }
+    if runtime::has_key(LEGACY_MAX_OPTIONS_PER_ACCOUNT_KEY) {
+        runtime::remove_key(LEGACY_MAX_OPTIONS_PER_ACCOUNT_KEY);
+    }
+    // Upgraded registries charge no funding
+    if !runtime::has_key(FUNDING_RATE_PER_DAY_KEY) {
+        runtime::put_key(FUNDING_RATE_PER_DAY_KEY, storage::new_uref(0i64).into());
+    }
+    // Upgraded registries need as many governance votes as fresh installs
+    if !runtime::has_key(GOVERNANCE_VOTE_THRESHOLD_KEY) {
+        let threshold = DEFAULT_GOVERNANCE_VOTE_THRESHOLD;
+        runtime::put_key(GOVERNANCE_VOTE_THRESHOLD_KEY, storage::new_uref(threshold).into());
+    }
+    // Upgraded registries keep paying early exercises in full
+    if !runtime::has_key(EARLY_EXERCISE_PENALTY_BPS_KEY) {
+        runtime::put_key(EARLY_EXERCISE_PENALTY_BPS_KEY, storage::new_uref(0u32).into());
+    }
+    if !runtime::has_key(PRICE_VALIDATION_ENABLED_KEY) {
+        runtime::put_key(PRICE_VALIDATION_ENABLED_KEY, storage::new_uref(false).into());
+        let max_deviation_bps = DEFAULT_MAX_PRICE_DEVIATION_BPS;
+        runtime::put_key(MAX_PRICE_DEVIATION_BPS_KEY, storage::new_uref(max_deviation_bps).into());
+    }
+    for key in [VOLUME_CALL_EXERCISED_KEY, VOLUME_PUT_EXERCISED_KEY] {
+        if !runtime::has_key(key) {
+            runtime::put_key(key, storage::new_uref(U256::zero()).into());
+        }
+    }
+    // Upgraded registries keep settling at the spot price
+    if !runtime::has_key(SNAPSHOT_COUNT_KEY) {
+        runtime::put_key(SNAPSHOT_COUNT_KEY, storage::new_uref(0u64).into());
+        let interval = DEFAULT_SNAPSHOT_INTERVAL_SECS;
+        runtime::put_key(SNAPSHOT_INTERVAL_KEY, storage::new_uref(interval).into());
+        runtime::put_key(USE_TWAP_KEY, storage::new_uref(false).into());
+        runtime::put_key(TWAP_WINDOW_KEY, storage::new_uref(DEFAULT_TWAP_WINDOW_SECS).into());
+    }
+    // Upgraded registries take no token collateral until a DEX is set
+    if !runtime::has_key(COLLATERAL_DEX_KEY) {
+        runtime::put_key(COLLATERAL_DEX_KEY, storage::new_uref(Option::<Key>::None).into());
+    }
+    // Upgraded registries keep creating options without NFTs
+    if !runtime::has_key(NFT_CONTRACT_KEY) {
+        runtime::put_key(NFT_CONTRACT_KEY, storage::new_uref(Option::<Key>::None).into());
+    }
+    
+    // Contracts installed before the dictionary layout keep strike, expiry,
+    // exercised and owner in flat named keys until `migrate_options` moves them
+    if !runtime::has_key(OPTIONS_STRIKE_DICT) {
+        for dictionary in [
+            OPTIONS_STRIKE_DICT,
+            OPTIONS_EXPIRY_DICT,
+            OPTIONS_EXERCISED_DICT,
+            OPTIONS_OWNER_DICT,
+        ] {
+            storage::new_dictionary(dictionary).unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+        }
+        runtime::put_key(MIGRATION_CURSOR_KEY, storage::new_uref(0u64).into());
+    }
+}
+
+/// Moves option `id`'s strike, expiry, exercised flag and owner from the flat
+/// `option_{id}_*` named keys into their dictionaries and removes the flat
+/// keys. Options already in the dictionary layout are left alone.
+fn migrate_option(id: u64) {
+    let key_name = option_key(id);
+    let strike_key = key_name.clone() + "_strike";
+    if !runtime::has_key(&strike_key) {
+        return;
+    }
+    let expiry_key = key_name.clone() + "_expiry";
+    let exercised_key = key_name.clone() + "_exercised";
+    let owner_key = key_name + "_owner";
+    
+    let strike_price: U256 = read_option_value(&strike_key);
+    let expiry: u64 = read_option_value(&expiry_key);
+    let exercised: bool = read_option_value(&exercised_key);
+    let owner: AccountHash = read_option_value(&owner_key);
+    write_option_field(OPTIONS_STRIKE_DICT, id, strike_price);
+    write_option_field(OPTIONS_EXPIRY_DICT, id, expiry);
+    write_option_field(OPTIONS_EXERCISED_DICT, id, exercised);
+    write_option_field(OPTIONS_OWNER_DICT, id, owner);
+    
+    for key in [strike_key, expiry_key, exercised_key, owner_key] {
+        runtime::remove_key(&key);
+    }
+}
+
+/// Moves the given options to the dictionary layout, then advances
+/// `migration_cursor` past the options, in creation order, that no longer
+/// have flat keys. Takes at most 50 ids per call to stay within gas limits
+/// (admin only).
+#[no_mangle]
+pub extern "C" fn migrate_options() {
+    require_admin();
+    
+    let ids: Vec<u64> = runtime::get_named_arg(ARG_IDS);
+    if ids.len() > MAX_MIGRATION_BATCH {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    for id in ids {
+        migrate_option(id);
+    }
+    
+    let cursor_uref = named_uref(MIGRATION_CURSOR_KEY, ErrorCode::MissingNamedKey);
+    let mut cursor: u64 = read_uref(cursor_uref);
+    let count: u64 = read_named_value(OPTION_COUNT_KEY);
+    let end = count.min(cursor + MAX_MIGRATION_BATCH as u64);
+    while cursor < end {
+        let id: u64 = read_named_value(&all_options_key(cursor));
+        if runtime::has_key(&(option_key(id) + "_strike")) {
+            break;
+        }
+        cursor += 1;
+    }
+    storage::write(cursor_uref, cursor);
+}
+
+/// Returns whether every option is in the dictionary layout. Contracts
+/// installed with that layout never had anything to migrate.
+#[no_mangle]
+pub extern "C" fn is_migration_complete() {
+    let complete = match runtime::get_key(MIGRATION_CURSOR_KEY) {
+        Some(_) => {
+            let cursor: u64 = read_named_value(MIGRATION_CURSOR_KEY);
+            let count: u64 = read_named_value(OPTION_COUNT_KEY);
+            cursor >= count
+        }
+        None => true,
+    };
+    ret(complete);
+}
+
+#[no_mangle]
+pub extern "C" fn get_option() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let key_name = option_key(id);
+    
+    let stored_id: u64 = read_option_value(&key_name);
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    
+    ret((stored_id, strike_price, expiry));
+}
+
+/// `value` serialized as a `CLValue`, type tag included.
+fn cl_value_bytes<T: CLTyped + ToBytes>(value: T) -> Bytes {
+    let cl_value = CLValue::from_t(value).unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    cl_value.to_bytes().unwrap_or_revert_with(ErrorCode::InvalidStorageValue).into()
+}
+
+/// Returns option `id`'s fields in one map from `id`, `strike`, `expiry`,
+/// `type`, `style`, `state`, `owner`, `writer`, `premium`, `amount` and `iv`
+/// to each field's serialized `CLValue`, which carries its type.
+#[no_mangle]
+pub extern "C" fn get_option_full() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let key_name = option_key(id);
+    
+    let stored_id: u64 = read_option_value(&key_name);
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let option_type: bool = read_option_value(&(key_name.clone() + "_type"));
+    let option_style: u8 = read_option_value(&(key_name.clone() + "_style"));
+    let state: u8 = read_option_value(&option_state_key(id));
+    let owner: AccountHash = read_option_field(OPTIONS_OWNER_DICT, id);
+    let writer: AccountHash = read_option_value(&option_writer_key(id));
+    let premium: U512 = read_option_value(&(key_name.clone() + "_premium"));
+    let amount: U256 = read_option_value(&(key_name + "_amount"));
+    let iv: u64 = read_option_value_or_default(&option_iv_key(id));
+    
+    let mut fields: BTreeMap<String, Bytes> = BTreeMap::new();
+    fields.insert("id".to_string(), cl_value_bytes(stored_id));
+    fields.insert("strike".to_string(), cl_value_bytes(strike_price));
+    fields.insert("expiry".to_string(), cl_value_bytes(expiry));
+    fields.insert("type".to_string(), cl_value_bytes(option_type));
+    fields.insert("style".to_string(), cl_value_bytes(option_style));
+    fields.insert("state".to_string(), cl_value_bytes(state));
+    fields.insert("owner".to_string(), cl_value_bytes(owner));
+    fields.insert("writer".to_string(), cl_value_bytes(writer));
+    fields.insert("premium".to_string(), cl_value_bytes(premium));
+    fields.insert("amount".to_string(), cl_value_bytes(amount));
+    fields.insert("iv".to_string(), cl_value_bytes(iv));
+    ret(fields);
+}
+
+#[no_mangle]
+pub extern "C" fn get_option_type() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let option_type: bool = read_option_value(&(option_key(id) + "_type"));
+    ret(option_type);
+}
+
+#[no_mangle]
+pub extern "C" fn get_option_state() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let state: u8 = read_option_value(&option_state_key(id));
+    ret(state);
+}
+
+/// Returns when option `id` was created, in seconds since the unix epoch.
+#[no_mangle]
+pub extern "C" fn get_creation_timestamp() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let created_at: u64 = read_option_value(&option_created_at_key(id));
+    ret(created_at);
+}
+
+/// Returns when option `id` was exercised in full, in seconds since the unix
+/// epoch, reverting with `NotExercised` if it has units left.
+#[no_mangle]
+pub extern "C" fn get_exercise_timestamp() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    let exercised_at_key = option_exercised_at_key(id);
+    if !runtime::has_key(&exercised_at_key) {
+        runtime::revert(ErrorCode::NotExercised);
+    }
+    let exercised_at: u64 = read_named_value(&exercised_at_key);
+    ret(exercised_at);
+}
+
+/// Returns the seconds left until option `id`'s expiry, 0 once it has passed.
+#[no_mangle]
+pub extern "C" fn get_time_to_expiry() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    ret(expiry.saturating_sub(current_timestamp()));
+}
+
+/// Returns whether the current block time is past option `id`'s expiry,
+/// whatever its lifecycle state.
+#[no_mangle]
+pub extern "C" fn is_expired() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    ret(current_timestamp() > expiry);
+}
+
+/// Returns whether option `id` would pay out with the underlying at
+/// `current_price`, more than one basis point from its strike. Like the
+/// other moneyness queries, it ignores the option's lifecycle state.
+#[no_mangle]
+pub extern "C" fn is_in_the_money() {
+    let (in_the_money, _) = moneyness();
+    ret(in_the_money);
+}
+
+/// Returns whether option `id` would be worthless with the underlying at
+/// `current_price`, and not within one basis point of its strike.
+#[no_mangle]
+pub extern "C" fn is_out_of_the_money() {
+    let (in_the_money, at_the_money) = moneyness();
+    ret(!in_the_money && !at_the_money);
+}
+
+/// Returns whether `current_price` is within one basis point of option
+/// `id`'s strike.
+#[no_mangle]
+pub extern "C" fn is_at_the_money() {
+    let (_, at_the_money) = moneyness();
+    ret(at_the_money);
+}
+
+/// Returns the intrinsic value of option `id` with the underlying at
+/// `current_price`: `max(0, price - strike)` for a call or
+/// `max(0, strike - price)` for a put, times its `option_amount`.
+#[no_mangle]
+pub extern "C" fn get_intrinsic_value() {
+    ret(total_intrinsic_value());
+}
+
+/// Returns how much of `option_price`, a price quoted for all of option
+/// `id`'s units, is time value: whatever exceeds the intrinsic value at
+/// `current_price`, or 0.
+#[no_mangle]
+pub extern "C" fn get_time_value() {
+    let option_price: U256 = runtime::get_named_arg(ARG_OPTION_PRICE);
+    ret(option_price.saturating_sub(total_intrinsic_value()));
+}
+
+/// Returns the owner's unrealized PnL on option `id` with the underlying at
+/// `current_price`: the cash value of its unexercised units less the
+/// premium paid, as `(is_loss, magnitude)`.
+#[no_mangle]
+pub extern "C" fn get_unrealized_pnl() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    require_option_exists(id);
+    
+    let exercised: bool = read_option_field(OPTIONS_EXERCISED_DICT, id);
+    let value = if exercised {
+        U512::zero()
+    } else {
+        option_payout(id, remaining_amount(id), price)
+    };
+    let premium: U512 = read_option_value(&(option_key(id) + "_premium"));
+    if value >= premium {
+        ret((false, value - premium))
+    } else {
+        ret((true, premium - value))
+    }
+}
+
+/// Returns `(delta, gamma, (theta, vega))` for option `id`, scaled by 1e8.
+/// Greeks never set by `update_greeks` read as zero.
+#[no_mangle]
+pub extern "C" fn get_greeks() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    let greek = |name: &str| -> i64 { read_option_value_or_default(&option_greek_key(id, name)) };
+    ret((greek(ARG_DELTA), greek(ARG_GAMMA), (greek(ARG_THETA), greek(ARG_VEGA))));
+}
+
+/// Returns option `id`'s implied volatility in basis points, 0 if unknown.
+#[no_mangle]
+pub extern "C" fn get_iv() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    require_option_exists(id);
+    let iv: u64 = read_option_value_or_default(&option_iv_key(id));
+    ret(iv);
+}
+
+/// Returns the volatility surface's implied volatility in basis points at
+/// `strike` and `expiry`, 0 if unset.
+#[no_mangle]
+pub extern "C" fn get_volatility_surface_point() {
+    let strike: U256 = runtime::get_named_arg(ARG_STRIKE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    ret(vol_surface_point(strike, expiry));
+}
+
+/// Returns the Black-Scholes value, at a zero interest rate, of a call with
+/// option `id`'s strike, time to expiry and implied volatility when the
+/// underlying trades at `underlying_price`. Options with no implied
+/// volatility or past expiry are valued at their intrinsic value.
+#[no_mangle]
+pub extern "C" fn estimate_call_price() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let underlying_price: U256 = runtime::get_named_arg(ARG_UNDERLYING_PRICE);
+    require_option_exists(id);
+    
+    let strike_price: U256 = read_option_field(OPTIONS_STRIKE_DICT, id);
+    let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+    let iv: u64 = read_option_value_or_default(&option_iv_key(id));
+    let seconds_to_expiry = expiry.saturating_sub(current_timestamp());
+    ret(pricing::call_price(underlying_price, strike_price, iv, seconds_to_expiry));
+}
+
+/// Returns the premium `buy_option_from_pool` would charge right now for an
+/// option (a call by default) struck at `strike_price` on `option_amount`
+/// units (one by default) expiring at `expiry`.
+#[no_mangle]
+pub extern "C" fn get_amm_option_price() {
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let amount: U256 = runtime::try_get_named_arg(ARG_OPTION_AMOUNT).unwrap_or(U256::one());
+    // true = Call, false = Put
+    let option_type: bool = runtime::try_get_named_arg(ARG_OPTION_TYPE).unwrap_or(true);
+    ret(amm_option_price(option_type, strike_price, amount, expiry));
+}
+
+/// Returns whether `caller` could exercise option `id` right now. Never
+/// reverts; `exercise_revert_reason` says why not.
+#[no_mangle]
+pub extern "C" fn is_exercisable() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let caller: AccountHash = runtime::get_named_arg(ARG_CALLER);
+    ret(exercise_blocker(id, caller).is_none());
+}
+
+/// Returns the error code `exercise_option` would revert with if `caller`
+/// exercised option `id` right now, or 0 if it would go through.
+#[no_mangle]
+pub extern "C" fn exercise_revert_reason() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let caller: AccountHash = runtime::get_named_arg(ARG_CALLER);
+    ret(exercise_blocker(id, caller).map_or(0, |error| error as u8));
+}
+
+/// Returns whether `writer` is on the writer whitelist, whether or not the
+/// whitelist is enabled.
+#[no_mangle]
+pub extern "C" fn is_whitelisted() {
+    let writer: AccountHash = runtime::get_named_arg(ARG_WRITER);
+    ret(account_listed(WRITER_WHITELIST_DICT, &writer));
+}
+
+/// Returns `account`'s LP shares.
+#[no_mangle]
+pub extern "C" fn get_lp_balance() {
+    let account: AccountHash = runtime::get_named_arg(ARG_ACCOUNT);
+    ret(lp_balance(&account));
+}
+
+/// Returns the CSPR the insurance fund holds for settlement shortfalls.
+#[no_mangle]
+pub extern "C" fn get_insurance_fund_balance() {
+    let balance: U512 = read_named_value(INSURANCE_FUND_BALANCE_KEY);
+    ret(balance);
+}
+
+/// Returns the CSPR `account` has contributed to the insurance fund in total.
+#[no_mangle]
+pub extern "C" fn get_insurance_fund_contribution() {
+    let account: AccountHash = runtime::get_named_arg(ARG_ACCOUNT);
+    ret(insurance_contribution(&account));
+}
+
+/// Returns how many of `owner`'s LP shares `spender` may still move.
+#[no_mangle]
+pub extern "C" fn get_lp_allowance() {
+    let owner: AccountHash = runtime::get_named_arg(ARG_OWNER);
+    let spender: AccountHash = runtime::get_named_arg(ARG_SPENDER);
+    ret(lp_allowance(&owner, &spender));
+}
+
+/// Pages through the ids of options currently in `state`. Ids are not kept
+/// in any particular order: an option leaving the state is replaced by the
+/// bucket's last id.
+#[no_mangle]
+pub extern "C" fn get_options_in_state() {
+    let state: u8 = runtime::get_named_arg(ARG_STATE);
+    let offset: u64 = runtime::get_named_arg(ARG_OFFSET);
+    let limit: u64 = runtime::get_named_arg(ARG_LIMIT);
+    if state > OPTION_STATE_SETTLED {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    ret(page(state_count(state), offset, limit, |slot| state_slot(state, slot)));
+}
+
+/// Returns how many options are Active.
+#[no_mangle]
+pub extern "C" fn get_active_option_count() {
+    ret(state_count(OPTION_STATE_ACTIVE));
+}
+
+/// Returns how many options are Exercised.
+#[no_mangle]
+pub extern "C" fn get_exercised_option_count() {
+    ret(state_count(OPTION_STATE_EXERCISED));
+}
+
+/// Returns how many options are Expired.
+#[no_mangle]
+pub extern "C" fn get_expired_option_count() {
+    ret(state_count(OPTION_STATE_EXPIRED));
+}
+
+/// Returns how many options are Cancelled.
+#[no_mangle]
+pub extern "C" fn get_cancelled_option_count() {
+    ret(state_count(OPTION_STATE_CANCELLED));
+}
+
+/// Returns how many options are Settled.
+#[no_mangle]
+pub extern "C" fn get_settled_option_count() {
+    ret(state_count(OPTION_STATE_SETTLED));
+}
+
+/// Returns how many options there are in every state, Created included.
+#[no_mangle]
+pub extern "C" fn get_total_option_count() {
+    let total = (OPTION_STATE_CREATED..=OPTION_STATE_SETTLED)
+        .map(state_count)
+        .fold(0u64, u64::saturating_add);
+    ret(total);
+}
+
+#[no_mangle]
+pub extern "C" fn get_contract_version() {
+    let version: u32 = read_named_value(CONTRACT_VERSION_KEY);
+    ret(version);
+}
+
+#[no_mangle]
+pub extern "C" fn get_option_count() {
+    let count: u64 = read_named_value(OPTION_COUNT_KEY);
+    ret(count);
+}
+
+#[no_mangle]
+pub extern "C" fn get_collateral() {
+    let id: u64 = runtime::get_named_arg(ARG_ID);
+    let collateral: U512 = read_option_value(&option_collateral_key(id));
+    ret(collateral);
+}
+
+#[no_mangle]
+pub extern "C" fn get_series_options() {
+    let series_id: u64 = runtime::get_named_arg(ARG_SERIES_ID);
+    let offset: u64 = runtime::get_named_arg(ARG_OFFSET);
+    let limit: u64 = runtime::get_named_arg(ARG_LIMIT);
+    ret(page(series_count(series_id), offset, limit, |index| {
+        read_named_value(&series_entry_key(series_id, index))
+    }));
+}
+
+/// Pages through every option id in creation order.
+#[no_mangle]
+pub extern "C" fn get_all_options() {
+    let offset: u64 = runtime::get_named_arg(ARG_OFFSET);
+    let limit: u64 = runtime::get_named_arg(ARG_LIMIT);
+    let total: u64 = read_named_value(OPTION_COUNT_KEY);
+    ret(page(total, offset, limit, |index| read_named_value(&all_options_key(index))));
+}
+
+/// Returns ids of options expiring in `from_expiry..=to_expiry`, walking one
+/// `expiry_index` bucket per day in the range, so wide ranges cost more gas.
+#[no_mangle]
+pub extern "C" fn get_options_expiring_between() {
+    let from_expiry: u64 = runtime::get_named_arg(ARG_FROM_EXPIRY);
+    let to_expiry: u64 = runtime::get_named_arg(ARG_TO_EXPIRY);
+    if from_expiry > to_expiry {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    let mut ids = Vec::new();
+    for bucket in (from_expiry / DAY_IN_SECONDS)..=(to_expiry / DAY_IN_SECONDS) {
+        for id in expiry_bucket_options(bucket) {
+            let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+            if (from_expiry..=to_expiry).contains(&expiry) {
+                ids.push(id);
+            }
+        }
+    }
+    ret(ids);
+}
+
+/// Returns up to `limit` distinct option expiries from position `offset` of
+/// the ascending list of every expiry seen. All of them are read to sort the
+/// list, so gas grows with the number of distinct expiries.
+#[no_mangle]
+pub extern "C" fn get_all_expiries() {
+    let offset: u64 = runtime::get_named_arg(ARG_OFFSET);
+    let limit: u64 = runtime::get_named_arg(ARG_LIMIT);
+    let count: u64 = read_named_value(EXPIRY_SET_COUNT_KEY);
+    
+    let mut expiries: Vec<u64> = (0..count)
+        .map(|index| read_named_value(&expiry_set_key(index)))
+        .collect();
+    expiries.sort_unstable();
+    let start = usize::try_from(offset).unwrap_or(usize::MAX);
+    let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+    ret(expiries.into_iter().skip(start).take(limit).collect::<Vec<u64>>());
+}
+
+/// Returns the distinct strikes of options expiring at `expiry`, ascending;
+/// empty for an expiry no option uses.
+#[no_mangle]
+pub extern "C" fn get_strikes_for_expiry() {
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    ret(strikes_for_expiry(expiry));
+}
+
+/// Returns the option chain at `expiry`: for each strike, ascending,
+/// `(strike_price, call_ids, put_ids)` with the ids of every call and put
+/// created at that strike and expiry, whatever their current state.
+#[no_mangle]
+pub extern "C" fn get_option_chain() {
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let chain: Vec<(U256, Vec<u64>, Vec<u64>)> = strikes_for_expiry(expiry)
+        .into_iter()
+        .map(|strike_price| {
+            let (calls, puts) = option_chain_entry(expiry, strike_price).unwrap_or_default();
+            (strike_price, calls, puts)
+        })
+        .collect();
+    ret(chain);
+}
+
+/// Returns the max pain strike for `expiry`: of the strikes used at that
+/// expiry between `price_range_low` and `price_range_high`, the one at which
+/// the open calls and puts would pay their holders least in total. Returns
+/// zero when no strike is in range.
+#[no_mangle]
+pub extern "C" fn calculate_max_pain() {
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let low: U256 = runtime::get_named_arg(ARG_PRICE_RANGE_LOW);
+    let high: U256 = runtime::get_named_arg(ARG_PRICE_RANGE_HIGH);
+    if low > high {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    ret(max_pain(expiry, low, high));
+}
+
+/// Returns the number of unexercised, unexpired and uncancelled options
+/// struck at `strike_price` and expiring at `expiry`.
+#[no_mangle]
+pub extern "C" fn get_open_interest_by_strike() {
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    ret(stored_count(&oi_strike_key(expiry, strike_price)));
+}
+
+/// Returns the number of open options expiring at `expiry`.
+#[no_mangle]
+pub extern "C" fn get_open_interest_by_expiry() {
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    ret(stored_count(&oi_expiry_key(expiry)));
+}
+
+/// Returns the number of open options across all strikes and expiries.
+#[no_mangle]
+pub extern "C" fn get_total_open_interest() {
+    ret(stored_count(TOTAL_OPEN_INTEREST_KEY));
+}
+
+/// Returns the notional, `amount * strike`, exercised across all calls and
+/// puts, counting expired options settled in the money.
+#[no_mangle]
+pub extern "C" fn get_total_volume() {
+    let calls: U256 = read_named_value(VOLUME_CALL_EXERCISED_KEY);
+    let puts: U256 = read_named_value(VOLUME_PUT_EXERCISED_KEY);
+    ret(calls.saturating_add(puts));
+}
+
+/// Returns the notional exercised on calls, or puts if `option_type` is
+/// `false`, struck at `strike_price`.
+#[no_mangle]
+pub extern "C" fn get_volume_by_strike() {
+    let strike_price: U256 = runtime::get_named_arg(ARG_STRIKE_PRICE);
+    let option_type: bool = runtime::get_named_arg(ARG_OPTION_TYPE);
+    ret(read_option_value_or_default::<U256>(&volume_strike_key(strike_price, option_type)));
+}
+
+/// Returns the notional exercised on options expiring at `expiry`.
+#[no_mangle]
+pub extern "C" fn get_volume_by_expiry() {
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    ret(read_option_value_or_default::<U256>(&volume_expiry_key(expiry)));
+}
+
+/// Returns `(puts, calls)`: how many puts and calls were ever created. The
+/// ratio is left to the caller, as the contract has no fractional numbers.
+#[no_mangle]
+pub extern "C" fn get_put_call_ratio() {
+    ret((stored_count(TOTAL_PUTS_CREATED_KEY), stored_count(TOTAL_CALLS_CREATED_KEY)));
+}
+
+/// Returns `(puts, calls)` created with expiry `expiry`.
+#[no_mangle]
+pub extern "C" fn get_put_call_ratio_for_expiry() {
+    let expiry: u64 = runtime::get_named_arg(ARG_EXPIRY);
+    let puts = stored_count(&expiry_created_key(expiry, false));
+    let calls = stored_count(&expiry_created_key(expiry, true));
+    ret((puts, calls));
+}
+
+/// Pages through `owner`'s portfolio slots. Slots vacated by transfers are
+/// skipped, so a page can hold fewer than `limit` ids; `total` counts slots,
+/// not live options, and is what callers page against.
+#[no_mangle]
+pub extern "C" fn get_options_by_owner() {
+    let owner: AccountHash = runtime::get_named_arg(ARG_OWNER);
+    let offset: u64 = runtime::get_named_arg(ARG_OFFSET);
+    let limit: u64 = runtime::get_named_arg(ARG_LIMIT);
+    
+    let count_key = owner_count_key(&owner);
+    let total: u64 = if runtime::get_key(&count_key).is_some() {
+        read_named_value(&count_key)
+    } else {
+        0
+    };
+    let end = offset.saturating_add(limit).min(total);
+    let ids: Vec<u64> = (offset..end)
+        .filter_map(|index| read_named_value::<Option<u64>>(&owner_entry_key(&owner, index)))
+        .collect();
+    ret((ids, total));
+}
+
+/// The ids in `owner`'s portfolio slots that are still open and not past
+/// expiry, in slot order.
+fn live_portfolio(owner: &AccountHash) -> Vec<u64> {
+    let now = current_timestamp();
+    (0..stored_count(&owner_count_key(owner)))
+        .filter_map(|index| read_named_value::<Option<u64>>(&owner_entry_key(owner, index)))
+        .filter(|&id| {
+            let state: u8 = read_option_value(&option_state_key(id));
+            let expiry: u64 = read_option_field(OPTIONS_EXPIRY_DICT, id);
+            is_open_state(state) && now <= expiry
+        })
+        .collect()
+}
+
+/// Returns the ids of every option `owner` holds that has not expired, been
+/// exercised or been cancelled. Unlike `get_options_by_owner` it is not
+/// paged, and options transferred away are left out.
+#[no_mangle]
+pub extern "C" fn get_user_portfolio() {
+    let owner: AccountHash = runtime::get_named_arg(ARG_OWNER);
+    ret(live_portfolio(&owner));
+}
+
+/// Returns the sum of the stored deltas, scaled by 1e8, of the options
+/// `get_user_portfolio` lists for `owner`. Deltas come from `update_greeks`,
+/// so `current_price` only identifies the spot the caller is valuing at;
+/// options never given greeks count as zero.
+#[no_mangle]
+pub extern "C" fn get_user_portfolio_delta() {
+    let owner: AccountHash = runtime::get_named_arg(ARG_OWNER);
+    let _current_price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    let delta = live_portfolio(&owner).into_iter().fold(0i64, |total, id| {
+        let delta: i64 = read_option_value_or_default(&option_greek_key(id, ARG_DELTA));
+        total.saturating_add(delta)
+    });
+    ret(delta);
+}
+
+/// Returns the net delta, gamma, theta and vega, scaled by 1e8, of the
+/// active options in `owner`'s portfolio as `(delta, gamma, (theta, vega))`.
+/// Long calls add their delta and long puts subtract it, whichever sign the
+/// greeks oracle stored; the other greeks are summed as stored.
+#[no_mangle]
+pub extern "C" fn get_portfolio_greeks() {
+    let owner: AccountHash = runtime::get_named_arg(ARG_OWNER);
+    let _current_price: U256 = runtime::get_named_arg(ARG_CURRENT_PRICE);
+    
+    let mut totals = [0i64; 4];
+    for id in live_portfolio(&owner) {
+        let state: u8 = read_option_value(&option_state_key(id));
+        if state != OPTION_STATE_ACTIVE {
+            continue;
+        }
+        let is_call: bool = read_option_value(&(option_key(id) + "_type"));
+        for (total, name) in totals.iter_mut().zip([ARG_DELTA, ARG_GAMMA, ARG_THETA, ARG_VEGA]) {
+            let mut greek: i64 = read_option_value_or_default(&option_greek_key(id, name));
+            if name == ARG_DELTA {
+                greek = if is_call { greek.saturating_abs() } else { -greek.saturating_abs() };
+            }
+            *total = total.saturating_add(greek);
+        }
+    }
+    let [delta, gamma, theta, vega] = totals;
+    ret((delta, gamma, (theta, vega)));
+}
+
+#[no_mangle]
+pub extern "C" fn get_event() {
+    let index: u64 = runtime::get_named_arg(ARG_INDEX);
+    let event_uref = named_uref(&events::event_key(index), ErrorCode::EventNotFound);
+    let event: EventRecord = read_uref(event_uref);
+    ret(event);
+}
+
+#[no_mangle]
+pub extern "C" fn get_event_count() {
+    let count: u64 = read_named_value(EVENT_COUNTER_KEY);
+    ret(count);
+}
+
+/// The option term arguments `create_option` and `create_option_with_permit`
+/// share.
+fn option_terms_parameters() -> Vec<Parameter> {
+    vec![
+        Parameter::new(ARG_ID, CLType::U64),
+        Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+        Parameter::new(ARG_EXPIRY, CLType::U64),
+        Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        Parameter::new(ARG_NONCE, CLType::U64),
+        Parameter::new(ARG_OPTION_STYLE, CLType::U8),
+        Parameter::new(ARG_ACTIVATION, CLType::U64),
+        Parameter::new(ARG_PREMIUM, CLType::U512),
+        Parameter::new(ARG_PURSE, CLType::URef),
+        Parameter::new(ARG_AMOUNT, CLType::U512),
+        Parameter::new(ARG_OPTION_AMOUNT, CLType::U256),
+        Parameter::new(ARG_UNDERLYING_TOKEN, CLType::Key),
+        Parameter::new(ARG_UNDERLYING_AMOUNT, CLType::U256),
+        Parameter::new(ARG_SERIES_ID, CLType::U64),
+        Parameter::new(ARG_IV, CLType::U64),
+        Parameter::new(ARG_METADATA_URI, CLType::String),
+        Parameter::new(ARG_UNDERLYING_PRICE_AT_CREATION, CLType::U256),
+        Parameter::new(ARG_EXERCISE_DATES, CLType::List(Box::new(CLType::U64))),
+        Parameter::new(ARG_COLLATERAL_TOKEN, CLType::Key),
+        Parameter::new(ARG_COLLATERAL_AMOUNT, CLType::U256),
+        Parameter::new(ARG_VESTING_START, CLType::U64),
+        Parameter::new(ARG_CLIFF_SECONDS, CLType::U64),
+        Parameter::new(ARG_VESTING_DURATION_SECONDS, CLType::U64),
+    ]
+}
+
+/// Every entry point of the current contract version.
+fn registry_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    
+    let mut create_option_parameters = option_terms_parameters();
+    create_option_parameters.push(Parameter::new(ARG_WRITER, CLType::ByteArray(32)));
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CREATE_OPTION,
+        create_option_parameters,
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    let mut permit_parameters = option_terms_parameters();
+    permit_parameters.extend([
+        Parameter::new(ARG_PERMIT_SIGNATURE, CLType::ByteArray(64)),
+        Parameter::new(ARG_SIGNER, CLType::ByteArray(32)),
+        Parameter::new(ARG_PERMIT_NONCE, CLType::U64),
+        Parameter::new(ARG_PERMIT_DEADLINE, CLType::U64),
+    ]);
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CREATE_OPTION_WITH_PERMIT,
+        permit_parameters,
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_DEPOSIT_PERMIT_COLLATERAL,
+        vec![
+            Parameter::new(ARG_PURSE, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_WITHDRAW_PERMIT_COLLATERAL,
+        vec![
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CREATE_OPTION_BATCH,
+        vec![
+            Parameter::new(ARG_IDS, CLType::List(Box::new(CLType::U64))),
+            Parameter::new(ARG_STRIKE_PRICES, CLType::List(Box::new(CLType::U256))),
+            Parameter::new(ARG_EXPIRIES, CLType::List(Box::new(CLType::U64))),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_EXERCISE_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_EXERCISE_AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_EXERCISE_OPTIONS_BATCH,
+        vec![
+            Parameter::new(ARG_IDS, CLType::List(Box::new(CLType::U64))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_EXERCISE_WITH_SIGNATURE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_SIGNATURE, CLType::ByteArray(64)),
+            Parameter::new(ARG_SIGNER, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_EXERCISE_APPROVAL_POLICY,
+        vec![
+            Parameter::new(ARG_HIGH_VALUE_THRESHOLD, CLType::U256),
+            Parameter::new(ARG_SIGNERS, CLType::List(Box::new(CLType::ByteArray(32)))),
+            Parameter::new(ARG_EXERCISE_THRESHOLD, CLType::U32),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_APPROVE_EXERCISE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_APPROVE_EXERCISE_FOR,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_APPROVED, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_REVOKE_EXERCISE_APPROVAL,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_APPROVED, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_ACTIVATE_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_EXPIRE_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CANCEL_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CANCEL_SERIES,
+        vec![
+            Parameter::new(ARG_SERIES_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_APPROVE_ROLLOVER,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_NEW_EXPIRY, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_ROLLOVER_OPTION,
+        vec![
+            Parameter::new(ARG_OLD_ID, CLType::U64),
+            Parameter::new(ARG_NEW_ID, CLType::U64),
+            Parameter::new(ARG_NEW_EXPIRY, CLType::U64),
+            Parameter::new(ARG_NONCE, CLType::U64),
+            Parameter::new(ARG_PREMIUM, CLType::U512),
+            Parameter::new(ARG_PURSE, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_ROLLOVER_HISTORY,
+        vec![
+            Parameter::new(ARG_OFFSET, CLType::U64),
+            Parameter::new(ARG_LIMIT, CLType::U64),
+        ],
+        CLType::List(Box::new(CLType::Tuple3([
+            Box::new(CLType::U64),
+            Box::new(CLType::U64),
+            Box::new(CLType::U64),
+        ]))),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_EXERCISE_HISTORY,
+        vec![
+            Parameter::new(ARG_OFFSET, CLType::U64),
+            Parameter::new(ARG_LIMIT, CLType::U64),
+        ],
+        CLType::List(Box::new(CLType::Tuple3([
+            Box::new(CLType::U64),
+            Box::new(CLType::ByteArray(32)),
+            Box::new(CLType::Tuple2([Box::new(CLType::U64), Box::new(CLType::U256)])),
+        ]))),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_EXERCISE_COUNT_FOR_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CREATE_STRADDLE,
+        vec![
+            Parameter::new(ARG_CALL_ID, CLType::U64),
+            Parameter::new(ARG_PUT_ID, CLType::U64),
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CLONE_OPTION,
+        vec![
+            Parameter::new(ARG_SOURCE_ID, CLType::U64),
+            Parameter::new(ARG_NEW_ID, CLType::U64),
+            Parameter::new(ARG_NEW_STRIKE, CLType::Option(Box::new(CLType::U256))),
+            Parameter::new(ARG_NEW_EXPIRY, CLType::Option(Box::new(CLType::U64))),
+            Parameter::new(ARG_NONCE, CLType::U64),
+            Parameter::new(ARG_PREMIUM, CLType::U512),
+            Parameter::new(ARG_PURSE, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+            Parameter::new(ARG_UNDERLYING_PRICE_AT_CREATION, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_STRADDLE,
+        vec![
+            Parameter::new(ARG_STRADDLE_ID, CLType::U64),
+        ],
+        CLType::Tuple2([Box::new(CLType::U64), Box::new(CLType::U64)]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CANCEL_STRADDLE,
+        vec![
+            Parameter::new(ARG_STRADDLE_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CREATE_STRIP,
+        vec![
+            Parameter::new(ARG_BASE_ID, CLType::U64),
+            Parameter::new(ARG_STRIKE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CREATE_STRAP,
+        vec![
+            Parameter::new(ARG_BASE_ID, CLType::U64),
+            Parameter::new(ARG_STRIKE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_BUNDLE_OPTIONS,
+        vec![
+            Parameter::new(ARG_BASE_ID, CLType::U64),
+        ],
+        CLType::List(Box::new(CLType::U64)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_EXERCISE_BUNDLE,
+        vec![
+            Parameter::new(ARG_BASE_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CREATE_SPREAD,
+        vec![
+            Parameter::new(ARG_LONG_ID, CLType::U64),
+            Parameter::new(ARG_SHORT_ID, CLType::U64),
+            Parameter::new(ARG_LONG_STRIKE, CLType::U256),
+            Parameter::new(ARG_SHORT_STRIKE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_SPREAD_MAX_PROFIT,
+        vec![
+            Parameter::new(ARG_SPREAD_ID, CLType::U64),
+        ],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_SPREAD_MAX_LOSS,
+        vec![
+            Parameter::new(ARG_SPREAD_ID, CLType::U64),
+        ],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CREATE_SYNDICATED_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+            Parameter::new(ARG_OPTION_AMOUNT, CLType::U256),
+            Parameter::new(ARG_SYNDICATORS, CLType::List(Box::new(CLType::ByteArray(32)))),
+            Parameter::new(ARG_SHARES, CLType::List(Box::new(CLType::U64))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_JOIN_SYNDICATE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_PURSE, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_LEAVE_SYNDICATE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_PLACE_OPTION_ORDER,
+        vec![
+            Parameter::new(ARG_STRIKE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+            Parameter::new(ARG_IS_BUY, CLType::Bool),
+            Parameter::new(ARG_MAX_PRICE, CLType::U512),
+            Parameter::new(ARG_MIN_PRICE, CLType::U512),
+            Parameter::new(ARG_AMOUNT, CLType::U256),
+            Parameter::new(ARG_PURSE, CLType::URef),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CANCEL_ORDER,
+        vec![
+            Parameter::new(ARG_ORDER_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_MATCH_ORDERS,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_BUY_ORDER_ID, CLType::U64),
+            Parameter::new(ARG_SELL_ORDER_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_BEST_BID,
+        vec![
+            Parameter::new(ARG_STRIKE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        ],
+        CLType::Option(Box::new(CLType::U512)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_BEST_ASK,
+        vec![
+            Parameter::new(ARG_STRIKE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        ],
+        CLType::Option(Box::new(CLType::U512)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_TRANSFER_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_NEW_OWNER, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CLAIM_PREMIUM,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SETTLE_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CONTRIBUTE_TO_INSURANCE_FUND,
+        vec![
+            Parameter::new(ARG_PURSE, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_IS_LIQUIDATABLE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_LIQUIDATE_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_FINALIZE_SETTLEMENT_PRICE,
+        vec![
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_DISPUTE_SETTLEMENT_PRICE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_RESOLVE_DISPUTE,
+        vec![
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_IS_SETTLEMENT_FINAL,
+        vec![
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_ADD_COLLATERAL,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_PURSE, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_WITHDRAW_COLLATERAL,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_PAUSE,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_UNPAUSE,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_TRANSFER_ADMIN,
+        vec![
+            Parameter::new(ARG_NEW_ADMIN, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_ACCEPT_ADMIN,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_COLLECT_FEES,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_WITHDRAW_FEES_TO_TREASURY,
+        vec![
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+            Parameter::new(ARG_DESTINATION, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_EMERGENCY_CANCEL_BATCH,
+        vec![
+            Parameter::new(ARG_IDS, CLType::List(Box::new(CLType::U64))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_DEPRECATE,
+        vec![
+            Parameter::new(ARG_DESTINATION, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_WITHDRAW_MY_COLLATERAL,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_ACCUMULATED_FEES,
+        vec![],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_TOTAL_FEES_COLLECTED,
+        vec![],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_MAX_NOTIONAL_PER_ACCOUNT,
+        vec![
+            Parameter::new(ARG_MAX_NOTIONAL_PER_ACCOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_POSITION_LIMIT,
+        vec![],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_USER_NOTIONAL,
+        vec![
+            Parameter::new(ARG_ACCOUNT, CLType::ByteArray(32)),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_EARLY_EXERCISE_PENALTY,
+        vec![
+            Parameter::new(ARG_EARLY_EXERCISE_PENALTY_BPS, CLType::U32),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_PRICE_VALIDATION,
+        vec![
+            Parameter::new(ARG_PRICE_VALIDATION_ENABLED, CLType::Bool),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_MAX_PRICE_DEVIATION,
+        vec![
+            Parameter::new(ARG_MAX_PRICE_DEVIATION_BPS, CLType::U32),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_REGISTER_KEEPER,
+        vec![
+            Parameter::new(ARG_KEEPER, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_DEREGISTER_KEEPER,
+        vec![
+            Parameter::new(ARG_KEEPER, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_ADD_WRITER,
+        vec![
+            Parameter::new(ARG_WRITER, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_REMOVE_WRITER,
+        vec![
+            Parameter::new(ARG_WRITER, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_ADD_PRICE_PUBLISHER,
+        vec![
+            Parameter::new(ARG_PUBLISHER, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_REMOVE_PRICE_PUBLISHER,
+        vec![
+            Parameter::new(ARG_PUBLISHER, CLType::ByteArray(32)),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_UPDATE_ORACLE_PRICE,
+        vec![
+            Parameter::new(ARG_PRICE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_RECORD_PRICE_SNAPSHOT,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_COMPUTE_TWAP,
+        vec![
+            Parameter::new(ARG_WINDOW_SECONDS, CLType::U64),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_GREEKS_ORACLE,
+        vec![
+            Parameter::new(ARG_GREEKS_ORACLE, CLType::Option(Box::new(CLType::ByteArray(32)))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_UPDATE_GREEKS,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_DELTA, CLType::I64),
+            Parameter::new(ARG_GAMMA, CLType::I64),
+            Parameter::new(ARG_THETA, CLType::I64),
+            Parameter::new(ARG_VEGA, CLType::I64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_DEPLOY_OPTION_TOKEN,
+        vec![
+            Parameter::new(ARG_UNDERLYING_TOKEN, CLType::Key),
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Factory,
+    ).into());
+    option_token::add_template_entry_points(&mut entry_points);
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTION_TOKEN_ADDRESS,
+        vec![
+            Parameter::new(ARG_UNDERLYING_TOKEN, CLType::Key),
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        ],
+        CLType::Option(Box::new(CLType::Key)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_ADD_COLLATERAL_TYPE,
+        vec![
+            Parameter::new(ARG_TOKEN, CLType::Key),
+            Parameter::new(ARG_COLLATERAL_FACTOR_BPS, CLType::U32),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_REMOVE_COLLATERAL_TYPE,
+        vec![
+            Parameter::new(ARG_TOKEN, CLType::Key),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_COLLATERAL_DEX,
+        vec![
+            Parameter::new(ARG_DEX, CLType::Key),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_VOL_ORACLE,
+        vec![
+            Parameter::new(ARG_VOL_ORACLE, CLType::Option(Box::new(CLType::ByteArray(32)))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_UPDATE_IV,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_IV, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_UPDATE_VOLATILITY_SURFACE_POINT,
+        vec![
+            Parameter::new(ARG_STRIKE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_IV_BPS, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_UPDATE_VOLATILITY_SURFACE_BATCH,
+        vec![
+            Parameter::new(ARG_STRIKE_PRICES, CLType::List(Box::new(CLType::U256))),
+            Parameter::new(ARG_EXPIRIES, CLType::List(Box::new(CLType::U64))),
+            Parameter::new(ARG_IVS_BPS, CLType::List(Box::new(CLType::U64))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_KEEPER_EXPIRE_OPTIONS,
+        vec![
+            Parameter::new(ARG_IDS, CLType::List(Box::new(CLType::U64))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_COLLECT_FUNDING,
+        vec![
+            Parameter::new(ARG_IDS, CLType::List(Box::new(CLType::U64))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_ACCRUED_FUNDING,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Tuple2([Box::new(CLType::Bool), Box::new(CLType::U512)]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SCHEDULE_EXERCISE,
+        vec![Parameter::new(ARG_ID, CLType::U64)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CANCEL_SCHEDULED_EXERCISE,
+        vec![Parameter::new(ARG_ID, CLType::U64)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_PROCESS_SCHEDULED_EXERCISES,
+        vec![
+            Parameter::new(ARG_IDS, CLType::List(Box::new(CLType::U64))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_AUTO_EXERCISE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_AUTO_EXERCISE, CLType::Bool),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_PROCESS_AUTO_EXERCISES,
+        vec![
+            Parameter::new(ARG_FROM_EXPIRY, CLType::U64),
+            Parameter::new(ARG_TO_EXPIRY, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_DEPOSIT_TO_POOL,
+        vec![
+            Parameter::new(ARG_PURSE, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_WITHDRAW_FROM_POOL,
+        vec![
+            Parameter::new(ARG_SHARES, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_TRANSFER_LP_SHARES,
+        vec![
+            Parameter::new(ARG_RECIPIENT, CLType::ByteArray(32)),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_APPROVE_LP_SHARES,
+        vec![
+            Parameter::new(ARG_SPENDER, CLType::ByteArray(32)),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_TRANSFER_LP_SHARES_FROM,
+        vec![
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_RECIPIENT, CLType::ByteArray(32)),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_WRITE_OPTION_FROM_POOL,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+            Parameter::new(ARG_OPTION_AMOUNT, CLType::U256),
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_OPTION_STYLE, CLType::U8),
+            Parameter::new(ARG_PREMIUM, CLType::U512),
+            Parameter::new(ARG_PURSE, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+            Parameter::new(ARG_IV, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_BUY_OPTION_FROM_POOL,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+            Parameter::new(ARG_OPTION_AMOUNT, CLType::U256),
+            Parameter::new(ARG_OPTION_STYLE, CLType::U8),
+            Parameter::new(ARG_PURSE, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+            Parameter::new(ARG_IV, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_PROPOSE_PARAMETER_CHANGE,
+        vec![
+            Parameter::new(ARG_PARAM_KEY, CLType::String),
+            Parameter::new(ARG_PARAM_VALUE, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_EXECUTE_PARAMETER_CHANGE,
+        vec![
+            Parameter::new(ARG_NONCE, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CANCEL_PARAMETER_CHANGE,
+        vec![
+            Parameter::new(ARG_NONCE, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CREATE_GOVERNANCE_PROPOSAL,
+        vec![
+            Parameter::new(ARG_PARAM_NAME, CLType::String),
+            Parameter::new(ARG_NEW_VALUE, CLType::U64),
+            Parameter::new(ARG_DESCRIPTION, CLType::String),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_VOTE_FOR_PROPOSAL,
+        vec![
+            Parameter::new(ARG_PROPOSAL_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_EXECUTE_PROPOSAL,
+        vec![
+            Parameter::new(ARG_PROPOSAL_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CANCEL_PROPOSAL,
+        vec![
+            Parameter::new(ARG_PROPOSAL_ID, CLType::U64),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_UPGRADE,
+        vec![
+            Parameter::new(ARG_CONTRACT_HASH, CLType::ByteArray(32)),
+            Parameter::new(ARG_CONTRACT_VERSION, CLType::U32),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_CONTRACT_VERSION,
+        vec![],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_MIGRATE_OPTIONS,
+        vec![
+            Parameter::new(ARG_IDS, CLType::List(Box::new(CLType::U64))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_IS_MIGRATION_COMPLETE,
+        vec![],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTION,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::U64),
+            Box::new(CLType::U256),
+            Box::new(CLType::U64),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTION_FULL,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Map {
+            key: Box::new(CLType::String),
+            value: Box::new(CLType::List(Box::new(CLType::U8))),
+        },
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTION_TYPE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_CREATION_TIMESTAMP,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_EXERCISE_TIMESTAMP,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_TIME_TO_EXPIRY,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_IS_EXPIRED,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_IS_IN_THE_MONEY,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_IS_OUT_OF_THE_MONEY,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_IS_AT_THE_MONEY,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_INTRINSIC_VALUE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_TIME_VALUE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+            Parameter::new(ARG_OPTION_PRICE, CLType::U256),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_UNREALIZED_PNL,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Tuple2([Box::new(CLType::Bool), Box::new(CLType::U512)]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTION_STATE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::U8,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_GREEKS,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::I64),
+            Box::new(CLType::I64),
+            Box::new(CLType::Tuple2([Box::new(CLType::I64), Box::new(CLType::I64)])),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_IV,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_VOLATILITY_SURFACE_POINT,
+        vec![
+            Parameter::new(ARG_STRIKE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_ESTIMATE_CALL_PRICE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_UNDERLYING_PRICE, CLType::U256),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_AMM_OPTION_PRICE,
+        vec![
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_OPTION_AMOUNT, CLType::U256),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        ],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_IS_EXERCISABLE,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CALLER, CLType::ByteArray(32)),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_EXERCISE_REVERT_REASON,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_CALLER, CLType::ByteArray(32)),
+        ],
+        CLType::U8,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_IS_WHITELISTED,
+        vec![
+            Parameter::new(ARG_WRITER, CLType::ByteArray(32)),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_LP_BALANCE,
+        vec![
+            Parameter::new(ARG_ACCOUNT, CLType::ByteArray(32)),
+        ],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_LP_ALLOWANCE,
+        vec![
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_SPENDER, CLType::ByteArray(32)),
+        ],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_INSURANCE_FUND_BALANCE,
+        vec![],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_INSURANCE_FUND_CONTRIBUTION,
+        vec![
+            Parameter::new(ARG_ACCOUNT, CLType::ByteArray(32)),
+        ],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTIONS_IN_STATE,
+        vec![
+            Parameter::new(ARG_STATE, CLType::U8),
+            Parameter::new(ARG_OFFSET, CLType::U64),
+            Parameter::new(ARG_LIMIT, CLType::U64),
+        ],
+        CLType::Tuple2([Box::new(CLType::List(Box::new(CLType::U64))), Box::new(CLType::U64)]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_ACTIVE_OPTION_COUNT,
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_EXERCISED_OPTION_COUNT,
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_EXPIRED_OPTION_COUNT,
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_CANCELLED_OPTION_COUNT,
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_SETTLED_OPTION_COUNT,
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_TOTAL_OPTION_COUNT,
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTION_COUNT,
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_COLLATERAL,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_SERIES_OPTIONS,
+        vec![
+            Parameter::new(ARG_SERIES_ID, CLType::U64),
+            Parameter::new(ARG_OFFSET, CLType::U64),
+            Parameter::new(ARG_LIMIT, CLType::U64),
+        ],
+        CLType::Tuple2([Box::new(CLType::List(Box::new(CLType::U64))), Box::new(CLType::U64)]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_APPLY_DIVIDEND_ADJUSTMENT,
+        vec![
+            Parameter::new(ARG_SERIES_ID, CLType::U64),
+            Parameter::new(ARG_DIVIDEND_PER_SHARE, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_ADJUSTMENT_HISTORY,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_ALL_OPTIONS,
+        vec![
+            Parameter::new(ARG_OFFSET, CLType::U64),
+            Parameter::new(ARG_LIMIT, CLType::U64),
+        ],
+        CLType::Tuple2([Box::new(CLType::List(Box::new(CLType::U64))), Box::new(CLType::U64)]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTIONS_EXPIRING_BETWEEN,
+        vec![
+            Parameter::new(ARG_FROM_EXPIRY, CLType::U64),
+            Parameter::new(ARG_TO_EXPIRY, CLType::U64),
+        ],
+        CLType::List(Box::new(CLType::U64)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_ALL_EXPIRIES,
+        vec![
+            Parameter::new(ARG_OFFSET, CLType::U64),
+            Parameter::new(ARG_LIMIT, CLType::U64),
+        ],
+        CLType::List(Box::new(CLType::U64)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_STRIKES_FOR_EXPIRY,
+        vec![
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTION_CHAIN,
+        vec![
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::List(Box::new(CLType::Tuple3([
+            Box::new(CLType::U256),
+            Box::new(CLType::List(Box::new(CLType::U64))),
+            Box::new(CLType::List(Box::new(CLType::U64))),
+        ]))),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_CALCULATE_MAX_PAIN,
+        vec![
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+            Parameter::new(ARG_PRICE_RANGE_LOW, CLType::U256),
+            Parameter::new(ARG_PRICE_RANGE_HIGH, CLType::U256),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPEN_INTEREST_BY_STRIKE,
+        vec![
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPEN_INTEREST_BY_EXPIRY,
+        vec![
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_TOTAL_OPEN_INTEREST,
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_TOTAL_VOLUME,
+        vec![],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_VOLUME_BY_STRIKE,
+        vec![
+            Parameter::new(ARG_STRIKE_PRICE, CLType::U256),
+            Parameter::new(ARG_OPTION_TYPE, CLType::Bool),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_VOLUME_BY_EXPIRY,
+        vec![
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_PUT_CALL_RATIO,
+        vec![],
+        CLType::Tuple2([Box::new(CLType::U64), Box::new(CLType::U64)]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_PUT_CALL_RATIO_FOR_EXPIRY,
+        vec![
+            Parameter::new(ARG_EXPIRY, CLType::U64),
+        ],
+        CLType::Tuple2([Box::new(CLType::U64), Box::new(CLType::U64)]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTIONS_BY_OWNER,
+        vec![
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_OFFSET, CLType::U64),
+            Parameter::new(ARG_LIMIT, CLType::U64),
+        ],
+        CLType::Tuple2([Box::new(CLType::List(Box::new(CLType::U64))), Box::new(CLType::U64)]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_USER_PORTFOLIO,
+        vec![Parameter::new(ARG_OWNER, CLType::ByteArray(32))],
+        CLType::List(Box::new(CLType::U64)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_USER_PORTFOLIO_DELTA,
+        vec![
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::I64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_PORTFOLIO_GREEKS,
+        vec![
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_CURRENT_PRICE, CLType::U256),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::I64),
+            Box::new(CLType::I64),
+            Box::new(CLType::Tuple2([Box::new(CLType::I64), Box::new(CLType::I64)])),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_EVENT,
+        vec![
+            Parameter::new(ARG_INDEX, CLType::U64),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::U8),
+            Box::new(CLType::U64),
+            Box::new(CLType::Tuple2([Box::new(CLType::U64), Box::new(CLType::ByteArray(32))])),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_EVENT_COUNT,
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_OPTION_NFT_TOKEN_ID,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Option(Box::new(CLType::U64)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_SET_METADATA_URI,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+            Parameter::new(ARG_METADATA_URI, CLType::String),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_GET_METADATA_URI,
+        vec![
+            Parameter::new(ARG_ID, CLType::U64),
+        ],
+        CLType::Option(Box::new(CLType::String)),
+        EntryPointAccess::Public,
+        EntryPointType::Called,
+    ).into());
+    
+    entry_points
+}
+
+/// Adds the current code as a new version of the installed package. The
+/// previous version's named keys, and so every option, carry over; it is then
+/// disabled so its copy of the named keys can't diverge.
+fn upgrade_contract(package_key: Key) {
+    let package_hash = package_key
+        .into_package_addr()
+        .map(ContractPackageHash::new)
+        .unwrap_or_revert_with(ErrorCode::MissingNamedKey);
+    let previous_hash = runtime::get_key(CONTRACT_KEY)
+        .and_then(Key::into_hash_addr)
+        .map(ContractHash::new)
+        .unwrap_or_revert_with(ErrorCode::MissingNamedKey);
+    
+    let (contract_hash, version) = storage::add_contract_version(
+        package_hash,
+        registry_entry_points(),
+        NamedKeys::new(),
+        BTreeMap::new(),
+    );
+    storage::disable_contract_version(package_hash, previous_hash).unwrap_or_revert();
+    runtime::call_contract::<()>(
+        contract_hash,
+        ENTRY_POINT_UPGRADE,
+        runtime_args! {
+            ARG_CONTRACT_HASH => contract_hash,
+            ARG_CONTRACT_VERSION => version,
+        },
+    );
+    
+    runtime::put_key(CONTRACT_KEY, contract_hash.into());
+}
+
+/// Installs the registry, or upgrades it when the caller already holds the
+/// package from an earlier install.
+#[no_mangle]
+pub extern "C" fn call() {
+    if let Some(package_key) = runtime::get_key(CONTRACT_PACKAGE_KEY) {
+        upgrade_contract(package_key);
+        return;
+    }
+    
+    let oracle_hash: ContractHash = runtime::get_named_arg(ARG_ORACLE_CONTRACT_HASH);
+    // Share of each CSPR settlement payout kept by the protocol
+    let fee_bps: u32 = runtime::get_named_arg(ARG_FEE_BPS);
+    if u64::from(fee_bps) > BPS_DENOMINATOR {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    // Paid out of accumulated fees for each option a keeper expires
+    let keeper_reward: U512 = runtime::get_named_arg(ARG_KEEPER_REWARD_PER_OPTION);
+    // Paid out of accumulated fees for each scheduled exercise a keeper processes
+    let keeper_exercise_reward: U512 =
+        runtime::try_get_named_arg(ARG_KEEPER_REWARD_PER_EXERCISE).unwrap_or_default();
+    // Restricts option creation to whitelisted writers; off by default
+    let whitelist_enabled: bool = runtime::try_get_named_arg(ARG_WHITELIST_ENABLED).unwrap_or(false);
+    // Most of the LP pool's value its options may lock; defaults to all of it
+    let max_utilization_bps: u32 = runtime::try_get_named_arg(ARG_MAX_UTILIZATION_BPS)
+        .unwrap_or(BPS_DENOMINATOR as u32);
+    if u64::from(max_utilization_bps) > BPS_DENOMINATOR {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    // Steepness of the LP pool's AMM pricing curve
+    let amm_lambda_bps: u32 = runtime::try_get_named_arg(ARG_AMM_LAMBDA_BPS)
+        .unwrap_or(DEFAULT_AMM_LAMBDA_BPS);
+    // Collateral-to-payout ratio below which an option can be liquidated
+    let liquidation_threshold_bps: u32 = runtime::try_get_named_arg(ARG_LIQUIDATION_THRESHOLD_BPS)
+        .unwrap_or(DEFAULT_LIQUIDATION_THRESHOLD_BPS);
+    // Share of the collateral paid to whoever liquidates an option
+    let liquidation_bonus_bps: u32 = runtime::try_get_named_arg(ARG_LIQUIDATION_BONUS_BPS)
+        .unwrap_or(DEFAULT_LIQUIDATION_BONUS_BPS);
+    if u64::from(liquidation_bonus_bps) > BPS_DENOMINATOR {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    // CEP-78 collection that mints a token for each option; none by default
+    let nft_contract: Option<Key> = runtime::try_get_named_arg(ARG_OPTION_NFT_CONTRACT_HASH);
+    // Oldest a published price may be before it is rejected as stale
+    let staleness_threshold: u64 = runtime::try_get_named_arg(ARG_ORACLE_STALENESS_THRESHOLD)
+        .unwrap_or(DEFAULT_ORACLE_STALENESS_THRESHOLD_SECS);
+    if staleness_threshold == 0 {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    // Shortest gap keepers must leave between price snapshots
+    let snapshot_interval: u64 = runtime::try_get_named_arg(ARG_SNAPSHOT_INTERVAL)
+        .unwrap_or(DEFAULT_SNAPSHOT_INTERVAL_SECS);
+    // Settle at the TWAP over `twap_window_seconds` instead of the spot price
+    let use_twap: bool = runtime::try_get_named_arg(ARG_USE_TWAP).unwrap_or(false);
+    let twap_window: u64 = runtime::try_get_named_arg(ARG_TWAP_WINDOW)
+        .unwrap_or(DEFAULT_TWAP_WINDOW_SECS);
+    if snapshot_interval == 0 || twap_window == 0 {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    // Most notional of open options `create_option` lets a single account create
+    let max_notional_per_account: U256 = runtime::try_get_named_arg(ARG_MAX_NOTIONAL_PER_ACCOUNT)
+        .unwrap_or(DEFAULT_MAX_NOTIONAL_PER_ACCOUNT);
+    // Share of the payout kept when an American option is exercised early
+    let early_exercise_penalty_bps: u32 = runtime::try_get_named_arg(ARG_EARLY_EXERCISE_PENALTY_BPS)
+        .unwrap_or(DEFAULT_EARLY_EXERCISE_PENALTY_BPS);
+    if u64::from(early_exercise_penalty_bps) > BPS_DENOMINATOR {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    // Votes a governance proposal needs before its timelock starts
+    let governance_vote_threshold: u32 = runtime::try_get_named_arg(ARG_GOVERNANCE_VOTE_THRESHOLD)
+        .unwrap_or(DEFAULT_GOVERNANCE_VOTE_THRESHOLD);
+    if governance_vote_threshold == 0 {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    // Basis points of notional holders pay writers per day; negative reverses it
+    let funding_rate_per_day: i64 =
+        runtime::try_get_named_arg(ARG_FUNDING_RATE_PER_DAY).unwrap_or(0);
+    if funding_rate_per_day.unsigned_abs() > BPS_DENOMINATOR {
+        runtime::revert(ErrorCode::InvalidParameter);
+    }
+    
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(OPTION_COUNT_KEY.to_string(), storage::new_uref(0u64).into());
+    named_keys.insert(EXPIRY_SET_COUNT_KEY.to_string(), storage::new_uref(0u64).into());
+    named_keys.insert(EVENT_COUNTER_KEY.to_string(), storage::new_uref(0u64).into());
+    named_keys.insert(CONTRACT_PURSE_KEY.to_string(), system::create_purse().into());
+    named_keys.insert(PAUSED_KEY.to_string(), storage::new_uref(false).into());
+    named_keys.insert(DEPRECATED_KEY.to_string(), storage::new_uref(false).into());
+    named_keys.insert(IN_PROGRESS_KEY.to_string(), storage::new_uref(false).into());
+    named_keys.insert(ADMIN_KEY.to_string(), storage::new_uref(runtime::get_caller()).into());
+    named_keys.insert(PENDING_ADMIN_KEY.to_string(), storage::new_uref(Option::<AccountHash>::None).into());
+    named_keys.insert(ORACLE_KEY.to_string(), storage::new_uref(oracle_hash).into());
+    named_keys.insert(GREEKS_ORACLE_KEY.to_string(), storage::new_uref(Option::<AccountHash>::None).into());
+    named_keys.insert(VOL_ORACLE_KEY.to_string(), storage::new_uref(Option::<AccountHash>::None).into());
+    named_keys.insert(NFT_CONTRACT_KEY.to_string(), storage::new_uref(nft_contract).into());
+    named_keys.insert(
+        COLLATERAL_DEX_KEY.to_string(),
+        storage::new_uref(Option::<Key>::None).into(),
+    );
+    named_keys.insert(ORACLE_PRICE_CACHE_KEY.to_string(), storage::new_uref(U256::zero()).into());
+    named_keys.insert(LAST_ORACLE_UPDATE_TIME_KEY.to_string(), storage::new_uref(0u64).into());
+    named_keys.insert(
+        ORACLE_STALENESS_THRESHOLD_KEY.to_string(),
+        storage::new_uref(staleness_threshold).into(),
+    );
+    named_keys.insert(SNAPSHOT_COUNT_KEY.to_string(), storage::new_uref(0u64).into());
+    named_keys.insert(
+        SNAPSHOT_INTERVAL_KEY.to_string(),
+        storage::new_uref(snapshot_interval).into(),
+    );
+    named_keys.insert(USE_TWAP_KEY.to_string(), storage::new_uref(use_twap).into());
+    named_keys.insert(TWAP_WINDOW_KEY.to_string(), storage::new_uref(twap_window).into());
+    named_keys.insert(
+        MAX_NOTIONAL_PER_ACCOUNT_KEY.to_string(),
+        storage::new_uref(max_notional_per_account).into(),
+    );
+    named_keys.insert(
+        EARLY_EXERCISE_PENALTY_BPS_KEY.to_string(),
+        storage::new_uref(early_exercise_penalty_bps).into(),
+    );
+    named_keys.insert(PRICE_VALIDATION_ENABLED_KEY.to_string(), storage::new_uref(false).into());
+    for key in [VOLUME_CALL_EXERCISED_KEY, VOLUME_PUT_EXERCISED_KEY] {
+        named_keys.insert(key.to_string(), storage::new_uref(U256::zero()).into());
+    }
+    named_keys.insert(
+        MAX_PRICE_DEVIATION_BPS_KEY.to_string(),
+        storage::new_uref(DEFAULT_MAX_PRICE_DEVIATION_BPS).into(),
+    );
+    named_keys.insert(
+        GOVERNANCE_VOTE_THRESHOLD_KEY.to_string(),
+        storage::new_uref(governance_vote_threshold).into(),
+    );
+    named_keys.insert(
+        FUNDING_RATE_PER_DAY_KEY.to_string(),
+        storage::new_uref(funding_rate_per_day).into(),
+    );
+    named_keys.insert(FEE_BPS_KEY.to_string(), storage::new_uref(fee_bps).into());
+    named_keys.insert(ACCUMULATED_FEES_KEY.to_string(), storage::new_uref(U512::zero()).into());
+    named_keys.insert(TOTAL_FEES_COLLECTED_KEY.to_string(), storage::new_uref(U512::zero()).into());
+    named_keys.insert(KEEPER_REWARD_KEY.to_string(), storage::new_uref(keeper_reward).into());
+    named_keys.insert(
+        KEEPER_EXERCISE_REWARD_KEY.to_string(),
+        storage::new_uref(keeper_exercise_reward).into(),
+    );
+    named_keys.insert(WHITELIST_ENABLED_KEY.to_string(), storage::new_uref(whitelist_enabled).into());
+    named_keys.insert(MAX_UTILIZATION_BPS_KEY.to_string(), storage::new_uref(max_utilization_bps).into());
+    named_keys.insert(AMM_LAMBDA_BPS_KEY.to_string(), storage::new_uref(amm_lambda_bps).into());
+    named_keys.insert(
+        LIQUIDATION_THRESHOLD_BPS_KEY.to_string(),
+        storage::new_uref(liquidation_threshold_bps).into(),
+    );
+    named_keys.insert(
+        LIQUIDATION_BONUS_BPS_KEY.to_string(),
+        storage::new_uref(liquidation_bonus_bps).into(),
+    );
+    for pool_key in [POOL_TOTAL_VALUE_KEY, POOL_TOTAL_SHARES_KEY, POOL_UTILIZED_COLLATERAL_KEY] {
+        named_keys.insert(pool_key.to_string(), storage::new_uref(U512::zero()).into());
+    }
+    named_keys.insert(INSURANCE_FUND_BALANCE_KEY.to_string(), storage::new_uref(U512::zero()).into());
+    named_keys.insert(EMERGENCY_CANCELLED_COUNT_KEY.to_string(), storage::new_uref(0u64).into());
+    named_keys.insert(
+        EMERGENCY_REFUND_TOTAL_KEY.to_string(),
+        storage::new_uref(U512::zero()).into(),
+    );
+    // Filled in once the contract exists; signed exercises bind to this hash
+    let contract_hash_uref = storage::new_uref(ContractHash::default());
+    named_keys.insert(CONTRACT_HASH_KEY.to_string(), contract_hash_uref.into());
+    let version_uref = storage::new_uref(0u32);
+    named_keys.insert(CONTRACT_VERSION_KEY.to_string(), version_uref.into());
+    
+    for dictionary in [
+        EXPIRY_INDEX_KEY,
+        OPTIONS_STRIKE_DICT,
+        OPTIONS_EXPIRY_DICT,
+        OPTIONS_EXERCISED_DICT,
+        OPTIONS_OWNER_DICT,
+        NONCES_DICT,
+        STATE_INDEX_KEY,
+        PROPOSALS_DICT,
+        KEEPER_REGISTRY_DICT,
+        WRITER_WHITELIST_DICT,
+        LP_BALANCES_DICT,
+        LP_ALLOWANCES_DICT,
+        EXPIRY_SET_MEMBERS_DICT,
+        OPTION_CHAIN_DICT,
+        INSURANCE_CONTRIBUTIONS_DICT,
+        EXERCISE_APPROVALS_DICT,
+        EXERCISE_DELEGATES_DICT,
+        SYNDICATES_DICT,
+        ORDER_BOOK_DICT,
+        PRICE_PUBLISHERS_DICT,
+        VOL_SURFACE_DICT,
+        COLLATERAL_WHITELIST_DICT,
+        USED_PERMITS_DICT,
+        PERMIT_COLLATERAL_DICT,
+    ] {
+        // new_dictionary also names the dictionary in the installing account;
+        // hand it to the contract and drop the account's copy
+        let dictionary_uref = storage::new_dictionary(dictionary)
+            .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+        runtime::remove_key(dictionary);
+        named_keys.insert(dictionary.to_string(), dictionary_uref.into());
+    }
     
-    let (contract_hash, _) = storage::new_contract(
-        entry_points,
+    let (contract_hash, version) = storage::new_contract(
+        registry_entry_points(),
         Some(named_keys),
         Some(CONTRACT_PACKAGE_KEY.to_string()),
         Some(CONTRACT_ACCESS_KEY.to_string()),
         None,
     );
+    storage::write(contract_hash_uref, contract_hash);
+    storage::write(version_uref, version);
     
     runtime::put_key(CONTRACT_KEY, contract_hash.into());
 }
@@ -0,0 +1,317 @@
+//! Fungible CEP-18 option tokens, one contract per option class.
+//!
+//! `deploy_option_token` is a factory entry point: it creates a new contract
+//! from the registry's own code whose entry points are the CEP-18 ones below,
+//! registered on the registry itself only as templates. Each token contract
+//! keeps its balances under `balance_{key}` and allowances under
+//! `allowance_{owner}_{spender}` named keys, and only the registry, recorded
+//! under `minter`, may mint and burn.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    CLType, EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter, U256,
+    account::AccountHash,
+    contracts::{ContractHash, EntryPoint, NamedKeys},
+    runtime_args,
+};
+
+use crate::error::ErrorCode;
+use crate::{put_named_value, read_named_value, ret};
+
+const NAME_KEY: &str = "name";
+const SYMBOL_KEY: &str = "symbol";
+const DECIMALS_KEY: &str = "decimals";
+const TOTAL_SUPPLY_KEY: &str = "total_supply";
+const MINTER_KEY: &str = "minter";
+
+const ENTRY_POINT_NAME: &str = "name";
+const ENTRY_POINT_SYMBOL: &str = "symbol";
+const ENTRY_POINT_DECIMALS: &str = "decimals";
+const ENTRY_POINT_TOTAL_SUPPLY: &str = "total_supply";
+const ENTRY_POINT_BALANCE_OF: &str = "balance_of";
+const ENTRY_POINT_ALLOWANCE: &str = "allowance";
+const ENTRY_POINT_APPROVE: &str = "approve";
+const ENTRY_POINT_TRANSFER: &str = "transfer";
+const ENTRY_POINT_TRANSFER_FROM: &str = "transfer_from";
+const ENTRY_POINT_MINT: &str = "mint";
+const ENTRY_POINT_BURN: &str = "burn";
+
+const ARG_ADDRESS: &str = "address";
+const ARG_OWNER: &str = "owner";
+const ARG_SPENDER: &str = "spender";
+const ARG_RECIPIENT: &str = "recipient";
+const ARG_AMOUNT: &str = "amount";
+
+/// Option tokens count whole option units.
+const DECIMALS: u8 = 0;
+const SYMBOL: &str = "OPT";
+
+/// `CallerInfo` field indices of the calling account and contract.
+const CALLER_FIELD_ACCOUNT: u8 = 0;
+const CALLER_FIELD_CONTRACT: u8 = 4;
+
+fn balance_key(address: &Key) -> String {
+    String::from("balance_") + &address.to_formatted_string()
+}
+
+fn allowance_key(owner: &Key, spender: &Key) -> String {
+    String::from("allowance_")
+        + &owner.to_formatted_string()
+        + "_"
+        + &spender.to_formatted_string()
+}
+
+/// Reads a `U256` named key, zero if it was never written.
+fn read_amount(name: &str) -> U256 {
+    if runtime::has_key(name) {
+        read_named_value(name)
+    } else {
+        U256::zero()
+    }
+}
+
+/// The immediate caller: `Key::Hash` of its contract hash for a contract,
+/// `Key::Account` for an account.
+fn caller_key() -> Key {
+    let caller = runtime::get_immediate_caller().unwrap_or_revert();
+    let field = |index| {
+        caller
+            .get_field_by_index(index)
+            .cloned()
+            .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+    };
+    let contract: Option<ContractHash> = field(CALLER_FIELD_CONTRACT)
+        .into_t()
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    match contract {
+        Some(contract_hash) => Key::Hash(contract_hash.value()),
+        None => {
+            let account: Option<AccountHash> = field(CALLER_FIELD_ACCOUNT)
+                .into_t()
+                .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+            Key::Account(account.unwrap_or_revert_with(ErrorCode::InvalidStorageValue))
+        }
+    }
+}
+
+fn move_tokens(owner: &Key, recipient: &Key, amount: U256) {
+    let owner_balance = read_amount(&balance_key(owner));
+    if owner_balance < amount {
+        runtime::revert(ErrorCode::InsufficientOptionTokens);
+    }
+    put_named_value(&balance_key(owner), owner_balance - amount);
+    let recipient_balance = read_amount(&balance_key(recipient));
+    put_named_value(&balance_key(recipient), recipient_balance + amount);
+}
+
+fn require_minter() {
+    let minter: Key = read_named_value(MINTER_KEY);
+    if caller_key() != minter {
+        runtime::revert(ErrorCode::NotOptionTokenMinter);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn name() {
+    let name: String = read_named_value(NAME_KEY);
+    ret(name);
+}
+
+#[no_mangle]
+pub extern "C" fn symbol() {
+    let symbol: String = read_named_value(SYMBOL_KEY);
+    ret(symbol);
+}
+
+#[no_mangle]
+pub extern "C" fn decimals() {
+    let decimals: u8 = read_named_value(DECIMALS_KEY);
+    ret(decimals);
+}
+
+#[no_mangle]
+pub extern "C" fn total_supply() {
+    ret(read_amount(TOTAL_SUPPLY_KEY));
+}
+
+#[no_mangle]
+pub extern "C" fn balance_of() {
+    let address: Key = runtime::get_named_arg(ARG_ADDRESS);
+    ret(read_amount(&balance_key(&address)));
+}
+
+#[no_mangle]
+pub extern "C" fn allowance() {
+    let owner: Key = runtime::get_named_arg(ARG_OWNER);
+    let spender: Key = runtime::get_named_arg(ARG_SPENDER);
+    ret(read_amount(&allowance_key(&owner, &spender)));
+}
+
+/// Lets `spender` move up to `amount` of the caller's tokens, replacing any
+/// earlier allowance.
+#[no_mangle]
+pub extern "C" fn approve() {
+    let spender: Key = runtime::get_named_arg(ARG_SPENDER);
+    let amount: U256 = runtime::get_named_arg(ARG_AMOUNT);
+    put_named_value(&allowance_key(&caller_key(), &spender), amount);
+}
+
+#[no_mangle]
+pub extern "C" fn transfer() {
+    let recipient: Key = runtime::get_named_arg(ARG_RECIPIENT);
+    let amount: U256 = runtime::get_named_arg(ARG_AMOUNT);
+    move_tokens(&caller_key(), &recipient, amount);
+}
+
+/// Moves `amount` of `owner`'s tokens to `recipient` out of the caller's
+/// allowance from `owner`.
+#[no_mangle]
+pub extern "C" fn transfer_from() {
+    let owner: Key = runtime::get_named_arg(ARG_OWNER);
+    let recipient: Key = runtime::get_named_arg(ARG_RECIPIENT);
+    let amount: U256 = runtime::get_named_arg(ARG_AMOUNT);
+
+    let allowance_name = allowance_key(&owner, &caller_key());
+    let allowance = read_amount(&allowance_name);
+    if allowance < amount {
+        runtime::revert(ErrorCode::InsufficientOptionTokenAllowance);
+    }
+    put_named_value(&allowance_name, allowance - amount);
+    move_tokens(&owner, &recipient, amount);
+}
+
+/// Mints `amount` tokens to `owner` (registry only).
+#[no_mangle]
+pub extern "C" fn mint() {
+    require_minter();
+
+    let owner: Key = runtime::get_named_arg(ARG_OWNER);
+    let amount: U256 = runtime::get_named_arg(ARG_AMOUNT);
+    put_named_value(&balance_key(&owner), read_amount(&balance_key(&owner)) + amount);
+    put_named_value(TOTAL_SUPPLY_KEY, read_amount(TOTAL_SUPPLY_KEY) + amount);
+}
+
+/// Burns `amount` of `owner`'s tokens (registry only).
+#[no_mangle]
+pub extern "C" fn burn() {
+    require_minter();
+
+    let owner: Key = runtime::get_named_arg(ARG_OWNER);
+    let amount: U256 = runtime::get_named_arg(ARG_AMOUNT);
+    let balance = read_amount(&balance_key(&owner));
+    if balance < amount {
+        runtime::revert(ErrorCode::InsufficientOptionTokens);
+    }
+    put_named_value(&balance_key(&owner), balance - amount);
+    put_named_value(TOTAL_SUPPLY_KEY, read_amount(TOTAL_SUPPLY_KEY) - amount);
+}
+
+/// The CEP-18 entry points of an option token contract, with `access`.
+fn token_entry_points(access: EntryPointAccess) -> [EntryPoint; 11] {
+    let key = || CLType::Key;
+    let entry_point = |name: &str, params: Vec<Parameter>, ret: CLType| {
+        EntryPoint::new(name, params, ret, access.clone(), EntryPointType::Called)
+    };
+    [
+        entry_point(ENTRY_POINT_NAME, vec![], CLType::String),
+        entry_point(ENTRY_POINT_SYMBOL, vec![], CLType::String),
+        entry_point(ENTRY_POINT_DECIMALS, vec![], CLType::U8),
+        entry_point(ENTRY_POINT_TOTAL_SUPPLY, vec![], CLType::U256),
+        entry_point(
+            ENTRY_POINT_BALANCE_OF,
+            vec![Parameter::new(ARG_ADDRESS, key())],
+            CLType::U256,
+        ),
+        entry_point(
+            ENTRY_POINT_ALLOWANCE,
+            vec![Parameter::new(ARG_OWNER, key()), Parameter::new(ARG_SPENDER, key())],
+            CLType::U256,
+        ),
+        entry_point(
+            ENTRY_POINT_APPROVE,
+            vec![Parameter::new(ARG_SPENDER, key()), Parameter::new(ARG_AMOUNT, CLType::U256)],
+            CLType::Unit,
+        ),
+        entry_point(
+            ENTRY_POINT_TRANSFER,
+            vec![Parameter::new(ARG_RECIPIENT, key()), Parameter::new(ARG_AMOUNT, CLType::U256)],
+            CLType::Unit,
+        ),
+        entry_point(
+            ENTRY_POINT_TRANSFER_FROM,
+            vec![
+                Parameter::new(ARG_OWNER, key()),
+                Parameter::new(ARG_RECIPIENT, key()),
+                Parameter::new(ARG_AMOUNT, CLType::U256),
+            ],
+            CLType::Unit,
+        ),
+        entry_point(
+            ENTRY_POINT_MINT,
+            vec![Parameter::new(ARG_OWNER, key()), Parameter::new(ARG_AMOUNT, CLType::U256)],
+            CLType::Unit,
+        ),
+        entry_point(
+            ENTRY_POINT_BURN,
+            vec![Parameter::new(ARG_OWNER, key()), Parameter::new(ARG_AMOUNT, CLType::U256)],
+            CLType::Unit,
+        ),
+    ]
+}
+
+/// Registers the token entry points on the registry as templates, so the
+/// registry's code exports them without the registry itself serving them.
+pub fn add_template_entry_points(entry_points: &mut EntryPoints) {
+    for entry_point in token_entry_points(EntryPointAccess::Template) {
+        entry_points.add_entry_point(entry_point.into());
+    }
+}
+
+/// Creates a token contract named `name` that `minter` mints and burns, and
+/// returns its hash.
+pub fn deploy(name: String, minter: Key) -> ContractHash {
+    let mut entry_points = EntryPoints::new();
+    for entry_point in token_entry_points(EntryPointAccess::Public) {
+        entry_points.add_entry_point(entry_point.into());
+    }
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(NAME_KEY.to_string(), storage::new_uref(name).into());
+    named_keys.insert(SYMBOL_KEY.to_string(), storage::new_uref(SYMBOL.to_string()).into());
+    named_keys.insert(DECIMALS_KEY.to_string(), storage::new_uref(DECIMALS).into());
+    named_keys.insert(TOTAL_SUPPLY_KEY.to_string(), storage::new_uref(U256::zero()).into());
+    named_keys.insert(MINTER_KEY.to_string(), storage::new_uref(minter).into());
+    let (contract_hash, _) = storage::new_contract(entry_points, Some(named_keys), None, None, None);
+    contract_hash
+}
+
+/// Mints `amount` of `token` to `owner`.
+pub fn mint_to(token: ContractHash, owner: AccountHash, amount: U256) {
+    runtime::call_contract::<()>(
+        token,
+        ENTRY_POINT_MINT,
+        runtime_args! {
+            ARG_OWNER => Key::Account(owner),
+            ARG_AMOUNT => amount,
+        },
+    );
+}
+
+/// Burns `amount` of `owner`'s `token`, reverting if they hold fewer.
+pub fn burn_from(token: ContractHash, owner: AccountHash, amount: U256) {
+    runtime::call_contract::<()>(
+        token,
+        ENTRY_POINT_BURN,
+        runtime_args! {
+            ARG_OWNER => Key::Account(owner),
+            ARG_AMOUNT => amount,
+        },
+    );
+}
@@ -0,0 +1,200 @@
+//! Fixed-point option pricing without floating point or `libm`: Black-Scholes
+//! for calls and puts, and the LP pool's AMM curve.
+//!
+//! Intermediate values are `i128` fixed-point numbers with 12 decimals. The
+//! normal CDF uses the Abramowitz & Stegun 26.2.17 approximation (absolute
+//! error below 7.5e-8), and the risk-free rate is taken to be zero.
+
+use casper_types::{U256, U512};
+
+/// 1.0 in fixed point.
+const ONE: i128 = 1_000_000_000_000;
+const LN_2: i128 = 693_147_180_560;
+const SQRT_2_PI: i128 = 2_506_628_274_631;
+
+/// Abramowitz & Stegun 26.2.17 coefficients.
+const CDF_P: i128 = 231_641_900_000;
+const CDF_B1: i128 = 319_381_530_000;
+const CDF_B2: i128 = -356_563_782_000;
+const CDF_B3: i128 = 1_781_477_937_000;
+const CDF_B4: i128 = -1_821_255_978_000;
+const CDF_B5: i128 = 1_330_274_429_000;
+
+/// Beyond this many standard deviations the CDF is taken as exactly 0 or 1.
+const CDF_CUTOFF: i128 = 10 * ONE;
+
+/// `e^x` is taken as exactly 0 below `-EXP_CUTOFF`.
+const EXP_CUTOFF: i128 = 40 * ONE;
+
+const SECONDS_PER_YEAR: u64 = 365 * 86_400;
+const BPS_PER_UNIT: u64 = 10_000;
+
+/// Volatility and term are capped here to keep the fixed-point arithmetic in
+/// range; the price is indistinguishable from spot well before either cap.
+const MAX_IV_BPS: u64 = 1_000_000;
+const MAX_TERM_SECS: u64 = 100 * SECONDS_PER_YEAR;
+
+fn mul(a: i128, b: i128) -> i128 {
+    a * b / ONE
+}
+
+fn div(a: i128, b: i128) -> i128 {
+    a * ONE / b
+}
+
+fn sqrt(x: i128) -> i128 {
+    let n = (x as u128) * (ONE as u128);
+    if n == 0 {
+        return 0;
+    }
+    let mut root = n;
+    let mut next = (root + 1) / 2;
+    while next < root {
+        root = next;
+        next = (root + n / root) / 2;
+    }
+    root as i128
+}
+
+/// Natural log of a positive fixed-point `x`: `x` is scaled into `[1, 2)` by
+/// powers of two and the rest comes from the series for `2 * atanh(z)`.
+fn ln(mut x: i128) -> i128 {
+    let mut exponent: i128 = 0;
+    while x >= 2 * ONE {
+        x /= 2;
+        exponent += 1;
+    }
+    while x < ONE {
+        x *= 2;
+        exponent -= 1;
+    }
+
+    let z = div(x - ONE, x + ONE);
+    let z_squared = mul(z, z);
+    let mut term = z;
+    let mut sum = 0;
+    let mut n = 1;
+    while term != 0 {
+        sum += term / n;
+        term = mul(term, z_squared);
+        n += 2;
+    }
+    exponent * LN_2 + 2 * sum
+}
+
+/// Natural log of the integer `n` as fixed point. Only the top 40 bits are
+/// kept, which is far more precision than the CDF approximation has.
+fn ln_u256(n: U256) -> i128 {
+    let shift = n.bits().saturating_sub(40);
+    let mantissa = (n >> shift).as_u64() as i128;
+    ln(mantissa * ONE) + shift as i128 * LN_2
+}
+
+/// `e^x` for fixed-point `x <= 0`, by splitting off a power of two and
+/// summing the Taylor series of the remainder.
+fn exp_non_positive(x: i128) -> i128 {
+    if x < -EXP_CUTOFF {
+        return 0;
+    }
+    let halvings = (-x).div_euclid(LN_2) + 1;
+    let remainder = x + halvings * LN_2;
+
+    let mut term = ONE;
+    let mut sum = ONE;
+    let mut n = 1;
+    while term != 0 {
+        term = mul(term, remainder) / n;
+        sum += term;
+        n += 1;
+    }
+    sum >> halvings
+}
+
+/// Standard normal CDF of fixed-point `x`.
+fn norm_cdf(x: i128) -> i128 {
+    if x < 0 {
+        return ONE - norm_cdf(-x);
+    }
+    if x > CDF_CUTOFF {
+        return ONE;
+    }
+
+    let density = div(exp_non_positive(-mul(x, x) / 2), SQRT_2_PI);
+    let t = div(ONE, ONE + mul(CDF_P, x));
+    let mut poly = CDF_B5;
+    for coefficient in [CDF_B4, CDF_B3, CDF_B2, CDF_B1] {
+        poly = coefficient + mul(t, poly);
+    }
+    ONE - mul(density, mul(t, poly))
+}
+
+/// `value * fraction` for a fixed-point `fraction` in `[0, 1]`, without
+/// overflowing for any `value`.
+fn scale(value: U256, fraction: i128) -> U256 {
+    let fraction = U256::from(fraction as u128);
+    let one = U256::from(ONE as u128);
+    (value / one) * fraction + (value % one) * fraction / one
+}
+
+/// Black-Scholes price of a call struck at `strike` with `spot` underlying,
+/// `iv_bps` implied volatility and `seconds_to_expiry` left, in the units of
+/// `spot` and `strike`. With no time or volatility left it is the intrinsic
+/// value.
+pub fn call_price(spot: U256, strike: U256, iv_bps: u64, seconds_to_expiry: u64) -> U256 {
+    if spot.is_zero() || strike.is_zero() || iv_bps == 0 || seconds_to_expiry == 0 {
+        return spot.saturating_sub(strike);
+    }
+
+    let sigma = i128::from(iv_bps.min(MAX_IV_BPS)) * ONE / i128::from(BPS_PER_UNIT);
+    let term_secs = seconds_to_expiry.min(MAX_TERM_SECS);
+    let term = i128::from(term_secs) * ONE / i128::from(SECONDS_PER_YEAR);
+    let sigma_root_term = mul(sigma, sqrt(term));
+
+    let log_moneyness = ln_u256(spot) - ln_u256(strike);
+    let variance = mul(mul(sigma, sigma), term);
+    let d1 = div(log_moneyness + variance / 2, sigma_root_term);
+    let d2 = d1 - sigma_root_term;
+
+    scale(spot, norm_cdf(d1)).saturating_sub(scale(strike, norm_cdf(d2)))
+}
+
+/// Black-Scholes price of a put with the same terms as `call_price`, from
+/// put-call parity at a zero interest rate: `call + strike - spot`.
+pub fn put_price(spot: U256, strike: U256, iv_bps: u64, seconds_to_expiry: u64) -> U256 {
+    call_price(spot, strike, iv_bps, seconds_to_expiry)
+        .saturating_add(strike)
+        .saturating_sub(spot)
+}
+
+/// Premium the LP pool charges for an option locking `collateral`:
+/// `pool_value * (1 - e^(-lambda * delta))`, where `lambda` is
+/// `lambda_bps / 10_000` and `delta` is `collateral` as a share of the pool's
+/// `free_liquidity`, times the years left to expiry. The premium rises with
+/// utilization and never exceeds `pool_value`.
+pub fn amm_premium(
+    pool_value: U512,
+    collateral: U512,
+    free_liquidity: U512,
+    lambda_bps: u32,
+    seconds_to_expiry: u64,
+) -> U512 {
+    if collateral.is_zero() || lambda_bps == 0 || seconds_to_expiry == 0 {
+        return U512::zero();
+    }
+
+    // Capping the share keeps the fixed-point products below in range
+    let one = U512::from(ONE as u128);
+    let max_share = U512::from(EXP_CUTOFF as u128);
+    let share = if free_liquidity.is_zero() {
+        max_share
+    } else {
+        (collateral * one / free_liquidity).min(max_share)
+    };
+    let lambda = i128::from(lambda_bps) * ONE / i128::from(BPS_PER_UNIT);
+    let term_secs = seconds_to_expiry.min(MAX_TERM_SECS);
+    let term = i128::from(term_secs) * ONE / i128::from(SECONDS_PER_YEAR);
+    let exponent = mul(mul(share.as_u128() as i128, lambda), term).min(EXP_CUTOFF);
+
+    let fraction = ONE - exp_non_positive(-exponent);
+    pool_value * U512::from(fraction as u128) / one
+}
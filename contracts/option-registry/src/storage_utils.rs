@@ -0,0 +1,39 @@
+//! Read-modify-write helpers for values stored behind contract URefs.
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::URef;
+
+use crate::error::ErrorCode;
+
+/// Adds 1 to the `u64` stored under `uref`, saturating at `u64::MAX` rather
+/// than wrapping.
+pub fn increment_u64(uref: URef) {
+    let value: u64 = storage::read(uref)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    storage::write(uref, value.saturating_add(1));
+}
+
+/// Subtracts 1 from the `u64` stored under `uref`, saturating at 0.
+pub fn decrement_u64(uref: URef) {
+    let value: u64 = storage::read(uref)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue)
+        .unwrap_or_revert_with(ErrorCode::InvalidStorageValue);
+    storage::write(uref, value.saturating_sub(1));
+}
+
+/// Returns the `u64` counter stored under the named key `name`, creating it
+/// at 0 on first use.
+pub fn named_counter(name: &str) -> URef {
+    match runtime::get_key(name) {
+        Some(key) => key.into_uref().unwrap_or_revert_with(ErrorCode::InvalidStorageValue),
+        None => {
+            let uref = storage::new_uref(0u64);
+            runtime::put_key(name, uref.into());
+            uref
+        }
+    }
+}